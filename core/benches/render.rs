@@ -0,0 +1,81 @@
+/*
+For Criterion /sa https://bheisler.github.io/criterion.rs/book/getting_started.html
+*/
+
+use cpp_proj_gen_core::template_vars::{VarReplacer, VarValue};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::BTreeMap;
+
+// A template pack of 1000 files with 50 vars, each file about 4 KiB
+const FILES: usize = 1000;
+const VARS: usize = 50;
+
+fn create_vars() -> BTreeMap<String, VarValue> {
+    (0..VARS)
+        .map(|index| {
+            let value = match index % 3 {
+                0 => VarValue::Bool(index % 2 == 0),
+                1 => VarValue::List(vec![format!("item{}a", index), format!("item{}b", index)]),
+                _ => VarValue::from(format!("value-{}", index).as_str()),
+            };
+            (format!("@VAR_{}@", index), value)
+        })
+        .collect()
+}
+
+fn create_templates() -> Vec<String> {
+    (0..FILES)
+        .map(|file| {
+            (0..64)
+                .map(|line| match line % 4 {
+                    0 => format!(
+                        "set(OPTION_{} @VAR_{}|cmake@)\n",
+                        line,
+                        (file + line) % VARS
+                    ),
+                    1 => format!("// @VAR_{}@ in file {}\n", (file * 7 + line) % VARS, file),
+                    _ => format!(
+                        "int value_{}_{} = {}; // plain C++ without vars\n",
+                        file, line, line
+                    ),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// What rendering did before, one String::replace over the whole file per var
+fn replace_each(contents: &str, vars: &BTreeMap<String, VarValue>) -> String {
+    vars.iter()
+        .fold(String::from(contents), |result, (var, value)| {
+            result.replace(var, &value.render())
+        })
+}
+
+fn bench_render(c: &mut Criterion) {
+    let vars = create_vars();
+    let templates = create_templates();
+    let mut group = c.benchmark_group("render 1000 files with 50 vars");
+    group.sample_size(10);
+
+    group.bench_function("single pass", |b| {
+        b.iter(|| {
+            let replacer = VarReplacer::new(&vars);
+            for template in &templates {
+                black_box(replacer.replace(template));
+            }
+        })
+    });
+    group.bench_function("replace per var, plain vars only", |b| {
+        b.iter(|| {
+            for template in &templates {
+                black_box(replace_each(template, &vars));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_render);
+criterion_main!(benches);
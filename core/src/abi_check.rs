@@ -0,0 +1,151 @@
+/*
+For abidiff /sa https://sourceware.org/libabigail/manual/abidiff.html
+For abi-compliance-checker /sa https://lvc.github.io/abi-compliance-checker/
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use std::{io, path::PathBuf, str::FromStr};
+
+const ABIDIFF_CMAKE: &str = include_str!("../res/abi_check/abidiff.snippet.in");
+const ABI_COMPLIANCE_CHECKER_CMAKE: &str =
+    include_str!("../res/abi_check/abi-compliance-checker.snippet.in");
+const BASELINE_SCRIPT: &str = include_str!("../res/abi_check/abi-baseline.sh.in");
+
+// Tool comparing the library's ABI with a stored baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AbiCheck {
+    Abidiff,
+    AbiComplianceChecker,
+}
+
+impl AbiCheck {
+    pub const VARIANTS: &'static [&'static str] = &["abidiff", "abi-compliance-checker"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AbiCheck::Abidiff => "abidiff",
+            AbiCheck::AbiComplianceChecker => "abi-compliance-checker",
+        }
+    }
+
+    // The CMake snippet and the baseline file name below abi/
+    fn templates(self) -> (&'static str, &'static str) {
+        match self {
+            AbiCheck::Abidiff => (ABIDIFF_CMAKE, "@CMAKE_TARGET_NAME@.abi"),
+            AbiCheck::AbiComplianceChecker => {
+                (ABI_COMPLIANCE_CHECKER_CMAKE, "@CMAKE_TARGET_NAME@.dump")
+            }
+        }
+    }
+}
+
+impl FromStr for AbiCheck {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abidiff" => Ok(AbiCheck::Abidiff),
+            "abi-compliance-checker" => Ok(AbiCheck::AbiComplianceChecker),
+            _ => Err(format!(
+                "unknown ABI check '{}', expected one of: {}",
+                s,
+                AbiCheck::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Both tools read shared libraries
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    match opt.abi_check {
+        Some(abi_check) if opt.kind != Kind::Shared => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "--abi-check {} needs kind shared, not {}",
+                abi_check.name(),
+                opt.kind.name()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Adds the abi-dump and abi-check targets plus scripts/abi-baseline.sh capturing the baseline
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (cmake, baseline) = match gen.opt().abi_check {
+        Some(abi_check) => abi_check.templates(),
+        None => return gen,
+    };
+
+    gen.add_file(
+        PathBuf::from("scripts/abi-baseline.sh"),
+        BASELINE_SCRIPT.replace("@ABI_BASELINE@", baseline),
+    )
+    .add_cmake_snippet(String::from(cmake.trim_end()))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::contents;
+
+    fn create_test_opt(abi_check: AbiCheck) -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::Shared,
+            abi_check: Some(abi_check),
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        for variant in AbiCheck::VARIANTS {
+            assert_eq!(variant.parse::<AbiCheck>().unwrap().name(), *variant);
+        }
+        assert!("abi-dumper".parse::<AbiCheck>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&create_test_opt(AbiCheck::Abidiff)).is_ok());
+        assert!(validate(&Opt {
+            kind: Kind::Static,
+            ..create_test_opt(AbiCheck::Abidiff)
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = CppProjGen::new(create_test_opt(AbiCheck::Abidiff))
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .plan()
+            .unwrap();
+
+        assert!(contents(&plan, "scripts/abi-baseline.sh")
+            .unwrap()
+            .contains("cp \"build/abi/abi/tgtnm.abi\" \"abi/tgtnm.abi\"\n"));
+        let cmake_lists = contents(&plan, "CMakeLists.txt").unwrap();
+        assert!(
+            cmake_lists.contains("            --headers-dir ${CMAKE_CURRENT_LIST_DIR}/include\n")
+        );
+        assert!(cmake_lists.contains("    add_custom_target(abi-check\n"));
+
+        let plan = CppProjGen::new(create_test_opt(AbiCheck::AbiComplianceChecker))
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .plan()
+            .unwrap();
+
+        assert!(contents(&plan, "scripts/abi-baseline.sh")
+            .unwrap()
+            .contains("abi/tgtnm.dump"));
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("            -old ${CMAKE_CURRENT_LIST_DIR}/abi/tgtnm.dump\n"));
+    }
+}
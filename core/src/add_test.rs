@@ -0,0 +1,197 @@
+/*
+For the project manifest /sa project_manifest.rs
+*/
+
+use crate::cpp_proj_gen::CppProjGen;
+use crate::error::{self, invalid_input, Error};
+use crate::naming::HEADER_EXTS;
+use crate::project_manifest::{self, ProjectManifest};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Files add_test wrote, the CMakeLists.txt is not registered to if it globs its sources
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddedTest {
+    pub test_path: PathBuf,
+    pub cmake_lists: PathBuf,
+    pub registered: bool,
+}
+
+// Creates e.g. test/test_<name>.<ext>, named by the project's test pattern, for a class and adds it to the test executable
+pub fn add_test(project_dir: &Path, class_name: &str) -> error::Result<AddedTest> {
+    let mut manifest = ProjectManifest::load(project_dir)?;
+    let opt = manifest.to_opt()?;
+
+    let framework = opt.test_framework.ok_or_else(|| {
+        invalid_input(String::from(
+            "the project has no test framework, generate it with --test-framework",
+        ))
+    })?;
+    let template = framework.add_test_template().ok_or_else(|| {
+        invalid_input(format!(
+            "add-test supports gtest, catch2 and doctest, {} tests need their own main",
+            framework.name()
+        ))
+    })?;
+    if !is_identifier(class_name) {
+        return Err(Error::from(invalid_input(format!(
+            "'{}' is not a C++ class name",
+            class_name
+        ))));
+    }
+
+    let include_dir = manifest.include_dir().to_string();
+    let test_dir = manifest.test_dir().to_string();
+    let gen = CppProjGen::new(opt).add_include_dir(PathBuf::from(&include_dir));
+    let test_name = to_snake_case(class_name);
+    let test_file = format!(
+        "{}{}{}",
+        gen.cmake_var("@TEST_FILE_PREFIX@").unwrap_or_default(),
+        test_name,
+        gen.cmake_var("@TEST_FILE_SUFFIX@").unwrap_or_default()
+    );
+    let manifest_file = format!("{}/{}", test_dir, test_file);
+    let test_path = project_dir.join(&test_dir).join(&test_file);
+    if test_path.exists() {
+        return Err(Error::from(invalid_input(format!(
+            "{} already exists",
+            test_path.display()
+        ))));
+    }
+
+    let include_domain_dir = gen.cmake_var("@INCLUDE_DOMAIN_DIR@").unwrap_or_default();
+    let header_dir = project_dir.join(&include_dir).join(include_domain_dir);
+    let target_name = &gen.opt().target_name;
+    // Adopted projects may use .hpp for the project header too
+    let header = find_header(&header_dir, class_name, &test_name)
+        .or_else(|| find_header(&header_dir, target_name, target_name))
+        .unwrap_or_else(|| format!("{}.{}", target_name, gen.opt().header_ext));
+
+    let contents = gen
+        .add_cmake_var("@TEST_CLASS@", class_name)
+        .add_cmake_var("@TEST_NAME@", &test_name)
+        .add_cmake_var("@TEST_HEADER@", &header)
+        .render(template);
+    let cmake_lists = project_dir.join(&test_dir).join("CMakeLists.txt");
+    let cmake_contents =
+        fs::read_to_string(&cmake_lists).map_err(Error::fs("read", &cmake_lists))?;
+
+    fs::write(&test_path, contents).map_err(Error::fs("write", &test_path))?;
+    let registered = match register_test_file(&cmake_contents, &manifest.target_name, &test_file) {
+        Some(cmake_contents) => {
+            fs::write(&cmake_lists, cmake_contents).map_err(Error::fs("write", &cmake_lists))?;
+            true
+        }
+        None => false,
+    };
+
+    manifest.files.push(manifest_file);
+    manifest.files.sort();
+    manifest.store(project_dir)?;
+
+    Ok(AddedTest {
+        test_path,
+        cmake_lists,
+        registered,
+    })
+}
+
+// e.g. Widget.h or widget.hpp next to the project header
+fn find_header(include_dir: &Path, class_name: &str, test_name: &str) -> Option<String> {
+    [class_name, test_name]
+        .iter()
+        .flat_map(|name| {
+            HEADER_EXTS
+                .iter()
+                .map(move |ext| format!("{}.{}", name, ext))
+        })
+        .find(|header| {
+            include_dir
+                .join(project_manifest::from_manifest_path(header))
+                .is_file()
+        })
+}
+
+// Adds the file as last source of add_executable(<target>-test ...), None if sources are globbed
+fn register_test_file(contents: &str, target_name: &str, test_file: &str) -> Option<String> {
+    if contents.contains("file(GLOB") {
+        return None;
+    }
+
+    let start = format!("add_executable({}-test", target_name);
+    let mut lines: Vec<&str> = contents.lines().collect();
+    let begin = lines.iter().position(|line| line.starts_with(&start))?;
+    let end = begin + lines[begin..].iter().position(|line| line.trim() == ")")?;
+    let source = format!("    {}", test_file);
+    lines.insert(end, &source);
+
+    Some(lines.join("\n") + "\n")
+}
+
+// e.g. HttpServer to http_server
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let chars: Vec<char> = name.chars().collect();
+
+    for (index, c) in chars.iter().enumerate() {
+        let starts_word = c.is_ascii_uppercase()
+            && index > 0
+            && (chars[index - 1].is_ascii_lowercase()
+                || chars
+                    .get(index + 1)
+                    .is_some_and(|next| next.is_ascii_lowercase()));
+        if starts_word && !result.ends_with('_') {
+            result.push('_');
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+
+    result
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Widget"), "widget");
+        assert_eq!(to_snake_case("HttpServer"), "http_server");
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("my_class"), "my_class");
+    }
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("Widget"));
+        assert!(!is_identifier("3d"));
+        assert!(!is_identifier("a::b"));
+    }
+
+    #[test]
+    fn test_register_test_file() {
+        let contents =
+            "add_executable(tgtnm-test\n    test_tgtnm.cpp\n)\n\nadd_test(NAME tgtnm-test)\n";
+
+        assert_eq!(
+            register_test_file(contents, "tgtnm", "test_widget.cpp").unwrap(),
+            "add_executable(tgtnm-test\n    test_tgtnm.cpp\n    test_widget.cpp\n)\n\nadd_test(NAME tgtnm-test)\n"
+        );
+        assert_eq!(
+            register_test_file("file(GLOB SOURCES *.cpp)", "tgtnm", "a.cpp"),
+            None
+        );
+    }
+}
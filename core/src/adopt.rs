@@ -0,0 +1,354 @@
+/*
+For the project manifest /sa project_manifest.rs
+*/
+
+use crate::collisions;
+use crate::cpp_proj_gen::{Kind, Opt};
+use crate::error::{self, invalid_input, Error};
+use crate::language::Language;
+use crate::project_manifest::{self, ProjectManifest, MANIFEST_PATH};
+use crate::source_listing::SourceListing;
+use crate::test_framework::TestFramework;
+use std::{fs, io, path::Path};
+
+const INCLUDE_DIRS: &[&str] = &["include", "inc"];
+const SOURCE_DIRS: &[&str] = &["source", "src", "lib"];
+const TEST_DIRS: &[&str] = &["test", "tests"];
+const CMAKE_LISTS: &str = "CMakeLists.txt";
+
+// Keywords in the test CMakeLists.txt, e.g. find_package(GTest) or FetchContent_Declare(Catch2
+const TEST_FRAMEWORK_HINTS: &[(&str, TestFramework)] = &[
+    ("gtest", TestFramework::GTest),
+    ("catch2", TestFramework::Catch2),
+    ("doctest", TestFramework::Doctest),
+    ("unity", TestFramework::Unity),
+    ("cmocka", TestFramework::CMocka),
+];
+
+// Layout found in an existing project
+#[derive(Debug, Default, PartialEq)]
+struct Layout {
+    include_dir: Option<String>,
+    source_dir: Option<String>,
+    test_dir: Option<String>,
+}
+
+// Writes a project manifest for a CMake project not generated by cpp-proj-gen, returns it as TOML
+pub fn adopt_project(
+    project_dir: &Path,
+    target_name: Option<&str>,
+    name_space: Option<&str>,
+    dry_run: bool,
+) -> error::Result<String> {
+    let manifest_path = project_dir.join(MANIFEST_PATH);
+    if manifest_path.exists() {
+        return Err(Error::from(invalid_input(format!(
+            "{} already exists, the project is managed by cpp-proj-gen",
+            manifest_path.display()
+        ))));
+    }
+
+    let cmake_lists = project_dir.join(CMAKE_LISTS);
+    let cmake_contents =
+        fs::read_to_string(&cmake_lists).map_err(Error::fs("read", &cmake_lists))?;
+
+    let layout = Layout {
+        include_dir: find_dir(project_dir, INCLUDE_DIRS),
+        source_dir: find_dir(project_dir, SOURCE_DIRS),
+        test_dir: find_dir(project_dir, TEST_DIRS),
+    };
+    if layout.include_dir.is_none() && layout.source_dir.is_none() {
+        return Err(Error::from(invalid_input(format!(
+            "no include or source directory found in {}, expected one of: {}",
+            project_dir.display(),
+            [INCLUDE_DIRS, SOURCE_DIRS].concat().join(", ")
+        ))));
+    }
+
+    let targets = find_targets(&cmake_contents);
+    let target_name = match target_name {
+        Some(target_name) => String::from(target_name),
+        None => infer_target_name(project_dir, &cmake_contents, &targets)?,
+    };
+
+    let mut files = vec![String::from(CMAKE_LISTS)];
+    for dir in [&layout.include_dir, &layout.source_dir, &layout.test_dir]
+        .iter()
+        .filter_map(|dir| dir.as_ref())
+    {
+        collect_files(project_dir, &project_dir.join(dir), &mut files)?;
+    }
+    files.sort();
+
+    let name_space = match name_space {
+        Some(name_space) => Some(String::from(name_space)),
+        None => layout
+            .include_dir
+            .as_ref()
+            .and_then(|dir| infer_name_space(&project_dir.join(dir), &target_name)),
+    };
+    let test_framework = match &layout.test_dir {
+        Some(dir) => {
+            let path = project_dir.join(dir).join(CMAKE_LISTS);
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|contents| infer_test_framework(&contents))
+        }
+        None => None,
+    };
+
+    let opt = Opt {
+        target_name,
+        name_space,
+        kind: infer_kind(&targets),
+        language: infer_language(&files),
+        test_framework,
+        source_listing: infer_source_listing(&cmake_contents),
+        ..Opt::default()
+    };
+    collisions::validate(&opt, &[])?;
+
+    let mut manifest = ProjectManifest::new(&opt, files);
+    manifest.include_dir = layout.include_dir;
+    manifest.source_dir = layout.source_dir;
+    manifest.test_dir = layout.test_dir;
+
+    let toml = manifest.to_toml()?;
+    if dry_run {
+        return Ok(toml);
+    }
+
+    if let Some(parent) = manifest_path.parent() {
+        fs::create_dir_all(parent).map_err(Error::fs("create directory", parent))?;
+    }
+    manifest.store(project_dir)?;
+
+    Ok(toml)
+}
+
+fn find_dir(project_dir: &Path, candidates: &[&str]) -> Option<String> {
+    candidates
+        .iter()
+        .find(|dir| project_dir.join(dir).is_dir())
+        .map(|dir| String::from(*dir))
+}
+
+// Tracked like generated files, relative with / separators
+fn collect_files(project_dir: &Path, dir: &Path, files: &mut Vec<String>) -> error::Result<()> {
+    for entry in fs::read_dir(dir).map_err(Error::fs("read", dir))? {
+        let path = entry.map_err(Error::fs("read", dir))?.path();
+
+        if path.is_dir() {
+            collect_files(project_dir, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(project_dir) {
+            files.push(project_manifest::to_manifest_path(relative));
+        }
+    }
+
+    Ok(())
+}
+
+// Own targets as (name, type), e.g. ("tgtnm", "STATIC") or ("tgtnm-app", "EXECUTABLE")
+fn find_targets(cmake_contents: &str) -> Vec<(String, String)> {
+    let mut targets = Vec::new();
+
+    for (command, default_type) in [
+        ("add_library(", "STATIC"),
+        ("add_executable(", "EXECUTABLE"),
+    ] {
+        for (index, _) in cmake_contents.match_indices(command) {
+            let mut args = cmake_contents[index + command.len()..]
+                .split(|c: char| c.is_whitespace() || c == ')')
+                .filter(|arg| !arg.is_empty());
+            let name = match args.next() {
+                Some(name) if !name.contains('$') => name,
+                _ => continue,
+            };
+            let target_type = match args.next() {
+                Some("ALIAS") | Some("IMPORTED") => continue,
+                Some(arg @ ("STATIC" | "SHARED" | "INTERFACE")) if command == "add_library(" => arg,
+                _ => default_type,
+            };
+
+            targets.push((String::from(name), String::from(target_type)));
+        }
+    }
+
+    targets
+}
+
+// The first library, else the first executable, else project(), else the directory name
+fn infer_target_name(
+    project_dir: &Path,
+    cmake_contents: &str,
+    targets: &[(String, String)],
+) -> io::Result<String> {
+    let library = targets
+        .iter()
+        .find(|(_, target_type)| target_type != "EXECUTABLE");
+    if let Some((name, _)) = library.or_else(|| targets.first()) {
+        return Ok(name.clone());
+    }
+
+    let project = cmake_contents.find("project(").and_then(|index| {
+        cmake_contents[index + "project(".len()..]
+            .split(|c: char| c.is_whitespace() || c == ')')
+            .find(|arg| !arg.is_empty())
+    });
+    let dir_name = project_dir.canonicalize().ok().and_then(|dir| {
+        dir.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    });
+
+    project
+        .map(String::from)
+        .or(dir_name)
+        .ok_or_else(|| invalid_input(String::from("no target name found, pass --target")))
+}
+
+fn infer_kind(targets: &[(String, String)]) -> Kind {
+    let has_executable = targets
+        .iter()
+        .any(|(_, target_type)| target_type == "EXECUTABLE");
+    let library = targets
+        .iter()
+        .find(|(_, target_type)| target_type != "EXECUTABLE")
+        .map(|(_, target_type)| target_type.as_str());
+
+    match (library, has_executable) {
+        (Some("INTERFACE"), _) => Kind::HeaderOnly,
+        (Some(_), true) => Kind::LibAndApp,
+        (Some("SHARED"), false) => Kind::Shared,
+        (Some(_), false) => Kind::Static,
+        (None, _) => Kind::Executable,
+    }
+}
+
+// e.g. include/nmspc/tgtnm/ like generated with --name-space nmspc
+fn infer_name_space(include_dir: &Path, target_name: &str) -> Option<String> {
+    let mut name_spaces = fs::read_dir(include_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join(target_name).is_dir())
+        .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()));
+
+    match (name_spaces.next(), name_spaces.next()) {
+        (Some(name_space), None) => Some(name_space),
+        _ => None,
+    }
+}
+
+fn infer_language(files: &[String]) -> Language {
+    let has_ext = |exts: &[&str]| {
+        files.iter().any(|file| {
+            Path::new(file)
+                .extension()
+                .is_some_and(|ext| exts.iter().any(|other| ext == *other))
+        })
+    };
+
+    match (has_ext(&["cpp", "cc", "cxx"]), has_ext(&["c"])) {
+        (true, true) => Language::CppAndC,
+        (false, true) => Language::C,
+        _ => Language::Cpp,
+    }
+}
+
+fn infer_test_framework(cmake_contents: &str) -> Option<TestFramework> {
+    let contents = cmake_contents.to_lowercase();
+
+    TEST_FRAMEWORK_HINTS
+        .iter()
+        .find(|(hint, _)| contents.contains(hint))
+        .map(|(_, framework)| *framework)
+}
+
+fn infer_source_listing(cmake_contents: &str) -> SourceListing {
+    match cmake_contents.contains("file(GLOB") {
+        true if cmake_contents.contains("CONFIGURE_DEPENDS") => SourceListing::GlobConfigureDepends,
+        true => SourceListing::Glob,
+        false => SourceListing::Explicit,
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CMAKE_LISTS_CONTENTS: &str = "project(prj VERSION 1.0)
+add_library(tgtnm SHARED
+    src/tgtnm.cpp
+)
+add_library(nmspc::tgtnm ALIAS tgtnm)
+add_executable(tgtnm-app src/main.cpp)
+";
+
+    #[test]
+    fn test_find_targets() {
+        assert_eq!(
+            find_targets(CMAKE_LISTS_CONTENTS),
+            vec![
+                (String::from("tgtnm"), String::from("SHARED")),
+                (String::from("tgtnm-app"), String::from("EXECUTABLE")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infer_kind() {
+        let targets = find_targets(CMAKE_LISTS_CONTENTS);
+
+        assert_eq!(infer_kind(&targets), Kind::LibAndApp);
+        assert_eq!(infer_kind(&targets[..1]), Kind::Shared);
+        assert_eq!(infer_kind(&targets[1..]), Kind::Executable);
+        assert_eq!(
+            infer_kind(&find_targets("add_library(tgtnm INTERFACE)")),
+            Kind::HeaderOnly
+        );
+    }
+
+    #[test]
+    fn test_infer_target_name() {
+        let targets = find_targets(CMAKE_LISTS_CONTENTS);
+        let dir = Path::new(".");
+
+        assert_eq!(
+            infer_target_name(dir, CMAKE_LISTS_CONTENTS, &targets[1..]).unwrap(),
+            "tgtnm-app"
+        );
+        assert_eq!(
+            infer_target_name(dir, CMAKE_LISTS_CONTENTS, &targets).unwrap(),
+            "tgtnm"
+        );
+        assert_eq!(
+            infer_target_name(dir, CMAKE_LISTS_CONTENTS, &[]).unwrap(),
+            "prj"
+        );
+    }
+
+    #[test]
+    fn test_infer_language() {
+        let files = |names: &[&str]| -> Vec<String> {
+            names.iter().map(|name| String::from(*name)).collect()
+        };
+
+        assert_eq!(infer_language(&files(&["src/a.c"])), Language::C);
+        assert_eq!(infer_language(&files(&["a.c", "b.cc"])), Language::CppAndC);
+        assert_eq!(infer_language(&files(&["a.h"])), Language::Cpp);
+    }
+
+    #[test]
+    fn test_infer_test_framework() {
+        assert_eq!(
+            infer_test_framework("find_package(GTest REQUIRED)"),
+            Some(TestFramework::GTest)
+        );
+        assert_eq!(
+            infer_test_framework("FetchContent_Declare(Catch2"),
+            Some(TestFramework::Catch2)
+        );
+        assert_eq!(infer_test_framework("add_executable(t t.cpp)"), None);
+    }
+}
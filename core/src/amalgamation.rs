@@ -0,0 +1,95 @@
+/*
+For add_custom_command(OUTPUT) /sa https://cmake.org/cmake/help/latest/command/add_custom_command.html
+For CMake scripts /sa https://cmake.org/cmake/help/latest/manual/cmake.1.html#run-a-script
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use std::{io, path::PathBuf};
+
+const AMALGAMATE_SCRIPT: &str = include_str!("../res/amalgamation/Amalgamate.cmake.in");
+const AMALGAMATE_CMAKE: &str = include_str!("../res/amalgamation/amalgamate.snippet.in");
+
+// Only header-only libraries can ship as one header
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    if opt.amalgamate && opt.kind != Kind::HeaderOnly {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "--amalgamate needs kind header-only, not {}",
+                opt.kind.name()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+// Adds cmake/Amalgamate.cmake and a <target>-amalgamate target writing single_include/
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().amalgamate {
+        return gen;
+    }
+
+    gen.add_file(
+        PathBuf::from("cmake/Amalgamate.cmake"),
+        String::from(AMALGAMATE_SCRIPT),
+    )
+    .add_cmake_snippet(String::from(AMALGAMATE_CMAKE.trim_end()))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::PlanEntry;
+    use std::path::Path;
+
+    fn create_test_opt() -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::HeaderOnly,
+            amalgamate: true,
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&create_test_opt()).is_ok());
+        assert!(validate(&Opt {
+            kind: Kind::Static,
+            ..create_test_opt()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = CppProjGen::new(create_test_opt())
+            .add_include_dir(PathBuf::from("include"))
+            .plan()
+            .unwrap();
+        let cmake_lists = plan
+            .iter()
+            .find_map(|entry| match entry {
+                PlanEntry::File { path, contents, .. }
+                    if path == Path::new("test_out_dir/tgtnm/CMakeLists.txt") =>
+                {
+                    Some(contents.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(plan
+            .iter()
+            .any(|entry| entry.path() == Path::new("test_out_dir/tgtnm/cmake/Amalgamate.cmake")));
+        assert!(cmake_lists.contains("add_custom_target(tgtnm-amalgamate\n"));
+        assert!(cmake_lists
+            .contains("        -DENTRY=${CMAKE_CURRENT_LIST_DIR}/include/tgtnm/tgtnm.h\n"));
+        assert!(
+            cmake_lists.contains("    ${CMAKE_CURRENT_BINARY_DIR}/single_include/tgtnm/tgtnm.h\n")
+        );
+    }
+}
@@ -0,0 +1,173 @@
+/*
+For <LANG>_CLANG_TIDY /sa https://cmake.org/cmake/help/latest/prop_tgt/LANG_CLANG_TIDY.html
+For <LANG>_INCLUDE_WHAT_YOU_USE /sa https://cmake.org/cmake/help/latest/prop_tgt/LANG_INCLUDE_WHAT_YOU_USE.html
+For IWYU mapping files /sa https://github.com/include-what-you-use/include-what-you-use/blob/master/docs/IWYUMappings.md
+For cppcheck suppressions /sa https://cppcheck.sourceforge.io/manual.html#suppressions
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind};
+use crate::language::Language;
+use std::path::PathBuf;
+
+const CLANG_TIDY_CMAKE: &str = include_str!("../res/analysis/clang-tidy.snippet.in");
+const CLANG_TIDY_CONFIG: &str = include_str!("../res/analysis/clang-tidy.in");
+const CPPCHECK_SUPPRESSIONS: &str = include_str!("../res/analysis/cppcheck-suppressions.txt.in");
+const IWYU_CMAKE: &str = include_str!("../res/analysis/iwyu.snippet.in");
+const IWYU_MAPPINGS: &str = include_str!("../res/analysis/iwyu.imp.in");
+
+// Enabled clang-tidy check groups and the checks disabled of them, shared with .clangd
+pub const CLANG_TIDY_CHECKS: &[&str] = &[
+    "bugprone-*",
+    "clang-analyzer-*",
+    "cppcoreguidelines-*",
+    "modernize-*",
+    "performance-*",
+    "readability-*",
+];
+pub const CLANG_TIDY_DISABLED: &[&str] = &[
+    "modernize-use-trailing-return-type",
+    "readability-magic-numbers",
+    "cppcoreguidelines-avoid-magic-numbers",
+];
+
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let gen = apply_static_analysis(gen);
+
+    apply_iwyu(gen)
+}
+
+// Adds clang-tidy and cppcheck wiring plus their configuration files
+fn apply_static_analysis(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().static_analysis {
+        return gen;
+    }
+
+    let cmake = CLANG_TIDY_CMAKE.replace(
+        "@CLANG_TIDY_PROPERTIES@",
+        &build_target_properties(
+            gen.opt().language,
+            gen.opt().kind,
+            "CLANG_TIDY",
+            "${CLANG_TIDY_EXE}",
+        ),
+    );
+
+    gen.add_file(
+        PathBuf::from(".clang-tidy"),
+        CLANG_TIDY_CONFIG.replace("@CLANG_TIDY_CHECKS@", &build_clang_tidy_checks()),
+    )
+    .add_file(
+        PathBuf::from("cppcheck-suppressions.txt"),
+        String::from(CPPCHECK_SUPPRESSIONS),
+    )
+    .add_cmake_snippet(cmake)
+}
+
+// e.g. "  bugprone-*,\n  -readability-magic-numbers" of the folded Checks list
+fn build_clang_tidy_checks() -> String {
+    let checks: Vec<String> = CLANG_TIDY_CHECKS
+        .iter()
+        .map(|check| format!("  {}", check))
+        .chain(
+            CLANG_TIDY_DISABLED
+                .iter()
+                .map(|check| format!("  -{}", check)),
+        )
+        .collect();
+
+    checks.join(",\n")
+}
+
+// Adds include-what-you-use wiring guarded by a CMake option and a mapping file stub
+fn apply_iwyu(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().iwyu {
+        return gen;
+    }
+
+    let cmake = IWYU_CMAKE.replace(
+        "@IWYU_PROPERTIES@",
+        &build_target_properties(
+            gen.opt().language,
+            gen.opt().kind,
+            "INCLUDE_WHAT_YOU_USE",
+            "${IWYU_COMMAND}",
+        ),
+    );
+
+    gen.add_file(PathBuf::from("iwyu.imp"), String::from(IWYU_MAPPINGS))
+        .add_cmake_snippet(cmake)
+}
+
+// Targets with own sources, e.g. set_target_properties(t PROPERTIES CXX_CLANG_TIDY ...)
+fn build_target_properties(language: Language, kind: Kind, property: &str, value: &str) -> String {
+    let targets: &[&str] = match kind {
+        Kind::HeaderOnly => &[],
+        Kind::LibAndApp => &["@CMAKE_TARGET_NAME@", "@CMAKE_TARGET_NAME@-app"],
+        _ => &["@CMAKE_TARGET_NAME@"],
+    };
+    let languages: &[&str] = match language {
+        Language::Cpp => &["CXX"],
+        Language::C => &["C"],
+        Language::CppAndC => &["CXX", "C"],
+    };
+
+    let properties: Vec<String> = languages
+        .iter()
+        .map(|lang| format!("{}_{} \"{}\"", lang, property, value))
+        .collect();
+
+    targets
+        .iter()
+        .map(|target| {
+            format!(
+                "    set_target_properties({}\n        PROPERTIES {}\n    )",
+                target,
+                properties.join(" ")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::Opt;
+
+    #[test]
+    fn test_build_target_properties() {
+        assert_eq!(
+            build_target_properties(
+                Language::CppAndC,
+                Kind::Static,
+                "CLANG_TIDY",
+                "${CLANG_TIDY_EXE}"
+            ),
+            "    set_target_properties(@CMAKE_TARGET_NAME@\n        PROPERTIES CXX_CLANG_TIDY \"${CLANG_TIDY_EXE}\" C_CLANG_TIDY \"${CLANG_TIDY_EXE}\"\n    )"
+        );
+        assert_eq!(
+            build_target_properties(Language::Cpp, Kind::LibAndApp, "CLANG_TIDY", "tidy")
+                .matches("set_target_properties")
+                .count(),
+            2
+        );
+        assert!(
+            build_target_properties(Language::Cpp, Kind::HeaderOnly, "CLANG_TIDY", "tidy")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_iwyu_files() {
+        let opt = Opt {
+            iwyu: true,
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        let paths = CppProjGen::new(opt).build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/iwyu.imp")));
+    }
+}
@@ -0,0 +1,277 @@
+/*
+For toml++ /sa https://marzer.github.io/tomlplusplus/
+For yaml-cpp /sa https://github.com/jbeder/yaml-cpp/wiki/Tutorial
+For nlohmann-json /sa https://json.nlohmann.me/
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::dependency::FeatureDependency;
+use crate::error::invalid_input;
+use crate::language::Language;
+use crate::test_framework::TestFramework;
+use std::{io, path::PathBuf, str::FromStr};
+
+const APP_CONFIG_HEADER: &str = include_str!("../res/app_config/app_config.h.in");
+const APP_CONFIG_CMAKE: &str = include_str!("../res/app_config/CMakeLists.snippet.in");
+const APP_CONFIG_TEST_CMAKE: &str = include_str!("../res/app_config/test.snippet.in");
+const TOML_SOURCE: &str = include_str!("../res/app_config/toml.cpp.in");
+const TOML_CONFIG: &str = include_str!("../res/app_config/config.toml.in");
+const YAML_SOURCE: &str = include_str!("../res/app_config/yaml.cpp.in");
+const YAML_CONFIG: &str = include_str!("../res/app_config/config.yaml.in");
+const JSON_SOURCE: &str = include_str!("../res/app_config/json.cpp.in");
+const JSON_CONFIG: &str = include_str!("../res/app_config/config.json.in");
+const TEST_GTEST: &str = include_str!("../res/app_config/test_app_config.gtest.cpp.in");
+const TEST_CATCH2: &str = include_str!("../res/app_config/test_app_config.catch2.cpp.in");
+const TEST_DOCTEST: &str = include_str!("../res/app_config/test_app_config.doctest.cpp.in");
+
+// Format of the application config file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppConfig {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl AppConfig {
+    pub const VARIANTS: &'static [&'static str] = &["toml", "yaml", "json"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AppConfig::Toml => "toml",
+            AppConfig::Yaml => "yaml",
+            AppConfig::Json => "json",
+        }
+    }
+
+    // Package spec, vcpkg port if different and CMake target of the parser library
+    fn package(self) -> (&'static str, Option<&'static str>, &'static str) {
+        match self {
+            AppConfig::Toml => ("tomlplusplus/3.4.0", None, "tomlplusplus::tomlplusplus"),
+            AppConfig::Yaml => ("yaml-cpp/0.8.0", None, "yaml-cpp::yaml-cpp"),
+            AppConfig::Json => (
+                "nlohmann_json/3.11.3",
+                Some("nlohmann-json"),
+                "nlohmann_json::nlohmann_json",
+            ),
+        }
+    }
+
+    // Parser source and sample config
+    fn templates(self) -> (&'static str, &'static str) {
+        match self {
+            AppConfig::Toml => (TOML_SOURCE, TOML_CONFIG),
+            AppConfig::Yaml => (YAML_SOURCE, YAML_CONFIG),
+            AppConfig::Json => (JSON_SOURCE, JSON_CONFIG),
+        }
+    }
+
+    // C++ string literals of a valid and a malformed config for the unit test
+    fn test_texts(self) -> (&'static str, &'static str) {
+        match self {
+            AppConfig::Toml => (r#""name = \"demo\"\nport = 9000\n""#, r#""port = ""#),
+            AppConfig::Yaml => (r#""name: demo\nport: 9000\n""#, r#""port: [""#),
+            AppConfig::Json => (r#"R"({"name": "demo", "port": 9000})""#, r#""{""#),
+        }
+    }
+}
+
+impl FromStr for AppConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "toml" => Ok(AppConfig::Toml),
+            "yaml" => Ok(AppConfig::Yaml),
+            "json" => Ok(AppConfig::Json),
+            _ => Err(format!(
+                "unknown app config '{}', expected one of: {}",
+                s,
+                AppConfig::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let app_config = match opt.app_config {
+        Some(app_config) => app_config,
+        None => return Ok(()),
+    };
+
+    if opt.kind.app_target().is_none() {
+        return Err(invalid_input(format!(
+            "--app-config needs an executable, kind {} has none",
+            opt.kind.name()
+        )));
+    }
+    if opt.language == Language::C {
+        return Err(invalid_input(format!(
+            "--app-config {} needs C++, use --language cpp or cpp-and-c",
+            app_config.name()
+        )));
+    }
+
+    Ok(())
+}
+
+// Parser library of the application, see dependency::apply
+pub fn dependency(opt: &Opt) -> Option<FeatureDependency> {
+    let (spec, vcpkg_port, link_target) = opt.app_config?.package();
+
+    Some(FeatureDependency {
+        spec,
+        vcpkg_port,
+        cmake_package: None,
+        nix_package: None,
+        conda_package: None,
+        link_target,
+        consumer: opt.kind.app_target()?,
+    })
+}
+
+// Adds config/<target>.<format>, the AppConfig class of the application and its unit test
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (app_config, app_target) = match (gen.opt().app_config, gen.opt().kind.app_target()) {
+        (Some(app_config), Some(app_target)) => (app_config, app_target),
+        _ => return gen,
+    };
+    let (source, config) = app_config.templates();
+    let test = match gen.opt().test_framework {
+        Some(TestFramework::GTest) => Some(TEST_GTEST),
+        Some(TestFramework::Catch2) => Some(TEST_CATCH2),
+        Some(TestFramework::Doctest) => Some(TEST_DOCTEST),
+        _ => None,
+    };
+
+    let gen = gen
+        .add_file(
+            PathBuf::from(format!("config/@CMAKE_TARGET_NAME@.{}", app_config.name())),
+            String::from(config),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/app_config.@HEADER_FILE_EXT@"),
+            APP_CONFIG_HEADER.replace("@APP_CONFIG_EXT@", app_config.name()),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/app_config.@SOURCE_FILE_EXT@"),
+            String::from(source),
+        )
+        .add_cmake_snippet(String::from(
+            APP_CONFIG_CMAKE
+                .replace("@APP_CONFIG_TARGET@", app_target)
+                .trim_end(),
+        ));
+
+    match test {
+        Some(test) => {
+            let (package, _, link_target) = app_config.package();
+            let (sample, invalid) = app_config.test_texts();
+            let package_name = package.split('/').next().unwrap_or(package);
+
+            gen.add_file(
+                PathBuf::from("@TEST_DIR@/@TEST_FILE_PREFIX@app_config@TEST_FILE_SUFFIX@"),
+                test.replace("@APP_CONFIG_SAMPLE@", sample)
+                    .replace("@APP_CONFIG_INVALID@", invalid),
+            )
+            .add_cmake_snippet_to(
+                PathBuf::from("@TEST_DIR@/CMakeLists.txt"),
+                String::from(
+                    APP_CONFIG_TEST_CMAKE
+                        .replace("@APP_CONFIG_PACKAGE@", package_name)
+                        .replace("@APP_CONFIG_LINK_TARGET@", link_target)
+                        .trim_end(),
+                ),
+            )
+        }
+        None => gen,
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Kind, PlanEntry};
+    use crate::dependency::PackageManager;
+    use crate::test_util::contents;
+
+    fn create_test_plan(opt: Opt) -> Vec<PlanEntry> {
+        CppProjGen::new(Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::LibAndApp,
+            ..opt
+        })
+        .add_include_dir(PathBuf::from("include"))
+        .add_source_dir(PathBuf::from("source"))
+        .add_test_dir(PathBuf::from("test"))
+        .plan()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            kind: Kind::Executable,
+            app_config: Some(AppConfig::Toml),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+
+        assert!(validate(&Opt {
+            kind: Kind::HeaderOnly,
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            language: Language::C,
+            ..opt
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = create_test_plan(Opt {
+            app_config: Some(AppConfig::Json),
+            test_framework: Some(TestFramework::GTest),
+            package_manager: Some(PackageManager::Vcpkg),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "config/tgtnm.json")
+            .unwrap()
+            .contains("\"name\": \"tgtnm\""));
+        assert!(contents(&plan, "source/app_config.h")
+            .unwrap()
+            .contains("config/tgtnm.json"));
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("target_sources(tgtnm-app\n"));
+        assert!(contents(&plan, "cmake/dependencies.cmake")
+            .unwrap()
+            .contains("target_link_libraries(tgtnm-app PRIVATE nlohmann_json::nlohmann_json)\n"));
+        assert!(contents(&plan, "vcpkg.json")
+            .unwrap()
+            .contains("\"nlohmann-json\""));
+
+        let test = contents(&plan, "test/test_app_config.cpp").unwrap();
+        assert!(test.contains(r#"AppConfig::parse(R"({"name": "demo", "port": 9000})")"#));
+        assert!(contents(&plan, "test/CMakeLists.txt")
+            .unwrap()
+            .contains("find_package(nlohmann_json CONFIG REQUIRED)\n"));
+    }
+
+    #[test]
+    fn test_apply_without_test_framework() {
+        let plan = create_test_plan(Opt {
+            app_config: Some(AppConfig::Yaml),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "source/app_config.cpp")
+            .unwrap()
+            .contains("#include <yaml-cpp/yaml.h>"));
+        assert!(contents(&plan, "test/test_app_config.cpp").is_none());
+    }
+}
@@ -0,0 +1,296 @@
+/*
+For the zip format /sa https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+For CRC-32 /sa https://en.wikipedia.org/wiki/Cyclic_redundancy_check
+For the ustar format /sa https://pubs.opengroup.org/onlinepubs/9699919799/utilities/pax.html#tag_20_92_13_06
+*/
+
+use std::convert::TryFrom;
+use std::io;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR_SIGNATURE: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+// Made on Unix, so the external attributes carry the file mode
+const VERSION_MADE_BY: u16 = (3 << 8) | VERSION;
+// UTF-8 file names
+const FLAGS: u16 = 1 << 11;
+// 1980-01-01 00:00, so equal trees give equal archives
+const DOS_TIME: u16 = 0;
+const DOS_DATE: u16 = (1 << 5) | 1;
+const TAR_BLOCK_SIZE: usize = 512;
+
+// Stored (uncompressed) zip archive, entries keep the order they are added in
+#[derive(Debug, Default)]
+pub struct ZipWriter {
+    data: Vec<u8>,
+    central_dir: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    // e.g. "my-target/include/", needed for empty directories
+    pub fn add_dir(&mut self, name: &str) -> io::Result<()> {
+        self.add(&format!("{}/", name.trim_end_matches('/')), &[], 0o040_755)
+    }
+
+    pub fn add_file(&mut self, name: &str, contents: &[u8], executable: bool) -> io::Result<()> {
+        let mode = if executable { 0o100_755 } else { 0o100_644 };
+        self.add(name, contents, mode)
+    }
+
+    // Unpacked as a symlink on Unix, the target is stored as the contents
+    pub fn add_link(&mut self, name: &str, target: &str) -> io::Result<()> {
+        self.add(name, target.as_bytes(), 0o120_777)
+    }
+
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        let central_dir_offset = to_u32(self.data.len())?;
+        let central_dir_size = to_u32(self.central_dir.len())?;
+        self.data.extend_from_slice(&self.central_dir);
+
+        put_u32(&mut self.data, END_OF_CENTRAL_DIR_SIGNATURE);
+        put_u16(&mut self.data, 0);
+        put_u16(&mut self.data, 0);
+        put_u16(&mut self.data, self.entries);
+        put_u16(&mut self.data, self.entries);
+        put_u32(&mut self.data, central_dir_size);
+        put_u32(&mut self.data, central_dir_offset);
+        put_u16(&mut self.data, 0);
+
+        Ok(self.data)
+    }
+
+    fn add(&mut self, name: &str, contents: &[u8], mode: u32) -> io::Result<()> {
+        let offset = to_u32(self.data.len())?;
+        let crc = crc32(contents);
+        let size = to_u32(contents.len())?;
+        let name_len = u16::try_from(name.len()).map_err(too_large)?;
+        self.entries = self.entries.checked_add(1).ok_or_else(|| too_large(()))?;
+
+        put_u32(&mut self.data, LOCAL_HEADER_SIGNATURE);
+        put_common_header(&mut self.data, crc, size, name_len);
+        put_u16(&mut self.data, 0);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(contents);
+
+        put_u32(&mut self.central_dir, CENTRAL_HEADER_SIGNATURE);
+        put_u16(&mut self.central_dir, VERSION_MADE_BY);
+        put_common_header(&mut self.central_dir, crc, size, name_len);
+        // Extra field, comment, disk and internal attributes
+        for _ in 0..4 {
+            put_u16(&mut self.central_dir, 0);
+        }
+        put_u32(&mut self.central_dir, mode << 16);
+        put_u32(&mut self.central_dir, offset);
+        self.central_dir.extend_from_slice(name.as_bytes());
+
+        Ok(())
+    }
+}
+
+// ustar archive owned by root with one mtime, so equal trees give equal archives
+#[derive(Debug, Default)]
+pub struct TarWriter {
+    data: Vec<u8>,
+    mtime: u64,
+}
+
+impl TarWriter {
+    pub fn new(mtime: u64) -> Self {
+        TarWriter {
+            data: Vec::new(),
+            mtime,
+        }
+    }
+
+    pub fn add_dir(&mut self, name: &str) -> io::Result<()> {
+        self.add(
+            &format!("{}/", name.trim_end_matches('/')),
+            &[],
+            0o755,
+            b'5',
+        )
+    }
+
+    pub fn add_file(&mut self, name: &str, contents: &[u8], executable: bool) -> io::Result<()> {
+        let mode = if executable { 0o755 } else { 0o644 };
+        self.add(name, contents, mode, b'0')
+    }
+
+    // Two zero blocks end the archive
+    pub fn finish(mut self) -> Vec<u8> {
+        self.data.resize(self.data.len() + 2 * TAR_BLOCK_SIZE, 0);
+        self.data
+    }
+
+    fn add(&mut self, name: &str, contents: &[u8], mode: u32, type_flag: u8) -> io::Result<()> {
+        let (prefix, name) = split_tar_name(name)?;
+        let mut header = [0u8; TAR_BLOCK_SIZE];
+
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        put_octal(&mut header[100..108], u64::from(mode))?;
+        put_octal(&mut header[108..116], 0)?;
+        put_octal(&mut header[116..124], 0)?;
+        put_octal(&mut header[124..136], contents.len() as u64)?;
+        put_octal(&mut header[136..148], self.mtime)?;
+        header[156] = type_flag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[265..269].copy_from_slice(b"root");
+        header[297..301].copy_from_slice(b"root");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+        // Summed with the checksum field as spaces
+        header[148..156].copy_from_slice(b"        ");
+        let checksum: u64 = header.iter().map(|byte| u64::from(*byte)).sum();
+        put_octal(&mut header[148..155], checksum)?;
+        header[155] = b' ';
+
+        self.data.extend_from_slice(&header);
+        self.data.extend_from_slice(contents);
+        let padding = (TAR_BLOCK_SIZE - contents.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+        self.data.resize(self.data.len() + padding, 0);
+
+        Ok(())
+    }
+}
+
+// Names over 100 bytes are split at a / into a prefix of up to 155 bytes
+fn split_tar_name(name: &str) -> io::Result<(&str, &str)> {
+    if name.len() <= 100 {
+        return Ok(("", name));
+    }
+
+    name.char_indices()
+        .filter(|(index, c)| *c == '/' && *index <= 155 && name.len() - index - 1 <= 100)
+        .map(|(index, _)| (&name[..index], &name[index + 1..]))
+        .find(|(_, rest)| !rest.is_empty())
+        .ok_or_else(|| io::Error::other(format!("'{}' is too long for a ustar archive", name)))
+}
+
+// Zero-padded octal number ending with a NUL, filling the field
+fn put_octal(field: &mut [u8], value: u64) -> io::Result<()> {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    if digits.len() >= field.len() {
+        return Err(io::Error::other(format!(
+            "{} exceeds the ustar limits",
+            value
+        )));
+    }
+
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+
+    Ok(())
+}
+
+// Version needed up to the name length, equal in local and central headers
+fn put_common_header(data: &mut Vec<u8>, crc: u32, size: u32, name_len: u16) {
+    put_u16(data, VERSION);
+    put_u16(data, FLAGS);
+    put_u16(data, 0);
+    put_u16(data, DOS_TIME);
+    put_u16(data, DOS_DATE);
+    put_u32(data, crc);
+    put_u32(data, size);
+    put_u32(data, size);
+    put_u16(data, name_len);
+}
+
+fn put_u16(data: &mut Vec<u8>, value: u16) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(too_large)
+}
+
+fn too_large<E>(_: E) -> io::Error {
+    io::Error::other("the archive exceeds the zip limits, zip64 is not supported")
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_tar_writer() {
+        let mut tar = TarWriter::new(1_700_000_000);
+        tar.add_dir("tgtnm-0.1.0").unwrap();
+        tar.add_file("tgtnm-0.1.0/build.sh", b"abc", true).unwrap();
+        let data = tar.finish();
+
+        // Two headers, one padded content block and two end blocks
+        assert_eq!(data.len(), 5 * TAR_BLOCK_SIZE);
+        assert_eq!(&data[..12], b"tgtnm-0.1.0/");
+        assert_eq!(data[156], b'5');
+        let file_header = &data[TAR_BLOCK_SIZE..2 * TAR_BLOCK_SIZE];
+        assert_eq!(&file_header[100..108], b"0000755\0");
+        assert_eq!(&file_header[124..136], b"00000000003\0");
+        assert_eq!(&file_header[257..263], b"ustar\0");
+        let checksum: u64 = file_header[..148]
+            .iter()
+            .chain(b"        ")
+            .chain(&file_header[156..])
+            .map(|byte| u64::from(*byte))
+            .sum();
+        assert_eq!(
+            &file_header[148..156],
+            format!("{:06o}\0 ", checksum).as_bytes()
+        );
+        assert_eq!(&data[2 * TAR_BLOCK_SIZE..2 * TAR_BLOCK_SIZE + 3], b"abc");
+    }
+
+    #[test]
+    fn test_split_tar_name() {
+        assert_eq!(split_tar_name("a/b").unwrap(), ("", "a/b"));
+        let long = format!("{}/{}", "d".repeat(120), "f".repeat(90));
+        assert_eq!(split_tar_name(&long).unwrap(), (&long[..120], &long[121..]));
+        assert!(split_tar_name(&"f".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn test_zip_writer() {
+        let mut zip = ZipWriter::default();
+        zip.add_dir("tgtnm").unwrap();
+        zip.add_file("tgtnm/a.txt", b"abc", false).unwrap();
+        zip.add_file("tgtnm/build.sh", b"#!/bin/sh", true).unwrap();
+        let data = zip.finish().unwrap();
+
+        assert_eq!(&data[..4], &LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        // End of central directory record with 2 entries
+        let end = &data[data.len() - 22..];
+        assert_eq!(&end[..4], &END_OF_CENTRAL_DIR_SIGNATURE.to_le_bytes());
+        assert_eq!(&end[10..12], &3u16.to_le_bytes());
+        assert!(data.windows(3).any(|window| window == b"abc"));
+        assert!(data
+            .windows(4)
+            .any(|window| window == (0o100_755u32 << 16).to_le_bytes()));
+    }
+}
@@ -0,0 +1,351 @@
+/*
+For serde /sa https://serde.rs/derive.html
+For serde_yaml /sa https://docs.rs/serde_yaml/0.9/serde_yaml/
+*/
+
+use crate::builtin_template::BuiltinTemplate;
+use crate::compiler::Compiler;
+use crate::cpp_proj_gen::{Kind, Opt};
+use crate::dependency::PackageManager;
+use crate::error::{self, Error};
+use crate::language::{Language, CXX_STANDARDS, C_STANDARDS};
+use crate::packaging::Packaging;
+use crate::platforms::Platform;
+use crate::source_listing::SourceListing;
+use crate::template_pack::parse_define;
+use crate::test_framework::TestFramework;
+use serde::Deserialize;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+// Batch spec file, e.g.
+//
+// projects:
+//   - name: my-lib
+//     namespace: my_company
+//     kind: shared
+//     cmake_version: 3.20.0
+//     output_dir: libs
+//     options:
+//       language: c
+//       test-framework: catch2
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchSpec {
+    projects: Vec<ProjectSpec>,
+}
+
+// Project spec, unset options fall back to the command line ones
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectSpec {
+    name: String,
+    #[serde(rename = "namespace")]
+    name_space: Option<String>,
+    kind: Option<String>,
+    cmake_version: Option<String>,
+    output_dir: Option<PathBuf>,
+    #[serde(default)]
+    options: ProjectOptions,
+}
+
+// Options of one project named like on the command line, lists replace the command line ones
+// Defines add to them, e.g. define: [VENDOR=acme]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ProjectOptions {
+    template: Vec<PathBuf>,
+    builtin_template: Option<String>,
+    source_listing: Option<String>,
+    language: Option<String>,
+    cxx_standard: Option<String>,
+    c_standard: Option<String>,
+    test_framework: Option<String>,
+    compiler: Option<String>,
+    package_manager: Option<String>,
+    author: Option<String>,
+    author_email: Option<String>,
+    license: Option<String>,
+    homepage: Option<String>,
+    project_version: Option<String>,
+    platforms: Vec<String>,
+    packaging: Vec<String>,
+    define: Vec<String>,
+    community_files: Option<bool>,
+    examples: Option<bool>,
+    scripts: Option<bool>,
+    static_analysis: Option<bool>,
+    git_init: Option<bool>,
+}
+
+impl ProjectSpec {
+    fn into_opt(self, base: &Opt) -> io::Result<Opt> {
+        let kind = match self.kind {
+            Some(kind) => kind.parse::<Kind>().map_err(invalid_data)?,
+            None => base.kind,
+        };
+
+        let opt = Opt {
+            target_name: self.name,
+            name_space: self.name_space.or_else(|| base.name_space.clone()),
+            cmake_version: self.cmake_version.or_else(|| base.cmake_version.clone()),
+            output_dir: self.output_dir.or_else(|| base.output_dir.clone()),
+            kind,
+            ..base.clone()
+        };
+
+        self.options.merge_over(opt)
+    }
+}
+
+impl ProjectOptions {
+    fn merge_over(self, opt: Opt) -> io::Result<Opt> {
+        let standard = |value: Option<String>, standards: &[&str], name: &str| match value {
+            Some(value) if !standards.contains(&value.as_str()) => Err(invalid_data(format!(
+                "unknown {} '{}', expected one of: {}",
+                name,
+                value,
+                standards.join(", ")
+            ))),
+            value => Ok(value),
+        };
+        let mut defines = opt.defines.clone();
+        for define in &self.define {
+            defines.push(parse_define(define).map_err(invalid_data)?);
+        }
+
+        Ok(Opt {
+            template_dirs: replace(self.template, opt.template_dirs),
+            builtin_template: parse::<BuiltinTemplate>(self.builtin_template)?
+                .unwrap_or(opt.builtin_template),
+            source_listing: parse::<SourceListing>(self.source_listing)?
+                .unwrap_or(opt.source_listing),
+            language: parse::<Language>(self.language)?.unwrap_or(opt.language),
+            cxx_standard: standard(self.cxx_standard, CXX_STANDARDS, "C++ standard")?
+                .unwrap_or(opt.cxx_standard),
+            c_standard: standard(self.c_standard, C_STANDARDS, "C standard")?
+                .unwrap_or(opt.c_standard),
+            test_framework: parse::<TestFramework>(self.test_framework)?.or(opt.test_framework),
+            compiler: parse::<Compiler>(self.compiler)?.or(opt.compiler),
+            package_manager: parse::<PackageManager>(self.package_manager)?.or(opt.package_manager),
+            author: self.author.or(opt.author),
+            author_email: self.author_email.or(opt.author_email),
+            license: self.license.or(opt.license),
+            homepage: self.homepage.or(opt.homepage),
+            project_version: self.project_version.unwrap_or(opt.project_version),
+            platforms: replace(parse_all::<Platform>(self.platforms)?, opt.platforms),
+            packaging: replace(parse_all::<Packaging>(self.packaging)?, opt.packaging),
+            defines,
+            community_files: self.community_files.unwrap_or(opt.community_files),
+            examples: self.examples.unwrap_or(opt.examples),
+            scripts: self.scripts.unwrap_or(opt.scripts),
+            static_analysis: self.static_analysis.unwrap_or(opt.static_analysis),
+            git_init: self.git_init.unwrap_or(opt.git_init),
+            ..opt
+        })
+    }
+}
+
+fn parse<T: FromStr<Err = String>>(value: Option<String>) -> io::Result<Option<T>> {
+    value
+        .map(|value| value.parse::<T>())
+        .transpose()
+        .map_err(invalid_data)
+}
+
+fn parse_all<T: FromStr<Err = String>>(values: Vec<String>) -> io::Result<Vec<T>> {
+    values
+        .iter()
+        .map(|value| value.parse::<T>().map_err(invalid_data))
+        .collect()
+}
+
+// An empty list keeps the command line one
+fn replace<T>(values: Vec<T>, base: Vec<T>) -> Vec<T> {
+    match values.is_empty() {
+        true => base,
+        false => values,
+    }
+}
+
+// Outcome of one project of the spec file
+#[derive(Debug)]
+pub struct BatchResult {
+    pub name: String,
+    pub result: error::Result<()>,
+}
+
+pub fn load_specs(spec_file: &Path, base: &Opt) -> error::Result<Vec<Opt>> {
+    let contents = fs::read_to_string(spec_file).map_err(Error::fs("read", spec_file))?;
+
+    Ok(parse_specs(&contents, base)?)
+}
+
+// Generates every project, a failed one does not stop the others
+pub fn gen_all<F>(spec_file: &Path, base: &Opt, gen: F) -> error::Result<Vec<BatchResult>>
+where
+    F: Fn(Opt) -> error::Result<()>,
+{
+    let opts = load_specs(spec_file, base)?;
+
+    Ok(opts
+        .into_iter()
+        .map(|opt| {
+            let name = opt.target_name.clone();
+            BatchResult {
+                name,
+                result: gen(opt),
+            }
+        })
+        .collect())
+}
+
+// Fails with the number of failed projects
+pub fn check_results(results: &[BatchResult]) -> error::Result<()> {
+    let failed = results
+        .iter()
+        .filter(|result| result.result.is_err())
+        .count();
+
+    match failed {
+        0 => Ok(()),
+        _ => Err(Error::from(io::Error::other(format!(
+            "{} of {} projects failed",
+            failed,
+            results.len()
+        )))),
+    }
+}
+
+fn parse_specs(contents: &str, base: &Opt) -> io::Result<Vec<Opt>> {
+    let spec: BatchSpec = serde_yaml::from_str(contents).map_err(invalid_data)?;
+
+    spec.projects
+        .into_iter()
+        .map(|project| project.into_opt(base))
+        .collect()
+}
+
+fn invalid_data<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+projects:
+  - name: first
+  - name: second
+    namespace: acme
+    kind: shared
+    cmake_version: 3.20.0
+    output_dir: libs
+"#;
+
+    fn create_base_opt() -> Opt {
+        Opt {
+            name_space: Some(String::from("nmspc")),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_specs() {
+        let opts = parse_specs(SPEC, &create_base_opt()).unwrap();
+        assert_eq!(opts.len(), 2);
+
+        assert_eq!(opts[0].target_name, "first");
+        assert_eq!(opts[0].name_space.as_deref(), Some("nmspc"));
+        assert_eq!(opts[0].kind, Kind::Static);
+        assert_eq!(opts[0].output_dir, Some(PathBuf::from("test_out_dir")));
+
+        assert_eq!(opts[1].target_name, "second");
+        assert_eq!(opts[1].name_space.as_deref(), Some("acme"));
+        assert_eq!(opts[1].kind, Kind::Shared);
+        assert_eq!(opts[1].cmake_version.as_deref(), Some("3.20.0"));
+        assert_eq!(opts[1].output_dir, Some(PathBuf::from("libs")));
+    }
+
+    #[test]
+    fn test_project_options() {
+        let spec = r#"
+projects:
+  - name: first
+    options:
+      language: c
+      c-standard: "17"
+      test-framework: catch2
+      author: Jane Doe
+      platforms: [linux, macos]
+      define: [VENDOR=acme]
+      community-files: true
+  - name: second
+"#;
+        let base = Opt {
+            author: Some(String::from("John Doe")),
+            defines: vec![(String::from("LEVEL"), String::from("high"))],
+            ..create_base_opt()
+        };
+        let opts = parse_specs(spec, &base).unwrap();
+
+        assert_eq!(opts[0].language, Language::C);
+        assert_eq!(opts[0].c_standard, "17");
+        assert_eq!(opts[0].test_framework, Some(TestFramework::Catch2));
+        assert_eq!(opts[0].author.as_deref(), Some("Jane Doe"));
+        assert_eq!(opts[0].platforms, vec![Platform::Linux, Platform::Macos]);
+        assert_eq!(opts[0].defines.len(), 2);
+        assert!(opts[0].community_files);
+        assert_eq!(opts[0].name_space.as_deref(), Some("nmspc"));
+
+        assert_eq!(opts[1].language, Language::Cpp);
+        assert_eq!(opts[1].author.as_deref(), Some("John Doe"));
+        assert_eq!(opts[1].defines, base.defines);
+        assert!(!opts[1].community_files);
+    }
+
+    #[test]
+    fn test_project_options_invalid() {
+        for options in [
+            "unknown-option: true",
+            "language: fortran",
+            "cxx-standard: \"98\"",
+            "define: [VENDOR]",
+        ] {
+            let spec = format!(
+                "projects:\n  - name: first\n    options:\n      {}\n",
+                options
+            );
+            let err = parse_specs(&spec, &create_base_opt()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", options);
+        }
+    }
+
+    #[test]
+    fn test_parse_specs_invalid_kind() {
+        let spec = "projects:\n  - name: first\n    kind: plugin\n";
+        let err = parse_specs(spec, &create_base_opt()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_check_results() {
+        let result = |name: &str, failed: bool| BatchResult {
+            name: String::from(name),
+            result: match failed {
+                true => Err(Error::from(io::Error::other("failed"))),
+                false => Ok(()),
+            },
+        };
+
+        assert!(check_results(&[result("first", false)]).is_ok());
+        let err = check_results(&[result("first", false), result("second", true)]).unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 projects failed");
+    }
+}
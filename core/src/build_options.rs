@@ -0,0 +1,130 @@
+/*
+For PROJECT_IS_TOP_LEVEL /sa https://cmake.org/cmake/help/latest/variable/PROJECT_IS_TOP_LEVEL.html
+For option /sa https://cmake.org/cmake/help/latest/command/option.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::language;
+use std::path::PathBuf;
+
+const TOP_LEVEL_CMAKE: &str = include_str!("../res/build_options.snippet.in");
+const EXAMPLES_CMAKE: &str = include_str!("../res/examples/CMakeLists.txt.in");
+
+// Option name suffix and description of the parts a consumer of the project does not need
+pub fn build_toggles(opt: &Opt) -> Vec<(&'static str, &'static str)> {
+    let mut toggles = Vec::new();
+
+    if opt.test_framework.is_some() {
+        toggles.push(("TESTS", "Build the tests"));
+    }
+    if has_examples(opt) {
+        toggles.push(("EXAMPLES", "Build the examples"));
+    }
+    if opt.docs.is_some() {
+        toggles.push(("DOCS", "Build the documentation"));
+    }
+
+    toggles
+}
+
+// Examples use the library, applications have none
+fn has_examples(opt: &Opt) -> bool {
+    opt.examples && opt.kind != Kind::Executable
+}
+
+// Declares the <PREFIX>_BUILD_* options, the features guard their parts with them
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let toggles = build_toggles(gen.opt());
+    if toggles.is_empty() {
+        return gen;
+    }
+
+    let options: Vec<String> = toggles
+        .iter()
+        .map(|(suffix, description)| {
+            format!(
+                "option(@CMAKE_VAR_PREFIX@_BUILD_{} \"{}\" ${{PROJECT_IS_TOP_LEVEL}})",
+                suffix, description
+            )
+        })
+        .collect();
+    let gen = gen.add_cmake_snippet(format!("{}\n{}", TOP_LEVEL_CMAKE, options.join("\n")));
+
+    match has_examples(gen.opt()) {
+        true => apply_examples(gen),
+        false => gen,
+    }
+}
+
+// Adds examples/ with the sample program linking the library
+fn apply_examples(gen: CppProjGen) -> CppProjGen {
+    let example = String::from(language::sample_main(gen.opt().language));
+
+    gen.add_file(
+        PathBuf::from("examples/CMakeLists.txt"),
+        String::from(EXAMPLES_CMAKE),
+    )
+    .add_file(PathBuf::from("examples/example.@SOURCE_FILE_EXT@"), example)
+    .add_cmake_snippet(String::from(
+        "if(@CMAKE_VAR_PREFIX@_BUILD_EXAMPLES)\n    add_subdirectory(examples)\nendif()",
+    ))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::PlanEntry;
+    use crate::docs::DocsBackend;
+    use crate::test_framework::TestFramework;
+    use std::path::Path;
+
+    fn cmake_lists(opt: Opt) -> String {
+        let plan = CppProjGen::new(opt).plan().unwrap();
+
+        plan.iter()
+            .find_map(|entry| match entry {
+                PlanEntry::File { path, contents, .. }
+                    if path == Path::new("test_out_dir/tgtnm/CMakeLists.txt") =>
+                {
+                    Some(contents.clone())
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            test_framework: Some(TestFramework::GTest),
+            examples: true,
+            docs: Some(DocsBackend::Doxygen),
+            ..Opt::default()
+        };
+        let contents = cmake_lists(opt);
+
+        assert!(contents
+            .contains("option(TGTNM_BUILD_TESTS \"Build the tests\" ${PROJECT_IS_TOP_LEVEL})"));
+        for option in &["TESTS", "EXAMPLES", "DOCS"] {
+            assert!(contents.contains(&format!("option(TGTNM_BUILD_{} ", option)));
+            assert!(contents.contains(&format!("if(TGTNM_BUILD_{})\n", option)));
+        }
+        assert!(contents.contains("    add_subdirectory(examples)\n"));
+    }
+
+    #[test]
+    fn test_apply_without_toggles() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::Executable,
+            examples: true,
+            ..Opt::default()
+        };
+
+        assert!(!cmake_lists(opt).contains("PROJECT_IS_TOP_LEVEL"));
+    }
+}
@@ -0,0 +1,138 @@
+/*
+For include_str! /sa https://doc.rust-lang.org/std/macro.include_str.html
+*/
+
+use crate::cpp_proj_gen::{Kind, Opt};
+use crate::test_framework::TestFramework;
+use std::str::FromStr;
+
+const STANDARD_CMLT: &str = include_str!("../res/CMakeLists.txt.in");
+const STANDARD_CMLT_EXECUTABLE: &str = include_str!("../res/CMakeLists.executable.txt.in");
+const STANDARD_CMLT_LIB_AND_APP: &str = include_str!("../res/CMakeLists.lib-and-app.txt.in");
+const STANDARD_CMLT_HEADER_ONLY: &str = include_str!("../res/CMakeLists.header-only.txt.in");
+const MINIMAL_CMLT: &str = include_str!("../res/templates/minimal/CMakeLists.txt.in");
+const MINIMAL_CMLT_EXECUTABLE: &str =
+    include_str!("../res/templates/minimal/CMakeLists.executable.txt.in");
+const MINIMAL_CMLT_LIB_AND_APP: &str =
+    include_str!("../res/templates/minimal/CMakeLists.lib-and-app.txt.in");
+const MINIMAL_CMLT_HEADER_ONLY: &str =
+    include_str!("../res/templates/minimal/CMakeLists.header-only.txt.in");
+
+// Built-in template pack
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuiltinTemplate {
+    Standard,
+    Minimal,
+    Full,
+}
+
+impl BuiltinTemplate {
+    pub const VARIANTS: &'static [&'static str] = &["standard", "minimal", "full"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BuiltinTemplate::Standard => "standard",
+            BuiltinTemplate::Minimal => "minimal",
+            BuiltinTemplate::Full => "full",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            BuiltinTemplate::Standard => "Versioned project with install rules and package config",
+            BuiltinTemplate::Minimal => {
+                "Targets only, without version, install rules or package config"
+            }
+            BuiltinTemplate::Full => {
+                "Standard plus Catch2 tests and static analysis unless chosen otherwise"
+            }
+        }
+    }
+
+    // Full shares the standard templates
+    pub fn cmake_lists_template(self, kind: Kind) -> &'static str {
+        match (self, kind) {
+            (BuiltinTemplate::Minimal, Kind::Static | Kind::Shared) => MINIMAL_CMLT,
+            (BuiltinTemplate::Minimal, Kind::HeaderOnly) => MINIMAL_CMLT_HEADER_ONLY,
+            (BuiltinTemplate::Minimal, Kind::Executable) => MINIMAL_CMLT_EXECUTABLE,
+            (BuiltinTemplate::Minimal, Kind::LibAndApp) => MINIMAL_CMLT_LIB_AND_APP,
+            (_, Kind::Static | Kind::Shared) => STANDARD_CMLT,
+            (_, Kind::HeaderOnly) => STANDARD_CMLT_HEADER_ONLY,
+            (_, Kind::Executable) => STANDARD_CMLT_EXECUTABLE,
+            (_, Kind::LibAndApp) => STANDARD_CMLT_LIB_AND_APP,
+        }
+    }
+
+    pub fn has_install_rules(self) -> bool {
+        self != BuiltinTemplate::Minimal
+    }
+}
+
+impl FromStr for BuiltinTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(BuiltinTemplate::Standard),
+            "minimal" => Ok(BuiltinTemplate::Minimal),
+            "full" => Ok(BuiltinTemplate::Full),
+            _ => Err(format!(
+                "unknown builtin template '{}', expected one of: {}",
+                s,
+                BuiltinTemplate::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Turns on the features of the full template the user did not choose otherwise
+pub fn resolve(opt: &mut Opt) {
+    if opt.builtin_template != BuiltinTemplate::Full {
+        return;
+    }
+
+    opt.static_analysis = true;
+    if opt.test_framework.is_none() {
+        opt.test_framework = Some(TestFramework::Catch2);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for variant in BuiltinTemplate::VARIANTS {
+            assert_eq!(variant.parse::<BuiltinTemplate>().unwrap().name(), *variant);
+        }
+        assert!("huge".parse::<BuiltinTemplate>().is_err());
+    }
+
+    #[test]
+    fn test_cmake_lists_template() {
+        assert!(BuiltinTemplate::Standard
+            .cmake_lists_template(Kind::Static)
+            .contains("VERSION @PROJECT_VERSION@"));
+        assert!(!BuiltinTemplate::Minimal
+            .cmake_lists_template(Kind::Static)
+            .contains("VERSION @PROJECT_VERSION@"));
+        assert!(BuiltinTemplate::Minimal
+            .cmake_lists_template(Kind::LibAndApp)
+            .contains("add_executable(@CMAKE_TARGET_NAME@-app"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut opt = Opt {
+            builtin_template: BuiltinTemplate::Full,
+            test_framework: Some(TestFramework::GTest),
+            ..Opt::default()
+        };
+        resolve(&mut opt);
+
+        assert!(opt.static_analysis);
+        assert_eq!(opt.test_framework, Some(TestFramework::GTest));
+    }
+}
@@ -0,0 +1,74 @@
+/*
+For AtomicBool /sa https://doc.rust-lang.org/std/sync/atomic/struct.AtomicBool.html
+*/
+
+use crate::error::{self, Error};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+// Aborts a generation from elsewhere, e.g. a GUI's cancel button or --timeout
+// Checked between rendered files, written paths and post-hooks
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    // From a background thread, a finished generation does not notice
+    pub fn cancel_after(&self, timeout: Duration) {
+        let token = self.clone();
+        thread::spawn(move || {
+            thread::sleep(timeout);
+            token.cancel();
+        });
+    }
+
+    pub fn check(&self) -> error::Result<()> {
+        match self.is_cancelled() {
+            true => Err(Error::Cancelled),
+            false => Ok(()),
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel() {
+        let token = CancelToken::default();
+        let clone = token.clone();
+        assert!(token.check().is_ok());
+
+        clone.cancel();
+        assert!(matches!(token.check(), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn test_cancel_after() {
+        let token = CancelToken::default();
+        token.cancel_after(Duration::from_millis(1));
+
+        for _ in 0..1000 {
+            if token.is_cancelled() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        panic!("not cancelled after the timeout");
+    }
+}
@@ -0,0 +1,233 @@
+/*
+For PartialOrd derive /sa https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html#derivable
+For CMake release notes /sa https://cmake.org/cmake/help/latest/release/index.html
+*/
+
+use crate::cpp_proj_gen::{Kind, Opt};
+use crate::extra_languages::ExtraLanguage;
+use crate::presets::Preset;
+use std::{fmt, io, str::FromStr};
+
+// Lowest version ever generated
+const BASE_VERSION: Version = Version::new(3, 15, 0);
+
+// CMake version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    // e.g. 3.15 or 3.15.0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('.').collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(format!("invalid CMake version '{}', expected e.g. 3.15.0", s));
+        }
+
+        let parse = |part: &str| {
+            part.parse::<u32>()
+                .map_err(|_| format!("invalid CMake version '{}', expected e.g. 3.15.0", s))
+        };
+
+        Ok(Version::new(
+            parse(parts[0])?,
+            parse(parts[1])?,
+            match parts.get(2) {
+                Some(patch) => parse(patch)?,
+                None => 0,
+            },
+        ))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// A feature requiring a minimum CMake version
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub feature: &'static str,
+    pub version: Version,
+}
+
+pub fn requirements(opt: &Opt) -> Vec<Requirement> {
+    let mut requirements = vec![Requirement {
+        feature: "project generation",
+        version: BASE_VERSION,
+    }];
+
+    if opt.test_framework.is_some() || opt.preset == Some(Preset::CliApp) {
+        requirements.push(Requirement {
+            feature: "FetchContent_MakeAvailable",
+            version: Version::new(3, 14, 0),
+        });
+    }
+
+    if opt.language.has_cpp() && opt.cxx_standard == "20" {
+        requirements.push(Requirement {
+            feature: "cxx_std_20",
+            version: Version::new(3, 12, 0),
+        });
+    }
+
+    if opt.language.has_cpp() && opt.cxx_standard == "23" {
+        requirements.push(Requirement {
+            feature: "cxx_std_23",
+            version: Version::new(3, 20, 0),
+        });
+    }
+
+    if opt.language.has_c() && (opt.c_standard == "17" || opt.c_standard == "23") {
+        requirements.push(Requirement {
+            feature: "c_std_17 and c_std_23",
+            version: Version::new(3, 21, 0),
+        });
+    }
+
+    if opt.compiler.is_some() || opt.preset == Some(Preset::Sycl) {
+        requirements.push(Requirement {
+            feature: "CMake presets",
+            version: Version::new(3, 21, 0),
+        });
+    }
+
+    if opt.preset == Some(Preset::AppleFramework) {
+        requirements.push(Requirement {
+            feature: "OBJCXX language",
+            version: Version::new(3, 16, 0),
+        });
+    }
+
+    if opt.preset == Some(Preset::GameSdl) {
+        requirements.push(Requirement {
+            feature: "FetchContent FIND_PACKAGE_ARGS",
+            version: Version::new(3, 24, 0),
+        });
+    }
+
+    if opt.preset == Some(Preset::AudioPlugin) {
+        requirements.push(Requirement {
+            feature: "JUCE 8",
+            version: Version::new(3, 22, 0),
+        });
+    }
+
+    if opt.extra_languages.contains(&ExtraLanguage::Cuda) {
+        requirements.push(Requirement {
+            feature: "CUDA_ARCHITECTURES and FindCUDAToolkit",
+            version: Version::new(3, 18, 0),
+        });
+    }
+
+    if opt.kind == Kind::HeaderOnly {
+        requirements.push(Requirement {
+            feature: "INTERFACE library sources",
+            version: Version::new(3, 19, 0),
+        });
+    }
+
+    requirements
+}
+
+// Lowest version satisfying all selected features
+pub fn infer(opt: &Opt) -> Version {
+    requirements(opt)
+        .iter()
+        .map(|requirement| requirement.version)
+        .max()
+        .unwrap_or(BASE_VERSION)
+}
+
+// Infers a missing version, returns warnings for features an explicit version is too low for
+pub fn resolve(opt: &mut Opt) -> io::Result<Vec<String>> {
+    let explicit = match &opt.cmake_version {
+        Some(version) => version
+            .parse::<Version>()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+        None => {
+            opt.cmake_version = Some(infer(opt).to_string());
+            return Ok(Vec::new());
+        }
+    };
+
+    let warnings = requirements(opt)
+        .iter()
+        .filter(|requirement| requirement.version > explicit)
+        .map(|requirement| {
+            format!(
+                "{} requires CMake {} but --cmake-version is {}",
+                requirement.feature, requirement.version, explicit
+            )
+        })
+        .collect();
+
+    Ok(warnings)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!("3.15.0".parse::<Version>(), Ok(Version::new(3, 15, 0)));
+        assert_eq!("3.28".parse::<Version>(), Ok(Version::new(3, 28, 0)));
+        assert!("3".parse::<Version>().is_err());
+        assert!("3.x.0".parse::<Version>().is_err());
+        assert!(Version::new(3, 9, 0) < Version::new(3, 19, 0));
+    }
+
+    #[test]
+    fn test_infer() {
+        let opt = Opt::default();
+        assert_eq!(infer(&opt), BASE_VERSION);
+
+        let opt = Opt {
+            kind: Kind::HeaderOnly,
+            ..Opt::default()
+        };
+        assert_eq!(infer(&opt), Version::new(3, 19, 0));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut opt = Opt {
+            kind: Kind::HeaderOnly,
+            ..Opt::default()
+        };
+        assert!(resolve(&mut opt).unwrap().is_empty());
+        assert_eq!(opt.cmake_version.as_deref(), Some("3.19.0"));
+
+        let mut opt = Opt {
+            kind: Kind::HeaderOnly,
+            cmake_version: Some(String::from("3.16")),
+            ..Opt::default()
+        };
+        assert_eq!(resolve(&mut opt).unwrap().len(), 1);
+
+        let mut opt = Opt {
+            cmake_version: Some(String::from("latest")),
+            ..Opt::default()
+        };
+        assert!(resolve(&mut opt).is_err());
+    }
+}
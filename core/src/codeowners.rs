@@ -0,0 +1,166 @@
+/*
+For GitHub code owners /sa https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/customizing-your-repository/about-code-owners
+For GitLab code owners /sa https://docs.gitlab.com/ee/user/project/codeowners/reference.html
+*/
+
+use crate::community_files::Forge;
+use crate::cpp_proj_gen::CppProjGen;
+use std::{path::PathBuf, str::FromStr};
+
+// Owner and the paths it owns, e.g. of --codeowners core@my-org:/@SOURCE_DIR@/,*.cmake
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeOwner {
+    owner: String,
+    patterns: Vec<String>,
+}
+
+impl FromStr for CodeOwner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (owner, patterns) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected OWNER:PATTERN[,PATTERN...], got '{}'", s))?;
+        let patterns: Vec<String> = patterns
+            .split(',')
+            .filter(|pattern| !pattern.is_empty())
+            .map(String::from)
+            .collect();
+
+        if owner.is_empty() || patterns.is_empty() {
+            return Err(format!("expected OWNER:PATTERN[,PATTERN...], got '{}'", s));
+        }
+        if owner.contains(char::is_whitespace)
+            || patterns
+                .iter()
+                .any(|pattern| pattern.contains(char::is_whitespace))
+        {
+            return Err(format!("code owners can not contain spaces, got '{}'", s));
+        }
+
+        Ok(CodeOwner {
+            owner: build_owner(owner),
+            patterns,
+        })
+    }
+}
+
+// team@org becomes @org/team, e-mail addresses and @user or @org/team stay as they are
+fn build_owner(owner: &str) -> String {
+    if owner.starts_with('@') {
+        return String::from(owner);
+    }
+
+    match owner.split_once('@') {
+        Some((_, domain)) if domain.contains('.') => String::from(owner),
+        Some((team, org)) => format!("@{}/{}", org, team),
+        None => format!("@{}", owner),
+    }
+}
+
+// One line per pattern, owners of the same pattern share it as later lines override earlier ones
+fn build_lines(code_owners: &[CodeOwner]) -> String {
+    let mut lines: Vec<(&str, Vec<&str>)> = Vec::new();
+    for code_owner in code_owners {
+        for pattern in &code_owner.patterns {
+            match lines.iter_mut().find(|(other, _)| other == pattern) {
+                Some((_, owners)) if owners.contains(&code_owner.owner.as_str()) => {}
+                Some((_, owners)) => owners.push(&code_owner.owner),
+                None => lines.push((pattern, vec![&code_owner.owner])),
+            }
+        }
+    }
+
+    lines
+        .iter()
+        .map(|(pattern, owners)| format!("{} {}\n", pattern, owners.join(" ")))
+        .collect()
+}
+
+// Adds .github/CODEOWNERS or .gitlab/CODEOWNERS, of --codeowners-flavor or else --forge
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if gen.opt().codeowners.is_empty() {
+        return gen;
+    }
+
+    let path = match gen.opt().codeowners_flavor.or(gen.opt().forge) {
+        Some(Forge::Gitlab) => ".gitlab/CODEOWNERS",
+        Some(Forge::Github) | None => ".github/CODEOWNERS",
+    };
+    let contents = format!(
+        "# Later patterns take precedence\n{}",
+        build_lines(&gen.opt().codeowners)
+    );
+
+    gen.add_file(PathBuf::from(path), contents)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Opt, PlanEntry};
+    use std::path::Path;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "core@my-org:/include/,/source/".parse::<CodeOwner>(),
+            Ok(CodeOwner {
+                owner: String::from("@my-org/core"),
+                patterns: vec![String::from("/include/"), String::from("/source/")],
+            })
+        );
+        assert_eq!(
+            "dev@example.com:*".parse::<CodeOwner>().unwrap().owner,
+            "dev@example.com"
+        );
+        assert_eq!("@me:*".parse::<CodeOwner>().unwrap().owner, "@me");
+        assert_eq!("me:*".parse::<CodeOwner>().unwrap().owner, "@me");
+        assert!("core@my-org".parse::<CodeOwner>().is_err());
+        assert!("core@my-org:".parse::<CodeOwner>().is_err());
+        assert!(":*".parse::<CodeOwner>().is_err());
+        assert!("core@my-org:my dir".parse::<CodeOwner>().is_err());
+    }
+
+    #[test]
+    fn test_build_lines() {
+        let code_owners: Vec<CodeOwner> = ["core@org:*,/source/", "qa@org:/test/,/source/"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        assert_eq!(
+            build_lines(&code_owners),
+            "* @org/core\n/source/ @org/core @org/qa\n/test/ @org/qa\n"
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            codeowners: vec!["core@org:/@SOURCE_DIR@/".parse().unwrap()],
+            codeowners_flavor: Some(Forge::Gitlab),
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt)
+            .add_source_dir(PathBuf::from("source"))
+            .plan()
+            .unwrap();
+        let codeowners = plan.iter().find_map(|entry| match entry {
+            PlanEntry::File { path, contents, .. }
+                if path == Path::new("test_out_dir/tgtnm/.gitlab/CODEOWNERS") =>
+            {
+                Some(contents.clone())
+            }
+            _ => None,
+        });
+
+        assert_eq!(
+            codeowners.unwrap(),
+            "# Later patterns take precedence\n/source/ @org/core\n"
+        );
+    }
+}
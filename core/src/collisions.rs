@@ -0,0 +1,146 @@
+/*
+For reserved target names /sa https://cmake.org/cmake/help/latest/policy/CMP0037.html
+For identifiers /sa https://en.cppreference.com/w/cpp/language/identifiers
+*/
+
+use crate::cpp_proj_gen::Opt;
+use crate::error::invalid_input;
+use std::io;
+
+// Built-in targets of the CMake generators, e.g. make install or the Visual Studio ALL_BUILD
+const RESERVED_TARGET_NAMES: &[&str] = &[
+    "all",
+    "clean",
+    "help",
+    "install",
+    "package",
+    "package_source",
+    "test",
+    "edit_cache",
+    "rebuild_cache",
+    "list_install_components",
+    "ALL_BUILD",
+    "ZERO_CHECK",
+    "RUN_TESTS",
+    "INSTALL",
+    "PACKAGE",
+    "Continuous",
+    "Experimental",
+    "Nightly",
+];
+
+// Letters, digits and _.+- as CMake allows for target names, nothing that ends a CMake argument
+pub fn is_target_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.+-".contains(c))
+}
+
+// ASCII C++ identifier, e.g. my_company
+pub fn is_cpp_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Checks the characters of the names, then the target name against reserved names, the namespace and the top-level directories
+pub fn validate(opt: &Opt, top_level_dirs: &[String]) -> io::Result<()> {
+    let target_name = &opt.target_name;
+
+    // Both end up in CMake code, e.g. project() and add_library()
+    if !is_target_name(target_name) {
+        return Err(invalid_input(format!(
+            "target name '{}' may only contain letters, digits and _.+-, pick another --target-name",
+            target_name.escape_debug()
+        )));
+    }
+    if let Some(name_space) = opt
+        .name_space
+        .as_ref()
+        .filter(|name| !is_cpp_identifier(name))
+    {
+        return Err(invalid_input(format!(
+            "namespace '{}' is no C++ identifier, use letters, digits and _ in --name-space",
+            name_space.escape_debug()
+        )));
+    }
+
+    // Case-insensitive as file systems on Windows and macOS are
+    if let Some(reserved) = RESERVED_TARGET_NAMES
+        .iter()
+        .find(|reserved| reserved.eq_ignore_ascii_case(target_name))
+    {
+        return Err(invalid_input(format!(
+            "target name '{}' is reserved by CMake ({}), pick another --target-name",
+            target_name, reserved
+        )));
+    }
+
+    if let Some(name_space) = &opt.name_space {
+        if name_space.eq_ignore_ascii_case(target_name) {
+            return Err(invalid_input(format!(
+                "namespace and target name are both '{}', which repeats it in include paths and the \
+                 ns::target alias, drop --name-space or pick another --target-name",
+                target_name
+            )));
+        }
+    }
+
+    // The build directory would contain both the binary and the subdirectory's build tree
+    if let Some(dir) = top_level_dirs
+        .iter()
+        .find(|dir| dir.eq_ignore_ascii_case(target_name))
+    {
+        return Err(invalid_input(format!(
+            "target name '{}' collides with the top-level directory {}/, pick another --target-name",
+            target_name, dir
+        )));
+    }
+
+    Ok(())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_opt(name_space: Option<&str>, target_name: &str) -> Opt {
+        Opt {
+            name_space: name_space.map(String::from),
+            target_name: String::from(target_name),
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let dirs = vec![String::from("include"), String::from("fuzz")];
+
+        assert!(validate(&create_test_opt(Some("nmspc"), "tgtnm"), &dirs).is_ok());
+        assert!(validate(&create_test_opt(None, "install"), &dirs).is_err());
+        assert!(validate(&create_test_opt(None, "all_build"), &dirs).is_err());
+        assert!(validate(&create_test_opt(Some("tgtnm"), "tgtnm"), &dirs).is_err());
+        assert!(validate(&create_test_opt(None, "Fuzz"), &dirs).is_err());
+    }
+
+    #[test]
+    fn test_validate_characters() {
+        assert!(validate(&create_test_opt(Some("my_company"), "my-lib.v2+x"), &[]).is_ok());
+        assert!(validate(&create_test_opt(None, ""), &[]).is_err());
+        assert!(validate(&create_test_opt(None, "my lib"), &[]).is_err());
+        assert!(validate(
+            &create_test_opt(None, "my lib)\nexecute_process(COMMAND id)\n#"),
+            &[]
+        )
+        .is_err());
+        assert!(validate(&create_test_opt(None, "x\r\nSet-Cookie: a=1"), &[]).is_err());
+        assert!(validate(&create_test_opt(Some("my-company"), "tgtnm"), &[]).is_err());
+        assert!(validate(&create_test_opt(Some("1st"), "tgtnm"), &[]).is_err());
+        assert!(validate(&create_test_opt(Some("a)\n#"), "tgtnm"), &[]).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+/*
+For Keep a Changelog /sa https://keepachangelog.com/en/1.1.0/
+For the Contributor Covenant /sa https://www.contributor-covenant.org/version/2/1/code_of_conduct/
+For GitHub issue forms /sa https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/syntax-for-issue-forms
+For GitLab description templates /sa https://docs.gitlab.com/ee/user/project/description_templates.html
+*/
+
+use crate::cpp_proj_gen::{self, CppProjGen};
+use std::{path::PathBuf, str::FromStr};
+
+const CHANGELOG: &str = include_str!("../res/community/CHANGELOG.md.in");
+const CONTRIBUTING: &str = include_str!("../res/community/CONTRIBUTING.md.in");
+const CODE_OF_CONDUCT: &str = include_str!("../res/community/CODE_OF_CONDUCT.md.in");
+
+// Path in the project and template of the issue and pull request templates
+const GITHUB_TEMPLATES: &[(&str, &str)] = &[
+    (
+        ".github/ISSUE_TEMPLATE/bug_report.yml",
+        include_str!("../res/community/github/bug_report.yml.in"),
+    ),
+    (
+        ".github/ISSUE_TEMPLATE/feature_request.yml",
+        include_str!("../res/community/github/feature_request.yml.in"),
+    ),
+    (
+        ".github/PULL_REQUEST_TEMPLATE.md",
+        include_str!("../res/community/github/PULL_REQUEST_TEMPLATE.md.in"),
+    ),
+];
+const GITLAB_TEMPLATES: &[(&str, &str)] = &[
+    (
+        ".gitlab/issue_templates/Bug.md",
+        include_str!("../res/community/gitlab/Bug.md.in"),
+    ),
+    (
+        ".gitlab/issue_templates/Feature.md",
+        include_str!("../res/community/gitlab/Feature.md.in"),
+    ),
+    (
+        ".gitlab/merge_request_templates/Default.md",
+        include_str!("../res/community/gitlab/Default.md.in"),
+    ),
+];
+
+// Forge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Forge {
+    Github,
+    Gitlab,
+}
+
+impl Forge {
+    pub const VARIANTS: &'static [&'static str] = &["github", "gitlab"];
+
+    fn templates(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Forge::Github => GITHUB_TEMPLATES,
+            Forge::Gitlab => GITLAB_TEMPLATES,
+        }
+    }
+}
+
+impl FromStr for Forge {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "github" => Ok(Forge::Github),
+            "gitlab" => Ok(Forge::Gitlab),
+            _ => Err(format!(
+                "unknown forge '{}', expected one of: {}",
+                s,
+                Forge::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let gen = apply_forge_templates(gen);
+
+    apply_community_files(gen)
+}
+
+// Adds the issue and pull request templates of the forge
+fn apply_forge_templates(gen: CppProjGen) -> CppProjGen {
+    let forge = match gen.opt().forge {
+        Some(forge) => forge,
+        None => return gen,
+    };
+
+    forge.templates().iter().fold(gen, |gen, (path, template)| {
+        gen.add_file(PathBuf::from(path), String::from(*template))
+    })
+}
+
+// Adds the changelog, contribution guide and code of conduct of open-source projects
+fn apply_community_files(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().community_files {
+        return gen;
+    }
+
+    let contact = match &gen.opt().author {
+        Some(author) => author.clone(),
+        None => String::from("the project maintainers"),
+    };
+    let license_suffix = match &gen.opt().license {
+        Some(license) => format!(" ({})", license),
+        None => String::new(),
+    };
+    // A release heading needs a date, unlike @GENERATION_DATE@
+    let release_date =
+        cpp_proj_gen::build_iso_date(cpp_proj_gen::timestamp_or_now(gen.opt().timestamp));
+
+    gen.add_cmake_var("@COMMUNITY_CONTACT@", &contact)
+        .add_cmake_var("@COMMUNITY_LICENSE_SUFFIX@", &license_suffix)
+        .add_cmake_var("@COMMUNITY_RELEASE_DATE@", &release_date)
+        .add_file(PathBuf::from("CHANGELOG.md"), String::from(CHANGELOG))
+        .add_file(PathBuf::from("CONTRIBUTING.md"), String::from(CONTRIBUTING))
+        .add_file(
+            PathBuf::from("CODE_OF_CONDUCT.md"),
+            String::from(CODE_OF_CONDUCT),
+        )
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::Opt;
+    use crate::test_util::contents;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_forge() {
+        assert_eq!("github".parse::<Forge>().unwrap(), Forge::Github);
+        assert_eq!("gitlab".parse::<Forge>().unwrap(), Forge::Gitlab);
+        assert!("bitbucket".parse::<Forge>().is_err());
+    }
+
+    #[test]
+    fn test_forge_templates() {
+        let opt = Opt {
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            forge: Some(Forge::Gitlab),
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+
+        assert!(plan.iter().any(|entry| entry.path()
+            == Path::new("test_out_dir/my-target/.gitlab/merge_request_templates/Default.md")));
+        assert!(!plan
+            .iter()
+            .any(|entry| entry.path().starts_with("test_out_dir/my-target/.github")));
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            author: Some(String::from("Jane Doe")),
+            license: Some(String::from("MIT")),
+            community_files: true,
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+
+        let changelog = contents(&plan, "CHANGELOG.md").unwrap();
+        let release = changelog
+            .lines()
+            .find(|line| line.starts_with("## [0.1.0] - "));
+        assert_eq!(release.map(str::len), Some("## [0.1.0] - 2024-02-29".len()));
+        assert!(contents(&plan, "CONTRIBUTING.md")
+            .unwrap()
+            .contains("license of the project (MIT)"));
+        assert!(contents(&plan, "CODE_OF_CONDUCT.md")
+            .unwrap()
+            .contains("reported to Jane Doe."));
+    }
+}
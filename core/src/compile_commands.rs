@@ -0,0 +1,189 @@
+/*
+For CMAKE_EXPORT_COMPILE_COMMANDS /sa https://cmake.org/cmake/help/latest/variable/CMAKE_EXPORT_COMPILE_COMMANDS.html
+For file(CREATE_LINK) /sa https://cmake.org/cmake/help/latest/command/file.html#create-link
+For .clangd /sa https://clangd.llvm.org/config
+*/
+
+use crate::analysis::{CLANG_TIDY_CHECKS, CLANG_TIDY_DISABLED};
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::language::Language;
+use std::path::PathBuf;
+
+const COMPILE_COMMANDS_CMAKE: &str = include_str!("../res/compile_commands.snippet.in");
+const CLANGD_CONFIG: &str = include_str!("../res/clangd.in");
+// Before project(), which defines the cache variable empty, and the targets, which take it over
+const EXPORT_CMAKE: &str = "# For clangd, -DCMAKE_EXPORT_COMPILE_COMMANDS=OFF turns it off
+set(CMAKE_EXPORT_COMPILE_COMMANDS ON CACHE BOOL \"Export compile_commands.json\")
+
+";
+// Of the cacheVariables in CMakePresets.json
+const PRESETS_LINE: &str = "                \"CMAKE_EXPORT_COMPILE_COMMANDS\": \"ON\",\n";
+
+// Exports compile_commands.json, links it into the source directory and configures clangd for it
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if gen.opt().no_compile_commands {
+        return gen;
+    }
+
+    let clangd = build_clangd_config(gen.opt());
+    gen.add_file(PathBuf::from(".clangd"), clangd)
+        .add_cmake_snippet(String::from(COMPILE_COMMANDS_CMAKE.trim_end()))
+}
+
+// The standards apply to files without compile commands yet, e.g. new ones
+fn build_clangd_config(opt: &Opt) -> String {
+    let list = |checks: &[&str]| {
+        let items: Vec<String> = checks
+            .iter()
+            .map(|check| format!("      - {}", check))
+            .collect();
+        items.join("\n")
+    };
+    let mut config = CLANGD_CONFIG
+        .replace("@CLANGD_TIDY_ADD@", &list(CLANG_TIDY_CHECKS))
+        .replace("@CLANGD_TIDY_REMOVE@", &list(CLANG_TIDY_DISABLED));
+
+    // C headers are .h too, the samples of mixed projects are C++
+    let (cpp_files, c_files) = match opt.language {
+        Language::Cpp => (Some("PathExclude: .*\\.c$"), None),
+        Language::C => (None, Some("PathMatch: .*\\.[ch]$")),
+        Language::CppAndC => (Some("PathExclude: .*\\.c$"), Some("PathMatch: .*\\.c$")),
+    };
+    if let Some(condition) = cpp_files {
+        config.push_str(&build_clangd_block(
+            condition,
+            &format!("-std=c++{}", opt.cxx_standard),
+        ));
+    }
+    if let Some(condition) = c_files {
+        config.push_str(&build_clangd_block(
+            condition,
+            &format!("-std=c{}", opt.c_standard),
+        ));
+    }
+
+    config
+}
+
+fn build_clangd_block(condition: &str, flag: &str) -> String {
+    format!(
+        "---\nIf:\n  {}\nCompileFlags:\n  Add: [{}]\n",
+        condition, flag
+    )
+}
+
+// Replaces @COMPILE_COMMANDS_EXPORT@, placed before project()
+pub fn render_export(template: &str, opt: &Opt) -> String {
+    let export = match opt.no_compile_commands {
+        true => "",
+        false => EXPORT_CMAKE,
+    };
+
+    template.replace("@COMPILE_COMMANDS_EXPORT@", export)
+}
+
+// The presets template enables the export
+pub fn render_presets(template: &str, no_compile_commands: bool) -> String {
+    match no_compile_commands {
+        true => template.replace(PRESETS_LINE, ""),
+        false => String::from(template),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Compiler;
+    use crate::cpp_proj_gen::{Opt, PlanEntry};
+    use crate::test_util::contents;
+    use std::path::PathBuf;
+
+    fn create_test_plan(opt: Opt) -> Vec<PlanEntry> {
+        CppProjGen::new(Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            compiler: Some(Compiler::Clang),
+            ..opt
+        })
+        .plan()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = create_test_plan(Opt::default());
+        let cmake_lists = contents(&plan, "CMakeLists.txt").unwrap();
+        assert!(cmake_lists.contains("RESULT TGTNM_COMPILE_COMMANDS_LINK\n"));
+        let export = cmake_lists
+            .find("set(CMAKE_EXPORT_COMPILE_COMMANDS ON CACHE BOOL")
+            .unwrap();
+        assert!(export < cmake_lists.find("project(tgtnm").unwrap());
+        assert!(contents(&plan, "CMakePresets.json")
+            .unwrap()
+            .contains("\"CMAKE_EXPORT_COMPILE_COMMANDS\": \"ON\""));
+    }
+
+    #[test]
+    fn test_static_analysis() {
+        let opt = Opt {
+            static_analysis: true,
+            ..Opt::default()
+        };
+        let cmake_lists = contents(&create_test_plan(opt.clone()), "CMakeLists.txt").unwrap();
+        assert_eq!(
+            cmake_lists
+                .matches("CMAKE_EXPORT_COMPILE_COMMANDS ON")
+                .count(),
+            1
+        );
+
+        let opt = Opt {
+            no_compile_commands: true,
+            ..opt
+        };
+        assert!(!contents(&create_test_plan(opt), "CMakeLists.txt")
+            .unwrap()
+            .contains("CMAKE_EXPORT_COMPILE_COMMANDS"));
+    }
+
+    #[test]
+    fn test_build_clangd_config() {
+        let config = build_clangd_config(&Opt {
+            language: Language::CppAndC,
+            cxx_standard: String::from("20"),
+            c_standard: String::from("11"),
+            ..Opt::default()
+        });
+
+        assert!(config.contains("    Add:\n      - bugprone-*\n"));
+        assert!(config.contains("      - readability-magic-numbers\n"));
+        assert!(
+            config.contains("If:\n  PathExclude: .*\\.c$\nCompileFlags:\n  Add: [-std=c++20]\n")
+        );
+        assert!(config.contains("If:\n  PathMatch: .*\\.c$\nCompileFlags:\n  Add: [-std=c11]\n"));
+    }
+
+    #[test]
+    fn test_apply_suppressed() {
+        let opt = Opt {
+            no_compile_commands: true,
+            ..Opt::default()
+        };
+
+        let plan = create_test_plan(opt);
+        let cmake_lists = contents(&plan, "CMakeLists.txt").unwrap();
+        assert!(!cmake_lists.contains("compile_commands.json"));
+        assert!(!cmake_lists.contains("@COMPILE_COMMANDS_EXPORT@"));
+        let presets = contents(&plan, "CMakePresets.json").unwrap();
+        assert!(!presets.contains("CMAKE_EXPORT_COMPILE_COMMANDS"));
+        assert!(!CppProjGen::new(Opt {
+            no_compile_commands: true,
+            ..Opt::default()
+        })
+        .build_paths()
+        .iter()
+        .any(|path| path.ends_with(".clangd")));
+        assert!(serde_json::from_str::<serde_json::Value>(&presets).is_ok());
+    }
+}
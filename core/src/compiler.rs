@@ -0,0 +1,150 @@
+/*
+For CMake presets /sa https://cmake.org/cmake/help/latest/manual/cmake-presets.7.html
+For MSVC warning levels /sa https://learn.microsoft.com/en-us/cpp/build/reference/compiler-option-warning-level
+*/
+
+use crate::compile_commands;
+use crate::cpp_proj_gen::{CppProjGen, Kind};
+use crate::toolchain;
+use std::{path::PathBuf, str::FromStr};
+
+const CMAKE_PRESETS_FILE_NAME: &str = "CMakePresets.json";
+const CMAKE_PRESETS: &str = include_str!("../res/CMakePresets.json.in");
+
+// Compiler
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compiler {
+    Gcc,
+    Clang,
+    Msvc,
+    AppleClang,
+}
+
+impl Compiler {
+    pub const VARIANTS: &'static [&'static str] = &["gcc", "clang", "msvc", "apple-clang"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Compiler::Gcc => "gcc",
+            Compiler::Clang => "clang",
+            Compiler::Msvc => "msvc",
+            Compiler::AppleClang => "apple-clang",
+        }
+    }
+
+    pub fn c_compiler(self) -> &'static str {
+        match self {
+            Compiler::Gcc => "gcc",
+            Compiler::Clang | Compiler::AppleClang => "clang",
+            Compiler::Msvc => "cl",
+        }
+    }
+
+    pub fn cxx_compiler(self) -> &'static str {
+        match self {
+            Compiler::Gcc => "g++",
+            Compiler::Clang | Compiler::AppleClang => "clang++",
+            Compiler::Msvc => "cl",
+        }
+    }
+
+    pub fn warning_flags(self) -> &'static [&'static str] {
+        match self {
+            Compiler::Gcc | Compiler::Clang | Compiler::AppleClang => {
+                &["-Wall", "-Wextra", "-Wpedantic", "-Wshadow", "-Wconversion"]
+            }
+            Compiler::Msvc => &["/W4", "/permissive-"],
+        }
+    }
+}
+
+impl FromStr for Compiler {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gcc" => Ok(Compiler::Gcc),
+            "clang" => Ok(Compiler::Clang),
+            "msvc" => Ok(Compiler::Msvc),
+            "apple-clang" => Ok(Compiler::AppleClang),
+            _ => Err(format!(
+                "unknown compiler '{}', expected one of: {}",
+                s,
+                Compiler::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Adds warning flags and CMake presets for the selected compiler
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (compiler, kind) = match gen.opt().compiler {
+        Some(compiler) => (compiler, gen.opt().kind),
+        None => return gen,
+    };
+
+    let presets = compile_commands::render_presets(CMAKE_PRESETS, gen.opt().no_compile_commands);
+    let presets = toolchain::render_presets(presets, gen.opt());
+
+    let gen = gen
+        .add_cmake_var("@COMPILER_NAME@", compiler.name())
+        .add_cmake_var("@CMAKE_C_COMPILER@", compiler.c_compiler())
+        .add_cmake_var("@CMAKE_CXX_COMPILER@", compiler.cxx_compiler())
+        .add_file(PathBuf::from(CMAKE_PRESETS_FILE_NAME), presets);
+
+    // Warnings are meant for the own sources, header-only targets have none
+    if kind == Kind::HeaderOnly {
+        return gen;
+    }
+
+    let gen = gen.add_cmake_snippet(build_compile_options(compiler, "@CMAKE_TARGET_NAME@"));
+
+    match kind {
+        Kind::LibAndApp => {
+            gen.add_cmake_snippet(build_compile_options(compiler, "@CMAKE_TARGET_NAME@-app"))
+        }
+        _ => gen,
+    }
+}
+
+fn build_compile_options(compiler: Compiler, target: &str) -> String {
+    format!(
+        "target_compile_options({}\n    PRIVATE\n        {}\n)",
+        target,
+        compiler.warning_flags().join("\n        ")
+    )
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::Opt;
+
+    #[test]
+    fn test_from_str() {
+        for variant in Compiler::VARIANTS {
+            assert_eq!(variant.parse::<Compiler>().unwrap().name(), *variant);
+        }
+        assert!("icc".parse::<Compiler>().is_err());
+    }
+
+    #[test]
+    fn test_build_compile_options() {
+        let options = build_compile_options(Compiler::Msvc, "@CMAKE_TARGET_NAME@");
+        assert!(options.contains("/W4"));
+        assert!(options.starts_with("target_compile_options(@CMAKE_TARGET_NAME@"));
+    }
+
+    #[test]
+    fn test_presets_file() {
+        let opt = Opt {
+            compiler: Some(Compiler::Clang),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        let paths = CppProjGen::new(opt).build_paths();
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/CMakePresets.json")));
+    }
+}
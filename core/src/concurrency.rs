@@ -0,0 +1,214 @@
+/*
+For FindThreads /sa https://cmake.org/cmake/help/latest/module/FindThreads.html
+For oneTBB /sa https://oneapi-src.github.io/oneTBB/
+For Taskflow /sa https://taskflow.github.io/taskflow/
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::dependency::FeatureDependency;
+use crate::error::invalid_input;
+use crate::language::Language;
+use std::{io, path::PathBuf, str::FromStr};
+
+const CONCURRENCY_CMAKE: &str = include_str!("../res/concurrency/CMakeLists.snippet.in");
+const CONCURRENCY_HEADER: &str = include_str!("../res/concurrency/concurrency.h.in");
+const THREADS_CMAKE: &str = include_str!("../res/concurrency/threads.snippet.in");
+const THREAD_POOL_HEADER: &str = include_str!("../res/concurrency/thread_pool.h.in");
+const STD_THREAD_SOURCE: &str = include_str!("../res/concurrency/std-thread.cpp.in");
+const TBB_SOURCE: &str = include_str!("../res/concurrency/tbb.cpp.in");
+const TASKFLOW_SOURCE: &str = include_str!("../res/concurrency/taskflow.cpp.in");
+
+// Threading library of the example, std-thread needs no package
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Concurrency {
+    StdThread,
+    Tbb,
+    Taskflow,
+}
+
+impl Concurrency {
+    pub const VARIANTS: &'static [&'static str] = &["std-thread", "tbb", "taskflow"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Concurrency::StdThread => "std-thread",
+            Concurrency::Tbb => "tbb",
+            Concurrency::Taskflow => "taskflow",
+        }
+    }
+}
+
+impl FromStr for Concurrency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "std-thread" => Ok(Concurrency::StdThread),
+            "tbb" => Ok(Concurrency::Tbb),
+            "taskflow" => Ok(Concurrency::Taskflow),
+            _ => Err(format!(
+                "unknown concurrency '{}', expected one of: {}",
+                s,
+                Concurrency::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// The example is compiled into the target
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let concurrency = match opt.concurrency {
+        Some(concurrency) => concurrency,
+        None => return Ok(()),
+    };
+
+    if opt.kind == Kind::HeaderOnly {
+        return Err(invalid_input(String::from(
+            "--concurrency needs compiled sources, kind header-only has none",
+        )));
+    }
+    if opt.language == Language::C {
+        return Err(invalid_input(format!(
+            "--concurrency {} needs C++, use --language cpp or cpp-and-c",
+            concurrency.name()
+        )));
+    }
+
+    Ok(())
+}
+
+// Package of the external libraries, see dependency::apply
+pub fn dependency(opt: &Opt) -> Option<FeatureDependency> {
+    match opt.concurrency? {
+        Concurrency::Tbb => Some(FeatureDependency {
+            spec: "onetbb/2021.12.0",
+            vcpkg_port: Some("tbb"),
+            cmake_package: Some("TBB"),
+            nix_package: Some("tbb"),
+            conda_package: Some("tbb-devel"),
+            link_target: "TBB::tbb",
+            consumer: "@CMAKE_TARGET_NAME@",
+        }),
+        Concurrency::Taskflow => Some(FeatureDependency {
+            spec: "taskflow/3.7.0",
+            vcpkg_port: None,
+            cmake_package: Some("Taskflow"),
+            nix_package: None,
+            conda_package: None,
+            link_target: "Taskflow::Taskflow",
+            consumer: "@CMAKE_TARGET_NAME@",
+        }),
+        Concurrency::StdThread => None,
+    }
+}
+
+// Adds parallel_sum, with a thread pool for std-thread, to the target
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let concurrency = match gen.opt().concurrency {
+        Some(concurrency) if gen.opt().kind != Kind::HeaderOnly => concurrency,
+        _ => return gen,
+    };
+    let source = match concurrency {
+        Concurrency::StdThread => STD_THREAD_SOURCE,
+        Concurrency::Tbb => TBB_SOURCE,
+        Concurrency::Taskflow => TASKFLOW_SOURCE,
+    };
+
+    let gen = gen
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/concurrency.@HEADER_FILE_EXT@"),
+            String::from(CONCURRENCY_HEADER),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/concurrency.@SOURCE_FILE_EXT@"),
+            String::from(source),
+        )
+        .add_cmake_snippet(String::from(CONCURRENCY_CMAKE.trim_end()));
+
+    match concurrency {
+        Concurrency::StdThread => gen
+            .add_file(
+                PathBuf::from("@SOURCE_DIR@/thread_pool.@HEADER_FILE_EXT@"),
+                String::from(THREAD_POOL_HEADER),
+            )
+            .add_cmake_snippet(String::from(THREADS_CMAKE.trim_end())),
+        _ => gen,
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::PlanEntry;
+    use crate::dependency::PackageManager;
+    use crate::test_util::contents;
+
+    fn create_test_plan(opt: Opt) -> Vec<PlanEntry> {
+        CppProjGen::new(Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..opt
+        })
+        .add_include_dir(PathBuf::from("include"))
+        .add_source_dir(PathBuf::from("source"))
+        .plan()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            concurrency: Some(Concurrency::Tbb),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+
+        assert!(validate(&Opt {
+            kind: Kind::HeaderOnly,
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            language: Language::C,
+            ..opt
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply_std_thread() {
+        let plan = create_test_plan(Opt {
+            concurrency: Some(Concurrency::StdThread),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("target_link_libraries(tgtnm PRIVATE Threads::Threads)"));
+        assert!(contents(&plan, "source/thread_pool.h")
+            .unwrap()
+            .contains("class ThreadPool"));
+        assert!(contents(&plan, "cmake/dependencies.cmake").is_none());
+    }
+
+    #[test]
+    fn test_apply_tbb() {
+        let plan = create_test_plan(Opt {
+            concurrency: Some(Concurrency::Tbb),
+            package_manager: Some(PackageManager::Conan),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "source/concurrency.cpp")
+            .unwrap()
+            .contains("tbb::parallel_reduce("));
+        let dependencies = contents(&plan, "cmake/dependencies.cmake").unwrap();
+        assert!(dependencies.contains("find_package(TBB 2021.12.0 CONFIG REQUIRED)\n"));
+        assert!(dependencies.contains("target_link_libraries(tgtnm PRIVATE TBB::tbb)\n"));
+        assert!(contents(&plan, "conanfile.txt")
+            .unwrap()
+            .contains("onetbb/2021.12.0\n"));
+        assert!(contents(&plan, "source/thread_pool.h").is_none());
+    }
+}
@@ -0,0 +1,122 @@
+/*
+For environment.yml /sa https://docs.conda.io/projects/conda/en/latest/user-guide/tasks/manage-environments.html#create-env-file-manually
+For the conda-forge compilers /sa https://conda-forge.org/docs/maintainer/infrastructure/#compilers-and-runtimes
+*/
+
+use crate::compiler::Compiler;
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::dependency::{self, PackageManager};
+use crate::language::Language;
+use crate::task_runner::TaskRunner;
+use std::path::PathBuf;
+
+const ENVIRONMENT_YML: &str = include_str!("../res/conda/environment.yml.in");
+
+// conda-forge packages of the tools and, without a package manager, of the libraries
+fn build_packages(opt: &Opt) -> Vec<&'static str> {
+    let mut packages = vec!["cmake", "ninja"];
+    let (c_compiler, cxx_compiler) = match opt.compiler {
+        Some(Compiler::Gcc) => ("gcc", "gxx"),
+        Some(Compiler::Clang) | Some(Compiler::AppleClang) => ("clang", "clangxx"),
+        // The default compilers of the platform, MSVC on Windows
+        Some(Compiler::Msvc) | None => ("c-compiler", "cxx-compiler"),
+    };
+    if opt.language != Language::C {
+        packages.push(cxx_compiler);
+    }
+    if opt.language != Language::Cpp {
+        packages.push(c_compiler);
+    }
+    match opt.package_manager {
+        // vcpkg is cloned and bootstrapped, conda-forge has no package of it
+        Some(PackageManager::Vcpkg) => (),
+        Some(PackageManager::Conan) => packages.push("conan"),
+        None => packages.extend(dependency::build_feature_dependencies(opt).iter().map(
+            |feature| {
+                feature
+                    .conda_package
+                    .unwrap_or_else(|| feature.spec.split('/').next().unwrap())
+            },
+        )),
+    }
+    // clang-format and run-clang-tidy of the format and lint recipes
+    if let Some(task_runner) = opt.task_runner {
+        packages.push("clang-tools");
+        packages.push(match task_runner {
+            TaskRunner::Just => "just",
+            TaskRunner::Task => "go-task",
+            TaskRunner::Make => "make",
+        });
+    }
+
+    packages
+}
+
+// Adds environment.yml for conda and micromamba
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().conda_env {
+        return gen;
+    }
+
+    let packages: Vec<String> = build_packages(gen.opt())
+        .into_iter()
+        .map(|package| format!("  - {}", package))
+        .collect();
+    let environment = ENVIRONMENT_YML.replace("@CONDA_PACKAGES@", &packages.join("\n"));
+
+    gen.add_file(PathBuf::from("environment.yml"), environment)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::AppConfig;
+    use crate::concurrency::Concurrency;
+    use crate::cpp_proj_gen::Kind;
+
+    fn create_test_opt() -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            conda_env: true,
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_build_packages() {
+        assert_eq!(
+            build_packages(&create_test_opt()),
+            vec!["cmake", "ninja", "cxx-compiler"]
+        );
+        assert_eq!(
+            build_packages(&Opt {
+                kind: Kind::LibAndApp,
+                language: Language::CppAndC,
+                compiler: Some(Compiler::Gcc),
+                app_config: Some(AppConfig::Json),
+                concurrency: Some(Concurrency::Tbb),
+                task_runner: Some(TaskRunner::Make),
+                ..create_test_opt()
+            }),
+            vec![
+                "cmake",
+                "ninja",
+                "gxx",
+                "gcc",
+                "nlohmann_json",
+                "tbb-devel",
+                "clang-tools",
+                "make"
+            ]
+        );
+        assert_eq!(
+            build_packages(&Opt {
+                concurrency: Some(Concurrency::Taskflow),
+                package_manager: Some(PackageManager::Vcpkg),
+                ..create_test_opt()
+            }),
+            vec!["cmake", "ninja", "cxx-compiler"]
+        );
+    }
+}
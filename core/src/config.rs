@@ -0,0 +1,277 @@
+/*
+For toml /sa https://docs.rs/toml/0.8/toml/
+For XDG base directories /sa https://specifications.freedesktop.org/basedir-spec/latest/
+*/
+
+use crate::cpp_proj_gen::Kind;
+use crate::defaults::Defaults;
+use crate::naming::TestPattern;
+use crate::test_framework::TestFramework;
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+const PROJECT_CONFIG_FILE_NAME: &str = "cpp-proj-gen.toml";
+
+// Config file, e.g.
+//
+// namespace = "my_company"
+// author = "Jane Doe"
+// license = "MIT"
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub namespace: Option<String>,
+    pub cmake_version: Option<String>,
+    pub kind: Option<String>,
+    pub output_dir: Option<PathBuf>,
+    pub template_dir: Option<PathBuf>,
+    pub author: Option<String>,
+    pub author_email: Option<String>,
+    pub license: Option<String>,
+    pub test_framework: Option<String>,
+    pub header_ext: Option<String>,
+    pub source_ext: Option<String>,
+    pub test_pattern: Option<String>,
+    pub template_registry: Option<String>,
+}
+
+impl Config {
+    pub const KEYS: &'static [&'static str] = &[
+        "namespace",
+        "cmake-version",
+        "kind",
+        "output-dir",
+        "template-dir",
+        "author",
+        "author-email",
+        "license",
+        "test-framework",
+        "header-ext",
+        "source-ext",
+        "test-pattern",
+        "template-registry",
+    ];
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        parse_config(&contents, path)
+    }
+
+    pub fn into_defaults(self) -> io::Result<Defaults> {
+        let kind = match self.kind {
+            Some(kind) => Some(kind.parse::<Kind>().map_err(invalid_data)?),
+            None => None,
+        };
+        let test_framework = match self.test_framework {
+            Some(framework) => Some(framework.parse::<TestFramework>().map_err(invalid_data)?),
+            None => None,
+        };
+        let test_pattern = match self.test_pattern {
+            Some(pattern) => Some(pattern.parse::<TestPattern>().map_err(invalid_data)?),
+            None => None,
+        };
+
+        Ok(Defaults {
+            name_space: self.namespace,
+            cmake_version: self.cmake_version,
+            output_dir: self.output_dir,
+            kind,
+            template_dir: self.template_dir,
+            template_registry: self.template_registry,
+            author: self.author,
+            author_email: self.author_email,
+            license: self.license,
+            test_framework,
+            header_ext: self.header_ext,
+            source_ext: self.source_ext,
+            test_pattern,
+        })
+    }
+}
+
+// e.g. ~/.config/cpp-proj-gen/config.toml
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(config_dir.join("cpp-proj-gen").join(CONFIG_FILE_NAME))
+}
+
+// Project config in the current directory, e.g. ./cpp-proj-gen.toml
+pub fn project_config_path() -> PathBuf {
+    PathBuf::from(PROJECT_CONFIG_FILE_NAME)
+}
+
+// Project config takes precedence over the user config
+pub fn load_defaults() -> io::Result<Defaults> {
+    let project = Config::load(&project_config_path())?.into_defaults()?;
+    let user = match user_config_path() {
+        Some(path) => Config::load(&path)?.into_defaults()?,
+        None => Defaults::default(),
+    };
+
+    Ok(project.or(user))
+}
+
+pub fn get(path: &Path, key: &str) -> io::Result<Option<String>> {
+    let table = load_table(path)?;
+
+    Ok(table.get(check_key(key)?).map(display_value))
+}
+
+pub fn set(path: &Path, key: &str, value: &str) -> io::Result<()> {
+    let mut table = load_table(path)?;
+    table.insert(String::from(check_key(key)?), toml::Value::from(value));
+    store_table(path, &table)
+}
+
+pub fn unset(path: &Path, key: &str) -> io::Result<()> {
+    let mut table = load_table(path)?;
+    table.remove(check_key(key)?);
+    store_table(path, &table)
+}
+
+// Keys and values of a config file, sorted by key
+pub fn list(path: &Path) -> io::Result<Vec<(String, String)>> {
+    Ok(load_table(path)?
+        .iter()
+        .map(|(key, value)| (key.clone(), display_value(value)))
+        .collect())
+}
+
+fn parse_config(contents: &str, path: &Path) -> io::Result<Config> {
+    toml::from_str(contents)
+        .map_err(|err| invalid_data(format!("{}: {}", path.display(), err.message())))
+}
+
+fn load_table(path: &Path) -> io::Result<toml::Table> {
+    if !path.is_file() {
+        return Ok(toml::Table::new());
+    }
+
+    let contents = fs::read_to_string(path)?;
+    contents.parse::<toml::Table>().map_err(invalid_data)
+}
+
+// Validates the table against the config before writing it
+fn store_table(path: &Path, table: &toml::Table) -> io::Result<()> {
+    let contents = toml::to_string(table).map_err(invalid_data)?;
+    parse_config(&contents, path)?.into_defaults()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, contents)
+}
+
+fn check_key(key: &str) -> io::Result<&str> {
+    if Config::KEYS.contains(&key) {
+        Ok(key)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "unknown config key '{}', expected one of: {}",
+                key,
+                Config::KEYS.join(", ")
+            ),
+        ))
+    }
+}
+
+fn display_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn invalid_data<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let contents = r#"
+namespace = "nmspc"
+cmake-version = "3.20.0"
+kind = "shared"
+author = "Jane Doe"
+test-framework = "catch2"
+template-registry = "https://github.com/my-company"
+"#;
+
+        let config = parse_config(contents, Path::new("config.toml")).unwrap();
+        let defaults = config.into_defaults().unwrap();
+        assert_eq!(defaults.name_space.as_deref(), Some("nmspc"));
+        assert_eq!(defaults.cmake_version.as_deref(), Some("3.20.0"));
+        assert_eq!(defaults.kind, Some(Kind::Shared));
+        assert_eq!(defaults.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(defaults.test_framework, Some(TestFramework::Catch2));
+        assert_eq!(
+            defaults.template_registry.as_deref(),
+            Some("https://github.com/my-company")
+        );
+    }
+
+    #[test]
+    fn test_parse_config_unknown_key() {
+        let result = parse_config("colour = \"blue\"", Path::new("config.toml"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_get_unset() {
+        let path = env::temp_dir().join("cpp-proj-gen-config/config.toml");
+        let _ = fs::remove_file(&path);
+
+        set(&path, "namespace", "nmspc").unwrap();
+        set(&path, "author", "Jane Doe").unwrap();
+        assert_eq!(get(&path, "namespace").unwrap().as_deref(), Some("nmspc"));
+        assert_eq!(
+            list(&path).unwrap(),
+            vec![
+                (String::from("author"), String::from("Jane Doe")),
+                (String::from("namespace"), String::from("nmspc")),
+            ]
+        );
+        assert!(set(&path, "kind", "plugin").is_err());
+        assert!(get(&path, "colour").is_err());
+
+        unset(&path, "namespace").unwrap();
+        assert_eq!(get(&path, "namespace").unwrap(), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_keys_match_config_fields() {
+        let mut table = toml::Table::new();
+        for key in Config::KEYS {
+            let value = match *key {
+                "test-framework" => "gtest",
+                _ => "static",
+            };
+            table.insert(String::from(*key), toml::Value::from(value));
+        }
+
+        let contents = toml::to_string(&table).unwrap();
+        assert!(parse_config(&contents, Path::new("config.toml")).is_ok());
+    }
+}
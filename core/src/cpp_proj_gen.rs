@@ -0,0 +1,2399 @@
+/*
+For 'unwrap' /sa https://doc.rust-lang.org/rust-by-example/error/option_unwrap.html
+For 'iter' and 'collect' /sa  https://doc.rust-lang.org/std/path/struct.PathBuf.html#examples
+For '?' /sa https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html#a-shortcut-for-propagating-errors-the--operator
+For HashMap /sa https://doc.rust-lang.org/std/collections/struct.HashMap.html
+*/
+
+use crate::abi_check::{self, AbiCheck};
+use crate::amalgamation;
+use crate::analysis;
+use crate::app_config::{self, AppConfig};
+use crate::build_options;
+use crate::builtin_template::BuiltinTemplate;
+use crate::cancel::CancelToken;
+use crate::cmake_version;
+use crate::codeowners::{self, CodeOwner};
+use crate::collisions;
+use crate::community_files::{self, Forge};
+use crate::compile_commands;
+use crate::compiler::{self, Compiler};
+use crate::concurrency::{self, Concurrency};
+use crate::conda_env;
+use crate::dependency::{self, PackageManager};
+use crate::dependency_updates::{self, DependencyUpdates};
+use crate::dist;
+use crate::docs::{self, DocsBackend};
+use crate::embed_resources;
+use crate::encoding;
+use crate::error::{self, Error};
+use crate::export;
+use crate::extra_languages::{self, ExtraLanguage};
+use crate::file_header;
+use crate::file_mode;
+use crate::fuzzing::{self, FuzzEngine};
+use crate::i18n::{self, I18n};
+use crate::ide_folders;
+use crate::language::{self, Language};
+use crate::limits::Limits;
+use crate::logging::{self, Logging};
+use crate::naming::{self, TestPattern};
+use crate::nix;
+use crate::normalize::{self, Indent, LineEndings, TrailingNewline};
+use crate::output::{OutputAdapter, OutputUrl};
+use crate::packaging::{self, Packaging};
+use crate::platforms::{self, Platform};
+use crate::presets::{self, Preset};
+use crate::project_manifest::{self, ProjectManifest, MANIFEST_PATH};
+use crate::project_model::{CmakeVarsMap, ExecutablesMap, ProjectModel};
+use crate::reporter::{GenEvent, Reporter};
+use crate::resources;
+use crate::scripts;
+use crate::source_listing::{self, SourceListing};
+use crate::streaming::{StreamText, VarsReader};
+use crate::submodules;
+use crate::task_runner::{self, TaskRunner};
+use crate::template_vars::{self, VarReplacer, VarValue};
+use crate::test_framework::{self, TestDiscovery, TestFramework};
+use crate::toolchain;
+use crate::warning::Warning;
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub const CMLT_FILE_NAME: &str = "CMakeLists.txt";
+const EMPTY_DIR_KEEP_FILE_NAME: &str = ".gitkeep";
+
+// Options
+#[derive(Debug, Clone)]
+pub struct Opt {
+    // Project name
+    pub name_space: Option<String>,
+
+    // Target name
+    pub target_name: String,
+
+    // CMake version
+    pub cmake_version: Option<String>,
+
+    // Output directory
+    pub output_dir: Option<PathBuf>,
+
+    pub output: Option<OutputUrl>,
+
+    // Project kind
+    pub kind: Kind,
+
+    // Template directories, later ones overlay earlier ones
+    pub template_dirs: Vec<PathBuf>,
+
+    // Template registry
+    pub template_registry: Option<String>,
+
+    // Offline mode
+    pub offline: bool,
+
+    // Remote template pack verification
+    pub template_sha256: Vec<String>,
+
+    pub verify_template_signature: bool,
+
+    pub allow_unverified: bool,
+
+    // Built-in template pack
+    pub builtin_template: BuiltinTemplate,
+
+    // Source listing
+    pub source_listing: SourceListing,
+
+    // Author
+    pub author: Option<String>,
+
+    pub author_email: Option<String>,
+
+    // License
+    pub license: Option<String>,
+
+    // Homepage
+    pub homepage: Option<String>,
+
+    // Project version
+    pub project_version: String,
+
+    // Community files
+    pub community_files: bool,
+
+    // Forge
+    pub forge: Option<Forge>,
+
+    // Code owners
+    pub codeowners: Vec<CodeOwner>,
+
+    pub codeowners_flavor: Option<Forge>,
+
+    // Package manager
+    pub package_manager: Option<PackageManager>,
+
+    // Dependency update bots
+    pub dependency_updates: Option<DependencyUpdates>,
+
+    // Documentation
+    pub docs: Option<DocsBackend>,
+
+    pub docs_material: bool,
+
+    // Timestamp
+    pub timestamp: Option<i64>,
+
+    // Language
+    pub language: Language,
+
+    // C++ standard
+    pub cxx_standard: String,
+
+    // C standard
+    pub c_standard: String,
+
+    // Extra languages
+    pub extra_languages: Vec<ExtraLanguage>,
+
+    // Sample sources
+    pub sample_sources: bool,
+
+    // Naming policy
+    pub header_ext: String,
+
+    pub source_ext: String,
+
+    pub test_pattern: TestPattern,
+
+    // Resources
+    pub resources: Option<PathBuf>,
+
+    pub embed_resources: bool,
+
+    // Internationalization
+    pub i18n: Option<I18n>,
+
+    // Logging
+    pub logging: Option<Logging>,
+
+    // Application config
+    pub app_config: Option<AppConfig>,
+
+    // Concurrency
+    pub concurrency: Option<Concurrency>,
+
+    // Compilation database
+    pub no_compile_commands: bool,
+
+    // Convenience scripts
+    pub scripts: bool,
+
+    // Task runner
+    pub task_runner: Option<TaskRunner>,
+
+    // Nix development shell
+    pub nix: bool,
+
+    // conda environment
+    pub conda_env: bool,
+
+    // Distribution packages
+    pub packaging: Vec<Packaging>,
+
+    // Source archive
+    pub dist: bool,
+
+    // Single header
+    pub amalgamate: bool,
+
+    // ABI compatibility
+    pub abi_check: Option<AbiCheck>,
+
+    // Platforms
+    pub platforms: Vec<Platform>,
+
+    // Preset
+    pub preset: Option<Preset>,
+
+    // Android Gradle module
+    pub android_gradle: bool,
+
+    pub graceful_shutdown: bool,
+
+    // Audio plugin codes
+    pub plugin_manufacturer_code: Option<String>,
+
+    pub plugin_code: Option<String>,
+
+    // Test framework
+    pub test_framework: Option<TestFramework>,
+
+    // CTest timeout
+    pub ctest_timeout: Option<u32>,
+
+    // CTest labels
+    pub test_labels: Vec<String>,
+
+    // CTest registration
+    pub test_discovery: Option<TestDiscovery>,
+
+    // CDash
+    pub cdash_drop_site: Option<String>,
+
+    // Fuzzing
+    pub fuzzing: Option<FuzzEngine>,
+
+    // Static analysis
+    pub static_analysis: bool,
+
+    // include-what-you-use
+    pub iwyu: bool,
+
+    // Compiler
+    pub compiler: Option<Compiler>,
+
+    // Toolchain pinning
+    pub toolchain_version: Option<String>,
+
+    // IDE folders
+    pub ide_folders: bool,
+
+    // Placeholders in empty directories
+    pub empty_dir_keep: Option<Option<String>>,
+
+    // Normalization of the rendered text files
+    pub line_endings: LineEndings,
+
+    pub trailing_newline: TrailingNewline,
+
+    pub indent: Indent,
+
+    pub tab_width: usize,
+
+    pub keep_bom: bool,
+
+    pub deny_warnings: bool,
+
+    // Git submodules
+    pub submodules: Vec<(String, String)>,
+
+    // Git repository
+    pub git_init: bool,
+
+    // Examples
+    pub examples: bool,
+
+    // Template variables
+    pub defines: Vec<(String, String)>,
+
+    // Interactive mode
+    pub interactive: bool,
+
+    // Tool check
+    pub check_tools: bool,
+
+    // Lock of the output directory
+    pub wait: bool,
+
+    pub no_lock: bool,
+
+    pub timeout: Option<u64>,
+
+    // Statistics
+    pub stats: bool,
+
+    // Discovery
+    pub list_presets: bool,
+
+    pub list_build_systems: bool,
+
+    pub list_test_frameworks: bool,
+
+    pub list_licenses: bool,
+
+    pub list_builtin_templates: bool,
+
+    pub json: bool,
+}
+
+// The defaults of the command line, without parsing one
+impl Default for Opt {
+    fn default() -> Self {
+        Opt {
+            name_space: None,
+            target_name: String::from("my-target"),
+            cmake_version: None,
+            output_dir: None,
+            output: None,
+            kind: Kind::Static,
+            template_dirs: Vec::new(),
+            template_registry: None,
+            offline: false,
+            template_sha256: Vec::new(),
+            verify_template_signature: false,
+            allow_unverified: false,
+            builtin_template: BuiltinTemplate::Standard,
+            source_listing: SourceListing::Explicit,
+            author: None,
+            author_email: None,
+            license: None,
+            homepage: None,
+            project_version: String::from("0.1.0"),
+            community_files: false,
+            forge: None,
+            codeowners: Vec::new(),
+            codeowners_flavor: None,
+            package_manager: None,
+            dependency_updates: None,
+            docs: None,
+            docs_material: false,
+            timestamp: None,
+            language: Language::Cpp,
+            cxx_standard: String::from("17"),
+            c_standard: String::from("11"),
+            extra_languages: Vec::new(),
+            sample_sources: false,
+            header_ext: String::from("h"),
+            source_ext: String::from("cpp"),
+            test_pattern: TestPattern::default(),
+            resources: None,
+            embed_resources: false,
+            i18n: None,
+            logging: None,
+            app_config: None,
+            concurrency: None,
+            no_compile_commands: false,
+            scripts: false,
+            task_runner: None,
+            nix: false,
+            conda_env: false,
+            packaging: Vec::new(),
+            dist: false,
+            amalgamate: false,
+            abi_check: None,
+            platforms: Vec::new(),
+            preset: None,
+            android_gradle: false,
+            graceful_shutdown: false,
+            plugin_manufacturer_code: None,
+            plugin_code: None,
+            test_framework: None,
+            ctest_timeout: None,
+            test_labels: Vec::new(),
+            test_discovery: None,
+            cdash_drop_site: None,
+            fuzzing: None,
+            static_analysis: false,
+            iwyu: false,
+            compiler: None,
+            toolchain_version: None,
+            ide_folders: false,
+            empty_dir_keep: None,
+            line_endings: LineEndings::Lf,
+            trailing_newline: TrailingNewline::Single,
+            indent: Indent::Keep,
+            tab_width: 4,
+            keep_bom: false,
+            deny_warnings: false,
+            submodules: Vec::new(),
+            git_init: false,
+            examples: false,
+            defines: Vec::new(),
+            interactive: false,
+            check_tools: false,
+            wait: false,
+            no_lock: false,
+            timeout: None,
+            stats: false,
+            list_presets: false,
+            list_build_systems: false,
+            list_test_frameworks: false,
+            list_licenses: false,
+            list_builtin_templates: false,
+            json: false,
+        }
+    }
+}
+
+// Kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Static,
+    Shared,
+    HeaderOnly,
+    Executable,
+    // A static library plus an application target linking it
+    LibAndApp,
+}
+
+impl Kind {
+    pub const VARIANTS: &'static [&'static str] = &[
+        "static",
+        "shared",
+        "header-only",
+        "executable",
+        "lib-and-app",
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Static => "static",
+            Kind::Shared => "shared",
+            Kind::HeaderOnly => "header-only",
+            Kind::Executable => "executable",
+            Kind::LibAndApp => "lib-and-app",
+        }
+    }
+
+    // The executable target, None for libraries
+    pub fn app_target(self) -> Option<&'static str> {
+        match self {
+            Kind::Executable => Some("@CMAKE_TARGET_NAME@"),
+            Kind::LibAndApp => Some("@CMAKE_TARGET_NAME@-app"),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "static" => Ok(Kind::Static),
+            "shared" => Ok(Kind::Shared),
+            "header-only" => Ok(Kind::HeaderOnly),
+            "executable" => Ok(Kind::Executable),
+            "lib-and-app" => Ok(Kind::LibAndApp),
+            _ => Err(format!(
+                "unknown kind '{}', expected one of: {}",
+                s,
+                Kind::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Absolute file path and rendered contents
+type FileContentsMap = BTreeMap<PathBuf, String>;
+// Absolute file path and copied contents
+type BinaryContentsMap = BTreeMap<PathBuf, Vec<u8>>;
+
+// Directory or rendered file gen() writes, paths include the output directory
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanEntry {
+    Dir(PathBuf),
+    File {
+        path: PathBuf,
+        contents: String,
+        executable: bool,
+    },
+    // Copied without rendering, e.g. an icon of a template pack
+    Binary {
+        path: PathBuf,
+        contents: Vec<u8>,
+        executable: bool,
+    },
+    // Too large to keep in memory, rendered or copied from the source while written
+    Stream {
+        path: PathBuf,
+        source: PathBuf,
+        // None copies the source as it is
+        text: Option<StreamText>,
+        keep_bom: bool,
+        executable: bool,
+    },
+    // Symbolic link, after everything else so that copies can stand in for it
+    Link {
+        path: PathBuf,
+        target: PathBuf,
+    },
+}
+
+impl PlanEntry {
+    pub fn path(&self) -> &Path {
+        match self {
+            PlanEntry::Dir(path)
+            | PlanEntry::File { path, .. }
+            | PlanEntry::Binary { path, .. }
+            | PlanEntry::Stream { path, .. }
+            | PlanEntry::Link { path, .. } => path,
+        }
+    }
+
+    pub fn is_file(&self) -> bool {
+        !matches!(self, PlanEntry::Dir(_))
+    }
+}
+
+// Timings and counts of one generation, e.g. for dashboards over bulk generation
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GenerationReport {
+    pub render_time: Duration,
+    pub write_time: Duration,
+    pub files: usize,
+    pub directories: usize,
+    pub bytes_written: u64,
+    pub warnings: Vec<Warning>,
+    // Paths created in order, up to the failing one on a partial write
+    pub written: Vec<PathBuf>,
+    // Those of written that were not there before, only these are rolled back
+    pub created: Vec<PathBuf>,
+}
+
+impl GenerationReport {
+    pub fn total_time(&self) -> Duration {
+        self.render_time + self.write_time
+    }
+
+    // Counts and paths of a write started at write_start, also for the report of a partial write
+    pub fn add_written(
+        self,
+        written: error::Result<GenerationReport>,
+        write_start: Instant,
+    ) -> error::Result<GenerationReport> {
+        let merge = |written: GenerationReport| GenerationReport {
+            render_time: self.render_time,
+            write_time: write_start.elapsed(),
+            warnings: self.warnings.clone(),
+            ..written
+        };
+
+        match written {
+            Ok(written) => Ok(merge(written)),
+            Err(Error::PartialWrite { report, source }) => Err(Error::PartialWrite {
+                report: Box::new(merge(*report)),
+                source,
+            }),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl fmt::Display for GenerationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Render:  {:?}", self.render_time)?;
+        writeln!(f, "Write:   {:?}", self.write_time)?;
+        writeln!(f, "Total:   {:?}", self.total_time())?;
+        write!(
+            f,
+            "Created: {} files, {} directories, {} bytes",
+            self.files, self.directories, self.bytes_written
+        )
+    }
+}
+
+// CppProjGen
+#[derive(Debug)]
+pub struct CppProjGen {
+    model: ProjectModel,
+    // Compiled on first use, reset by adding a var
+    var_replacer: OnceLock<VarReplacer>,
+    limits: Limits,
+    cancel: CancelToken,
+    opt: Opt,
+    out_dir: PathBuf,
+}
+
+impl CppProjGen {
+    pub fn new(opt: Opt) -> Self {
+        let model = ProjectModel::new(&opt);
+
+        Self::from_model(opt, model).add_features()
+    }
+
+    // Renders the model as it is, the options only tell how, e.g. line endings
+    pub fn from_model(opt: Opt, model: ProjectModel) -> Self {
+        Self {
+            model,
+            var_replacer: OnceLock::new(),
+            limits: Limits::default(),
+            cancel: CancelToken::default(),
+            out_dir: build_out_dir(&opt),
+            opt,
+        }
+    }
+
+    pub fn model(&self) -> &ProjectModel {
+        &self.model
+    }
+
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    pub fn opt(&self) -> &Opt {
+        &self.opt
+    }
+
+    pub fn set_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn set_cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    pub fn add_include_dir(mut self, dir: PathBuf) -> Self {
+        self = self.add_path_var("@INCLUDE_DIR@", &dir);
+
+        let local_include_dir: PathBuf = build_cmake_local_include_dir(&self.opt, dir);
+
+        self.add_toplevel_dir(local_include_dir)
+    }
+
+    pub fn add_source_dir(mut self, dir: PathBuf) -> Self {
+        self = self.add_path_var("@SOURCE_DIR@", &dir);
+
+        self.add_toplevel_dir(dir)
+    }
+
+    pub fn add_test_dir(mut self, dir: PathBuf) -> Self {
+        self = self.add_path_var("@TEST_DIR@", &dir);
+
+        self.add_toplevel_dir(dir)
+    }
+
+    // CMake files are text, so they get the lossy UTF-8 form of the path
+    fn add_path_var(mut self, var: &str, dir: &Path) -> Self {
+        self.model.vars.insert(
+            String::from(var),
+            VarValue::Text(dir.to_string_lossy().into_owned()),
+        );
+        self.var_replacer = OnceLock::new();
+        self.model
+            .path_vars
+            .insert(String::from(var), dir.to_path_buf());
+
+        self
+    }
+
+    pub fn add_toplevel_dir(mut self, dir: PathBuf) -> Self {
+        self.model.directories.push(dir);
+
+        self
+    }
+
+    // Text vars only, bools and lists need rendering
+    pub fn cmake_var(&self, var: &str) -> Option<&str> {
+        self.model.vars.get(var).and_then(VarValue::as_text)
+    }
+
+    // Template with the CMake vars replaced, for files outside of the generated project
+    pub fn render(&self, template: &str) -> String {
+        self.var_replacer()
+            .replace(&platforms::render_blocks(template, &self.opt.platforms))
+    }
+
+    fn var_replacer(&self) -> &VarReplacer {
+        self.var_replacer
+            .get_or_init(|| VarReplacer::new(&self.model.vars))
+    }
+
+    pub fn add_cmake_var(self, var: &str, value: &str) -> Self {
+        self.add_cmake_value(var, VarValue::from(value))
+    }
+
+    // Typed var, e.g. a bool rendered as ON or OFF by @VAR|cmake@
+    pub fn add_cmake_value(mut self, var: &str, value: VarValue) -> Self {
+        self.model.vars.insert(String::from(var), value);
+        self.var_replacer = OnceLock::new();
+
+        self
+    }
+
+    // Var set from outside, a warning if no template uses it
+    pub fn add_defined_var(mut self, var: &str, value: VarValue) -> Self {
+        self.model.defined_vars.insert(String::from(var));
+
+        self.add_cmake_value(var, value)
+    }
+
+    // e.g. a missing optional tool, reported with the warnings of planning
+    pub fn add_warning(mut self, warning: Warning) -> Self {
+        self.model.warnings.push(warning);
+
+        self
+    }
+
+    // Compared rendered, e.g. source/main.cpp is @SOURCE_DIR@/main.@SOURCE_FILE_EXT@
+    pub fn has_file(&self, path: &Path) -> bool {
+        let path = self.render_path(path);
+
+        path == self.model.cmake_lists_file
+            || self
+                .model
+                .file_paths()
+                .any(|other| self.render_path(other) == path)
+    }
+
+    // File relative to the output directory, path and template get the CMake vars replaced
+    // Adding a path twice replaces the former template
+    pub fn add_file(mut self, path: PathBuf, template: String) -> Self {
+        self.model.add_file(path, template);
+
+        self
+    }
+
+    // File copied as it is, only its path gets the CMake vars replaced
+    pub fn add_binary_file(mut self, path: PathBuf, contents: Vec<u8>) -> Self {
+        self.model.add_binary_file(path, contents);
+
+        self
+    }
+
+    // Large template read from source only while written, e.g. a bundled data set
+    // Text gets the vars replaced but no banner, platform blocks or normalization
+    pub fn add_streamed_file(mut self, path: PathBuf, source: PathBuf, binary: bool) -> Self {
+        self.model.add_streamed_file(path, source, binary);
+
+        self
+    }
+
+    // Symbolic link to a path relative to its directory, e.g. include/latest to v2
+    // Both get the CMake vars replaced, the target has to stay in the project
+    pub fn add_link(mut self, path: PathBuf, target: PathBuf) -> Self {
+        self.model.add_link(path, target);
+
+        self
+    }
+
+    // Files starting with a shebang are executable unless set otherwise
+    pub fn set_executable(mut self, path: PathBuf, executable: bool) -> Self {
+        self.model.executables.insert(path, executable);
+
+        self
+    }
+
+    // Replaces the built-in top-level CMakeLists.txt template of the kind
+    pub fn set_cmake_lists_template(mut self, template: String) -> Self {
+        self.model.cmake_lists_template = Some(template);
+
+        self
+    }
+
+    // Appended to the top-level CMakeLists.txt
+    pub fn add_cmake_snippet(self, snippet: String) -> Self {
+        let path = self.model.cmake_lists_file.clone();
+
+        self.add_cmake_snippet_to(path, snippet)
+    }
+
+    // Replaces @CMAKE_SNIPPETS@ in the file added with the same path
+    pub fn add_cmake_snippet_to(mut self, path: PathBuf, snippet: String) -> Self {
+        self.model.cmake_snippets.push((path, snippet));
+
+        self
+    }
+
+    pub fn validate(&self) -> error::Result<()> {
+        self.validate_paths()?;
+        collisions::validate(&self.opt, &self.build_top_level_dirs())?;
+        presets::validate(&self.opt)?;
+        resources::validate(&self.opt)?;
+        embed_resources::validate(&self.opt)?;
+        i18n::validate(&self.opt)?;
+        logging::validate(&self.opt)?;
+        app_config::validate(&self.opt)?;
+        concurrency::validate(&self.opt)?;
+        extra_languages::validate(&self.opt)?;
+        nix::validate(&self.opt)?;
+        toolchain::validate(&self.opt)?;
+        packaging::validate(&self.opt)?;
+        amalgamation::validate(&self.opt)?;
+        abi_check::validate(&self.opt)?;
+        naming::validate(&self.opt)?;
+        normalize::validate(&self.opt)?;
+        Ok(test_framework::validate(&self.opt)?)
+    }
+
+    // Untrusted template packs and requests must not write next to the project
+    fn validate_paths(&self) -> error::Result<()> {
+        let target_name = Path::new(&self.opt.target_name);
+        if target_name.components().count() != 1 || !is_below_root(target_name) {
+            return Err(Error::PathEscape {
+                path: target_name.to_path_buf(),
+            });
+        }
+
+        if let Some(file_name) = self.empty_dir_keep_file_name().map(Path::new) {
+            if file_name.components().count() != 1 || !is_below_root(file_name) {
+                return Err(Error::PathEscape {
+                    path: file_name.to_path_buf(),
+                });
+            }
+        }
+
+        let rendered_files = self.model.file_paths().map(|path| self.render_path(path));
+        if let Some(path) = self
+            .model
+            .directories
+            .iter()
+            .cloned()
+            .chain(rendered_files)
+            .find(|path| !is_below_root(path))
+        {
+            return Err(Error::PathEscape { path });
+        }
+
+        match self.model.links.iter().find(|(path, target)| {
+            !is_link_below_root(&self.render_path(path), &self.render_path(target))
+        }) {
+            Some((_, target)) => Err(Error::PathEscape {
+                path: target.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    // Writes through the output adapter, its finish() is up to the caller
+    pub fn gen(
+        &self,
+        output: &mut dyn OutputAdapter,
+        mut reporter: Option<&mut dyn Reporter>,
+    ) -> error::Result<GenerationReport> {
+        let (plan, report) = self.plan_to_write(
+            reporter
+                .as_mut()
+                .map(|reporter| &mut **reporter as &mut dyn Reporter),
+        )?;
+        output.prepare(&self.out_dir)?;
+
+        let write_start = Instant::now();
+        let written = create_all_paths(plan, output, reporter, &self.cancel);
+
+        report.add_written(written, write_start)
+    }
+
+    // Validated plan with a report of the render time and warnings, as gen() writes it
+    pub fn plan_to_write(
+        &self,
+        reporter: Option<&mut dyn Reporter>,
+    ) -> error::Result<(Vec<PlanEntry>, GenerationReport)> {
+        self.validate()?;
+
+        let render_start = Instant::now();
+        let plan = self.plan()?;
+        let warnings = self.warnings()?;
+        let render_time = render_start.elapsed();
+        if self.opt.deny_warnings && !warnings.is_empty() {
+            return Err(Error::DeniedWarnings(warnings));
+        }
+
+        if let Some(reporter) = reporter {
+            let files = plan.iter().filter(|entry| entry.is_file()).count();
+            reporter.event(&GenEvent::Planned {
+                directories: plan.len() - files,
+                files,
+            });
+            for entry in plan.iter().filter(|entry| entry.is_file()) {
+                reporter.event(&GenEvent::FileRendered {
+                    path: entry.path().to_path_buf(),
+                });
+            }
+        }
+
+        let report = GenerationReport {
+            render_time,
+            warnings,
+            ..GenerationReport::default()
+        };
+
+        Ok((plan, report))
+    }
+
+    // Warnings added so far and those of planning, e.g. vars no template uses
+    pub fn warnings(&self) -> error::Result<Vec<Warning>> {
+        let mut templates = vec![self.load_cmake_lists()?];
+        templates.extend(
+            self.model
+                .cmake_snippets
+                .iter()
+                .map(|(_, snippet)| snippet.clone()),
+        );
+        for (path, template) in &self.model.files {
+            templates.push(path.to_string_lossy().into_owned());
+            templates.push(template.clone());
+        }
+        for path in self.model.binary_files.iter().map(|(path, _)| path) {
+            templates.push(path.to_string_lossy().into_owned());
+        }
+        for (path, _, _) in &self.model.streamed_files {
+            templates.push(path.to_string_lossy().into_owned());
+        }
+        for (path, target) in &self.model.links {
+            templates.push(path.to_string_lossy().into_owned());
+            templates.push(target.to_string_lossy().into_owned());
+        }
+
+        let mut warnings = self.model.warnings.clone();
+        for var in &self.model.defined_vars {
+            let used = templates
+                .iter()
+                .any(|template| template_vars::uses_var(template, var));
+            if !used && !self.streamed_files_use_var(var)? {
+                warnings.push(Warning::UnusedVariable {
+                    name: String::from(var.trim_matches('@')),
+                });
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    // Line by line, the templates are too large to read whole
+    fn streamed_files_use_var(&self, var: &str) -> error::Result<bool> {
+        for (_, source, _) in self.model.streamed_files.iter().filter(|file| !file.2) {
+            let file = fs::File::open(source).map_err(Error::fs("read", source))?;
+            for line in BufReader::new(file).split(b'\n') {
+                let line = line.map_err(Error::fs("read", source))?;
+                if template_vars::uses_var(&String::from_utf8_lossy(&line), var) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    // Everything gen() writes, in order, with the rendered file contents
+    pub fn plan(&self) -> error::Result<Vec<PlanEntry>> {
+        let render_start = Instant::now();
+        let mut contents = FileContentsMap::new();
+        contents.insert(
+            make_absolute_path(&self.out_dir, &self.model.cmake_lists_file),
+            self.render_cmake_lists()?,
+        );
+
+        for (path, template) in &self.model.files {
+            self.limits.check_render_time(render_start)?;
+            self.cancel.check()?;
+            let rendered_path = self.render_path(path);
+            let rendered = file_header::add_banner(
+                &rendered_path,
+                self.render_template(path, template),
+                &self.opt,
+            );
+            contents.insert(make_absolute_path(&self.out_dir, &rendered_path), rendered);
+        }
+
+        let manifest = ProjectManifest::new(&self.opt, self.build_manifest_files());
+        contents.insert(
+            make_absolute_path(&self.out_dir, &PathBuf::from(MANIFEST_PATH)),
+            manifest.to_toml()?,
+        );
+        add_subdirectories(&mut contents, &self.out_dir);
+        for (path, text) in contents.iter_mut() {
+            *text = normalize::apply(path, text, &self.opt);
+        }
+        let mut binaries: BinaryContentsMap = self
+            .model
+            .binary_files
+            .iter()
+            .map(|(path, bytes)| {
+                let rendered_path = self.render_path(path);
+                (
+                    make_absolute_path(&self.out_dir, &rendered_path),
+                    bytes.clone(),
+                )
+            })
+            .collect();
+        let mut streamed = BTreeMap::new();
+        let mut streamed_size = 0;
+        for (path, source, binary) in &self.model.streamed_files {
+            let rendered_path = self.render_path(path);
+            let text = match binary {
+                true => None,
+                false => Some(StreamText {
+                    replacer: self.var_replacer().clone(),
+                    platforms: self.opt.platforms.clone(),
+                    banner: file_header::source_banner(&rendered_path, &self.opt),
+                    normalizer: normalize::LineNormalizer::new(&rendered_path, &self.opt),
+                }),
+            };
+            streamed_size += match (&text, self.limits.max_bytes) {
+                // Rendered once more just to count, only if the bytes are limited
+                (Some(text), Some(_)) => {
+                    let file = fs::File::open(source).map_err(Error::fs("read", source))?;
+                    let mut reader = VarsReader::new(file, text.clone(), self.opt.keep_bom);
+                    io::copy(&mut reader, &mut io::sink()).map_err(|err| {
+                        reader
+                            .encoding_error(source)
+                            .unwrap_or_else(|| Error::fs("read", source)(err))
+                    })?
+                }
+                _ => fs::metadata(source)
+                    .map_err(Error::fs("read", source))?
+                    .len(),
+            };
+            streamed.insert(
+                make_absolute_path(&self.out_dir, &rendered_path),
+                (source.clone(), text),
+            );
+        }
+        self.limits.check_render_time(render_start)?;
+        self.limits.check_output(
+            contents.len() + binaries.len() + streamed.len(),
+            contents
+                .values()
+                .map(|contents| contents.len() as u64)
+                .chain(binaries.values().map(|contents| contents.len() as u64))
+                .sum::<u64>()
+                + streamed_size,
+        )?;
+
+        let executables: ExecutablesMap = self
+            .model
+            .executables
+            .iter()
+            .map(|(path, executable)| {
+                let rendered_path = self.render_path(path);
+                (
+                    make_absolute_path(&self.out_dir, &rendered_path),
+                    *executable,
+                )
+            })
+            .collect();
+        let is_executable = |path: &PathBuf, contents: &[u8]| {
+            executables
+                .get(path)
+                .copied()
+                .unwrap_or_else(|| file_mode::has_shebang(contents))
+        };
+
+        let mut links: BTreeMap<PathBuf, PathBuf> = self
+            .model
+            .links
+            .iter()
+            .map(|(path, target)| {
+                let rendered_path = self.render_path(path);
+                (
+                    make_absolute_path(&self.out_dir, &rendered_path),
+                    self.render_path(target),
+                )
+            })
+            .collect();
+
+        let plan = self
+            .build_paths()
+            .into_iter()
+            .map(|path| {
+                match (
+                    contents.remove(&path),
+                    binaries.remove(&path),
+                    streamed.remove(&path),
+                    links.remove(&path),
+                ) {
+                    (Some(contents), _, _, _) => PlanEntry::File {
+                        executable: is_executable(&path, contents.as_bytes()),
+                        path,
+                        contents,
+                    },
+                    (None, Some(contents), _, _) => PlanEntry::Binary {
+                        executable: is_executable(&path, &contents),
+                        path,
+                        contents,
+                    },
+                    // Not read for a shebang, only executable if set so
+                    (None, None, Some((source, text)), _) => PlanEntry::Stream {
+                        executable: executables.get(&path).copied().unwrap_or(false),
+                        path,
+                        source,
+                        text,
+                        keep_bom: self.opt.keep_bom,
+                    },
+                    (None, None, None, Some(target)) => PlanEntry::Link { path, target },
+                    (None, None, None, None) => PlanEntry::Dir(path),
+                }
+            })
+            .collect();
+
+        let mut plan = match self.empty_dir_keep_file_name() {
+            Some(file_name) => add_empty_dir_keep_files(plan, file_name),
+            None => plan,
+        };
+        // Stable, so the links keep their order
+        plan.sort_by_key(|entry| matches!(entry, PlanEntry::Link { .. }));
+
+        Ok(plan)
+    }
+
+    fn empty_dir_keep_file_name(&self) -> Option<&str> {
+        self.opt
+            .empty_dir_keep
+            .as_ref()
+            .map(|file_name| file_name.as_deref().unwrap_or(EMPTY_DIR_KEEP_FILE_NAME))
+    }
+
+    pub fn build_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        for dir in &self.model.directories {
+            paths.push(make_absolute_path(&self.out_dir, dir));
+        }
+
+        paths.push(make_absolute_path(
+            &self.out_dir,
+            &self.model.cmake_lists_file,
+        ));
+
+        for path in self.model.file_paths() {
+            paths.push(make_absolute_path(&self.out_dir, &self.render_path(path)));
+        }
+
+        paths.push(make_absolute_path(
+            &self.out_dir,
+            &PathBuf::from(MANIFEST_PATH),
+        ));
+
+        paths.sort();
+        paths.dedup();
+
+        paths
+    }
+
+    // All generated files but the manifest itself
+    fn build_manifest_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = std::iter::once(self.model.cmake_lists_file.clone())
+            .chain(self.model.file_paths().map(|path| self.render_path(path)))
+            .map(|path| project_manifest::to_manifest_path(&path))
+            .collect();
+        files.sort();
+        files.dedup();
+
+        files
+    }
+
+    // e.g. include, source and test
+    fn build_top_level_dirs(&self) -> Vec<String> {
+        let rendered_files = self
+            .model
+            .files
+            .iter()
+            .map(|(path, _)| self.render_path(path));
+        let mut dirs: Vec<String> = self
+            .model
+            .directories
+            .iter()
+            .cloned()
+            .chain(rendered_files.filter(|path| path.components().count() > 1))
+            .filter_map(|path| {
+                path.components()
+                    .next()
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        dirs
+    }
+
+    fn add_features(self) -> Self {
+        let gen = language::apply(self);
+        let gen = export::apply(gen);
+        let gen = build_options::apply(gen);
+        let gen = test_framework::apply(gen);
+        let gen = fuzzing::apply(gen);
+        let gen = analysis::apply(gen);
+        let gen = presets::apply(gen);
+        let gen = community_files::apply(gen);
+        let gen = codeowners::apply(gen);
+        let gen = dependency_updates::apply(gen);
+        let gen = docs::apply(gen);
+        let gen = submodules::apply(gen);
+        let gen = platforms::apply(gen);
+        let gen = resources::apply(gen);
+        let gen = embed_resources::apply(gen);
+        let gen = i18n::apply(gen);
+        let gen = logging::apply(gen);
+        let gen = app_config::apply(gen);
+        let gen = concurrency::apply(gen);
+        let gen = extra_languages::apply(gen);
+        let gen = dependency::apply(gen);
+        let gen = compile_commands::apply(gen);
+        let gen = scripts::apply(gen);
+        let gen = task_runner::apply(gen);
+        let gen = nix::apply(gen);
+        let gen = conda_env::apply(gen);
+        let gen = packaging::apply(gen);
+        let gen = dist::apply(gen);
+        let gen = amalgamation::apply(gen);
+        let gen = abi_check::apply(gen);
+
+        let gen = compiler::apply(gen);
+        let gen = toolchain::apply(gen);
+
+        ide_folders::apply(gen)
+    }
+
+    // Per component, so path vars and non-UTF-8 names are kept as they are
+    fn render_path(&self, path: &Path) -> PathBuf {
+        path.components()
+            .map(|component| {
+                let part = component.as_os_str();
+                match part.to_str() {
+                    Some(part) => match self.model.path_vars.get(part) {
+                        Some(dir) => dir.clone(),
+                        None => PathBuf::from(self.var_replacer().replace(part)),
+                    },
+                    None => PathBuf::from(part),
+                }
+            })
+            .collect()
+    }
+
+    fn render_cmake_lists(&self) -> error::Result<String> {
+        let template = self.load_cmake_lists()?;
+
+        Ok(self.render_template(&self.model.cmake_lists_file, &template))
+    }
+
+    fn load_cmake_lists(&self) -> error::Result<String> {
+        let builtin = match &self.model.cmake_lists_template {
+            Some(template) => template,
+            None => self
+                .opt
+                .builtin_template
+                .cmake_lists_template(self.opt.kind),
+        };
+        let template = load_cmake_lists_template(&self.opt, builtin)?;
+
+        let template = compile_commands::render_export(&template, &self.opt);
+
+        Ok(source_listing::render_target_sources(&template, &self.opt))
+    }
+
+    // Snippets first, they may contain CMake vars and platform blocks themselves
+    fn render_template(&self, path: &Path, template: &str) -> String {
+        let snippets: String = self
+            .model
+            .cmake_snippets
+            .iter()
+            .filter(|(snippet_path, _)| snippet_path == path)
+            .map(|(_, snippet)| format!("\n{}\n", snippet))
+            .collect();
+
+        let template = template.replace("@CMAKE_SNIPPETS@", &snippets);
+
+        self.render(&template)
+    }
+}
+
+// Vars every project has, e.g. @CMAKE_TARGET_NAME@
+pub fn build_cmake_vars(opt: &Opt) -> CmakeVarsMap {
+    let (generation_year, generation_date) = build_generation_date(opt.timestamp);
+
+    [
+        (
+            String::from("@CMAKE_MINIMUM_VERSION@"),
+            opt.cmake_version
+                .clone()
+                .unwrap_or_else(|| cmake_version::infer(opt).to_string()),
+        ),
+        (
+            String::from("@CMAKE_TARGET_NAME@"),
+            String::from(&opt.target_name),
+        ),
+        (
+            String::from("@CMAKE_PROJECT_NAME@"),
+            build_cmake_project_name(opt, "-"),
+        ),
+        (
+            String::from("@INCLUDE_DOMAIN_DIR@"),
+            build_cmake_project_name(opt, "/"),
+        ),
+        (
+            String::from("@CMAKE_LIBRARY_TYPE@"),
+            String::from(build_cmake_library_type(opt.kind)),
+        ),
+        (
+            String::from("@AUTHOR@"),
+            opt.author.clone().unwrap_or_default(),
+        ),
+        (
+            String::from("@LICENSE@"),
+            opt.license.clone().unwrap_or_default(),
+        ),
+        (
+            String::from("@PROJECT_VERSION@"),
+            String::from(&opt.project_version),
+        ),
+        (String::from("@GENERATION_YEAR@"), generation_year),
+        (String::from("@GENERATION_DATE@"), generation_date),
+    ]
+    .iter()
+    .map(|(var, value)| (var.clone(), VarValue::from(value.as_str())))
+    .collect()
+}
+
+fn build_cmake_local_include_dir(opt: &Opt, dir: PathBuf) -> PathBuf {
+    match &opt.name_space {
+        // e.g. include/name-space/target-name
+        Some(name_space) => [dir, PathBuf::from(name_space), PathBuf::from(&opt.target_name)]
+            .iter()
+            .collect(),
+        // e.g. include/target-name
+        None => [dir, PathBuf::from(&opt.target_name)].iter().collect(),
+    }
+}
+
+fn build_cmake_library_type(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Shared => "SHARED",
+        _ => "STATIC",
+    }
+}
+
+fn cmake_lists_template_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Static | Kind::Shared => "CMakeLists.txt.in",
+        Kind::HeaderOnly => "CMakeLists.header-only.txt.in",
+        Kind::Executable => "CMakeLists.executable.txt.in",
+        Kind::LibAndApp => "CMakeLists.lib-and-app.txt.in",
+    }
+}
+
+// The last template directory having the template wins, otherwise the built-in one
+// CMakeLists.txt of any depth, e.g. from template packs, is added to the closest one above it
+fn add_subdirectories(contents: &mut FileContentsMap, out_dir: &Path) {
+    let cmake_lists_dirs: Vec<PathBuf> = contents
+        .keys()
+        .filter(|path| path.file_name() == Some(CMLT_FILE_NAME.as_ref()))
+        .filter_map(|path| path.parent())
+        .filter(|dir| *dir != out_dir)
+        .map(Path::to_path_buf)
+        .collect();
+
+    for dir in cmake_lists_dirs {
+        let parent = match dir
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor| ancestor.starts_with(out_dir))
+            .find(|ancestor| contents.contains_key(&ancestor.join(CMLT_FILE_NAME)))
+        {
+            Some(parent) => parent,
+            None => continue,
+        };
+        let relative_dir = match dir.strip_prefix(parent) {
+            Ok(relative_dir) => project_manifest::to_manifest_path(relative_dir),
+            Err(_) => continue,
+        };
+
+        let parent_contents = match contents.get_mut(&parent.join(CMLT_FILE_NAME)) {
+            Some(parent_contents) => parent_contents,
+            None => continue,
+        };
+        // e.g. add_subdirectory(test) of the test framework, or with a binary directory
+        let call = format!("add_subdirectory({}", relative_dir);
+        if [")", " "]
+            .iter()
+            .any(|end| parent_contents.contains(&format!("{}{}", call, end)))
+        {
+            continue;
+        }
+        if !parent_contents.is_empty() && !parent_contents.ends_with('\n') {
+            parent_contents.push('\n');
+        }
+        parent_contents.push_str(&format!("{})\n", call));
+    }
+}
+
+// Directories git would drop get an empty file, e.g. test/.gitkeep, after them in the sorted plan
+fn add_empty_dir_keep_files(plan: Vec<PlanEntry>, file_name: &str) -> Vec<PlanEntry> {
+    let mut result = Vec::with_capacity(plan.len());
+
+    for (index, entry) in plan.iter().enumerate() {
+        result.push(entry.clone());
+
+        let dir = match entry {
+            PlanEntry::Dir(dir) => dir,
+            PlanEntry::File { .. }
+            | PlanEntry::Binary { .. }
+            | PlanEntry::Stream { .. }
+            | PlanEntry::Link { .. } => continue,
+        };
+        // Sorted, so anything below the directory follows it directly
+        let is_empty = plan
+            .get(index + 1)
+            .is_none_or(|next| !next.path().starts_with(dir));
+        if is_empty {
+            result.push(PlanEntry::File {
+                path: dir.join(file_name),
+                contents: String::new(),
+                executable: false,
+            });
+        }
+    }
+
+    result
+}
+
+fn load_cmake_lists_template(opt: &Opt, builtin: &str) -> error::Result<String> {
+    for template_dir in opt.template_dirs.iter().rev() {
+        let path = template_dir.join(cmake_lists_template_name(opt.kind));
+        if path.is_file() {
+            return encoding::read_text(&path, opt.keep_bom);
+        }
+    }
+
+    Ok(String::from(builtin))
+}
+
+fn build_out_dir(opt: &Opt) -> PathBuf {
+    let parent = match &opt.output_dir {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().unwrap(),
+    };
+
+    let out_dir: PathBuf = [parent, PathBuf::from(&opt.target_name)].iter().collect();
+
+    out_dir
+}
+
+// Relative without '..', e.g. docs/guide.md but not ../guide.md or /etc/guide.md
+pub fn is_below_root(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+// Link target that resolves inside the project, e.g. v2 or ../docs for include/latest but not ../../x
+fn is_link_below_root(link: &Path, target: &Path) -> bool {
+    let depth = link
+        .parent()
+        .map_or(0, |parent| parent.components().count());
+
+    target
+        .components()
+        .try_fold(depth, |depth, component| match component {
+            Component::Normal(_) => Some(depth + 1),
+            Component::CurDir => Some(depth),
+            Component::ParentDir => depth.checked_sub(1),
+            Component::RootDir | Component::Prefix(_) => None,
+        })
+        .is_some()
+}
+
+// Joins without checks, gen() validates the paths before
+fn make_absolute_path(out_dir: &PathBuf, dir: &PathBuf) -> PathBuf {
+    [out_dir, dir].iter().collect()
+}
+
+// Year, month and day of a Unix timestamp in UTC
+pub fn civil_from_timestamp(timestamp: i64) -> (i64, i64, i64) {
+    // Days since 1970-01-01 to a civil date /sa http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = timestamp.div_euclid(86400) + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+// No wall-clock time is embedded, templates only get a date if one is passed in
+fn build_generation_date(timestamp: Option<i64>) -> (String, String) {
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => return (String::new(), String::new()),
+    };
+
+    let (year, _, _) = civil_from_timestamp(timestamp);
+
+    (year.to_string(), build_iso_date(timestamp))
+}
+
+// e.g. 2024-02-29
+pub fn build_iso_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_timestamp(timestamp);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// For files that need a date anyway, e.g. changelogs, the time of generation without --timestamp
+pub fn timestamp_or_now(timestamp: Option<i64>) -> i64 {
+    timestamp.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as i64)
+    })
+}
+
+fn build_cmake_project_name(opt: &Opt, delimiter: &str) -> String {
+    match &opt.name_space {
+        Some(name_space) => format!("{}{}{}", name_space, delimiter, &opt.target_name),
+        None => String::from(&opt.target_name),
+    }
+}
+
+// Stops at the first failing path, with the paths written before in a PartialWrite error
+// Cancelling removes the paths created before, replaced files keep their new contents
+fn create_all_paths(
+    plan: Vec<PlanEntry>,
+    output: &mut dyn OutputAdapter,
+    mut reporter: Option<&mut dyn Reporter>,
+    cancel: &CancelToken,
+) -> error::Result<GenerationReport> {
+    let mut report = GenerationReport::default();
+    let total = plan.len();
+
+    for (index, entry) in plan.into_iter().enumerate() {
+        if let Err(err) = cancel.check() {
+            roll_back(&report.created, output)?;
+            return Err(err);
+        }
+
+        let existed = output.exists(entry.path());
+        let bytes = match create_path(&entry, output) {
+            Ok(bytes) => bytes,
+            Err(source) => return Err(stop_writing(report, source)),
+        };
+        record_written(&entry, existed, &mut report);
+        let event = record_created(entry, bytes, &mut report, index + 1, total);
+
+        if let Some(reporter) = reporter.as_mut() {
+            reporter.event(&event);
+        }
+    }
+
+    Ok(report)
+}
+
+// Bytes written, none for directories and links
+pub fn create_path(entry: &PlanEntry, output: &mut dyn OutputAdapter) -> error::Result<u64> {
+    match entry {
+        PlanEntry::File {
+            path,
+            contents,
+            executable,
+        } => output
+            .write_file(path, contents.as_bytes(), *executable)
+            .map(|()| contents.len() as u64),
+        PlanEntry::Binary {
+            path,
+            contents,
+            executable,
+        } => output
+            .write_file(path, contents, *executable)
+            .map(|()| contents.len() as u64),
+        PlanEntry::Stream {
+            path,
+            source,
+            text,
+            keep_bom,
+            executable,
+        } => {
+            let file = fs::File::open(source).map_err(Error::fs("read", source))?;
+            let text = match text {
+                Some(text) => text.clone(),
+                None => return output.write_stream(path, &mut BufReader::new(file), *executable),
+            };
+            let mut reader = VarsReader::new(file, text, *keep_bom);
+
+            output
+                .write_stream(path, &mut reader, *executable)
+                .map_err(|err| reader.encoding_error(source).unwrap_or(err))
+        }
+        PlanEntry::Link { path, target } => output.create_link(path, target).map(|()| 0),
+        PlanEntry::Dir(path) => output.create_dir(path).map(|()| 0),
+    }
+}
+
+// Keeps the path for the report of a partial write and, if new, for rolling back
+pub fn record_written(entry: &PlanEntry, existed: bool, report: &mut GenerationReport) {
+    report.written.push(entry.path().to_path_buf());
+    if !existed {
+        report.created.push(entry.path().to_path_buf());
+    }
+}
+
+// Counts a created path and describes it for reporters
+pub fn record_created(
+    entry: PlanEntry,
+    bytes: u64,
+    report: &mut GenerationReport,
+    step: usize,
+    total: usize,
+) -> GenEvent {
+    match entry {
+        PlanEntry::File { path, .. }
+        | PlanEntry::Binary { path, .. }
+        | PlanEntry::Stream { path, .. } => {
+            report.files += 1;
+            report.bytes_written += bytes;
+
+            GenEvent::FileWritten {
+                step,
+                total,
+                path,
+                bytes,
+            }
+        }
+        PlanEntry::Link { path, target } => {
+            report.files += 1;
+
+            GenEvent::LinkCreated {
+                step,
+                total,
+                path,
+                target,
+            }
+        }
+        PlanEntry::Dir(path) => {
+            report.directories += 1;
+
+            GenEvent::DirCreated { step, total, path }
+        }
+    }
+}
+
+// The error of the failing path, with the paths written before if any
+pub fn stop_writing(report: GenerationReport, source: Error) -> Error {
+    match report.written.is_empty() {
+        true => source,
+        false => Error::PartialWrite {
+            report: Box::new(report),
+            source: Box::new(source),
+        },
+    }
+}
+
+// Last written first, so directories are empty when their turn comes
+pub fn roll_back(written: &[PathBuf], output: &mut dyn OutputAdapter) -> error::Result<()> {
+    for path in written.iter().rev() {
+        output.remove(path)?;
+    }
+
+    Ok(())
+}
+
+// Unit tests
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::let_and_return)]
+mod tests {
+    use super::*;
+    use crate::output::{LocalOutput, MemoryEntry, MemoryOutput};
+    use std::fs;
+
+    fn create_test_opt() -> Opt {
+        let opt = Opt {
+            name_space: Some(String::from("nmspc")),
+            target_name: String::from("tgtnm"),
+            cmake_version: Some(String::from("1.23.4")),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        opt
+    }
+
+    #[test]
+    fn test_path_vec_len() {
+        let opt = create_test_opt();
+
+        let cpp_proj_gen = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_toplevel_dir(PathBuf::from("test"))
+            .add_source_dir(PathBuf::from("source"));
+
+        let paths = cpp_proj_gen.build_paths();
+        assert_eq!(paths.len(), 7);
+    }
+
+    #[test]
+    fn test_sample_sources() {
+        let opt = Opt {
+            sample_sources: true,
+            ..create_test_opt()
+        };
+
+        let cpp_proj_gen = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_toplevel_dir(PathBuf::from("test"))
+            .add_source_dir(PathBuf::from("source"));
+
+        let paths = cpp_proj_gen.build_paths();
+        assert_eq!(paths.len(), 9);
+
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/tgtnm/include/nmspc/tgtnm/tgtnm.h"
+        )));
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/tgtnm/source/tgtnm.cpp")));
+    }
+
+    #[test]
+    fn test_path_vec_items() {
+        let opt = create_test_opt();
+
+        let cpp_proj_gen = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_toplevel_dir(PathBuf::from("test"))
+            .add_source_dir(PathBuf::from("source"));
+
+        let paths = cpp_proj_gen.build_paths();
+
+        assert_eq!(
+            paths.contains(&PathBuf::from("test_out_dir/tgtnm/include/nmspc/tgtnm")),
+            true
+        );
+
+        assert_eq!(
+            paths.contains(&PathBuf::from("test_out_dir/tgtnm/test")),
+            true
+        );
+
+        assert_eq!(
+            paths.contains(&PathBuf::from("test_out_dir/tgtnm/source")),
+            true
+        );
+
+        assert_eq!(
+            paths.contains(&PathBuf::from("test_out_dir/tgtnm/CMakeLists.txt")),
+            true
+        );
+
+        println!("{:#?}", paths);
+    }
+
+    #[test]
+    fn test_cmake_vars() {
+        let opt = create_test_opt();
+
+        let cpp_proj_gen = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_toplevel_dir(PathBuf::from("test"))
+            .add_source_dir(PathBuf::from("source"));
+
+        println!("{:#?}", cpp_proj_gen.model.vars);
+
+        let result = cpp_proj_gen
+            .var_replacer()
+            .replace(BuiltinTemplate::Standard.cmake_lists_template(Kind::Static));
+        println!("{}", result);
+    }
+
+    #[test]
+    fn test_include_dir_without_namespace() {
+        let opt = Opt {
+            name_space: None,
+            target_name: String::from("tgtnm"),
+            cmake_version: Some(String::from("1.23.4")),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        let cpp_proj_gen = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_toplevel_dir(PathBuf::from("test"))
+            .add_source_dir(PathBuf::from("source"));
+
+        let paths = cpp_proj_gen.build_paths();
+        // println!("{:#?}", paths);
+
+        assert_eq!(
+            paths.contains(&PathBuf::from("test_out_dir/tgtnm/include/tgtnm")),
+            true
+        );
+
+        // let result = cpp_proj_gen.var_replacer().replace(template);
+        // println!("{}", result);
+    }
+
+    #[test]
+    fn test_build_generation_date() {
+        assert_eq!(build_generation_date(None), (String::new(), String::new()));
+        assert_eq!(
+            build_generation_date(Some(0)),
+            (String::from("1970"), String::from("1970-01-01"))
+        );
+        assert_eq!(
+            build_generation_date(Some(1709251199)),
+            (String::from("2024"), String::from("2024-02-29"))
+        );
+    }
+
+    #[test]
+    fn test_build_paths_sorted() {
+        let cpp_proj_gen = CppProjGen::new(create_test_opt())
+            .add_toplevel_dir(PathBuf::from("test"))
+            .add_test_dir(PathBuf::from("test"))
+            .add_source_dir(PathBuf::from("source"));
+
+        let paths = cpp_proj_gen.build_paths();
+        let mut expected = paths.clone();
+        expected.sort();
+        expected.dedup();
+
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_generation_report() {
+        let report = GenerationReport {
+            render_time: Duration::from_millis(2),
+            write_time: Duration::from_millis(3),
+            files: 4,
+            directories: 5,
+            bytes_written: 678,
+            warnings: Vec::new(),
+            written: Vec::new(),
+            created: Vec::new(),
+        };
+
+        assert_eq!(report.total_time(), Duration::from_millis(5));
+        assert!(report
+            .to_string()
+            .ends_with("Created: 4 files, 5 directories, 678 bytes"));
+    }
+
+    #[test]
+    fn test_build_top_level_dirs() {
+        let opt = Opt {
+            test_framework: Some(TestFramework::GTest),
+            ..create_test_opt()
+        };
+        let cpp_proj_gen = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .add_test_dir(PathBuf::from("test"));
+
+        assert_eq!(
+            cpp_proj_gen.build_top_level_dirs(),
+            vec!["cmake", "include", "source", "test"]
+        );
+    }
+
+    #[test]
+    fn test_cmake_lists_per_kind() {
+        for (kind, expected) in [
+            (Kind::Static, "add_library(tgtnm STATIC"),
+            (Kind::Shared, "add_library(tgtnm SHARED"),
+            (Kind::HeaderOnly, "add_library(tgtnm INTERFACE"),
+            (Kind::Executable, "add_executable(tgtnm"),
+            (Kind::LibAndApp, "add_executable(tgtnm-app"),
+        ] {
+            let opt = Opt {
+                kind,
+                ..create_test_opt()
+            };
+            let cpp_proj_gen = CppProjGen::new(opt).add_source_dir(PathBuf::from("source"));
+
+            let result = cpp_proj_gen
+                .var_replacer()
+                .replace(BuiltinTemplate::Standard.cmake_lists_template(kind));
+            assert!(result.contains(expected));
+        }
+    }
+
+    #[test]
+    fn test_reporter_progress() {
+        struct Steps(Vec<(usize, usize)>);
+        impl Reporter for Steps {
+            fn event(&mut self, event: &GenEvent) {
+                if let Some((step, total, _)) = event.progress() {
+                    self.0.push((step, total));
+                }
+            }
+        }
+
+        let out_dir = std::env::temp_dir().join("cpp-proj-gen-reporter");
+        let _ = fs::remove_dir_all(&out_dir);
+        let plan = vec![
+            PlanEntry::Dir(out_dir.join("a")),
+            PlanEntry::File {
+                path: out_dir.join("a/b.txt"),
+                contents: String::from("b"),
+                executable: false,
+            },
+        ];
+
+        let mut steps = Steps(Vec::new());
+        let report = create_all_paths(
+            plan,
+            &mut LocalOutput,
+            Some(&mut steps),
+            &CancelToken::default(),
+        )
+        .unwrap();
+        let _ = fs::remove_dir_all(&out_dir);
+
+        assert_eq!(steps.0, vec![(1, 2), (2, 2)]);
+        assert_eq!((report.directories, report.files), (1, 1));
+    }
+
+    #[test]
+    fn test_cancel_rolls_back() {
+        // Cancels like a GUI would while the second path is written
+        struct CancelAt(usize, CancelToken);
+        impl Reporter for CancelAt {
+            fn event(&mut self, event: &GenEvent) {
+                if event.progress().is_some_and(|(step, _, _)| step == self.0) {
+                    self.1.cancel();
+                }
+            }
+        }
+
+        let out_dir = PathBuf::from("out");
+        let plan = vec![
+            PlanEntry::Dir(out_dir.join("a")),
+            PlanEntry::File {
+                path: out_dir.join("a/b.txt"),
+                contents: String::from("b"),
+                executable: false,
+            },
+            PlanEntry::Dir(out_dir.join("c")),
+        ];
+        let cancel = CancelToken::default();
+        let mut output = MemoryOutput::default();
+
+        let result = create_all_paths(
+            plan,
+            &mut output,
+            Some(&mut CancelAt(2, cancel.clone())),
+            &cancel,
+        );
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(output.entries().is_empty());
+
+        let gen = CppProjGen::new(create_test_opt()).set_cancel_token(cancel);
+        assert!(matches!(gen.plan(), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn test_cancel_keeps_existing_paths() {
+        struct CancelAt(usize, CancelToken);
+        impl Reporter for CancelAt {
+            fn event(&mut self, event: &GenEvent) {
+                if event.progress().is_some_and(|(step, _, _)| step == self.0) {
+                    self.1.cancel();
+                }
+            }
+        }
+
+        // Regenerating into a project with a.txt, a user's file
+        let out_dir = std::env::temp_dir().join("cpp-proj-gen-cancel-keeps-existing");
+        let _ = fs::remove_dir_all(&out_dir);
+        fs::create_dir_all(&out_dir).unwrap();
+        fs::write(out_dir.join("a.txt"), "old").unwrap();
+        let file = |path: &str| PlanEntry::File {
+            path: out_dir.join(path),
+            contents: String::from("new"),
+            executable: false,
+        };
+        let plan = vec![file("a.txt"), file("b.txt"), file("c.txt")];
+        let cancel = CancelToken::default();
+
+        let result = create_all_paths(
+            plan,
+            &mut LocalOutput,
+            Some(&mut CancelAt(2, cancel.clone())),
+            &cancel,
+        );
+        let a = fs::read_to_string(out_dir.join("a.txt"));
+        let b = out_dir.join("b.txt").exists();
+        let _ = fs::remove_dir_all(&out_dir);
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(a.unwrap(), "new");
+        assert!(!b);
+    }
+
+    #[test]
+    fn test_partial_write() {
+        let out_dir = std::env::temp_dir().join("cpp-proj-gen-partial-write");
+        let _ = fs::remove_dir_all(&out_dir);
+        let file = |path: &str| PlanEntry::File {
+            path: out_dir.join(path),
+            contents: String::from("x"),
+            executable: false,
+        };
+        // A file is in the way of the last one
+        let plan = vec![
+            PlanEntry::Dir(out_dir.join("a")),
+            file("a/b.txt"),
+            file("a/b.txt/c.txt"),
+        ];
+
+        let err =
+            create_all_paths(plan, &mut LocalOutput, None, &CancelToken::default()).unwrap_err();
+        let _ = fs::remove_dir_all(&out_dir);
+
+        match err {
+            Error::PartialWrite { report, source } => {
+                assert_eq!(
+                    report.written,
+                    vec![out_dir.join("a"), out_dir.join("a/b.txt")]
+                );
+                assert_eq!((report.directories, report.files), (1, 1));
+                assert!(matches!(*source, Error::Fs { .. }));
+            }
+            err => panic!("expected a partial write, got {}", err),
+        }
+    }
+
+    #[test]
+    fn test_gen_rejects_escaping_paths() {
+        let gen = CppProjGen::new(create_test_opt())
+            .add_cmake_var("@ESCAPE@", "../..")
+            .add_file(PathBuf::from("@ESCAPE@/evil.txt"), String::new());
+        let mut output = MemoryOutput::default();
+
+        assert!(matches!(
+            gen.gen(&mut output, None),
+            Err(Error::PathEscape { .. })
+        ));
+        assert!(output.entries().is_empty());
+
+        for dir in &["../outside", "include/../../outside", "/etc", "/"] {
+            let gen = CppProjGen::new(create_test_opt()).add_toplevel_dir(PathBuf::from(dir));
+            assert!(matches!(gen.validate(), Err(Error::PathEscape { .. })));
+        }
+        for target_name in &["..", "../tgtnm", "/tmp/tgtnm", "nested/tgtnm"] {
+            let opt = Opt {
+                target_name: String::from(*target_name),
+                ..create_test_opt()
+            };
+            assert!(matches!(
+                CppProjGen::new(opt).validate(),
+                Err(Error::PathEscape { .. })
+            ));
+        }
+
+        assert!(is_below_root(Path::new("docs/./guide.md")));
+        assert!(!is_below_root(Path::new("docs/../../guide.md")));
+        assert!(!is_below_root(Path::new("/etc/guide.md")));
+    }
+
+    #[test]
+    fn test_gen_warnings() {
+        let create_test_gen = |opt: Opt| {
+            CppProjGen::new(opt)
+                .add_defined_var("@VENDOR@", VarValue::from("acme"))
+                .add_defined_var("@VENDRO@", VarValue::from("acme"))
+                .add_file(PathBuf::from("vendor.txt"), String::from("@VENDOR|upper@"))
+                .add_warning(Warning::Other(String::from("cmake too old")))
+        };
+
+        let mut output = MemoryOutput::default();
+        let report = create_test_gen(create_test_opt())
+            .gen(&mut output, None)
+            .unwrap();
+        assert_eq!(
+            report.warnings,
+            vec![
+                Warning::Other(String::from("cmake too old")),
+                Warning::UnusedVariable {
+                    name: String::from("VENDRO")
+                },
+            ]
+        );
+
+        let opt = Opt {
+            deny_warnings: true,
+            ..create_test_opt()
+        };
+        let mut output = MemoryOutput::default();
+        let err = create_test_gen(opt).gen(&mut output, None).unwrap_err();
+        assert!(matches!(err, Error::DeniedWarnings(ref warnings) if warnings.len() == 2));
+        assert!(err
+            .to_string()
+            .contains("VENDRO is set but used by no template"));
+        assert!(output.entries().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let out_dir = PathBuf::from(OsStr::from_bytes(b"out-\xff"));
+        let test_dir = PathBuf::from(OsStr::from_bytes(b"t\xe9st"));
+        let opt = Opt {
+            output_dir: Some(out_dir.clone()),
+            ..create_test_opt()
+        };
+        let gen = CppProjGen::new(opt)
+            .add_test_dir(test_dir.clone())
+            .add_file(PathBuf::from("@TEST_DIR@/data.txt"), String::new());
+        let mut output = MemoryOutput::default();
+        gen.gen(&mut output, None).unwrap();
+
+        let expected = out_dir.join("tgtnm").join(&test_dir).join("data.txt");
+        assert!(output.entries().iter().any(|(path, _)| *path == expected));
+        assert_eq!(gen.cmake_var("@TEST_DIR@"), Some("t\u{fffd}st"));
+    }
+
+    #[test]
+    fn test_add_subdirectories() {
+        let out_dir = Path::new("out/tgtnm");
+        let mut contents: FileContentsMap = [
+            ("CMakeLists.txt", "project(tgtnm)\nadd_subdirectory(test)\n"),
+            ("test/CMakeLists.txt", ""),
+            ("tools/CMakeLists.txt", "project(tools)"),
+            ("tools/gen/CMakeLists.txt", ""),
+            ("docs/api/CMakeLists.txt", ""),
+        ]
+        .iter()
+        .map(|(path, contents)| (out_dir.join(path), String::from(*contents)))
+        .collect();
+        add_subdirectories(&mut contents, out_dir);
+
+        assert_eq!(
+            contents[&out_dir.join("CMakeLists.txt")],
+            "project(tgtnm)\nadd_subdirectory(test)\nadd_subdirectory(docs/api)\nadd_subdirectory(tools)\n"
+        );
+        assert_eq!(
+            contents[&out_dir.join("tools/CMakeLists.txt")],
+            "project(tools)\nadd_subdirectory(gen)\n"
+        );
+    }
+
+    #[test]
+    fn test_plan() {
+        let plan = CppProjGen::new(create_test_opt())
+            .add_test_dir(PathBuf::from("test"))
+            .plan()
+            .unwrap();
+
+        assert!(plan.contains(&PlanEntry::Dir(PathBuf::from("test_out_dir/tgtnm/test"))));
+        let cmake_lists = plan
+            .iter()
+            .find(|entry| entry.path() == Path::new("test_out_dir/tgtnm/CMakeLists.txt"))
+            .unwrap();
+        assert!(
+            matches!(cmake_lists, PlanEntry::File { contents, .. } if contents.contains("project("))
+        );
+    }
+
+    #[test]
+    fn test_plan_binary_files() {
+        let icon = vec![0, 0, 1, 0, 0xff, b'@'];
+        let plan = CppProjGen::new(create_test_opt())
+            .add_file(PathBuf::from("res/@CMAKE_TARGET_NAME@.ico"), String::new())
+            .add_binary_file(PathBuf::from("res/@CMAKE_TARGET_NAME@.ico"), icon.clone())
+            .plan()
+            .unwrap();
+
+        assert!(plan.contains(&PlanEntry::Binary {
+            path: PathBuf::from("test_out_dir/tgtnm/res/tgtnm.ico"),
+            contents: icon,
+            executable: false,
+        }));
+        assert!(plan
+            .iter()
+            .any(|entry| matches!(entry, PlanEntry::File { contents, .. } if contents.contains("res/tgtnm.ico"))));
+    }
+
+    #[test]
+    fn test_gen_streamed_files() {
+        let dir = std::env::temp_dir().join("cpp-proj-gen-test-streamed-files");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("data.txt"), "@CMAKE_TARGET_NAME@ by @VENDOR@\n").unwrap();
+        fs::write(dir.join("data.bin"), "@CMAKE_TARGET_NAME@").unwrap();
+
+        let mut output = MemoryOutput::default();
+        let report = CppProjGen::new(create_test_opt())
+            .add_defined_var("@VENDOR@", VarValue::from("acme"))
+            .add_file(PathBuf::from("data/@CMAKE_TARGET_NAME@.txt"), String::new())
+            .add_streamed_file(
+                PathBuf::from("data/@CMAKE_TARGET_NAME@.txt"),
+                dir.join("data.txt"),
+                false,
+            )
+            .add_streamed_file(PathBuf::from("data/raw.bin"), dir.join("data.bin"), true)
+            .gen(&mut output, None)
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let contents = |path: &str| {
+            output
+                .entries()
+                .iter()
+                .find_map(|(other, entry)| match entry {
+                    MemoryEntry::File { contents, .. } if other == Path::new(path) => {
+                        Some(contents.clone())
+                    }
+                    _ => None,
+                })
+        };
+        assert_eq!(
+            contents("test_out_dir/tgtnm/data/tgtnm.txt").unwrap(),
+            b"tgtnm by acme\n"
+        );
+        assert_eq!(
+            contents("test_out_dir/tgtnm/data/raw.bin").unwrap(),
+            b"@CMAKE_TARGET_NAME@"
+        );
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_plan_streamed_rendered_size() {
+        let dir = std::env::temp_dir().join("cpp-proj-gen-test-streamed-size");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("data.txt"), "@VALUE@\n").unwrap();
+        let gen = |value: &str, limits: Limits| {
+            CppProjGen::new(create_test_opt())
+                .add_cmake_var("@VALUE@", value)
+                .add_streamed_file(PathBuf::from("data.txt"), dir.join("data.txt"), false)
+                .set_limits(limits)
+                .plan()
+        };
+        let bytes = |plan: Vec<PlanEntry>| {
+            plan.iter()
+                .map(|entry| match entry {
+                    PlanEntry::File { contents, .. } => contents.len() as u64,
+                    _ => 0,
+                })
+                .sum::<u64>()
+        };
+
+        // Limited by the rendered 1001 bytes, not the 8 of the source
+        let others = bytes(gen("", Limits::default()).unwrap());
+        let limits = Limits {
+            max_bytes: Some(others + 500),
+            ..Limits::default()
+        };
+        let small = gen("x", limits);
+        let large = gen(&"x".repeat(1000), limits);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(small.is_ok());
+        assert!(large.is_err());
+    }
+
+    #[test]
+    fn test_plan_executables() {
+        let plan = CppProjGen::new(create_test_opt())
+            .add_file(PathBuf::from("tools/run.sh"), String::from("#!/bin/sh\n"))
+            .add_file(PathBuf::from("tools/lib.sh"), String::from("#!/bin/sh\n"))
+            .add_file(PathBuf::from("tools/release"), String::from("exec make\n"))
+            .set_executable(PathBuf::from("tools/lib.sh"), false)
+            .set_executable(PathBuf::from("tools/release"), true)
+            .plan()
+            .unwrap();
+
+        let executables: Vec<&Path> = plan
+            .iter()
+            .filter_map(|entry| match entry {
+                PlanEntry::File {
+                    path,
+                    executable: true,
+                    ..
+                } => Some(path.as_path()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            executables,
+            vec![
+                Path::new("test_out_dir/tgtnm/tools/release"),
+                Path::new("test_out_dir/tgtnm/tools/run.sh")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_links() {
+        let plan = CppProjGen::new(create_test_opt())
+            .add_file(PathBuf::from("include/v2/a.h"), String::new())
+            .add_link(PathBuf::from("include/latest"), PathBuf::from("v2"))
+            .add_link(
+                PathBuf::from("docs/@CMAKE_TARGET_NAME@.h"),
+                PathBuf::from("../include/v2/a.h"),
+            )
+            .plan()
+            .unwrap();
+
+        let links: Vec<&PlanEntry> = plan
+            .iter()
+            .skip_while(|entry| !matches!(entry, PlanEntry::Link { .. }))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                &PlanEntry::Link {
+                    path: PathBuf::from("test_out_dir/tgtnm/docs/tgtnm.h"),
+                    target: PathBuf::from("../include/v2/a.h"),
+                },
+                &PlanEntry::Link {
+                    path: PathBuf::from("test_out_dir/tgtnm/include/latest"),
+                    target: PathBuf::from("v2"),
+                },
+            ]
+        );
+
+        for target in &["../../etc", "/etc/passwd", "a/../../.."] {
+            let gen = CppProjGen::new(create_test_opt())
+                .add_link(PathBuf::from("include/latest"), PathBuf::from(target));
+            assert!(
+                matches!(gen.validate(), Err(Error::PathEscape { .. })),
+                "{}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_empty_dir_keep_files() {
+        let plan = vec![
+            PlanEntry::Dir(PathBuf::from("tgtnm/docs")),
+            PlanEntry::Dir(PathBuf::from("tgtnm/source")),
+            PlanEntry::File {
+                path: PathBuf::from("tgtnm/source/tgtnm.cpp"),
+                contents: String::new(),
+                executable: false,
+            },
+            PlanEntry::Dir(PathBuf::from("tgtnm/test")),
+        ];
+        let paths: Vec<PathBuf> = add_empty_dir_keep_files(plan, ".keep")
+            .iter()
+            .map(|entry| entry.path().to_path_buf())
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("tgtnm/docs"),
+                PathBuf::from("tgtnm/docs/.keep"),
+                PathBuf::from("tgtnm/source"),
+                PathBuf::from("tgtnm/source/tgtnm.cpp"),
+                PathBuf::from("tgtnm/test"),
+                PathBuf::from("tgtnm/test/.keep"),
+            ]
+        );
+
+        let opt = Opt {
+            empty_dir_keep: Some(Some(String::from("../.keep"))),
+            ..create_test_opt()
+        };
+        assert!(matches!(
+            CppProjGen::new(opt).validate(),
+            Err(Error::PathEscape { .. })
+        ));
+    }
+}
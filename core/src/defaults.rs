@@ -0,0 +1,142 @@
+/*
+For env vars /sa https://doc.rust-lang.org/std/env/fn.var.html
+*/
+
+use crate::cpp_proj_gen::Kind;
+use crate::naming::TestPattern;
+use crate::test_framework::TestFramework;
+use std::{env, io, path::PathBuf, str::FromStr};
+
+const ENV_PREFIX: &str = "CPP_PROJ_GEN_";
+
+// Defaults for options not given on the command line
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Defaults {
+    pub name_space: Option<String>,
+    pub cmake_version: Option<String>,
+    pub output_dir: Option<PathBuf>,
+    pub kind: Option<Kind>,
+    pub template_dir: Option<PathBuf>,
+    pub template_registry: Option<String>,
+    pub author: Option<String>,
+    pub author_email: Option<String>,
+    pub license: Option<String>,
+    pub test_framework: Option<TestFramework>,
+    pub header_ext: Option<String>,
+    pub source_ext: Option<String>,
+    pub test_pattern: Option<TestPattern>,
+}
+
+impl Defaults {
+    // e.g. CPP_PROJ_GEN_NAMESPACE=my_company
+    pub fn from_env() -> io::Result<Self> {
+        Self::from_vars(|name| env::var(format!("{}{}", ENV_PREFIX, name)).ok())
+    }
+
+    fn from_vars<F>(var: F) -> io::Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let kind = parse_var(var("KIND"))?;
+        let test_framework = parse_var(var("TEST_FRAMEWORK"))?;
+        let test_pattern = parse_var(var("TEST_PATTERN"))?;
+
+        Ok(Self {
+            name_space: var("NAMESPACE"),
+            cmake_version: var("CMAKE_VERSION"),
+            output_dir: var("OUTPUT_DIR").map(PathBuf::from),
+            kind,
+            template_dir: var("TEMPLATE_DIR").map(PathBuf::from),
+            template_registry: var("TEMPLATE_REGISTRY"),
+            author: var("AUTHOR"),
+            author_email: var("AUTHOR_EMAIL"),
+            license: var("LICENSE"),
+            test_framework,
+            header_ext: var("HEADER_EXT"),
+            source_ext: var("SOURCE_EXT"),
+            test_pattern,
+        })
+    }
+
+    // Merges with a source of lower precedence
+    pub fn or(self, lower: Defaults) -> Self {
+        Self {
+            name_space: self.name_space.or(lower.name_space),
+            cmake_version: self.cmake_version.or(lower.cmake_version),
+            output_dir: self.output_dir.or(lower.output_dir),
+            kind: self.kind.or(lower.kind),
+            template_dir: self.template_dir.or(lower.template_dir),
+            template_registry: self.template_registry.or(lower.template_registry),
+            author: self.author.or(lower.author),
+            author_email: self.author_email.or(lower.author_email),
+            license: self.license.or(lower.license),
+            test_framework: self.test_framework.or(lower.test_framework),
+            header_ext: self.header_ext.or(lower.header_ext),
+            source_ext: self.source_ext.or(lower.source_ext),
+            test_pattern: self.test_pattern.or(lower.test_pattern),
+        }
+    }
+}
+
+fn parse_var<T: FromStr<Err = String>>(value: Option<String>) -> io::Result<Option<T>> {
+    match value {
+        Some(value) => value
+            .parse::<T>()
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err)),
+        None => Ok(None),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_test_defaults() -> Defaults {
+        let vars: HashMap<&str, &str> = [
+            ("NAMESPACE", "env-nmspc"),
+            ("CMAKE_VERSION", "3.99.0"),
+            ("KIND", "shared"),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        Defaults::from_vars(|name| vars.get(name).map(|value| value.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let defaults = create_test_defaults();
+
+        assert_eq!(defaults.name_space.as_deref(), Some("env-nmspc"));
+        assert_eq!(defaults.cmake_version.as_deref(), Some("3.99.0"));
+        assert_eq!(defaults.kind, Some(Kind::Shared));
+        assert_eq!(defaults.output_dir, None);
+    }
+
+    #[test]
+    fn test_from_vars_invalid_kind() {
+        let result = Defaults::from_vars(|name| match name {
+            "KIND" => Some(String::from("plugin")),
+            _ => None,
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_or_precedence() {
+        let upper = Defaults {
+            name_space: Some(String::from("upper")),
+            ..Defaults::default()
+        };
+
+        let merged = upper.or(create_test_defaults());
+
+        assert_eq!(merged.name_space.as_deref(), Some("upper"));
+        assert_eq!(merged.cmake_version.as_deref(), Some("3.99.0"));
+    }
+}
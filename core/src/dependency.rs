@@ -0,0 +1,501 @@
+/*
+For find_package /sa https://cmake.org/cmake/help/latest/command/find_package.html
+For vcpkg.json /sa https://learn.microsoft.com/en-us/vcpkg/reference/vcpkg-json
+For conanfile.txt /sa https://docs.conan.io/2/reference/conanfile_txt.html
+*/
+
+use crate::app_config;
+use crate::concurrency;
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::error::{self, invalid_input, Error};
+use crate::logging;
+use crate::presets;
+use crate::project_manifest::{self, ProjectManifest};
+use crate::warning::Warning;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+const DEPENDENCIES_FILE: &str = "cmake/dependencies.cmake";
+const DEPENDENCIES_HEADER: &str = "# Dependencies added by cpp-proj-gen add-dependency\n";
+const INCLUDE_DEPENDENCIES: &str = "include(cmake/dependencies.cmake)";
+const VCPKG_FILE: &str = "vcpkg.json";
+const CONAN_FILE: &str = "conanfile.txt";
+const CONAN_DEFAULT: &str = "[requires]\n\n[generators]\nCMakeDeps\nCMakeToolchain\n";
+
+// Package manager providing the packages find_package finds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PackageManager {
+    Vcpkg,
+    Conan,
+}
+
+impl PackageManager {
+    pub const VARIANTS: &'static [&'static str] = &["vcpkg", "conan"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PackageManager::Vcpkg => "vcpkg",
+            PackageManager::Conan => "conan",
+        }
+    }
+}
+
+impl FromStr for PackageManager {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vcpkg" => Ok(PackageManager::Vcpkg),
+            "conan" => Ok(PackageManager::Conan),
+            _ => Err(format!(
+                "unknown package manager '{}', expected one of: {}",
+                s,
+                PackageManager::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// e.g. fmt/10.2.1 or fmt
+#[derive(Debug, Clone, PartialEq)]
+struct Dependency {
+    name: String,
+    version: Option<String>,
+}
+
+impl FromStr for Dependency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, version) = match s.split_once('/') {
+            Some((name, version)) => (name, Some(version)),
+            None => (s, None),
+        };
+
+        if name.is_empty() || version == Some("") {
+            return Err(format!("expected NAME or NAME/VERSION, got '{}'", s));
+        }
+
+        Ok(Dependency {
+            name: String::from(name),
+            version: version.map(String::from),
+        })
+    }
+}
+
+// Package the generated code uses, e.g. of --logging spdlog, and the target linking it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureDependency {
+    // e.g. spdlog/1.14.1, conan needs the version
+    pub spec: &'static str,
+    // Port name if it differs, e.g. nlohmann-json of nlohmann_json
+    pub vcpkg_port: Option<&'static str>,
+    // find_package name if it differs, e.g. TBB of onetbb
+    pub cmake_package: Option<&'static str>,
+    // nixpkgs attribute if it differs, e.g. tbb of onetbb
+    pub nix_package: Option<&'static str>,
+    // conda-forge package if it differs, e.g. tbb-devel of onetbb
+    pub conda_package: Option<&'static str>,
+    pub link_target: &'static str,
+    pub consumer: &'static str,
+}
+
+pub fn build_feature_dependencies(opt: &Opt) -> Vec<FeatureDependency> {
+    logging::dependency(opt)
+        .into_iter()
+        .chain(app_config::dependency(opt))
+        .chain(concurrency::dependency(opt))
+        .chain(presets::dependency(opt))
+        .collect()
+}
+
+// Writes the same files as add-dependency, so more can be added later
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let dependencies: Vec<(Dependency, FeatureDependency)> = gen
+        .model()
+        .dependencies
+        .iter()
+        .cloned()
+        .filter_map(|feature| {
+            feature
+                .spec
+                .parse::<Dependency>()
+                .ok()
+                .map(|dependency| (dependency, feature))
+        })
+        .collect();
+    if dependencies.is_empty() {
+        return gen;
+    }
+
+    let cmake_lines: String = dependencies
+        .iter()
+        .map(|(dependency, feature)| {
+            let package = Dependency {
+                name: String::from(feature.cmake_package.unwrap_or(&dependency.name)),
+                version: dependency.version.clone(),
+            };
+            build_cmake_lines(
+                &package,
+                feature.consumer,
+                gen.opt().kind,
+                feature.link_target,
+            )
+        })
+        .collect();
+    let package_file = match gen.opt().package_manager {
+        Some(PackageManager::Vcpkg) => Some((
+            VCPKG_FILE,
+            build_vcpkg_json(&gen.opt().target_name, &dependencies),
+        )),
+        Some(PackageManager::Conan) => Some((CONAN_FILE, build_conanfile(&dependencies))),
+        None => None,
+    };
+
+    let gen = gen
+        .add_file(
+            project_manifest::from_manifest_path(DEPENDENCIES_FILE),
+            format!("{}{}", DEPENDENCIES_HEADER, cmake_lines),
+        )
+        .add_cmake_snippet(String::from(INCLUDE_DEPENDENCIES));
+    match package_file {
+        Some((file, contents)) => gen.add_file(PathBuf::from(file), contents),
+        None => gen,
+    }
+}
+
+// vcpkg versions need a builtin-baseline, so the latest ones are used
+fn build_vcpkg_json(target_name: &str, dependencies: &[(Dependency, FeatureDependency)]) -> String {
+    let names: Vec<&str> = dependencies
+        .iter()
+        .map(|(dependency, feature)| feature.vcpkg_port.unwrap_or(&dependency.name))
+        .collect();
+    let json = serde_json::json!({ "name": target_name, "dependencies": names });
+
+    serde_json::to_string_pretty(&json).unwrap_or_default() + "\n"
+}
+
+fn build_conanfile(dependencies: &[(Dependency, FeatureDependency)]) -> String {
+    let requires: String = dependencies
+        .iter()
+        .map(|(_, feature)| format!("{}\n", feature.spec))
+        .collect();
+
+    CONAN_DEFAULT.replacen("[requires]\n", &format!("[requires]\n{}", requires), 1)
+}
+
+// Files add_dependency wrote, in order, and e.g. a vcpkg.json without builtin-baseline
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DependencyReport {
+    pub updated: Vec<PathBuf>,
+    pub warnings: Vec<Warning>,
+}
+
+// Adds find_package and target_link_libraries lines plus the package manager entry
+pub fn add_dependency(
+    project_dir: &Path,
+    spec: &str,
+    package_manager: Option<PackageManager>,
+    link_target: Option<&str>,
+) -> error::Result<DependencyReport> {
+    let dependency = spec.parse::<Dependency>().map_err(invalid_input)?;
+    let mut manifest = ProjectManifest::load(project_dir)?;
+    let opt = manifest.to_opt()?;
+
+    let package_manager = match package_manager {
+        Some(package_manager) => Some(package_manager),
+        None => match &manifest.package_manager {
+            Some(name) => Some(name.parse::<PackageManager>().map_err(invalid_input)?),
+            None => None,
+        },
+    };
+
+    let link_target = link_target
+        .map(String::from)
+        .unwrap_or_else(|| format!("{0}::{0}", dependency.name));
+
+    let mut report = DependencyReport::default();
+    // Everything is checked before the first file is written
+    let package_file = match package_manager {
+        Some(PackageManager::Vcpkg) => {
+            let path = project_dir.join(VCPKG_FILE);
+            let default = format!(
+                "{{\"name\": \"{}\", \"dependencies\": []}}",
+                manifest.target_name
+            );
+            let contents = read_or_default(&path, &default)?;
+            let contents = add_vcpkg_dependency(&contents, &dependency, &mut report.warnings)?;
+            Some((VCPKG_FILE, contents))
+        }
+        Some(PackageManager::Conan) => {
+            let path = project_dir.join(CONAN_FILE);
+            let contents = read_or_default(&path, CONAN_DEFAULT)?;
+            Some((CONAN_FILE, add_conan_requirement(&contents, &dependency)?))
+        }
+        None => None,
+    };
+
+    let path = project_dir.join(project_manifest::from_manifest_path(DEPENDENCIES_FILE));
+    let contents = read_or_default(&path, DEPENDENCIES_HEADER)?;
+    let contents = add_cmake_lines(
+        &contents,
+        &dependency,
+        &opt.target_name,
+        opt.kind,
+        &link_target,
+    )?;
+    write(&path, &contents, &mut report)?;
+    track(&mut manifest, DEPENDENCIES_FILE);
+
+    let path = project_dir.join("CMakeLists.txt");
+    let contents = fs::read_to_string(&path).map_err(Error::fs("read", &path))?;
+    if !contents.contains(INCLUDE_DEPENDENCIES) {
+        write(
+            &path,
+            &format!("{}\n\n{}\n", contents.trim_end(), INCLUDE_DEPENDENCIES),
+            &mut report,
+        )?;
+    }
+
+    if let Some((file, contents)) = package_file {
+        write(&project_dir.join(file), &contents, &mut report)?;
+        track(&mut manifest, file);
+    }
+
+    manifest.package_manager =
+        package_manager.map(|package_manager| String::from(package_manager.name()));
+    manifest.store(project_dir)?;
+
+    Ok(report)
+}
+
+fn add_cmake_lines(
+    contents: &str,
+    dependency: &Dependency,
+    target_name: &str,
+    kind: Kind,
+    link_target: &str,
+) -> io::Result<String> {
+    let find_package = format!("find_package({} ", dependency.name);
+    if contents.lines().any(|line| line.starts_with(&find_package)) {
+        return Err(invalid_input(format!(
+            "dependency {} is already added",
+            dependency.name
+        )));
+    }
+
+    Ok(format!(
+        "{}\n{}",
+        contents.trim_end(),
+        build_cmake_lines(dependency, target_name, kind, link_target)
+    ))
+}
+
+// Header-only libraries pass the dependency on, everything else keeps it private
+fn build_cmake_lines(
+    dependency: &Dependency,
+    target_name: &str,
+    kind: Kind,
+    link_target: &str,
+) -> String {
+    let scope = match kind {
+        Kind::HeaderOnly => "INTERFACE",
+        _ => "PRIVATE",
+    };
+    let version = match &dependency.version {
+        Some(version) => format!(" {}", version),
+        None => String::new(),
+    };
+
+    format!(
+        "find_package({}{} CONFIG REQUIRED)\ntarget_link_libraries({} {} {})\n",
+        dependency.name, version, target_name, scope, link_target
+    )
+}
+
+// Keeps all other fields, a version becomes a minimum version
+fn add_vcpkg_dependency(
+    contents: &str,
+    dependency: &Dependency,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<String> {
+    let mut json: serde_json::Value = serde_json::from_str(contents).map_err(invalid_data)?;
+    let entry = match &dependency.version {
+        Some(version) => serde_json::json!({ "name": dependency.name, "version>=": version }),
+        None => serde_json::json!(dependency.name),
+    };
+
+    let dependencies = json
+        .as_object_mut()
+        .ok_or_else(|| invalid_data("vcpkg.json is not an object"))?
+        .entry("dependencies")
+        .or_insert_with(|| serde_json::json!([]))
+        .as_array_mut()
+        .ok_or_else(|| invalid_data("dependencies in vcpkg.json is not an array"))?;
+    dependencies.retain(|existing| {
+        existing.as_str() != Some(&dependency.name)
+            && existing["name"].as_str() != Some(&dependency.name)
+    });
+    dependencies.push(entry);
+
+    if dependency.version.is_some() && json.get("builtin-baseline").is_none() {
+        warnings.push(Warning::Other(String::from(
+            "vcpkg needs a builtin-baseline in vcpkg.json for version constraints",
+        )));
+    }
+
+    let mut contents = serde_json::to_string_pretty(&json).map_err(invalid_data)?;
+    contents.push('\n');
+
+    Ok(contents)
+}
+
+// Inserts e.g. fmt/10.2.1 as last line of the [requires] section, conan needs a version
+fn add_conan_requirement(contents: &str, dependency: &Dependency) -> io::Result<String> {
+    let version = dependency.version.as_ref().ok_or_else(|| {
+        invalid_input(format!(
+            "conan needs a version, e.g. {}/1.0.0",
+            dependency.name
+        ))
+    })?;
+    let requirement = format!("{}/{}", dependency.name, version);
+    let prefix = format!("{}/", dependency.name);
+
+    let mut lines: Vec<String> = contents
+        .lines()
+        .filter(|line| !line.trim().starts_with(&prefix))
+        .map(String::from)
+        .collect();
+
+    let requires = match lines.iter().position(|line| line.trim() == "[requires]") {
+        Some(index) => index,
+        None => {
+            lines.insert(0, String::from("[requires]"));
+            0
+        }
+    };
+    let section_end = lines[requires + 1..]
+        .iter()
+        .position(|line| line.trim().is_empty() || line.trim().starts_with('['))
+        .map_or(lines.len(), |offset| requires + 1 + offset);
+    lines.insert(section_end, requirement);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn read_or_default(path: &Path, default: &str) -> error::Result<String> {
+    match path.is_file() {
+        true => fs::read_to_string(path).map_err(Error::fs("read", path)),
+        false => Ok(String::from(default)),
+    }
+}
+
+fn write(path: &Path, contents: &str, report: &mut DependencyReport) -> error::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(Error::fs("create directory", parent))?;
+    }
+    fs::write(path, contents).map_err(Error::fs("write", path))?;
+    report.updated.push(path.to_path_buf());
+
+    Ok(())
+}
+
+fn track(manifest: &mut ProjectManifest, file: &str) {
+    if !manifest.files.iter().any(|other| other == file) {
+        manifest.files.push(String::from(file));
+        manifest.files.sort();
+    }
+}
+
+fn invalid_data<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_dependency() -> Dependency {
+        "fmt/10.2.1".parse::<Dependency>().unwrap()
+    }
+
+    #[test]
+    fn test_parse_dependency() {
+        assert_eq!(create_test_dependency().version.as_deref(), Some("10.2.1"));
+        assert_eq!("fmt".parse::<Dependency>().unwrap().version, None);
+        assert!("fmt/".parse::<Dependency>().is_err());
+        assert!("/1.0".parse::<Dependency>().is_err());
+    }
+
+    #[test]
+    fn test_add_cmake_lines() {
+        let contents = add_cmake_lines(
+            DEPENDENCIES_HEADER,
+            &create_test_dependency(),
+            "tgtnm",
+            Kind::Static,
+            "fmt::fmt",
+        )
+        .unwrap();
+
+        assert!(contents.contains("find_package(fmt 10.2.1 CONFIG REQUIRED)\n"));
+        assert!(contents.contains("target_link_libraries(tgtnm PRIVATE fmt::fmt)\n"));
+        assert!(add_cmake_lines(
+            &contents,
+            &create_test_dependency(),
+            "tgtnm",
+            Kind::Static,
+            "fmt::fmt"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_add_vcpkg_dependency() {
+        let contents = r#"{"name": "tgtnm", "dependencies": ["fmt", "zlib"]}"#;
+        let mut warnings = Vec::new();
+        let contents =
+            add_vcpkg_dependency(contents, &create_test_dependency(), &mut warnings).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+
+        assert_eq!(json["dependencies"][0], "zlib");
+        assert_eq!(json["dependencies"][1]["version>="], "10.2.1");
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_add_conan_requirement() {
+        let contents = "[requires]\nfmt/9.0.0\nzlib/1.3\n\n[generators]\nCMakeDeps\n";
+
+        assert_eq!(
+            add_conan_requirement(contents, &create_test_dependency()).unwrap(),
+            "[requires]\nzlib/1.3\nfmt/10.2.1\n\n[generators]\nCMakeDeps\n"
+        );
+        assert!(add_conan_requirement(contents, &"fmt".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_build_vcpkg_json() {
+        let dependencies = vec![(
+            "spdlog/1.14.1".parse::<Dependency>().unwrap(),
+            FeatureDependency {
+                spec: "spdlog/1.14.1",
+                vcpkg_port: None,
+                cmake_package: None,
+                nix_package: None,
+                conda_package: None,
+                link_target: "spdlog::spdlog",
+                consumer: "tgtnm",
+            },
+        )];
+        let json: serde_json::Value =
+            serde_json::from_str(&build_vcpkg_json("tgtnm", &dependencies)).unwrap();
+
+        assert_eq!(json["name"], "tgtnm");
+        assert_eq!(json["dependencies"], serde_json::json!(["spdlog"]));
+    }
+}
@@ -0,0 +1,162 @@
+/*
+For Dependabot /sa https://docs.github.com/en/code-security/dependabot/working-with-dependabot/dependabot-options-reference
+For Renovate custom managers /sa https://docs.renovatebot.com/modules/manager/regex/
+*/
+
+use crate::community_files::Forge;
+use crate::cpp_proj_gen::CppProjGen;
+use crate::dependency::PackageManager;
+use std::{path::PathBuf, str::FromStr};
+
+const DEPENDABOT_FILE: &str = ".github/dependabot.yml";
+const RENOVATE_FILE: &str = "renovate.json";
+
+// Bot opening pull requests for outdated dependencies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DependencyUpdates {
+    Dependabot,
+    Renovate,
+}
+
+impl DependencyUpdates {
+    pub const VARIANTS: &'static [&'static str] = &["dependabot", "renovate"];
+}
+
+impl FromStr for DependencyUpdates {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dependabot" => Ok(DependencyUpdates::Dependabot),
+            "renovate" => Ok(DependencyUpdates::Renovate),
+            _ => Err(format!(
+                "unknown dependency updates '{}', expected one of: {}",
+                s,
+                DependencyUpdates::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Covers git submodules, the package manager and GitHub Actions on GitHub
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let package_manager = gen.opt().package_manager;
+    let github = gen.opt().forge == Some(Forge::Github);
+
+    match gen.opt().dependency_updates {
+        Some(DependencyUpdates::Dependabot) => gen.add_file(
+            PathBuf::from(DEPENDABOT_FILE),
+            build_dependabot_config(package_manager, github),
+        ),
+        Some(DependencyUpdates::Renovate) => gen.add_file(
+            PathBuf::from(RENOVATE_FILE),
+            build_renovate_config(package_manager),
+        ),
+        None => gen,
+    }
+}
+
+fn build_dependabot_config(package_manager: Option<PackageManager>, github: bool) -> String {
+    let mut ecosystems = vec!["gitsubmodule"];
+    if package_manager == Some(PackageManager::Vcpkg) {
+        ecosystems.push("vcpkg");
+    }
+    if github {
+        ecosystems.push("github-actions");
+    }
+
+    let mut config = String::from("version: 2\nupdates:\n");
+    for ecosystem in ecosystems {
+        config.push_str(&format!(
+            "  - package-ecosystem: \"{}\"\n    directory: \"/\"\n    schedule:\n      interval: \"weekly\"\n",
+            ecosystem
+        ));
+    }
+    if package_manager == Some(PackageManager::Conan) {
+        config.push_str("# Dependabot does not update conanfile.txt, Renovate does\n");
+    }
+
+    config
+}
+
+fn build_renovate_config(package_manager: Option<PackageManager>) -> String {
+    let custom_manager = match package_manager {
+        // The baseline pins the versions of all ports
+        Some(PackageManager::Vcpkg) => Some(
+            r#"    {
+      "customType": "regex",
+      "description": "vcpkg baseline",
+      "fileMatch": ["(^|/)vcpkg\\.json$"],
+      "matchStrings": ["\"builtin-baseline\"\\s*:\\s*\"(?<currentDigest>[0-9a-f]{40})\""],
+      "depNameTemplate": "vcpkg",
+      "packageNameTemplate": "https://github.com/microsoft/vcpkg",
+      "currentValueTemplate": "master",
+      "datasourceTemplate": "git-refs"
+    }"#,
+        ),
+        Some(PackageManager::Conan) => Some(
+            r#"    {
+      "customType": "regex",
+      "description": "conanfile.txt requirements",
+      "fileMatch": ["(^|/)conanfile\\.txt$"],
+      "matchStrings": ["(?<depName>[a-z0-9_.+-]+)/(?<currentValue>[0-9][^\\s@#]*)"],
+      "packageNameTemplate": "{{depName}}/{{currentValue}}@_/_",
+      "datasourceTemplate": "conan"
+    }"#,
+        ),
+        None => None,
+    };
+
+    let mut config = String::from(
+        "{\n  \"$schema\": \"https://docs.renovatebot.com/renovate-schema.json\",\n  \"extends\": [\"config:recommended\"],\n  \"git-submodules\": {\n    \"enabled\": true\n  }",
+    );
+    if let Some(custom_manager) = custom_manager {
+        config.push_str(&format!(
+            ",\n  \"customManagers\": [\n{}\n  ]",
+            custom_manager
+        ));
+    }
+    config.push_str("\n}\n");
+
+    config
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "renovate".parse::<DependencyUpdates>().unwrap(),
+            DependencyUpdates::Renovate
+        );
+        assert!("greenkeeper".parse::<DependencyUpdates>().is_err());
+    }
+
+    #[test]
+    fn test_build_dependabot_config() {
+        let config = build_dependabot_config(Some(PackageManager::Vcpkg), true);
+
+        assert!(config.contains("package-ecosystem: \"gitsubmodule\""));
+        assert!(config.contains("package-ecosystem: \"vcpkg\""));
+        assert!(config.contains("package-ecosystem: \"github-actions\""));
+        assert!(!build_dependabot_config(None, false).contains("github-actions"));
+    }
+
+    #[test]
+    fn test_build_renovate_config() {
+        for package_manager in &[
+            None,
+            Some(PackageManager::Vcpkg),
+            Some(PackageManager::Conan),
+        ] {
+            let config = build_renovate_config(*package_manager);
+            let json: serde_json::Value = serde_json::from_str(&config).unwrap();
+
+            assert_eq!(json["git-submodules"]["enabled"], true);
+            assert_eq!(json["customManagers"].is_array(), package_manager.is_some());
+        }
+    }
+}
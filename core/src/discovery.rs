@@ -0,0 +1,199 @@
+/*
+For serde_json /sa https://docs.rs/serde_json/1.0/serde_json/
+For SPDX license identifiers /sa https://spdx.org/licenses/
+*/
+
+use crate::builtin_template::BuiltinTemplate;
+use crate::cpp_proj_gen::Opt;
+use crate::presets::Preset;
+use crate::test_framework::TestFramework;
+use serde::Serialize;
+use std::{collections::BTreeMap, io};
+
+const BUILD_SYSTEMS: &[(&str, &str)] = &[("cmake", "CMake with CMakeLists.txt")];
+
+// Common SPDX identifiers, --license accepts any identifier
+const LICENSES: &[(&str, &str)] = &[
+    ("Apache-2.0", "Apache License 2.0"),
+    ("BSD-2-Clause", "BSD 2-Clause \"Simplified\" License"),
+    (
+        "BSD-3-Clause",
+        "BSD 3-Clause \"New\" or \"Revised\" License",
+    ),
+    ("BSL-1.0", "Boost Software License 1.0"),
+    ("GPL-2.0-only", "GNU General Public License v2.0 only"),
+    ("GPL-3.0-only", "GNU General Public License v3.0 only"),
+    (
+        "LGPL-3.0-only",
+        "GNU Lesser General Public License v3.0 only",
+    ),
+    ("MIT", "MIT License"),
+    ("MPL-2.0", "Mozilla Public License 2.0"),
+    ("Unlicense", "The Unlicense"),
+];
+
+// One listed item
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Entry {
+    name: &'static str,
+    description: &'static str,
+}
+
+pub fn is_requested(opt: &Opt) -> bool {
+    !build_listings(opt).is_empty()
+}
+
+// All requested listings, as JSON object keyed by listing with --json
+pub fn render_listings(opt: &Opt) -> io::Result<String> {
+    let listings = build_listings(opt);
+
+    if opt.json {
+        let json = serde_json::to_string_pretty(&listings).map_err(io::Error::other)?;
+        return Ok(json + "\n");
+    }
+
+    let with_headings = listings.len() > 1;
+    let mut text = String::new();
+    for (listing, entries) in &listings {
+        if with_headings {
+            text.push_str(&format!("{}:\n", listing));
+        }
+        text.push_str(&format_entries(entries));
+    }
+
+    Ok(text)
+}
+
+fn build_listings(opt: &Opt) -> BTreeMap<&'static str, Vec<Entry>> {
+    let mut listings = BTreeMap::new();
+
+    if opt.list_presets {
+        listings.insert("presets", preset_entries());
+    }
+    if opt.list_build_systems {
+        listings.insert("build-systems", to_entries(BUILD_SYSTEMS));
+    }
+    if opt.list_test_frameworks {
+        listings.insert("test-frameworks", test_framework_entries());
+    }
+    if opt.list_licenses {
+        listings.insert("licenses", to_entries(LICENSES));
+    }
+    if opt.list_builtin_templates {
+        listings.insert("builtin-templates", builtin_template_entries());
+    }
+
+    listings
+}
+
+fn preset_entries() -> Vec<Entry> {
+    Preset::VARIANTS
+        .iter()
+        .filter_map(|variant| variant.parse::<Preset>().ok())
+        .map(|preset| Entry {
+            name: preset.name(),
+            description: preset.description(),
+        })
+        .collect()
+}
+
+fn test_framework_entries() -> Vec<Entry> {
+    TestFramework::VARIANTS
+        .iter()
+        .filter_map(|variant| variant.parse::<TestFramework>().ok())
+        .map(|framework| Entry {
+            name: framework.name(),
+            description: framework.description(),
+        })
+        .collect()
+}
+
+fn builtin_template_entries() -> Vec<Entry> {
+    BuiltinTemplate::VARIANTS
+        .iter()
+        .filter_map(|variant| variant.parse::<BuiltinTemplate>().ok())
+        .map(|template| Entry {
+            name: template.name(),
+            description: template.description(),
+        })
+        .collect()
+}
+
+fn to_entries(items: &[(&'static str, &'static str)]) -> Vec<Entry> {
+    items
+        .iter()
+        .map(|(name, description)| Entry { name, description })
+        .collect()
+}
+
+// Names padded to one column, e.g. "  cmake  CMake with CMakeLists.txt"
+fn format_entries(entries: &[Entry]) -> String {
+    let width = entries
+        .iter()
+        .map(|entry| entry.name.len())
+        .max()
+        .unwrap_or(0);
+
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "  {:width$}  {}\n",
+                entry.name,
+                entry.description,
+                width = width
+            )
+        })
+        .collect()
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_listings() {
+        let opt = Opt::default();
+        assert!(!is_requested(&opt));
+
+        let opt = Opt {
+            list_presets: true,
+            list_licenses: true,
+            ..Opt::default()
+        };
+        let listings = build_listings(&opt);
+
+        assert_eq!(listings["presets"].len(), Preset::VARIANTS.len());
+        assert!(listings["licenses"].iter().any(|entry| entry.name == "MIT"));
+        assert!(!listings.contains_key("build-systems"));
+    }
+
+    #[test]
+    fn test_render_listings() {
+        let opt = Opt {
+            list_build_systems: true,
+            ..Opt::default()
+        };
+        assert_eq!(
+            render_listings(&opt).unwrap(),
+            "  cmake  CMake with CMakeLists.txt\n"
+        );
+
+        let opt = Opt {
+            list_build_systems: true,
+            list_licenses: true,
+            ..Opt::default()
+        };
+        assert!(render_listings(&opt)
+            .unwrap()
+            .starts_with("build-systems:\n  cmake"));
+    }
+
+    #[test]
+    fn test_format_entries() {
+        let entries = to_entries(&[("a", "first"), ("abc", "second")]);
+
+        assert_eq!(format_entries(&entries), "  a    first\n  abc  second\n");
+    }
+}
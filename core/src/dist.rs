@@ -0,0 +1,238 @@
+/*
+For CPack source packages /sa https://cmake.org/cmake/help/latest/module/CPack.html#variable:CPACK_SOURCE_IGNORE_FILES
+For SOURCE_DATE_EPOCH /sa https://reproducible-builds.org/docs/source-date-epoch/
+*/
+
+use crate::archive::TarWriter;
+use crate::cpp_proj_gen::CppProjGen;
+use crate::error::{self, Error};
+use crate::file_mode;
+use crate::project_manifest;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// Build artifacts and checkouts, at any depth, left out of source archives
+const EXCLUDED_NAMES: &[&str] = &[".git", ".cache", "build", "out", "compile_commands.json"];
+// IDE build directories, e.g. cmake-build-debug of CLion
+const EXCLUDED_PREFIX: &str = "cmake-build-";
+
+const DIST_CMAKE: &str = include_str!("../res/dist.snippet.in");
+
+fn is_excluded(name: &str) -> bool {
+    EXCLUDED_NAMES.contains(&name) || name.starts_with(EXCLUDED_PREFIX)
+}
+
+// CPACK_SOURCE_IGNORE_FILES regexes of the same names
+fn build_ignore_files() -> Vec<String> {
+    EXCLUDED_NAMES
+        .iter()
+        .map(|name| format!("/{}(/|$)", name.replace('.', "\\\\.")))
+        .chain(std::iter::once(format!("/{}[^/]*/", EXCLUDED_PREFIX)))
+        .map(|regex| format!("        \"{}\"", regex))
+        .collect()
+}
+
+// Adds a dist target packing the sources with CPack
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().dist {
+        return gen;
+    }
+
+    let snippet = DIST_CMAKE.replace("@DIST_IGNORE_FILES@", &build_ignore_files().join("\n"));
+
+    gen.add_cmake_snippet(String::from(snippet.trim_end()))
+}
+
+// e.g. my-target-0.1.0 of project(my-target VERSION 0.1.0 ...)
+fn find_archive_name(cmake_contents: &str) -> Option<String> {
+    let index = cmake_contents.find("project(")?;
+    let mut args = cmake_contents[index + "project(".len()..]
+        .split(|c: char| c.is_whitespace() || c == ')')
+        .filter(|arg| !arg.is_empty());
+    let name = args.next()?;
+
+    match (args.next(), args.next()) {
+        (Some("VERSION"), Some(version)) => Some(format!("{}-{}", name, version)),
+        _ => Some(String::from(name)),
+    }
+}
+
+// Sorted, so the archive does not depend on the directory order
+fn collect_entries(
+    project_dir: &Path,
+    dir: &Path,
+    skip: &Path,
+    entries: &mut Vec<PathBuf>,
+) -> error::Result<()> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).map_err(Error::fs("read", dir))? {
+        paths.push(entry.map_err(Error::fs("read", dir))?.path());
+    }
+    paths.sort();
+
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let metadata = fs::symlink_metadata(&path).map_err(Error::fs("read", &path))?;
+        // Links may point out of the project, e.g. compile_commands.json into build/
+        if is_excluded(&name) || metadata.file_type().is_symlink() || path == skip {
+            continue;
+        }
+
+        entries.push(path.strip_prefix(project_dir).unwrap().to_path_buf());
+        if metadata.is_dir() {
+            collect_entries(project_dir, &path, skip, entries)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Writes <name>-<version>.tar of the project sources, equal for equal sources and mtime, returns its path
+pub fn write_archive(
+    project_dir: &Path,
+    output: Option<&Path>,
+    mtime: u64,
+) -> error::Result<PathBuf> {
+    let cmake_lists = project_dir.join("CMakeLists.txt");
+    let cmake_contents =
+        fs::read_to_string(&cmake_lists).map_err(Error::fs("read", &cmake_lists))?;
+    let archive_name = find_archive_name(&cmake_contents).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("no project() found in {}", cmake_lists.display()),
+        )
+    })?;
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(format!("{}.tar", archive_name)));
+
+    // An earlier archive in the project is no source
+    let skip = output.canonicalize().unwrap_or_else(|_| output.clone());
+    let project_dir = project_dir
+        .canonicalize()
+        .map_err(Error::fs("read", project_dir))?;
+    let mut entries = Vec::new();
+    collect_entries(&project_dir, &project_dir, &skip, &mut entries)?;
+
+    let mut tar = TarWriter::new(mtime);
+    tar.add_dir(&archive_name)?;
+    for entry in entries {
+        let path = project_dir.join(&entry);
+        let name = format!(
+            "{}/{}",
+            archive_name,
+            project_manifest::to_manifest_path(&entry)
+        );
+        let metadata = fs::metadata(&path).map_err(Error::fs("read", &path))?;
+
+        if metadata.is_dir() {
+            tar.add_dir(&name)?;
+        } else {
+            let contents = fs::read(&path).map_err(Error::fs("read", &path))?;
+            tar.add_file(&name, &contents, file_mode::is_executable(&metadata))?;
+        }
+    }
+
+    fs::write(&output, tar.finish()).map_err(Error::fs("write", &output))?;
+
+    Ok(output)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Opt, PlanEntry};
+    use std::path::Path;
+
+    #[test]
+    fn test_find_archive_name() {
+        assert_eq!(
+            find_archive_name(
+                "cmake_minimum_required(VERSION 3.21)\nproject(tgtnm VERSION 1.2.3 LANGUAGES CXX)"
+            ),
+            Some(String::from("tgtnm-1.2.3"))
+        );
+        assert_eq!(
+            find_archive_name("project(tgtnm)"),
+            Some(String::from("tgtnm"))
+        );
+        assert_eq!(find_archive_name("add_library(tgtnm)"), None);
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        assert!(is_excluded("build"));
+        assert!(is_excluded("cmake-build-debug"));
+        assert!(!is_excluded("source"));
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            dist: true,
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+        let cmake_lists = plan
+            .iter()
+            .find_map(|entry| match entry {
+                PlanEntry::File { path, contents, .. }
+                    if path == Path::new("test_out_dir/tgtnm/CMakeLists.txt") =>
+                {
+                    Some(contents.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(cmake_lists.contains("        \"/\\\\.git(/|$)\"\n"));
+        assert!(cmake_lists.contains("        \"/cmake-build-[^/]*/\"\n    )\n"));
+        assert!(cmake_lists.contains("add_custom_target(dist\n"));
+        // The minimum CMake 3.15 has no PROJECT_IS_TOP_LEVEL
+        assert!(cmake_lists.contains("if(CMAKE_SOURCE_DIR STREQUAL PROJECT_SOURCE_DIR)\n"));
+        assert!(cmake_lists.contains("cmake_minimum_required(VERSION 3.15.0)"));
+    }
+
+    #[test]
+    fn test_run() {
+        let project_dir = std::env::temp_dir().join("cpp-proj-gen-test-dist");
+        let _ = fs::remove_dir_all(&project_dir);
+        fs::create_dir_all(project_dir.join("build")).unwrap();
+        fs::create_dir_all(project_dir.join("source")).unwrap();
+        fs::write(
+            project_dir.join("CMakeLists.txt"),
+            "project(tgtnm VERSION 0.1.0)\n",
+        )
+        .unwrap();
+        fs::write(project_dir.join("source/tgtnm.cpp"), "int x;\n").unwrap();
+        fs::write(project_dir.join("build/tgtnm.o"), "").unwrap();
+        let output = project_dir.join("tgtnm-0.1.0.tar");
+
+        write_archive(&project_dir, Some(&output), 0).unwrap();
+        let first = fs::read(&output).unwrap();
+        write_archive(&project_dir, Some(&output), 0).unwrap();
+        let second = fs::read(&output).unwrap();
+        fs::remove_dir_all(&project_dir).unwrap();
+
+        assert_eq!(first, second);
+        let names: Vec<&[u8]> = first
+            .chunks(512)
+            .filter(|block| block[257..262] == *b"ustar")
+            .map(|block| &block[..block.iter().position(|byte| *byte == 0).unwrap()])
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                &b"tgtnm-0.1.0/"[..],
+                b"tgtnm-0.1.0/CMakeLists.txt",
+                b"tgtnm-0.1.0/source/",
+                b"tgtnm-0.1.0/source/tgtnm.cpp"
+            ]
+        );
+    }
+}
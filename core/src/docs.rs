@@ -0,0 +1,147 @@
+/*
+For FindDoxygen /sa https://cmake.org/cmake/help/latest/module/FindDoxygen.html
+For mkdocs /sa https://www.mkdocs.org/user-guide/configuration/
+For Material for MkDocs /sa https://squidfunk.github.io/mkdocs-material/setup/
+For Read the Docs /sa https://docs.readthedocs.io/en/stable/config-file/v2.html
+*/
+
+use crate::cpp_proj_gen::CppProjGen;
+use std::{path::PathBuf, str::FromStr};
+
+const DOXYGEN_CMAKE: &str = include_str!("../res/docs/doxygen.snippet.in");
+const MKDOCS_CMAKE: &str = include_str!("../res/docs/mkdocs.snippet.in");
+const MKDOCS_CONFIG: &str = include_str!("../res/docs/mkdocs.yml.in");
+const MKDOCS_INDEX: &str = include_str!("../res/docs/index.md.in");
+const MKDOCS_API: &str = include_str!("../res/docs/api.md.in");
+const READTHEDOCS_CONFIG: &str = include_str!("../res/docs/readthedocs.yaml.in");
+
+const MKDOCS_THEME: &str = "theme:\n  name: mkdocs\n";
+const MATERIAL_THEME: &str = "theme:
+  name: material
+  features:
+    - navigation.sections
+    - content.code.copy
+markdown_extensions:
+  - admonition
+  - pymdownx.highlight
+  - pymdownx.superfences
+";
+
+// Documentation backend, both build a <target>-docs CMake target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocsBackend {
+    Doxygen,
+    Mkdocs,
+}
+
+impl DocsBackend {
+    pub const VARIANTS: &'static [&'static str] = &["doxygen", "mkdocs"];
+}
+
+impl FromStr for DocsBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "doxygen" => Ok(DocsBackend::Doxygen),
+            "mkdocs" => Ok(DocsBackend::Mkdocs),
+            _ => Err(format!(
+                "unknown docs backend '{}', expected one of: {}",
+                s,
+                DocsBackend::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    match gen.opt().docs {
+        Some(DocsBackend::Doxygen) => gen.add_cmake_snippet(String::from(DOXYGEN_CMAKE)),
+        Some(DocsBackend::Mkdocs) => apply_mkdocs(gen),
+        None => gen,
+    }
+}
+
+// Adds the site config, index and API pages, and the Read the Docs config
+fn apply_mkdocs(gen: CppProjGen) -> CppProjGen {
+    let (theme, requirements) = match gen.opt().docs_material {
+        true => (MATERIAL_THEME, "mkdocs\nmkdocs-material\n"),
+        false => (MKDOCS_THEME, "mkdocs\n"),
+    };
+
+    gen.add_file(
+        PathBuf::from("mkdocs.yml"),
+        MKDOCS_CONFIG.replace("@MKDOCS_THEME@\n", theme),
+    )
+    .add_file(PathBuf::from("docs/index.md"), String::from(MKDOCS_INDEX))
+    .add_file(PathBuf::from("docs/api.md"), String::from(MKDOCS_API))
+    .add_file(
+        PathBuf::from("docs/requirements.txt"),
+        String::from(requirements),
+    )
+    .add_file(
+        PathBuf::from(".readthedocs.yaml"),
+        String::from(READTHEDOCS_CONFIG),
+    )
+    .add_cmake_snippet(String::from(MKDOCS_CMAKE))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Opt, PlanEntry};
+    use std::path::Path;
+
+    fn find_contents(plan: &[PlanEntry], path: &str) -> Option<String> {
+        plan.iter().find_map(|entry| match entry {
+            PlanEntry::File {
+                path: other,
+                contents,
+                ..
+            } if other == Path::new(path) => Some(contents.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "mkdocs".parse::<DocsBackend>().unwrap(),
+            DocsBackend::Mkdocs
+        );
+        assert!("sphinx".parse::<DocsBackend>().is_err());
+    }
+
+    #[test]
+    fn test_mkdocs() {
+        let opt = Opt {
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            docs: Some(DocsBackend::Mkdocs),
+            docs_material: true,
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+
+        let config = find_contents(&plan, "test_out_dir/my-target/mkdocs.yml").unwrap();
+        assert!(config.starts_with("site_name: my-target\n"));
+        assert!(config.contains("name: material"));
+        assert!(find_contents(&plan, "test_out_dir/my-target/docs/api.md").is_some());
+        let cmake_lists = find_contents(&plan, "test_out_dir/my-target/CMakeLists.txt").unwrap();
+        assert!(cmake_lists.contains("add_custom_target(my-target-docs"));
+    }
+
+    #[test]
+    fn test_doxygen() {
+        let opt = Opt {
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            docs: Some(DocsBackend::Doxygen),
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+
+        let cmake_lists = find_contents(&plan, "test_out_dir/my-target/CMakeLists.txt").unwrap();
+        assert!(cmake_lists.contains("doxygen_add_docs(my-target-docs"));
+        assert!(find_contents(&plan, "test_out_dir/my-target/mkdocs.yml").is_none());
+    }
+}
@@ -0,0 +1,115 @@
+/*
+For file(READ ... HEX) /sa https://cmake.org/cmake/help/latest/command/file.html#read
+For CMAKE_CONFIGURE_DEPENDS /sa https://cmake.org/cmake/help/latest/prop_dir/CMAKE_CONFIGURE_DEPENDS.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::error::invalid_input;
+use crate::language::Language;
+use std::{io, path::PathBuf};
+
+const EMBED_CMAKE: &str = include_str!("../res/embed_resources/CMakeLists.snippet.in");
+const EMBED_MODULE: &str = include_str!("../res/embed_resources/EmbedResources.cmake.in");
+const EMBED_SOURCE: &str = include_str!("../res/embed_resources/EmbedResources.cpp.in");
+const EMBED_HEADER: &str = include_str!("../res/embed_resources/resources.h.in");
+const EMBED_SAMPLE: &str = include_str!("../res/embed_resources/hello.txt.in");
+
+// The accessor is C++ and needs a compiled library
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    if !opt.embed_resources {
+        return Ok(());
+    }
+
+    if opt.language == Language::C {
+        return Err(invalid_input(String::from(
+            "--embed-resources needs C++, use --language cpp or cpp-and-c",
+        )));
+    }
+    if opt.kind == Kind::HeaderOnly {
+        return Err(invalid_input(String::from(
+            "--embed-resources needs compiled sources, kind header-only has none",
+        )));
+    }
+
+    Ok(())
+}
+
+// Adds resources/ with a sample file, the CMake function embedding it and the accessor header
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().embed_resources {
+        return gen;
+    }
+
+    gen.add_toplevel_dir(PathBuf::from("resources"))
+        .add_file(
+            PathBuf::from("resources/hello.txt"),
+            String::from(EMBED_SAMPLE),
+        )
+        .add_file(
+            PathBuf::from("cmake/EmbedResources.cmake"),
+            String::from(EMBED_MODULE),
+        )
+        .add_file(
+            PathBuf::from("cmake/EmbedResources.cpp.in"),
+            String::from(EMBED_SOURCE),
+        )
+        .add_file(
+            PathBuf::from("@INCLUDE_DIR@/@INCLUDE_DOMAIN_DIR@/resources.@HEADER_FILE_EXT@"),
+            String::from(EMBED_HEADER),
+        )
+        .add_cmake_snippet(String::from(EMBED_CMAKE.trim_end()))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::contents;
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            embed_resources: true,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+
+        assert!(validate(&Opt {
+            language: Language::C,
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            kind: Kind::HeaderOnly,
+            ..opt
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            embed_resources: true,
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .plan()
+            .unwrap();
+
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("embed_resources(tgtnm ${CMAKE_CURRENT_LIST_DIR}/resources)\n"));
+        assert!(contents(&plan, "include/tgtnm/resources.h")
+            .unwrap()
+            .contains("Resource find(const char* name);"));
+        // Left for configure_file of the CMake function
+        let source = contents(&plan, "cmake/EmbedResources.cpp.in").unwrap();
+        assert!(source.contains("@EMBED_RESOURCE_ARRAYS@"));
+        assert!(source.contains("#include \"tgtnm/resources.h\""));
+        assert!(contents(&plan, "resources/hello.txt").is_some());
+    }
+}
@@ -0,0 +1,180 @@
+/*
+For byte order marks /sa https://www.unicode.org/faq/utf_bom.html#BOM
+For str::from_utf8 /sa https://doc.rust-lang.org/std/str/fn.from_utf8.html
+*/
+
+use crate::error::{self, Error};
+use std::{fs, io::Read, path::Path};
+
+pub const UTF8_BOM: &str = "\u{feff}";
+const UTF16_BOMS: &[&[u8]] = &[&[0xff, 0xfe], &[0xfe, 0xff]];
+// Copied as they are, even if they happen to be valid UTF-8
+const BINARY_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "icns", "pdf", "ttf", "otf", "woff", "woff2", "zip",
+    "gz", "tgz", "xz", "a", "lib", "so", "dll", "dylib", "bin",
+];
+// Larger templates are streamed when written instead of read into memory
+const STREAM_THRESHOLD: u64 = 8 * 1024 * 1024;
+// Looked at for NUL bytes in a streamed template
+const SNIFF_LEN: u64 = 8 * 1024;
+
+// Contents of a template file
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateContents {
+    // Rendered with the vars replaced
+    Text(String),
+    // Copied as it is, e.g. an icon
+    Binary(Vec<u8>),
+    // Too large to keep in memory, read again when written, e.g. a bundled data set
+    Large { binary: bool },
+}
+
+// Binary if told so or else if it has NUL bytes, text otherwise
+// The UTF-8 BOM of text is stripped unless kept
+// Large templates are only checked for NUL bytes at their start
+pub fn read_template(
+    path: &Path,
+    binary: Option<bool>,
+    keep_bom: bool,
+) -> error::Result<TemplateContents> {
+    let size = fs::metadata(path).map_err(Error::fs("read", path))?.len();
+    if size > STREAM_THRESHOLD {
+        let binary = match binary {
+            Some(binary) => binary,
+            None => looks_binary(&read_start(path)?),
+        };
+        return Ok(TemplateContents::Large { binary });
+    }
+
+    let bytes = fs::read(path).map_err(Error::fs("read", path))?;
+    let binary = binary.unwrap_or_else(|| looks_binary(&bytes));
+
+    match binary {
+        true => Ok(TemplateContents::Binary(bytes)),
+        false => Ok(TemplateContents::Text(decode(path, bytes, keep_bom)?)),
+    }
+}
+
+// e.g. icon.ico, or logo.svgz with "svgz" among the extra extensions
+pub fn has_binary_extension(path: &Path, extra_extensions: &[String]) -> bool {
+    let extension = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => extension.to_ascii_lowercase(),
+        None => return false,
+    };
+
+    BINARY_EXTENSIONS.contains(&extension.as_str())
+        || extra_extensions.iter().any(|extra| {
+            extra
+                .trim_start_matches('.')
+                .eq_ignore_ascii_case(&extension)
+        })
+}
+
+// Templates that have to be text, e.g. CMakeLists.txt
+pub fn read_text(path: &Path, keep_bom: bool) -> error::Result<String> {
+    let bytes = fs::read(path).map_err(Error::fs("read", path))?;
+
+    decode(path, bytes, keep_bom)
+}
+
+fn decode(path: &Path, bytes: Vec<u8>, keep_bom: bool) -> error::Result<String> {
+    let encoding_error = |reason: String| Error::Encoding {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    if is_utf16(&bytes) {
+        return Err(encoding_error(String::from("it is UTF-16")));
+    }
+    let text = String::from_utf8(bytes).map_err(|err| {
+        encoding_error(format!(
+            "invalid byte at offset {}",
+            err.utf8_error().valid_up_to()
+        ))
+    })?;
+
+    match text.strip_prefix(UTF8_BOM) {
+        Some(stripped) if !keep_bom => Ok(String::from(stripped)),
+        _ => Ok(text),
+    }
+}
+
+fn read_start(path: &Path) -> error::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(SNIFF_LEN).read_to_end(&mut bytes))
+        .map_err(Error::fs("read", path))?;
+
+    Ok(bytes)
+}
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) && !is_utf16(bytes)
+}
+
+pub fn is_utf16(bytes: &[u8]) -> bool {
+    UTF16_BOMS.iter().any(|bom| bytes.starts_with(bom))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn read(name: &str, bytes: &[u8], keep_bom: bool) -> error::Result<TemplateContents> {
+        let path = env::temp_dir().join(format!("cpp-proj-gen-encoding-{}", name));
+        fs::write(&path, bytes).unwrap();
+        let result = read_template(&path, None, keep_bom);
+        fs::remove_file(&path).unwrap();
+
+        result
+    }
+
+    #[test]
+    fn test_read_template() {
+        assert_eq!(
+            read("bom.txt", b"\xef\xbb\xbfx", false).unwrap(),
+            TemplateContents::Text(String::from("x"))
+        );
+        assert_eq!(
+            read("kept-bom.txt", b"\xef\xbb\xbfx", true).unwrap(),
+            TemplateContents::Text(String::from("\u{feff}x"))
+        );
+        assert_eq!(
+            read("icon.ico", b"\x00\x00\x01\x00\xff", false).unwrap(),
+            TemplateContents::Binary(vec![0, 0, 1, 0, 0xff])
+        );
+
+        let err = read("latin1.txt", b"caf\xe9", false).unwrap_err();
+        assert!(matches!(err, Error::Encoding { .. }));
+        assert!(err.to_string().contains("invalid byte at offset 3"));
+        let err = read("utf16.txt", b"\xff\xfex\x00", false).unwrap_err();
+        assert!(err.to_string().contains("UTF-16"));
+    }
+
+    #[test]
+    fn test_read_large_template() {
+        let mut bytes = vec![b'x'; STREAM_THRESHOLD as usize + 1];
+        assert_eq!(
+            read("large.txt", &bytes, false).unwrap(),
+            TemplateContents::Large { binary: false }
+        );
+        bytes[0] = 0;
+        assert_eq!(
+            read("large.bin", &bytes, false).unwrap(),
+            TemplateContents::Large { binary: true }
+        );
+    }
+
+    #[test]
+    fn test_has_binary_extension() {
+        assert!(has_binary_extension(Path::new("res/icon.ICO"), &[]));
+        assert!(!has_binary_extension(Path::new("res/logo.svgz"), &[]));
+        assert!(has_binary_extension(
+            Path::new("res/logo.svgz"),
+            &[String::from(".svgz")]
+        ));
+        assert!(!has_binary_extension(Path::new("Makefile"), &[]));
+    }
+}
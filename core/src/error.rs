@@ -0,0 +1,309 @@
+/*
+For std::error::Error /sa https://doc.rust-lang.org/std/error/trait.Error.html
+For io::ErrorKind /sa https://doc.rust-lang.org/std/io/enum.ErrorKind.html
+For exit codes /sa https://man.freebsd.org/cgi/man.cgi?query=sysexits
+*/
+
+use crate::cpp_proj_gen::GenerationReport;
+use crate::warning::Warning;
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+// Process exit codes, kept stable for CI wrappers
+pub const EXIT_IO: i32 = 1;
+pub const EXIT_VALIDATION: i32 = 2;
+pub const EXIT_TEMPLATE: i32 = 3;
+// Verification builds run as post-hooks, so a failing one exits with this too
+pub const EXIT_HOOK_FAILED: i32 = 4;
+pub const EXIT_DENIED_WARNINGS: i32 = 5;
+pub const EXIT_CANCELLED: i32 = 6;
+
+// File name probed to check if the output location is writable
+const WRITE_PROBE_FILE_NAME: &str = ".cpp-proj-gen-write-probe";
+
+// Error
+#[derive(Debug)]
+pub enum Error {
+    // A filesystem operation failed on a path
+    Fs {
+        operation: &'static str,
+        path: PathBuf,
+        source: io::Error,
+    },
+    // The output location cannot be written to
+    ReadOnly {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // Another run generates into the same output directory
+    Locked {
+        path: PathBuf,
+        holder: String,
+    },
+    // A directory, file or target name would be written outside the project directory
+    PathEscape {
+        path: PathBuf,
+    },
+    // A template is neither UTF-8 text nor binary
+    Encoding {
+        path: PathBuf,
+        reason: String,
+    },
+    // Warnings turned into failures by --deny-warnings
+    DeniedWarnings(Vec<Warning>),
+    // A post-hook, e.g. a verification build, exited unsuccessfully
+    HookFailed {
+        command: String,
+        status: String,
+    },
+    // Stopped through a CancelToken, written paths are rolled back
+    Cancelled,
+    // Writing stopped after some paths were written, listed in the report
+    PartialWrite {
+        report: Box<GenerationReport>,
+        source: Box<Error>,
+    },
+    // Invalid input and other failures without a path
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Validation error, e.g. of conflicting options, exits with EXIT_VALIDATION
+pub fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+impl Error {
+    // e.g. fs::write(&path, contents).map_err(Error::fs("write", &path))
+    pub fn fs(operation: &'static str, path: &Path) -> impl FnOnce(io::Error) -> Error {
+        let path = path.to_path_buf();
+        move |source| Error::Fs {
+            operation,
+            path,
+            source,
+        }
+    }
+
+    // Validation errors are invalid input, template errors invalid data like a bad template.toml
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::PathEscape { .. } => EXIT_VALIDATION,
+            Error::Encoding { .. } => EXIT_TEMPLATE,
+            Error::DeniedWarnings(_) => EXIT_DENIED_WARNINGS,
+            Error::HookFailed { .. } => EXIT_HOOK_FAILED,
+            Error::Cancelled => EXIT_CANCELLED,
+            Error::PartialWrite { source, .. } => source.exit_code(),
+            Error::Io(source) => match source.kind() {
+                io::ErrorKind::InvalidInput => EXIT_VALIDATION,
+                io::ErrorKind::InvalidData => EXIT_TEMPLATE,
+                _ => EXIT_IO,
+            },
+            Error::Fs { .. } | Error::ReadOnly { .. } | Error::Locked { .. } => EXIT_IO,
+        }
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Error::Fs { source, .. } => match source.kind() {
+                io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+                    Some("check the permissions or pass --output-dir with a writable location")
+                }
+                io::ErrorKind::StorageFull => {
+                    Some("free some space or pass --output-dir elsewhere")
+                }
+                io::ErrorKind::NotADirectory | io::ErrorKind::AlreadyExists => {
+                    Some("a file is in the way, remove it or pass --output-dir elsewhere")
+                }
+                _ => None,
+            },
+            Error::ReadOnly { .. } => Some("pass --output-dir with a writable location"),
+            Error::Locked { .. } => {
+                Some("pass --wait to wait for the other run, or --no-lock to generate anyway")
+            }
+            Error::PathEscape { .. } => {
+                Some("use relative paths without '..', e.g. from a trusted template pack")
+            }
+            Error::Encoding { .. } => {
+                Some("save the template as UTF-8, or set binary = true for it in template.toml")
+            }
+            Error::DeniedWarnings(_) => Some("fix the warnings or drop --deny-warnings"),
+            Error::HookFailed { .. } => Some("run the post-hook in the project to see why"),
+            // The source has its own
+            Error::Cancelled | Error::PartialWrite { .. } | Error::Io(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Fs {
+                operation,
+                path,
+                source,
+            } => write!(f, "cannot {} {}: {}", operation, path.display(), source)?,
+            Error::ReadOnly { path, source } => write!(
+                f,
+                "output location {} is not writable: {}",
+                path.display(),
+                source
+            )?,
+            Error::Locked { path, holder } => {
+                write!(f, "{} is held by {}", path.display(), holder)?
+            }
+            Error::PathEscape { path } => {
+                write!(f, "'{}' is outside the project directory", path.display())?
+            }
+            Error::Encoding { path, reason } => {
+                write!(f, "template {} is not UTF-8: {}", path.display(), reason)?
+            }
+            Error::DeniedWarnings(warnings) => {
+                write!(f, "{} warnings denied", warnings.len())?;
+                for warning in warnings {
+                    write!(f, "\n  {}", warning)?;
+                }
+            }
+            Error::HookFailed { command, status } => {
+                write!(f, "post-hook '{}' failed with {}", command, status)?
+            }
+            Error::Cancelled => write!(f, "generation was cancelled")?,
+            Error::PartialWrite { report, source } => {
+                write!(f, "writing stopped after {} paths:", report.written.len())?;
+                for path in &report.written {
+                    write!(f, "\n  {}", path.display())?;
+                }
+                write!(f, "\n{}", source)?
+            }
+            Error::Io(source) => write!(f, "{}", source)?,
+        }
+
+        match self.hint() {
+            Some(hint) => write!(f, "\nhint: {}", hint),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Fs { source, .. } | Error::ReadOnly { source, .. } | Error::Io(source) => {
+                Some(source)
+            }
+            Error::PartialWrite { source, .. } => Some(source.as_ref()),
+            Error::Locked { .. }
+            | Error::PathEscape { .. }
+            | Error::Encoding { .. }
+            | Error::DeniedWarnings(_)
+            | Error::HookFailed { .. }
+            | Error::Cancelled => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+// Probes the closest existing ancestor of the output directory before writing anything
+pub fn check_writable(out_dir: &Path) -> Result<()> {
+    let existing = match out_dir.ancestors().find(|dir| dir.exists()) {
+        Some(existing) => existing,
+        None => return Ok(()),
+    };
+
+    if !existing.is_dir() {
+        return Err(Error::Fs {
+            operation: "create directory in",
+            path: existing.to_path_buf(),
+            source: io::Error::from(io::ErrorKind::NotADirectory),
+        });
+    }
+
+    let probe = existing.join(WRITE_PROBE_FILE_NAME);
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => fs::remove_file(&probe).map_err(Error::fs("remove", &probe)),
+        Err(source)
+            if matches!(
+                source.kind(),
+                io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+            ) =>
+        {
+            Err(Error::ReadOnly {
+                path: existing.to_path_buf(),
+                source,
+            })
+        }
+        // e.g. a leftover probe file, the actual writes will tell
+        Err(_) => Ok(()),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::fs("write", Path::new("out/CMakeLists.txt"))(io::Error::from(
+            io::ErrorKind::PermissionDenied,
+        ));
+        let message = err.to_string();
+
+        assert!(message.starts_with("cannot write out/CMakeLists.txt: permission denied"));
+        assert!(message.contains("hint: check the permissions"));
+
+        let err = Error::from(io::Error::new(io::ErrorKind::InvalidInput, "bad kind"));
+        assert_eq!(err.to_string(), "bad kind");
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let invalid_input = io::Error::new(io::ErrorKind::InvalidInput, "bad kind");
+        let invalid_data = io::Error::new(io::ErrorKind::InvalidData, "bad template.toml");
+        let not_found = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(Error::from(invalid_input).exit_code(), EXIT_VALIDATION);
+        assert_eq!(Error::from(invalid_data).exit_code(), EXIT_TEMPLATE);
+        assert_eq!(
+            Error::fs("read", Path::new("a"))(not_found).exit_code(),
+            EXIT_IO
+        );
+        assert_eq!(
+            Error::DeniedWarnings(Vec::new()).exit_code(),
+            EXIT_DENIED_WARNINGS
+        );
+
+        let err = Error::PartialWrite {
+            report: Box::new(GenerationReport {
+                written: vec![PathBuf::from("out/a.txt")],
+                ..GenerationReport::default()
+            }),
+            source: Box::new(Error::HookFailed {
+                command: String::from("cmake --build build"),
+                status: String::from("exit status: 2"),
+            }),
+        };
+        assert_eq!(err.exit_code(), EXIT_HOOK_FAILED);
+        assert!(err
+            .to_string()
+            .starts_with("writing stopped after 1 paths:\n  out/a.txt\npost-hook"));
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let out_dir = std::env::temp_dir().join("cpp-proj-gen-check-writable/a/b");
+
+        assert!(check_writable(&out_dir).is_ok());
+        assert!(!std::env::temp_dir().join(WRITE_PROBE_FILE_NAME).exists());
+    }
+}
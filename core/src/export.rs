@@ -0,0 +1,89 @@
+/*
+For ALIAS targets /sa https://cmake.org/cmake/help/latest/command/add_library.html#alias-libraries
+For install(EXPORT) /sa https://cmake.org/cmake/help/latest/command/install.html#export
+For package config files /sa https://cmake.org/cmake/help/latest/module/CMakePackageConfigHelpers.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use std::path::PathBuf;
+
+const ALIAS_CMAKE: &str = include_str!("../res/export/alias.snippet.in");
+const EXPORT_CMAKE: &str = include_str!("../res/export/CMakeLists.snippet.in");
+const EXPORT_CONFIG: &str = include_str!("../res/export/config.cmake.in");
+
+// Adds an ns::target alias and installs the library under the same namespace
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if gen.opt().kind == Kind::Executable {
+        return gen;
+    }
+
+    let export_namespace = build_export_namespace(gen.opt());
+    let alias_name = format!("{}::{}", export_namespace, gen.opt().target_name);
+
+    let gen = gen
+        .add_cmake_var("@CMAKE_EXPORT_NAMESPACE@", &export_namespace)
+        .add_cmake_var("@CMAKE_ALIAS_NAME@", &alias_name)
+        .add_cmake_snippet(String::from(ALIAS_CMAKE));
+
+    // Tests link the alias, so only the install rules are optional
+    if !gen.opt().builtin_template.has_install_rules() {
+        return gen;
+    }
+
+    gen.add_file(
+        PathBuf::from("cmake/@CMAKE_PROJECT_NAME@-config.cmake.in"),
+        String::from(EXPORT_CONFIG),
+    )
+    .add_cmake_snippet(String::from(EXPORT_CMAKE))
+}
+
+// Falls back to the target name, e.g. my-target::my-target
+fn build_export_namespace(opt: &Opt) -> String {
+    opt.name_space
+        .clone()
+        .unwrap_or_else(|| opt.target_name.clone())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_export_namespace() {
+        let opt = Opt {
+            name_space: Some(String::from("nmspc")),
+            ..Opt::default()
+        };
+        assert_eq!(build_export_namespace(&opt), "nmspc");
+
+        let opt = Opt {
+            name_space: None,
+            ..Opt::default()
+        };
+        assert_eq!(build_export_namespace(&opt), "my-target");
+    }
+
+    #[test]
+    fn test_export_files() {
+        let opt = Opt {
+            name_space: Some(String::from("nmspc")),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+        let paths = CppProjGen::new(opt).build_paths();
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/cmake/nmspc-my-target-config.cmake.in"
+        )));
+
+        let opt = Opt {
+            kind: Kind::Executable,
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+        let paths = CppProjGen::new(opt).build_paths();
+        assert!(!paths
+            .iter()
+            .any(|path| path.starts_with("test_out_dir/my-target/cmake")));
+    }
+}
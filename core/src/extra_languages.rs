@@ -0,0 +1,170 @@
+/*
+For enable_language /sa https://cmake.org/cmake/help/latest/command/enable_language.html
+For Fortran C interoperability /sa https://gcc.gnu.org/onlinedocs/gfortran/Interoperability-with-C.html
+For FindCUDAToolkit /sa https://cmake.org/cmake/help/latest/module/FindCUDAToolkit.html
+*/
+
+use crate::compiler::Compiler;
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::error::invalid_input;
+use std::{io, path::PathBuf, str::FromStr};
+
+const FORTRAN_SOURCE: &str = include_str!("../res/extra_languages/answer.f90.in");
+const FORTRAN_CMAKE: &str = include_str!("../res/extra_languages/fortran.snippet.in");
+const ASM_SOURCE: &str = include_str!("../res/extra_languages/answer.S.in");
+const ASM_CMAKE: &str = include_str!("../res/extra_languages/asm.snippet.in");
+const CUDA_SOURCE: &str = include_str!("../res/extra_languages/answer.cu.in");
+const CUDA_CMAKE: &str = include_str!("../res/extra_languages/cuda.snippet.in");
+const DECLARATIONS_HEADER: &str = include_str!("../res/extra_languages/extra_languages.h.in");
+
+// Language compiled into the target next to C and C++
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtraLanguage {
+    Fortran,
+    Asm,
+    Cuda,
+}
+
+impl ExtraLanguage {
+    pub const VARIANTS: &'static [&'static str] = &["fortran", "asm", "cuda"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExtraLanguage::Fortran => "fortran",
+            ExtraLanguage::Asm => "asm",
+            ExtraLanguage::Cuda => "cuda",
+        }
+    }
+
+    // Sample file name, its template and the CMake snippet compiling it
+    fn templates(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            ExtraLanguage::Fortran => ("answer.f90", FORTRAN_SOURCE, FORTRAN_CMAKE),
+            ExtraLanguage::Asm => ("answer.S", ASM_SOURCE, ASM_CMAKE),
+            ExtraLanguage::Cuda => ("answer.cu", CUDA_SOURCE, CUDA_CMAKE),
+        }
+    }
+}
+
+impl FromStr for ExtraLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fortran" => Ok(ExtraLanguage::Fortran),
+            "asm" => Ok(ExtraLanguage::Asm),
+            "cuda" => Ok(ExtraLanguage::Cuda),
+            _ => Err(format!(
+                "unknown extra language '{}', expected one of: {}",
+                s,
+                ExtraLanguage::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// The samples are compiled into the target
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let extra_language = match opt.extra_languages.first() {
+        Some(extra_language) => extra_language,
+        None => return Ok(()),
+    };
+
+    if opt.kind == Kind::HeaderOnly {
+        return Err(invalid_input(format!(
+            "--extra-language {} needs compiled sources, kind header-only has none",
+            extra_language.name()
+        )));
+    }
+    if opt.extra_languages.contains(&ExtraLanguage::Asm) && opt.compiler == Some(Compiler::Msvc) {
+        return Err(invalid_input(String::from(
+            "--extra-language asm is in GNU assembler syntax, use --compiler gcc or clang",
+        )));
+    }
+
+    Ok(())
+}
+
+// Adds @SOURCE_DIR@/<language>/ samples returning 42 with C linkage and a header declaring them
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let extra_languages = gen.opt().extra_languages.clone();
+    if extra_languages.is_empty() {
+        return gen;
+    }
+
+    let declarations: Vec<String> = extra_languages
+        .iter()
+        .map(|extra_language| format!("int @C_PREFIX@_{}_answer(void);", extra_language.name()))
+        .collect();
+    let gen = gen.add_file(
+        PathBuf::from("@SOURCE_DIR@/extra_languages.@HEADER_FILE_EXT@"),
+        DECLARATIONS_HEADER.replace("@EXTRA_LANGUAGE_DECLARATIONS@", &declarations.join("\n")),
+    );
+
+    extra_languages.iter().fold(gen, |gen, extra_language| {
+        let (file_name, source, cmake) = extra_language.templates();
+
+        gen.add_file(
+            PathBuf::from("@SOURCE_DIR@")
+                .join(extra_language.name())
+                .join(file_name),
+            String::from(source),
+        )
+        .add_cmake_snippet(String::from(cmake.trim_end()))
+    })
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::contents;
+
+    fn create_test_opt() -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            extra_languages: vec![ExtraLanguage::Fortran, ExtraLanguage::Cuda],
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&create_test_opt()).is_ok());
+        assert!(validate(&Opt {
+            kind: Kind::HeaderOnly,
+            ..create_test_opt()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            extra_languages: vec![ExtraLanguage::Asm],
+            compiler: Some(Compiler::Msvc),
+            ..create_test_opt()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = CppProjGen::new(create_test_opt())
+            .add_source_dir(PathBuf::from("source"))
+            .plan()
+            .unwrap();
+
+        assert!(contents(&plan, "source/fortran/answer.f90")
+            .unwrap()
+            .contains("bind(C, name=\"tgtnm_fortran_answer\")"));
+        assert!(contents(&plan, "source/cuda/answer.cu").is_some());
+        assert!(contents(&plan, "source/asm/answer.S").is_none());
+        assert!(contents(&plan, "source/extra_languages.h")
+            .unwrap()
+            .contains("int tgtnm_fortran_answer(void);\nint tgtnm_cuda_answer(void);\n"));
+
+        let cmake_lists = contents(&plan, "CMakeLists.txt").unwrap();
+        assert!(cmake_lists.contains(
+            "enable_language(Fortran)\ntarget_sources(tgtnm PRIVATE source/fortran/answer.f90)"
+        ));
+        assert!(cmake_lists.contains("target_link_libraries(tgtnm PRIVATE CUDA::cudart)"));
+    }
+}
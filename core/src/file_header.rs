@@ -0,0 +1,160 @@
+/*
+For SPDX license identifiers in source files /sa https://spdx.dev/learn/handling-license-info/
+*/
+
+use crate::cpp_proj_gen::Opt;
+use crate::error::{self, invalid_input, Error};
+use crate::project_manifest::{self, ProjectManifest};
+use std::{fs, path::Path};
+
+const SOURCE_EXTS: &[&str] = &["h", "hpp", "hh", "hxx", "c", "cc", "cpp", "cxx", "mm"];
+const SPDX_PREFIX: &str = "// SPDX-License-Identifier:";
+const COPYRIGHT_PREFIX: &str = "// Copyright";
+
+// e.g. "// SPDX-License-Identifier: MIT\n// Copyright Jane Doe\n"
+pub fn build_banner(license: &str, author: Option<&str>) -> String {
+    match author {
+        Some(author) => format!(
+            "{} {}\n{} {}\n",
+            SPDX_PREFIX, license, COPYRIGHT_PREFIX, author
+        ),
+        None => format!("{} {}\n", SPDX_PREFIX, license),
+    }
+}
+
+// Generated sources start with the banner if a license is given
+pub fn add_banner(path: &Path, contents: String, opt: &Opt) -> String {
+    match source_banner(path, opt) {
+        Some(banner) => format!("{}\n{}", banner, contents),
+        None => contents,
+    }
+}
+
+pub fn source_banner(path: &Path, opt: &Opt) -> Option<String> {
+    match &opt.license {
+        Some(license) if is_source(path) => Some(build_banner(license, opt.author.as_deref())),
+        _ => None,
+    }
+}
+
+// Checks the tracked sources of a project for the banner, --fix adds or replaces it,
+// returns the manifest paths of the files without it
+pub fn check_headers(
+    project_dir: &Path,
+    license: Option<&str>,
+    author: Option<&str>,
+    fix: bool,
+) -> error::Result<Vec<String>> {
+    let manifest = ProjectManifest::load(project_dir)?;
+    let license = license
+        .or(manifest.license.as_deref())
+        .ok_or_else(|| invalid_input(String::from("the project has no license, pass --license")))?;
+    let banner = build_banner(license, author.or(manifest.author.as_deref()));
+
+    let mut missing = Vec::new();
+    for file in manifest
+        .files
+        .iter()
+        .filter(|file| is_source(Path::new(file)))
+    {
+        let path = project_dir.join(project_manifest::from_manifest_path(file));
+        // Files the user deleted since generation are skipped
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+        if contents.starts_with(&banner) {
+            continue;
+        }
+
+        if fix {
+            let fixed = format!("{}\n{}", banner, strip_banner(&contents));
+            fs::write(&path, fixed).map_err(Error::fs("write", &path))?;
+        }
+        missing.push(file.clone());
+    }
+
+    Ok(missing)
+}
+
+// Fails unless every file has the header or was fixed
+pub fn check_missing(missing: &[String], fix: bool) -> error::Result<()> {
+    match (missing.len(), fix) {
+        (0, _) | (_, true) => Ok(()),
+        (count, false) => Err(Error::from(invalid_input(format!(
+            "{} file(s) lack the header, run check-headers --fix",
+            count
+        )))),
+    }
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| SOURCE_EXTS.iter().any(|other| ext == *other))
+}
+
+// Drops an outdated banner, e.g. of another license, and the blank line after it
+fn strip_banner(contents: &str) -> &str {
+    let mut rest = contents;
+
+    while rest.starts_with(SPDX_PREFIX) || rest.starts_with(COPYRIGHT_PREFIX) {
+        rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+    if rest.len() < contents.len() {
+        rest = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+
+    rest
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_banner() {
+        assert_eq!(
+            build_banner("MIT", Some("Jane Doe")),
+            "// SPDX-License-Identifier: MIT\n// Copyright Jane Doe\n"
+        );
+        assert_eq!(
+            build_banner("MIT", None),
+            "// SPDX-License-Identifier: MIT\n"
+        );
+    }
+
+    #[test]
+    fn test_add_banner() {
+        let opt = Opt {
+            license: Some(String::from("MIT")),
+            ..Opt::default()
+        };
+
+        assert_eq!(
+            add_banner(
+                Path::new("source/tgtnm.cpp"),
+                String::from("int a;\n"),
+                &opt
+            ),
+            "// SPDX-License-Identifier: MIT\n\nint a;\n"
+        );
+        assert_eq!(
+            add_banner(
+                Path::new("CMakeLists.txt"),
+                String::from("project()\n"),
+                &opt
+            ),
+            "project()\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_banner() {
+        assert_eq!(
+            strip_banner("// SPDX-License-Identifier: BSD-3-Clause\n// Copyright Acme\n\nint a;\n"),
+            "int a;\n"
+        );
+        assert_eq!(strip_banner("\nint a;\n"), "\nint a;\n");
+    }
+}
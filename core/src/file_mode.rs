@@ -0,0 +1,146 @@
+/*
+For Unix permissions /sa https://doc.rust-lang.org/std/os/unix/fs/trait.PermissionsExt.html
+For shebangs /sa https://en.wikipedia.org/wiki/Shebang_(Unix)
+For symlinks on Windows /sa https://learn.microsoft.com/en-us/windows/security/threat-protection/security-policy-settings/create-symbolic-links
+*/
+
+use std::{fs, io, path::Path};
+
+// Scripts like scripts/build.sh or debian/rules start with e.g. #!/bin/sh
+pub fn has_shebang(contents: &[u8]) -> bool {
+    contents.starts_with(b"#!")
+}
+
+#[cfg(unix)]
+pub fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+// Windows has no executable bit
+#[cfg(not(unix))]
+pub fn is_executable(_: &fs::Metadata) -> bool {
+    false
+}
+
+// Like chmod +x, executable for whoever may read it
+#[cfg(unix)]
+pub fn set_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    permissions.set_mode(mode | ((mode & 0o444) >> 2));
+
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+pub fn set_executable(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+// Replaces whatever is at path, target is relative to the link's directory
+#[cfg(unix)]
+pub fn create_link(path: &Path, target: &Path) -> io::Result<()> {
+    remove_existing(path)?;
+
+    std::os::unix::fs::symlink(target, path)
+}
+
+// Symlinks need developer mode or a privilege on Windows, without them the target is copied
+#[cfg(windows)]
+pub fn create_link(path: &Path, target: &Path) -> io::Result<()> {
+    use std::os::windows::fs::{symlink_dir, symlink_file};
+
+    remove_existing(path)?;
+    let resolved = path.parent().unwrap_or_else(|| Path::new("")).join(target);
+    let linked = match resolved.is_dir() {
+        true => symlink_dir(target, path),
+        false => symlink_file(target, path),
+    };
+
+    match linked {
+        Ok(()) => Ok(()),
+        Err(_) => copy_all(&resolved, path),
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn create_link(path: &Path, target: &Path) -> io::Result<()> {
+    remove_existing(path)?;
+
+    copy_all(
+        &path.parent().unwrap_or_else(|| Path::new("")).join(target),
+        path,
+    )
+}
+
+fn remove_existing(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        // Links to directories are directories on Windows
+        Ok(metadata) if metadata.is_symlink() => {
+            fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+        }
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_all(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_all(&entry.path(), &to.join(entry.file_name()))?;
+    }
+
+    Ok(())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_shebang() {
+        assert!(has_shebang(b"#!/bin/sh\n"));
+        assert!(!has_shebang(b"# comment\n"));
+        assert!(!has_shebang(b""));
+    }
+
+    #[test]
+    fn test_set_executable() {
+        let path = std::env::temp_dir().join("cpp-proj-gen-test-set-executable.sh");
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+
+        set_executable(&path).unwrap();
+        let executable = is_executable(&fs::metadata(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(executable, cfg!(unix));
+    }
+
+    #[test]
+    fn test_create_link() {
+        let dir = std::env::temp_dir().join("cpp-proj-gen-test-create-link");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("v2")).unwrap();
+        fs::write(dir.join("v2/a.h"), "a").unwrap();
+
+        create_link(&dir.join("latest"), Path::new("v2")).unwrap();
+        // Again, replacing the former link
+        create_link(&dir.join("latest"), Path::new("v2")).unwrap();
+        let contents = fs::read_to_string(dir.join("latest/a.h")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(contents, "a");
+    }
+}
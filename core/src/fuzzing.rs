@@ -0,0 +1,139 @@
+/*
+For libFuzzer /sa https://llvm.org/docs/LibFuzzer.html
+For AFL++ with libFuzzer harnesses /sa https://github.com/AFLplusplus/AFLplusplus/blob/stable/utils/aflpp_driver/README.md
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind};
+use crate::language::Language;
+use std::{path::PathBuf, str::FromStr};
+
+const FUZZ_CMAKE: &str = include_str!("../res/fuzzing/CMakeLists.snippet.in");
+const LIBFUZZER_CMLT: &str = include_str!("../res/fuzzing/libfuzzer.CMakeLists.txt.in");
+const AFL_CMLT: &str = include_str!("../res/fuzzing/afl.CMakeLists.txt.in");
+const FUZZ_CPP: &str = include_str!("../res/fuzzing/fuzz.cpp.in");
+const FUZZ_C: &str = include_str!("../res/fuzzing/fuzz.c.in");
+const CORPUS_SEED: &str = include_str!("../res/fuzzing/corpus.seed.in");
+
+// Fuzzing engine
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FuzzEngine {
+    LibFuzzer,
+    Afl,
+}
+
+impl FuzzEngine {
+    pub const VARIANTS: &'static [&'static str] = &["libfuzzer", "afl"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FuzzEngine::LibFuzzer => "libfuzzer",
+            FuzzEngine::Afl => "afl",
+        }
+    }
+
+    fn cmake_lists(self) -> &'static str {
+        match self {
+            FuzzEngine::LibFuzzer => LIBFUZZER_CMLT,
+            FuzzEngine::Afl => AFL_CMLT,
+        }
+    }
+}
+
+impl FromStr for FuzzEngine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "libfuzzer" => Ok(FuzzEngine::LibFuzzer),
+            "afl" => Ok(FuzzEngine::Afl),
+            _ => Err(format!(
+                "unknown fuzzing engine '{}', expected one of: {}",
+                s,
+                FuzzEngine::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Adds fuzz/ with a harness and a seed corpus, built behind a CMake option
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let engine = match gen.opt().fuzzing {
+        Some(engine) => engine,
+        None => return gen,
+    };
+
+    let (harness, cmake_language, afl_compiler) = match gen.opt().language {
+        Language::C => (FUZZ_C, "C", "afl-clang-fast"),
+        _ => (FUZZ_CPP, "CXX", "afl-clang-fast++"),
+    };
+    let cmake_lists = engine
+        .cmake_lists()
+        .replace("@FUZZ_UNDER_TEST@", build_fuzz_under_test(gen.opt().kind));
+
+    gen.add_cmake_var("@FUZZ_ENGINE@", engine.name())
+        .add_cmake_var("@FUZZ_CMAKE_LANGUAGE@", cmake_language)
+        .add_cmake_var("@FUZZ_AFL_COMPILER@", afl_compiler)
+        .add_file(PathBuf::from("fuzz/CMakeLists.txt"), cmake_lists)
+        .add_file(
+            PathBuf::from("fuzz/fuzz_@CMAKE_TARGET_NAME@.@SOURCE_FILE_EXT@"),
+            String::from(harness),
+        )
+        .add_file(PathBuf::from("fuzz/corpus/seed"), String::from(CORPUS_SEED))
+        .add_cmake_snippet(String::from(FUZZ_CMAKE))
+}
+
+// Sources are compiled into the fuzzer so they get the coverage instrumentation
+fn build_fuzz_under_test(kind: Kind) -> &'static str {
+    match kind {
+        Kind::HeaderOnly => {
+            "target_link_libraries(@CMAKE_TARGET_NAME@-fuzz
+    PRIVATE
+        @CMAKE_ALIAS_NAME@
+)"
+        }
+        _ => {
+            "target_sources(@CMAKE_TARGET_NAME@-fuzz
+    PRIVATE
+        ${PROJECT_SOURCE_DIR}/@SOURCE_DIR@/@CMAKE_TARGET_NAME@.@SOURCE_FILE_EXT@
+)
+
+target_include_directories(@CMAKE_TARGET_NAME@-fuzz
+    PRIVATE
+        ${PROJECT_SOURCE_DIR}/@INCLUDE_DIR@
+)"
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::Opt;
+
+    #[test]
+    fn test_from_str() {
+        for variant in FuzzEngine::VARIANTS {
+            assert_eq!(variant.parse::<FuzzEngine>().unwrap().name(), *variant);
+        }
+        assert!("honggfuzz".parse::<FuzzEngine>().is_err());
+    }
+
+    #[test]
+    fn test_fuzz_files() {
+        let opt = Opt {
+            fuzzing: Some(FuzzEngine::Afl),
+            language: Language::C,
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        let paths = CppProjGen::new(opt).build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/fuzz/CMakeLists.txt")));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/fuzz/fuzz_my-target.c"
+        )));
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/fuzz/corpus/seed")));
+    }
+}
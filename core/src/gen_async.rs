@@ -0,0 +1,173 @@
+/*
+For spawn_blocking /sa https://docs.rs/tokio/1/tokio/task/fn.spawn_blocking.html
+For tokio::process /sa https://docs.rs/tokio/1/tokio/process/index.html
+*/
+
+use crate::cancel::CancelToken;
+use crate::cpp_proj_gen::{self, CppProjGen, GenerationReport, PlanEntry};
+use crate::error::{self, Error};
+use crate::output::{LocalOutput, OutputAdapter};
+use crate::reporter::{GenEvent, Reporter};
+use crate::template_pack::{self, Manifest};
+use std::{future::Future, io, path::Path, time::Instant};
+use tokio::{process::Command, runtime, task};
+
+// Runs a future on the calling thread, e.g. gen_async() of the CLI
+pub fn block_on<F: Future>(future: F) -> error::Result<F::Output> {
+    let runtime = runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    Ok(runtime.block_on(future))
+}
+
+impl CppProjGen {
+    // Like gen() into the local filesystem, the writes run on tokio's blocking threads
+    // Rendering stays on the executor, it only reads the templates
+    pub async fn gen_async(
+        &self,
+        mut reporter: Option<&mut (dyn Reporter + Send)>,
+    ) -> error::Result<GenerationReport> {
+        let (plan, report) = self.plan_to_write(
+            reporter
+                .as_mut()
+                .map(|reporter| &mut **reporter as &mut dyn Reporter),
+        )?;
+        let out_dir = self.out_dir().to_path_buf();
+        blocking(move || error::check_writable(&out_dir)).await?;
+
+        let write_start = Instant::now();
+        let written = create_all_paths(plan, reporter, self.cancel_token()).await;
+
+        report.add_written(written, write_start)
+    }
+}
+
+impl Manifest {
+    // Like run_post_hooks(), awaiting the hooks instead of blocking
+    pub async fn run_post_hooks_async(
+        &self,
+        project_dir: &Path,
+        reporter: &mut (dyn Reporter + Send),
+        cancel: &CancelToken,
+    ) -> error::Result<()> {
+        for hook in &self.post_hooks {
+            cancel.check()?;
+            reporter.event(&GenEvent::HookStarted {
+                command: hook.clone(),
+            });
+
+            let mut command = template_pack::shell_command(hook);
+            command.current_dir(project_dir);
+            let status = Command::from(command)
+                .status()
+                .await
+                .map_err(Error::fs("run post-hook in", project_dir))?;
+            reporter.event(&GenEvent::HookFinished {
+                command: hook.clone(),
+                success: status.success(),
+            });
+
+            if !status.success() {
+                return Err(Error::HookFailed {
+                    command: hook.clone(),
+                    status: status.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn create_all_paths(
+    plan: Vec<PlanEntry>,
+    mut reporter: Option<&mut (dyn Reporter + Send)>,
+    cancel: &CancelToken,
+) -> error::Result<GenerationReport> {
+    let mut report = GenerationReport::default();
+    let total = plan.len();
+
+    for (index, entry) in plan.into_iter().enumerate() {
+        if let Err(err) = cancel.check() {
+            let created = report.created;
+            blocking(move || cpp_proj_gen::roll_back(&created, &mut LocalOutput)).await?;
+            return Err(err);
+        }
+
+        let created = blocking(move || {
+            let existed = LocalOutput.exists(entry.path());
+            cpp_proj_gen::create_path(&entry, &mut LocalOutput).map(|bytes| (entry, bytes, existed))
+        })
+        .await;
+        let (entry, bytes, existed) = match created {
+            Ok(created) => created,
+            Err(source) => return Err(cpp_proj_gen::stop_writing(report, source)),
+        };
+        cpp_proj_gen::record_written(&entry, existed, &mut report);
+        let event = cpp_proj_gen::record_created(entry, bytes, &mut report, index + 1, total);
+
+        if let Some(reporter) = reporter.as_mut() {
+            reporter.event(&event);
+        }
+    }
+
+    Ok(report)
+}
+
+async fn blocking<T, F>(f: F) -> error::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> error::Result<T> + Send + 'static,
+{
+    task::spawn_blocking(f)
+        .await
+        .map_err(|err| Error::from(io::Error::other(err)))?
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::Opt;
+    use std::{fs, path::PathBuf};
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn test_gen_async() {
+        let out_dir = std::env::temp_dir().join("cpp-proj-gen-test-gen-async");
+        let _ = fs::remove_dir_all(&out_dir);
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(out_dir.clone()),
+            ..Opt::default()
+        };
+        let gen = CppProjGen::new(opt).add_file(PathBuf::from("a.txt"), String::from("a"));
+
+        let future = gen.gen_async(None);
+        assert_send(&future);
+        let report = block_on(future).unwrap().unwrap();
+        let contents = fs::read_to_string(out_dir.join("tgtnm/a.txt"));
+        fs::remove_dir_all(&out_dir).unwrap();
+
+        assert_eq!(contents.unwrap(), "a\n");
+        assert_eq!(report.files, report.written.len() - report.directories);
+    }
+
+    #[test]
+    fn test_run_post_hooks_async() {
+        let manifest = Manifest {
+            post_hooks: vec![String::from("exit 0"), String::from("exit 3")],
+            ..Manifest::default()
+        };
+        let result = block_on(manifest.run_post_hooks_async(
+            &std::env::temp_dir(),
+            &mut Vec::<GenEvent>::new(),
+            &CancelToken::default(),
+        ))
+        .unwrap();
+
+        assert!(matches!(result, Err(Error::HookFailed { command, .. }) if command == "exit 3"));
+    }
+}
@@ -0,0 +1,203 @@
+/*
+For gettext /sa https://www.gnu.org/software/gettext/manual/gettext.html
+For FindIntl /sa https://cmake.org/cmake/help/latest/module/FindIntl.html
+For qt_add_translations /sa https://doc.qt.io/qt-6/qtlinguist-cmake-qt-add-translations.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::error::invalid_input;
+use crate::language::Language;
+use std::{io, path::PathBuf, str::FromStr};
+
+const GETTEXT_CMAKE: &str = include_str!("../res/i18n/gettext.CMakeLists.snippet.in");
+const GETTEXT_POT: &str = include_str!("../res/i18n/messages.pot.in");
+const GETTEXT_PO: &str = include_str!("../res/i18n/de.po.in");
+const GETTEXT_MAIN_CPP: &str = include_str!("../res/i18n/gettext.main.cpp.in");
+const GETTEXT_MAIN_C: &str = include_str!("../res/i18n/gettext.main.c.in");
+const QT_CMAKE: &str = include_str!("../res/i18n/qt.CMakeLists.snippet.in");
+const QT_TS: &str = include_str!("../res/i18n/de.ts.in");
+const QT_MAIN: &str = include_str!("../res/i18n/qt.main.cpp.in");
+
+// Translation system of the application
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum I18n {
+    Gettext,
+    Qt,
+}
+
+impl I18n {
+    pub const VARIANTS: &'static [&'static str] = &["gettext", "qt"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            I18n::Gettext => "gettext",
+            I18n::Qt => "qt",
+        }
+    }
+}
+
+impl FromStr for I18n {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gettext" => Ok(I18n::Gettext),
+            "qt" => Ok(I18n::Qt),
+            _ => Err(format!(
+                "unknown i18n '{}', expected one of: {}",
+                s,
+                I18n::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// The localized string goes into the sample main, presets bring their own
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let i18n = match opt.i18n {
+        Some(i18n) => i18n,
+        None => return Ok(()),
+    };
+
+    if opt.kind.app_target().is_none() {
+        return Err(invalid_input(format!(
+            "--i18n needs an executable, kind {} has none",
+            opt.kind.name()
+        )));
+    }
+    if let Some(preset) = opt.preset {
+        return Err(invalid_input(format!(
+            "--i18n replaces the sample main, which preset {} provides itself",
+            preset.name()
+        )));
+    }
+    if i18n == I18n::Qt && opt.language == Language::C {
+        return Err(invalid_input(format!(
+            "--i18n {} needs C++, use --language cpp or cpp-and-c",
+            i18n.name()
+        )));
+    }
+
+    Ok(())
+}
+
+// Adds the catalogs with a German sample, the extract and compile rules and a localized main
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (i18n, app_target) = match (gen.opt().i18n, gen.opt().kind.app_target()) {
+        (Some(i18n), Some(app_target)) => (i18n, app_target),
+        _ => return gen,
+    };
+    let main_path = PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@");
+
+    match i18n {
+        I18n::Gettext => {
+            let main = match gen.opt().language {
+                Language::C => GETTEXT_MAIN_C,
+                _ => GETTEXT_MAIN_CPP,
+            };
+            gen.add_file(
+                PathBuf::from("po/@CMAKE_TARGET_NAME@.pot"),
+                String::from(GETTEXT_POT),
+            )
+            .add_file(PathBuf::from("po/de.po"), String::from(GETTEXT_PO))
+            .add_file(main_path, String::from(main))
+            .add_cmake_snippet(build_snippet(GETTEXT_CMAKE, app_target))
+        }
+        I18n::Qt => gen
+            .add_file(
+                PathBuf::from("translations/@CMAKE_TARGET_NAME@_de.ts"),
+                String::from(QT_TS),
+            )
+            .add_file(main_path, String::from(QT_MAIN))
+            .add_cmake_snippet(build_snippet(QT_CMAKE, app_target)),
+    }
+}
+
+fn build_snippet(template: &str, app_target: &str) -> String {
+    String::from(template.replace("@I18N_APP_TARGET@", app_target).trim_end())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Kind, PlanEntry};
+    use crate::presets::Preset;
+    use crate::test_util::contents;
+
+    fn create_test_plan(opt: Opt) -> Vec<PlanEntry> {
+        CppProjGen::new(Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::LibAndApp,
+            ..opt
+        })
+        .add_include_dir(PathBuf::from("include"))
+        .add_source_dir(PathBuf::from("source"))
+        .plan()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            kind: Kind::Executable,
+            language: Language::C,
+            i18n: Some(I18n::Gettext),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+
+        assert!(validate(&Opt {
+            i18n: Some(I18n::Qt),
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            kind: Kind::Static,
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            language: Language::Cpp,
+            preset: Some(Preset::CliApp),
+            ..opt
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply_gettext() {
+        let plan = create_test_plan(Opt {
+            i18n: Some(I18n::Gettext),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("target_link_libraries(tgtnm-app PRIVATE ${Intl_LIBRARIES})"));
+        assert!(contents(&plan, "source/main.cpp")
+            .unwrap()
+            .contains("bindtextdomain(\"tgtnm\", TGTNM_LOCALE_DIR);"));
+        assert!(contents(&plan, "po/de.po")
+            .unwrap()
+            .contains("msgstr \"Hallo, Welt!\""));
+        assert!(contents(&plan, "po/tgtnm.pot").is_some());
+    }
+
+    #[test]
+    fn test_apply_qt() {
+        let plan = create_test_plan(Opt {
+            i18n: Some(I18n::Qt),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("qt_add_translations(tgtnm-app\n    TS_FILES translations/tgtnm_de.ts\n)"));
+        assert!(contents(&plan, "source/main.cpp")
+            .unwrap()
+            .contains("QCoreApplication::translate(\"main\", \"Hello, world!\")"));
+        assert!(contents(&plan, "translations/tgtnm_de.ts").is_some());
+    }
+}
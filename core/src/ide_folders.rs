@@ -0,0 +1,93 @@
+/*
+For FOLDER /sa https://cmake.org/cmake/help/latest/prop_tgt/FOLDER.html
+For source_group(TREE) /sa https://cmake.org/cmake/help/latest/command/source_group.html
+*/
+
+use crate::cpp_proj_gen::CppProjGen;
+use std::path::PathBuf;
+
+// Project targets in one solution folder, tests and fuzzers below it like on disk
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().ide_folders {
+        return gen;
+    }
+
+    let targets: Vec<&str> = gen
+        .model()
+        .targets
+        .iter()
+        .map(|target| target.name.as_str())
+        .collect();
+
+    let mut cmake = vec![
+        String::from("# Solution folders and source groups for Visual Studio and Xcode\nset_property(GLOBAL PROPERTY USE_FOLDERS ON)"),
+        build_folder(&targets, "@CMAKE_PROJECT_NAME@"),
+    ];
+    cmake.extend(
+        targets
+            .iter()
+            .map(|target| build_source_group(target, "@CMAKE_VAR_PREFIX@")),
+    );
+    // The fuzz target only exists if enabled
+    if gen.opt().fuzzing.is_some() {
+        cmake.push(format!(
+            "if(TARGET @CMAKE_TARGET_NAME@-fuzz)\n    {}\nendif()",
+            build_folder(&["@CMAKE_TARGET_NAME@-fuzz"], "@CMAKE_PROJECT_NAME@/fuzz")
+        ));
+    }
+
+    let with_tests = gen.opt().test_framework.is_some();
+    let gen = gen.add_cmake_snippet(cmake.join("\n\n"));
+    if !with_tests {
+        return gen;
+    }
+
+    // Source groups are per directory, so the test target gets its own
+    let test_target = "@CMAKE_TARGET_NAME@-test";
+    gen.add_cmake_snippet_to(
+        PathBuf::from("@TEST_DIR@/CMakeLists.txt"),
+        format!(
+            "{}\n\n{}",
+            build_folder(&[test_target], "@CMAKE_PROJECT_NAME@/@TEST_DIR@"),
+            build_source_group(test_target, "@CMAKE_VAR_PREFIX@_TEST")
+        ),
+    )
+}
+
+fn build_folder(targets: &[&str], folder: &str) -> String {
+    format!(
+        "set_property(TARGET {} PROPERTY FOLDER \"{}\")",
+        targets.join(" "),
+        folder
+    )
+}
+
+// Mirrors the directory tree, e.g. include/ and source/ filters
+fn build_source_group(target: &str, var_prefix: &str) -> String {
+    format!(
+        "get_target_property({0}_GROUPED_SOURCES {1} SOURCES)\nsource_group(TREE ${{CMAKE_CURRENT_LIST_DIR}} FILES ${{{0}_GROUPED_SOURCES}})",
+        var_prefix, target
+    )
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_source_group() {
+        assert_eq!(
+            build_source_group("tgtnm", "TGTNM"),
+            "get_target_property(TGTNM_GROUPED_SOURCES tgtnm SOURCES)\nsource_group(TREE ${CMAKE_CURRENT_LIST_DIR} FILES ${TGTNM_GROUPED_SOURCES})"
+        );
+    }
+
+    #[test]
+    fn test_build_folder() {
+        assert_eq!(
+            build_folder(&["tgtnm", "tgtnm-app"], "tgtnm"),
+            "set_property(TARGET tgtnm tgtnm-app PROPERTY FOLDER \"tgtnm\")"
+        );
+    }
+}
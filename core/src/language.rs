@@ -0,0 +1,268 @@
+/*
+For CMake compile features /sa https://cmake.org/cmake/help/latest/prop_gbl/CMAKE_C_KNOWN_FEATURES.html
+For project LANGUAGES /sa https://cmake.org/cmake/help/latest/command/project.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::naming;
+use std::{path::PathBuf, str::FromStr};
+
+const CPP_HEADER: &str = include_str!("../res/samples/cpp/header.h.in");
+const CPP_HEADER_ONLY: &str = include_str!("../res/samples/cpp/header-only.h.in");
+const CPP_SOURCE: &str = include_str!("../res/samples/cpp/source.cpp.in");
+const CPP_MAIN: &str = include_str!("../res/samples/cpp/main.cpp.in");
+const C_HEADER: &str = include_str!("../res/samples/c/header.h.in");
+const C_HEADER_ONLY: &str = include_str!("../res/samples/c/header-only.h.in");
+const C_SOURCE: &str = include_str!("../res/samples/c/source.c.in");
+const C_MAIN: &str = include_str!("../res/samples/c/main.c.in");
+
+pub const CXX_STANDARDS: &[&str] = &["11", "14", "17", "20", "23"];
+pub const C_STANDARDS: &[&str] = &["90", "99", "11", "17", "23"];
+
+// Language
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Cpp,
+    C,
+    CppAndC,
+}
+
+impl Language {
+    pub const VARIANTS: &'static [&'static str] = &["cpp", "c", "cpp-and-c"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Cpp => "cpp",
+            Language::C => "c",
+            Language::CppAndC => "cpp-and-c",
+        }
+    }
+
+    pub fn has_c(self) -> bool {
+        self != Language::Cpp
+    }
+
+    pub fn has_cpp(self) -> bool {
+        self != Language::C
+    }
+
+    fn cmake_languages(self) -> &'static str {
+        match self {
+            Language::Cpp => "CXX",
+            Language::C => "C",
+            Language::CppAndC => "C CXX",
+        }
+    }
+
+    // Mixed projects get C++ samples
+    pub fn source_file_ext(self) -> &'static str {
+        match self {
+            Language::C => "c",
+            _ => "cpp",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cpp" => Ok(Language::Cpp),
+            "c" => Ok(Language::C),
+            "cpp-and-c" => Ok(Language::CppAndC),
+            _ => Err(format!(
+                "unknown language '{}', expected one of: {}",
+                s,
+                Language::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Adds language vars and, if wanted, sample sources
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let opt = gen.opt();
+    let language = opt.language;
+    let kind = opt.kind;
+    let samples = has_samples(opt);
+    let identifiers = build_identifiers(opt);
+    let (namespace_begin, namespace_end) = build_cpp_namespace_blocks(&identifiers);
+    let compile_features = build_compile_features(opt);
+    let include_guard = format!(
+        "{}_{}_H",
+        identifiers.join("_"),
+        identifiers.last().unwrap()
+    )
+    .to_uppercase();
+    let source_ext = String::from(naming::source_ext(opt));
+    let header_ext = opt.header_ext.clone();
+    let test_prefix = String::from(opt.test_pattern.prefix());
+    let test_suffix = opt.test_pattern.suffix(&source_ext);
+
+    let gen = gen
+        .add_cmake_var("@CMAKE_PROJECT_LANGUAGES@", language.cmake_languages())
+        .add_cmake_var("@CMAKE_COMPILE_FEATURES@", &compile_features)
+        .add_cmake_var("@SOURCE_FILE_EXT@", &source_ext)
+        .add_cmake_var("@HEADER_FILE_EXT@", &header_ext)
+        .add_cmake_var("@TEST_FILE_PREFIX@", &test_prefix)
+        .add_cmake_var("@TEST_FILE_SUFFIX@", &test_suffix)
+        .add_cmake_var("@CPP_NAMESPACE@", &identifiers.join("::"))
+        .add_cmake_var("@CPP_NAMESPACE_BEGIN@", &namespace_begin)
+        .add_cmake_var("@CPP_NAMESPACE_END@", &namespace_end)
+        .add_cmake_var("@C_PREFIX@", &identifiers.join("_"))
+        .add_cmake_var("@CMAKE_VAR_PREFIX@", &identifiers.join("_").to_uppercase())
+        .add_cmake_var("@INCLUDE_GUARD@", &include_guard);
+
+    if !samples {
+        return gen;
+    }
+    let (header, header_only, source) = match language {
+        Language::C => (C_HEADER, C_HEADER_ONLY, C_SOURCE),
+        _ => (CPP_HEADER, CPP_HEADER_ONLY, CPP_SOURCE),
+    };
+    let main = sample_main(language);
+
+    let header_path =
+        PathBuf::from("@INCLUDE_DIR@/@INCLUDE_DOMAIN_DIR@/@CMAKE_TARGET_NAME@.@HEADER_FILE_EXT@");
+    let source_path = PathBuf::from("@SOURCE_DIR@/@CMAKE_TARGET_NAME@.@SOURCE_FILE_EXT@");
+    let main_path = PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@");
+
+    match kind {
+        Kind::HeaderOnly => gen.add_file(header_path, String::from(header_only)),
+        Kind::Static | Kind::Shared => gen
+            .add_file(header_path, String::from(header))
+            .add_file(source_path, String::from(source)),
+        Kind::Executable | Kind::LibAndApp => gen
+            .add_file(header_path, String::from(header))
+            .add_file(source_path, String::from(source))
+            .add_file(main_path, String::from(main)),
+    }
+}
+
+// C projects and the features building on the sample library get it without --sample-sources
+pub fn has_samples(opt: &Opt) -> bool {
+    opt.sample_sources
+        || opt.language.has_c()
+        || opt.kind == Kind::LibAndApp
+        || opt.test_framework.is_some()
+        || opt.fuzzing.is_some()
+        || opt.examples
+        || opt.amalgamate
+        || opt.docs.is_some()
+        || opt.i18n.is_some()
+        || opt.logging.is_some()
+        || opt.preset.is_some()
+}
+
+// Program calling the sample library, also used by the examples
+pub fn sample_main(language: Language) -> &'static str {
+    match language {
+        Language::C => C_MAIN,
+        _ => CPP_MAIN,
+    }
+}
+
+// e.g. cxx_std_17 c_std_11
+fn build_compile_features(opt: &Opt) -> String {
+    let mut features = Vec::new();
+
+    if opt.language.has_cpp() {
+        features.push(format!("cxx_std_{}", opt.cxx_standard));
+    }
+    if opt.language.has_c() {
+        features.push(format!("c_std_{}", opt.c_standard));
+    }
+
+    features.join(" ")
+}
+
+// Name space and target name as C/C++ identifiers, e.g. [my_company, my_target]
+pub fn build_identifiers(opt: &Opt) -> Vec<String> {
+    opt.name_space
+        .iter()
+        .chain(std::iter::once(&opt.target_name))
+        .map(|name| to_identifier(name))
+        .collect()
+}
+
+fn to_identifier(name: &str) -> String {
+    let identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    match identifier.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("_{}", identifier),
+        _ => identifier,
+    }
+}
+
+// Nested blocks work with any C++ standard, unlike 'namespace a::b'
+fn build_cpp_namespace_blocks(identifiers: &[String]) -> (String, String) {
+    let begin: Vec<String> = identifiers
+        .iter()
+        .map(|identifier| format!("namespace {} {{", identifier))
+        .collect();
+
+    let end: Vec<String> = identifiers
+        .iter()
+        .rev()
+        .map(|identifier| format!("}} // namespace {}", identifier))
+        .collect();
+
+    (begin.join("\n"), end.join("\n"))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_identifier() {
+        assert_eq!(to_identifier("my-target"), "my_target");
+        assert_eq!(to_identifier("3d-engine"), "_3d_engine");
+    }
+
+    #[test]
+    fn test_build_cpp_namespace_blocks() {
+        let identifiers = vec![String::from("nmspc"), String::from("tgtnm")];
+        let (begin, end) = build_cpp_namespace_blocks(&identifiers);
+
+        assert_eq!(begin, "namespace nmspc {\nnamespace tgtnm {");
+        assert_eq!(end, "} // namespace tgtnm\n} // namespace nmspc");
+    }
+
+    #[test]
+    fn test_build_compile_features() {
+        let opt = Opt {
+            language: Language::CppAndC,
+            c_standard: String::from("99"),
+            ..Opt::default()
+        };
+
+        assert_eq!(build_compile_features(&opt), "cxx_std_17 c_std_99");
+    }
+
+    #[test]
+    fn test_c_sample_files() {
+        let opt = Opt {
+            language: Language::C,
+            kind: Kind::Executable,
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        let paths = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .build_paths();
+
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/include/my-target/my-target.h"
+        )));
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/source/my-target.c")));
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/source/main.c")));
+    }
+}
@@ -0,0 +1,75 @@
+/*
+For library crates /sa https://doc.rust-lang.org/cargo/reference/cargo-targets.html#library
+*/
+
+// The generator without its command line, the binary parses the options into Opt
+pub mod abi_check;
+pub mod add_test;
+pub mod adopt;
+pub mod amalgamation;
+pub mod analysis;
+pub mod app_config;
+pub mod archive;
+pub mod batch;
+pub mod build_options;
+pub mod builtin_template;
+pub mod cancel;
+pub mod cmake_version;
+pub mod codeowners;
+pub mod collisions;
+pub mod community_files;
+pub mod compile_commands;
+pub mod compiler;
+pub mod concurrency;
+pub mod conda_env;
+pub mod config;
+pub mod cpp_proj_gen;
+pub mod defaults;
+pub mod dependency;
+pub mod dependency_updates;
+pub mod discovery;
+pub mod dist;
+pub mod docs;
+pub mod embed_resources;
+pub mod encoding;
+pub mod error;
+pub mod export;
+pub mod extra_languages;
+pub mod file_header;
+pub mod file_mode;
+pub mod fuzzing;
+#[cfg(feature = "async")]
+pub mod gen_async;
+pub mod i18n;
+pub mod ide_folders;
+pub mod language;
+pub mod limits;
+pub mod lock;
+pub mod logging;
+pub mod naming;
+pub mod nix;
+pub mod normalize;
+pub mod output;
+pub mod packaging;
+pub mod platforms;
+pub mod presets;
+pub mod project_manifest;
+pub mod project_model;
+pub mod regex;
+pub mod rename;
+pub mod reporter;
+pub mod resources;
+pub mod scripts;
+pub mod source_listing;
+pub mod streaming;
+pub mod submodules;
+pub mod task_runner;
+pub mod template_cache;
+pub mod template_pack;
+pub mod template_vars;
+pub mod test_framework;
+#[cfg(test)]
+mod test_util;
+pub mod toolchain;
+pub mod tools;
+pub mod warning;
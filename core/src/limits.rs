@@ -0,0 +1,83 @@
+/*
+For Instant /sa https://doc.rust-lang.org/std/time/struct.Instant.html
+*/
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+// Bounds of a single generation, e.g. per server request, unlimited by default
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    pub max_files: Option<usize>,
+    pub max_bytes: Option<u64>,
+    // Checked between rendered files
+    pub render_timeout: Option<Duration>,
+}
+
+impl Limits {
+    pub fn check_render_time(&self, render_start: Instant) -> io::Result<()> {
+        match self.render_timeout {
+            Some(timeout) if render_start.elapsed() > timeout => Err(exceeded(format!(
+                "rendering took longer than {:?}",
+                timeout
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    // Checked before anything is written
+    pub fn check_output(&self, files: usize, bytes: u64) -> io::Result<()> {
+        if let Some(max_files) = self.max_files.filter(|max_files| files > *max_files) {
+            return Err(exceeded(format!(
+                "{} files exceed the limit of {}",
+                files, max_files
+            )));
+        }
+        if let Some(max_bytes) = self.max_bytes.filter(|max_bytes| bytes > *max_bytes) {
+            return Err(exceeded(format!(
+                "{} bytes exceed the limit of {}",
+                bytes, max_bytes
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn exceeded(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_output() {
+        let limits = Limits {
+            max_files: Some(2),
+            max_bytes: Some(100),
+            ..Limits::default()
+        };
+
+        assert!(limits.check_output(2, 100).is_ok());
+        assert!(limits.check_output(3, 10).is_err());
+        assert!(limits.check_output(1, 101).is_err());
+        assert!(Limits::default().check_output(usize::MAX, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_check_render_time() {
+        let limits = Limits {
+            render_timeout: Some(Duration::from_secs(0)),
+            ..Limits::default()
+        };
+        let render_start = Instant::now() - Duration::from_millis(10);
+
+        assert!(limits.check_render_time(render_start).is_err());
+        assert!(Limits::default().check_render_time(render_start).is_ok());
+    }
+}
@@ -0,0 +1,309 @@
+/*
+For OpenOptions::create_new /sa https://doc.rust-lang.org/std/fs/struct.OpenOptions.html#method.create_new
+For File::set_modified /sa https://doc.rust-lang.org/std/fs/struct.File.html#method.set_modified
+*/
+
+use crate::error::{self, Error};
+use crate::reporter::{GenEvent, Reporter};
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const LOCK_FILE_NAME: &str = ".cpp-proj-gen.lock";
+// Held locks are refreshed, one not refreshed for this long belongs to a killed run
+const STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Who holds a lock, stored as "<pid> <host> <unix seconds>"
+#[derive(Debug, Clone, PartialEq)]
+struct Holder {
+    pid: u32,
+    host: String,
+    started: u64,
+}
+
+impl Holder {
+    fn current() -> Self {
+        Holder {
+            pid: process::id(),
+            host: host_name(),
+            started: now(),
+        }
+    }
+
+    fn parse(contents: &str) -> Option<Self> {
+        let mut fields = contents.split_whitespace();
+
+        Some(Holder {
+            pid: fields.next()?.parse().ok()?,
+            host: String::from(fields.next()?),
+            started: fields.next()?.parse().ok()?,
+        })
+    }
+
+    fn read(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| Self::parse(&contents))
+            .unwrap_or_else(|| Holder {
+                pid: 0,
+                host: String::from("unknown host"),
+                started: modified(path),
+            })
+    }
+
+    // A process of this host that is gone where /proc tells, otherwise not refreshed for too long
+    fn is_stale(&self, refreshed: u64) -> bool {
+        let proc_dir = Path::new("/proc");
+        if self.host == host_name() && proc_dir.is_dir() {
+            return !proc_dir.join(self.pid.to_string()).exists();
+        }
+
+        now().saturating_sub(refreshed) > STALE_AFTER.as_secs()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "process {} on {}, {}s ago",
+            self.pid,
+            self.host,
+            now().saturating_sub(self.started)
+        )
+    }
+}
+
+// Advisory lock in the output root, refreshed while held and released when dropped
+#[derive(Debug)]
+pub struct GenLock {
+    path: PathBuf,
+    holder: Holder,
+    refresher: Option<(Sender<()>, JoinHandle<()>)>,
+}
+
+impl GenLock {
+    // Fails if another run holds the lock, polls until it is released with wait
+    pub fn acquire(
+        output_root: &Path,
+        wait: bool,
+        reporter: &mut dyn Reporter,
+    ) -> error::Result<Self> {
+        fs::create_dir_all(output_root).map_err(Error::fs("create directory", output_root))?;
+        let path = output_root.join(LOCK_FILE_NAME);
+        let mut waiting = false;
+
+        loop {
+            match try_create(&path) {
+                Ok(holder) => {
+                    let refresher = Some(refresh(path.clone()));
+                    return Ok(GenLock {
+                        path,
+                        holder,
+                        refresher,
+                    });
+                }
+                Err(err) if err.kind() != io::ErrorKind::AlreadyExists => {
+                    return Err(Error::fs("create lock", &path)(err))
+                }
+                Err(_) => {}
+            }
+
+            // Locks just created are not written yet, they age by modification time
+            let holder = Holder::read(&path);
+            let holder = match holder.is_stale(modified(&path)) {
+                false => holder,
+                true => {
+                    reporter.event(&GenEvent::Warning {
+                        message: format!("removing stale lock {}", path.display()),
+                    });
+                    let _ = remove_held(&path, &holder);
+                    continue;
+                }
+            };
+
+            if !wait {
+                return Err(Error::Locked {
+                    path,
+                    holder: holder.describe(),
+                });
+            }
+            if !waiting {
+                reporter.event(&GenEvent::LockWaiting {
+                    path: path.clone(),
+                    holder: holder.describe(),
+                });
+                waiting = true;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+// Another run may have taken over a lock this one held for too long, that lock stays
+impl Drop for GenLock {
+    fn drop(&mut self) {
+        if let Some((stop, refresher)) = self.refresher.take() {
+            drop(stop);
+            let _ = refresher.join();
+        }
+
+        let _ = remove_held(&self.path, &self.holder);
+    }
+}
+
+fn try_create(path: &Path) -> io::Result<Holder> {
+    let holder = Holder::current();
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+
+    writeln!(file, "{} {} {}", holder.pid, holder.host, holder.started)?;
+
+    Ok(holder)
+}
+
+// Touches the lock until the sender is dropped, post-hooks may run longer than STALE_AFTER
+fn refresh(path: PathBuf) -> (Sender<()>, JoinHandle<()>) {
+    let (stop, stopped) = mpsc::channel::<()>();
+    let refresher = thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(REFRESH_INTERVAL) {
+            let _ = fs::OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()));
+        }
+    });
+
+    (stop, refresher)
+}
+
+// Removes the lock only if it is still the expected one
+// Moving it aside first is atomic, a lock taken by another run meanwhile is linked back
+fn remove_held(path: &Path, expected: &Holder) -> io::Result<()> {
+    let aside = path.with_file_name(format!("{}.{}", LOCK_FILE_NAME, process::id()));
+    fs::rename(path, &aside)?;
+
+    if Holder::read(&aside) != *expected {
+        // Fails if yet another run took the lock, which then keeps it
+        let _ = fs::hard_link(&aside, path);
+    }
+
+    fs::remove_file(&aside)
+}
+
+fn host_name() -> String {
+    env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().replace(char::is_whitespace, "-"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| String::from("unknown"))
+}
+
+fn modified(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or_else(now, |duration| duration.as_secs())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_holder() {
+        let holder = Holder::parse("42 build-01 1700000000\n").unwrap();
+
+        assert_eq!(holder.pid, 42);
+        assert_eq!(holder.host, "build-01");
+        assert!(holder.is_stale(holder.started));
+        assert!(!holder.is_stale(now()));
+        assert_eq!(Holder::parse("42"), None);
+    }
+
+    #[test]
+    fn test_acquire() {
+        let output_root = env::temp_dir().join("cpp-proj-gen-lock");
+        let _ = fs::remove_dir_all(&output_root);
+
+        let mut events = Vec::new();
+        let lock = GenLock::acquire(&output_root, false, &mut events).unwrap();
+        assert!(matches!(
+            GenLock::acquire(&output_root, false, &mut events),
+            Err(Error::Locked { .. })
+        ));
+        drop(lock);
+        assert!(!output_root.join(LOCK_FILE_NAME).exists());
+        assert!(events.is_empty());
+
+        // Refreshed recently, so held although started long ago
+        let path = output_root.join(LOCK_FILE_NAME);
+        fs::write(&path, "42 other-host 0\n").unwrap();
+        assert!(GenLock::acquire(&output_root, false, &mut events).is_err());
+
+        let refreshed = SystemTime::now() - STALE_AFTER - REFRESH_INTERVAL;
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(refreshed))
+            .unwrap();
+        let lock = GenLock::acquire(&output_root, false, &mut events);
+        assert!(lock.is_ok());
+        assert!(matches!(events.as_slice(), [GenEvent::Warning { .. }]));
+
+        drop(lock);
+        let _ = fs::remove_dir_all(&output_root);
+    }
+
+    #[test]
+    fn test_remove_held() {
+        let output_root = env::temp_dir().join("cpp-proj-gen-lock-remove-held");
+        let _ = fs::remove_dir_all(&output_root);
+        fs::create_dir_all(&output_root).unwrap();
+        let path = output_root.join(LOCK_FILE_NAME);
+
+        // Taken over by another run, e.g. after this one was considered stale
+        let lock = GenLock::acquire(&output_root, false, &mut Vec::new()).unwrap();
+        fs::write(&path, "42 other-host 1700000000\n").unwrap();
+        drop(lock);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "42 other-host 1700000000\n"
+        );
+
+        let expected = Holder::parse("42 other-host 1700000000").unwrap();
+        remove_held(&path, &expected).unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(&output_root).unwrap().count(), 0);
+
+        fs::remove_dir_all(&output_root).unwrap();
+    }
+
+    #[test]
+    fn test_dead_process_is_stale() {
+        let holder = Holder {
+            pid: 999_999_999,
+            host: host_name(),
+            started: now(),
+        };
+
+        assert_eq!(holder.is_stale(now()), Path::new("/proc").is_dir());
+    }
+}
@@ -0,0 +1,216 @@
+/*
+For spdlog /sa https://github.com/gabime/spdlog
+For glog /sa https://google.github.io/glog/stable/
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::dependency::FeatureDependency;
+use crate::error::invalid_input;
+use crate::language::Language;
+use std::{io, path::PathBuf, str::FromStr};
+
+const SPDLOG_HEADER: &str = include_str!("../res/logging/spdlog.h.in");
+const SPDLOG_MAIN: &str = include_str!("../res/logging/spdlog.main.cpp.in");
+const GLOG_HEADER: &str = include_str!("../res/logging/glog.h.in");
+const GLOG_MAIN: &str = include_str!("../res/logging/glog.main.cpp.in");
+const NONE_HEADER: &str = include_str!("../res/logging/none.h.in");
+const NONE_MAIN: &str = include_str!("../res/logging/none.main.cpp.in");
+
+// Logging library of the application, none logs with std::clog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Logging {
+    Spdlog,
+    None,
+    Glog,
+}
+
+impl Logging {
+    pub const VARIANTS: &'static [&'static str] = &["spdlog", "none", "glog"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Logging::Spdlog => "spdlog",
+            Logging::None => "none",
+            Logging::Glog => "glog",
+        }
+    }
+}
+
+impl FromStr for Logging {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "spdlog" => Ok(Logging::Spdlog),
+            "none" => Ok(Logging::None),
+            "glog" => Ok(Logging::Glog),
+            _ => Err(format!(
+                "unknown logging '{}', expected one of: {}",
+                s,
+                Logging::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// The setup is called from the sample main, which presets and --i18n replace
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let logging = match opt.logging {
+        Some(logging) => logging,
+        None => return Ok(()),
+    };
+
+    if opt.kind.app_target().is_none() {
+        return Err(invalid_input(format!(
+            "--logging needs an executable, kind {} has none",
+            opt.kind.name()
+        )));
+    }
+    if opt.language == Language::C {
+        return Err(invalid_input(format!(
+            "--logging {} needs C++, use --language cpp or cpp-and-c",
+            logging.name()
+        )));
+    }
+    if let Some(preset) = opt.preset {
+        return Err(invalid_input(format!(
+            "--logging replaces the sample main, which preset {} provides itself",
+            preset.name()
+        )));
+    }
+    if opt.i18n.is_some() {
+        return Err(invalid_input(String::from(
+            "--logging and --i18n both replace the sample main, pick one",
+        )));
+    }
+
+    Ok(())
+}
+
+// Package linked to the application, see dependency::apply
+pub fn dependency(opt: &Opt) -> Option<FeatureDependency> {
+    let (spec, link_target) = match opt.logging? {
+        Logging::Spdlog => ("spdlog/1.14.1", "spdlog::spdlog"),
+        Logging::Glog => ("glog/0.7.1", "glog::glog"),
+        Logging::None => return None,
+    };
+
+    Some(FeatureDependency {
+        spec,
+        vcpkg_port: None,
+        cmake_package: None,
+        nix_package: None,
+        conda_package: None,
+        link_target,
+        consumer: opt.kind.app_target()?,
+    })
+}
+
+// Adds the logging setup header next to main and calls it from there
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (header, main) = match gen.opt().logging {
+        Some(Logging::Spdlog) => (SPDLOG_HEADER, SPDLOG_MAIN),
+        Some(Logging::Glog) => (GLOG_HEADER, GLOG_MAIN),
+        Some(Logging::None) => (NONE_HEADER, NONE_MAIN),
+        None => return gen,
+    };
+    if gen.opt().kind.app_target().is_none() {
+        return gen;
+    }
+
+    gen.add_file(
+        PathBuf::from("@SOURCE_DIR@/logging.@HEADER_FILE_EXT@"),
+        String::from(header),
+    )
+    .add_file(
+        PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+        String::from(main),
+    )
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Kind, PlanEntry};
+    use crate::dependency::PackageManager;
+    use crate::i18n::I18n;
+    use crate::test_util::contents;
+
+    fn create_test_plan(opt: Opt) -> Vec<PlanEntry> {
+        CppProjGen::new(Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::LibAndApp,
+            ..opt
+        })
+        .add_include_dir(PathBuf::from("include"))
+        .add_source_dir(PathBuf::from("source"))
+        .plan()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            kind: Kind::Executable,
+            logging: Some(Logging::Spdlog),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+
+        assert!(validate(&Opt {
+            kind: Kind::Shared,
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            language: Language::C,
+            ..opt.clone()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            i18n: Some(I18n::Gettext),
+            ..opt
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply_spdlog() {
+        let plan = create_test_plan(Opt {
+            logging: Some(Logging::Spdlog),
+            package_manager: Some(PackageManager::Conan),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "source/main.cpp")
+            .unwrap()
+            .contains("    tgtnm::init_logging(\"tgtnm\");\n"));
+        assert!(contents(&plan, "source/logging.h")
+            .unwrap()
+            .contains("spdlog::cfg::load_env_levels();"));
+        assert!(contents(&plan, "cmake/dependencies.cmake")
+            .unwrap()
+            .contains("target_link_libraries(tgtnm-app PRIVATE spdlog::spdlog)\n"));
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("include(cmake/dependencies.cmake)\n"));
+        assert!(contents(&plan, "conanfile.txt")
+            .unwrap()
+            .starts_with("[requires]\nspdlog/1.14.1\n\n"));
+    }
+
+    #[test]
+    fn test_apply_none() {
+        let plan = create_test_plan(Opt {
+            logging: Some(Logging::None),
+            ..Opt::default()
+        });
+
+        assert!(contents(&plan, "source/main.cpp")
+            .unwrap()
+            .contains("tgtnm::log_info("));
+        assert!(contents(&plan, "cmake/dependencies.cmake").is_none());
+    }
+}
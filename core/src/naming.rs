@@ -0,0 +1,193 @@
+/*
+For file name conventions /sa https://google.github.io/styleguide/cppguide.html#File_Names
+*/
+
+use crate::cpp_proj_gen::Opt;
+use crate::error::invalid_input;
+use crate::language::Language;
+use std::{fmt, io, str::FromStr};
+
+pub const HEADER_EXTS: &[&str] = &["h", "hpp", "hh", "hxx"];
+// Of C++ sources, C sources are always .c
+pub const SOURCE_EXTS: &[&str] = &["cpp", "cc", "cxx"];
+
+// Test file names with the tested name for the *, e.g. test_* or *_test.cc
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestPattern {
+    prefix: String,
+    suffix: String,
+    // The source extension if not given
+    ext: Option<String>,
+}
+
+impl TestPattern {
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    // e.g. _test.cc
+    pub fn suffix(&self, source_ext: &str) -> String {
+        format!(
+            "{}.{}",
+            self.suffix,
+            self.ext.as_deref().unwrap_or(source_ext)
+        )
+    }
+
+    pub fn ext<'a>(&'a self, source_ext: &'a str) -> &'a str {
+        self.ext.as_deref().unwrap_or(source_ext)
+    }
+}
+
+impl Default for TestPattern {
+    fn default() -> Self {
+        TestPattern {
+            prefix: String::from("test_"),
+            suffix: String::new(),
+            ext: None,
+        }
+    }
+}
+
+impl FromStr for TestPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid test pattern '{}', expected one * in a file name, e.g. test_* or *_test.cpp",
+                s
+            )
+        };
+
+        let (prefix, rest) = s.split_once('*').ok_or_else(invalid)?;
+        let (suffix, ext) = match rest.split_once('.') {
+            Some((suffix, ext)) => (suffix, Some(ext)),
+            None => (rest, None),
+        };
+        let is_name_part = |part: &str| !part.contains(['*', '.', '/', '\\']);
+        if !is_name_part(prefix)
+            || !is_name_part(suffix)
+            || ext.is_some_and(|ext| ext.is_empty() || !is_name_part(ext))
+        {
+            return Err(invalid());
+        }
+
+        Ok(TestPattern {
+            prefix: String::from(prefix),
+            suffix: String::from(suffix),
+            ext: ext.map(String::from),
+        })
+    }
+}
+
+impl fmt::Display for TestPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}*{}", self.prefix, self.suffix)?;
+        match &self.ext {
+            Some(ext) => write!(f, ".{}", ext),
+            None => Ok(()),
+        }
+    }
+}
+
+// Extension of the generated sources, the samples of mixed projects are C++
+pub fn source_ext(opt: &Opt) -> &str {
+    match opt.language {
+        Language::C => "c",
+        _ => &opt.source_ext,
+    }
+}
+
+// Config files are not checked by the command line parser
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    if !HEADER_EXTS.contains(&opt.header_ext.as_str()) {
+        return Err(invalid_input(format!(
+            "unknown header extension '{}', expected one of: {}",
+            opt.header_ext,
+            HEADER_EXTS.join(", ")
+        )));
+    }
+    if !SOURCE_EXTS.contains(&opt.source_ext.as_str()) {
+        return Err(invalid_input(format!(
+            "unknown source extension '{}', expected one of: {}",
+            opt.source_ext,
+            SOURCE_EXTS.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::CppProjGen;
+    use crate::test_framework::TestFramework;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_parse_test_pattern() {
+        let pattern = "*_test.cc".parse::<TestPattern>().unwrap();
+        assert_eq!(pattern.prefix(), "");
+        assert_eq!(pattern.suffix("cpp"), "_test.cc");
+        assert_eq!(pattern.to_string(), "*_test.cc");
+
+        let pattern = "test_*".parse::<TestPattern>().unwrap();
+        assert_eq!(pattern, TestPattern::default());
+        assert_eq!(pattern.suffix("cpp"), ".cpp");
+
+        for invalid in &["test", "*_test_*", "dir/test_*", "*_test.", "test.*"] {
+            assert!(invalid.parse::<TestPattern>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            test_framework: Some(TestFramework::GTest),
+            header_ext: String::from("hpp"),
+            source_ext: String::from("cc"),
+            test_pattern: "*_test".parse().unwrap(),
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .add_test_dir(PathBuf::from("test"))
+            .plan()
+            .unwrap();
+
+        for path in &[
+            "test_out_dir/tgtnm/include/tgtnm/tgtnm.hpp",
+            "test_out_dir/tgtnm/source/tgtnm.cc",
+            "test_out_dir/tgtnm/test/tgtnm_test.cc",
+        ] {
+            assert!(
+                plan.iter().any(|entry| entry.path() == Path::new(path)),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            header_ext: String::from("hpp"),
+            source_ext: String::from("cc"),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+        assert_eq!(source_ext(&opt), "cc");
+
+        let opt = Opt {
+            header_ext: String::from("txt"),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+    }
+}
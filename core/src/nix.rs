@@ -0,0 +1,133 @@
+/*
+For mkShell /sa https://nixos.org/manual/nixpkgs/stable/#sec-pkgs-mkShell
+For flakes /sa https://nixos.org/manual/nix/stable/command-ref/new-cli/nix3-flake.html
+*/
+
+use crate::compiler::Compiler;
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::dependency::{self, PackageManager};
+use crate::task_runner::TaskRunner;
+use std::{io, path::PathBuf};
+
+const SHELL_NIX: &str = include_str!("../res/nix/shell.nix.in");
+const FLAKE_NIX: &str = include_str!("../res/nix/flake.nix.in");
+
+// nixpkgs has no MSVC
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    if opt.nix && opt.compiler == Some(Compiler::Msvc) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--nix cannot provide --compiler msvc, use gcc or clang",
+        ));
+    }
+
+    Ok(())
+}
+
+// nixpkgs attributes of the tools and, without a package manager, of the libraries
+fn build_packages(opt: &Opt) -> Vec<&'static str> {
+    let mut packages = vec!["cmake", "ninja"];
+    match opt.compiler {
+        Some(Compiler::Gcc) => packages.push("gcc"),
+        Some(Compiler::Clang) | Some(Compiler::AppleClang) => packages.push("clang"),
+        // mkShell brings the default compiler of the platform
+        Some(Compiler::Msvc) | None => (),
+    }
+    match opt.package_manager {
+        Some(PackageManager::Vcpkg) => packages.push("vcpkg"),
+        Some(PackageManager::Conan) => packages.push("conan"),
+        None => packages.extend(dependency::build_feature_dependencies(opt).iter().map(
+            |feature| {
+                feature
+                    .nix_package
+                    .unwrap_or_else(|| feature.spec.split('/').next().unwrap())
+            },
+        )),
+    }
+    // clang-format and run-clang-tidy of the format and lint recipes
+    if let Some(task_runner) = opt.task_runner {
+        packages.push("clang-tools");
+        packages.push(match task_runner {
+            TaskRunner::Just => "just",
+            TaskRunner::Task => "go-task",
+            TaskRunner::Make => "gnumake",
+        });
+    }
+
+    packages
+}
+
+// Adds shell.nix and flake.nix providing its shell to nix develop
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().nix {
+        return gen;
+    }
+
+    let packages: Vec<String> = build_packages(gen.opt())
+        .into_iter()
+        .map(|package| format!("    {}", package))
+        .collect();
+    let shell = SHELL_NIX.replace("@NIX_PACKAGES@", &packages.join("\n"));
+
+    gen.add_file(PathBuf::from("shell.nix"), shell)
+        .add_file(PathBuf::from("flake.nix"), String::from(FLAKE_NIX))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrency::Concurrency;
+    use crate::cpp_proj_gen::Kind;
+    use crate::logging::Logging;
+
+    fn create_test_opt() -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            nix: true,
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&create_test_opt()).is_ok());
+        assert!(validate(&Opt {
+            compiler: Some(Compiler::Msvc),
+            ..create_test_opt()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_build_packages() {
+        assert_eq!(build_packages(&create_test_opt()), vec!["cmake", "ninja"]);
+        assert_eq!(
+            build_packages(&Opt {
+                kind: Kind::Executable,
+                compiler: Some(Compiler::AppleClang),
+                logging: Some(Logging::Spdlog),
+                concurrency: Some(Concurrency::Tbb),
+                task_runner: Some(TaskRunner::Task),
+                ..create_test_opt()
+            }),
+            vec![
+                "cmake",
+                "ninja",
+                "clang",
+                "spdlog",
+                "tbb",
+                "clang-tools",
+                "go-task"
+            ]
+        );
+        assert_eq!(
+            build_packages(&Opt {
+                logging: Some(Logging::Spdlog),
+                package_manager: Some(PackageManager::Conan),
+                ..create_test_opt()
+            }),
+            vec!["cmake", "ninja", "conan"]
+        );
+    }
+}
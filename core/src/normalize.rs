@@ -0,0 +1,292 @@
+/*
+For line endings in git /sa https://git-scm.com/docs/gitattributes#_eol
+For tabs in Makefiles /sa https://www.gnu.org/software/make/manual/html_node/Recipe-Syntax.html
+*/
+
+use crate::cpp_proj_gen::Opt;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+// Line endings of the rendered text files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEndings {
+    Lf,
+    Crlf,
+}
+
+impl LineEndings {
+    pub const VARIANTS: &'static [&'static str] = &["lf", "crlf"];
+}
+
+impl FromStr for LineEndings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lf" => Ok(LineEndings::Lf),
+            "crlf" => Ok(LineEndings::Crlf),
+            _ => Err(format!(
+                "unknown line endings '{}', expected one of: {}",
+                s,
+                LineEndings::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// End of the rendered text files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailingNewline {
+    // Exactly one newline, blank lines at the end dropped
+    Single,
+    // As rendered
+    Keep,
+}
+
+impl TrailingNewline {
+    pub const VARIANTS: &'static [&'static str] = &["single", "keep"];
+}
+
+impl FromStr for TrailingNewline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(TrailingNewline::Single),
+            "keep" => Ok(TrailingNewline::Keep),
+            _ => Err(format!(
+                "unknown trailing newline '{}', expected one of: {}",
+                s,
+                TrailingNewline::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Leading whitespace of the lines of the rendered text files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indent {
+    Keep,
+    Spaces,
+    Tabs,
+}
+
+impl Indent {
+    pub const VARIANTS: &'static [&'static str] = &["keep", "spaces", "tabs"];
+}
+
+impl FromStr for Indent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(Indent::Keep),
+            "spaces" => Ok(Indent::Spaces),
+            "tabs" => Ok(Indent::Tabs),
+            _ => Err(format!(
+                "unknown indent '{}', expected one of: {}",
+                s,
+                Indent::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    match opt.tab_width {
+        0 => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--tab-width needs at least one column",
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Rendered contents as written, the same on every host
+// Scripts stay LF and batch files CRLF whatever --line-endings says
+pub fn apply(path: &Path, contents: &str, opt: &Opt) -> String {
+    let normalizer = LineNormalizer::new(path, opt);
+    let mut lines: Vec<String> = contents
+        .split('\n')
+        .map(|line| normalizer.line(line))
+        .collect();
+
+    if normalizer.trailing_newline == TrailingNewline::Single {
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+    }
+
+    lines.join(normalizer.separator(contents))
+}
+
+// Settings of apply() for one file, also used line by line for templates rendered while read
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineNormalizer {
+    path: PathBuf,
+    line_endings: LineEndings,
+    indent: Indent,
+    tab_width: usize,
+    pub trailing_newline: TrailingNewline,
+}
+
+impl LineNormalizer {
+    pub fn new(path: &Path, opt: &Opt) -> Self {
+        LineNormalizer {
+            path: path.to_path_buf(),
+            line_endings: opt.line_endings,
+            indent: match keeps_indent(path) {
+                true => Indent::Keep,
+                false => opt.indent,
+            },
+            tab_width: opt.tab_width,
+            trailing_newline: opt.trailing_newline,
+        }
+    }
+
+    // Of contents starting with start, e.g. LF for scripts with a shebang
+    pub fn separator(&self, start: &str) -> &'static str {
+        match line_endings(&self.path, start).unwrap_or(self.line_endings) {
+            LineEndings::Lf => "\n",
+            LineEndings::Crlf => "\r\n",
+        }
+    }
+
+    // A line without its newline, a carriage return at its end is dropped
+    pub fn line(&self, line: &str) -> String {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        match self.indent {
+            Indent::Keep => String::from(line),
+            indent => reindent(line, indent, self.tab_width),
+        }
+    }
+}
+
+// Line endings some files need to run
+fn line_endings(path: &Path, contents: &str) -> Option<LineEndings> {
+    match extension(path).as_deref() {
+        Some("bat") | Some("cmd") => Some(LineEndings::Crlf),
+        Some("sh") => Some(LineEndings::Lf),
+        _ if contents.starts_with("#!") => Some(LineEndings::Lf),
+        _ => None,
+    }
+}
+
+// Makefile recipes need tabs and YAML spaces
+fn keeps_indent(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|file_name| file_name.to_str());
+
+    matches!(file_name, Some("Makefile") | Some("GNUmakefile"))
+        || matches!(
+            extension(path).as_deref(),
+            Some("mk") | Some("yml") | Some("yaml")
+        )
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+// Leading tabs and spaces by their width, e.g. a tab and 2 spaces are 6 columns at tab width 4
+fn reindent(line: &str, indent: Indent, tab_width: usize) -> String {
+    let body = line.trim_start_matches([' ', '\t']);
+    let leading = &line[..line.len() - body.len()];
+    let columns = leading.chars().fold(0, |columns, c| match c {
+        '\t' => (columns / tab_width + 1) * tab_width,
+        _ => columns + 1,
+    });
+
+    let leading = match indent {
+        Indent::Tabs => format!(
+            "{}{}",
+            "\t".repeat(columns / tab_width),
+            " ".repeat(columns % tab_width)
+        ),
+        Indent::Spaces | Indent::Keep => " ".repeat(columns),
+    };
+
+    leading + body
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_opt(line_endings: LineEndings, indent: Indent) -> Opt {
+        Opt {
+            line_endings,
+            indent,
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt::default();
+        let path = Path::new("source/a.cpp");
+        assert_eq!(apply(path, "a\r\nb", &opt), "a\nb\n");
+        assert_eq!(apply(path, "a\n\n\n", &opt), "a\n");
+        assert_eq!(apply(path, "", &opt), "");
+        assert_eq!(apply(path, "\tx", &opt), "\tx\n");
+
+        let keep = Opt {
+            trailing_newline: TrailingNewline::Keep,
+            ..Opt::default()
+        };
+        assert_eq!(apply(path, "a\n\n", &keep), "a\n\n");
+        assert_eq!(apply(path, "a", &keep), "a");
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let opt = create_test_opt(LineEndings::Crlf, Indent::Keep);
+        assert_eq!(
+            apply(Path::new("CMakeLists.txt"), "a\nb", &opt),
+            "a\r\nb\r\n"
+        );
+        assert_eq!(
+            apply(Path::new("scripts/build.sh"), "a\r\nb", &opt),
+            "a\nb\n"
+        );
+        assert_eq!(
+            apply(Path::new("debian/rules"), "#!/usr/bin/make -f\n", &opt),
+            "#!/usr/bin/make -f\n"
+        );
+
+        let opt = Opt::default();
+        assert_eq!(apply(Path::new("build.BAT"), "a\nb", &opt), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn test_indent() {
+        let opt = create_test_opt(LineEndings::Lf, Indent::Spaces);
+        assert_eq!(
+            apply(Path::new("a.cpp"), "\tx\n \ty", &opt),
+            "    x\n    y\n"
+        );
+        assert_eq!(
+            apply(Path::new("Makefile"), "all:\n\tmake", &opt),
+            "all:\n\tmake\n"
+        );
+
+        let opt = Opt {
+            tab_width: 2,
+            ..create_test_opt(LineEndings::Lf, Indent::Tabs)
+        };
+        assert_eq!(apply(Path::new("a.cpp"), "     x", &opt), "\t\t x\n");
+        assert_eq!(
+            apply(Path::new(".clang-format.yml"), "  x: 1", &opt),
+            "  x: 1\n"
+        );
+    }
+}
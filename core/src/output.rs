@@ -0,0 +1,555 @@
+/*
+For ssh /sa https://man.openbsd.org/ssh
+For tar /sa https://man.openbsd.org/tar
+For aws s3 cp /sa https://docs.aws.amazon.com/cli/latest/reference/s3/cp.html
+*/
+
+use crate::error::{self, Error};
+use crate::file_mode;
+use std::{
+    env, fmt, fs,
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Where CppProjGen::gen writes the planned directories and files
+pub trait OutputAdapter {
+    // Called before anything is written, e.g. to check permissions
+    fn prepare(&mut self, out_dir: &Path) -> error::Result<()>;
+    fn create_dir(&mut self, path: &Path) -> error::Result<()>;
+    // Target relative to the link's directory
+    fn create_link(&mut self, path: &Path, target: &Path) -> error::Result<()>;
+    // Executable files get the executable bit where there is one
+    fn write_file(&mut self, path: &Path, contents: &[u8], executable: bool) -> error::Result<()>;
+    // Large files as they are read, the bytes written are returned
+    // Adapters keeping files in memory anyway read them whole
+    fn write_stream(
+        &mut self,
+        path: &Path,
+        contents: &mut dyn Read,
+        executable: bool,
+    ) -> error::Result<u64> {
+        let mut bytes = Vec::new();
+        contents
+            .read_to_end(&mut bytes)
+            .map_err(Error::fs("write", path))?;
+        self.write_file(path, &bytes, executable)?;
+
+        Ok(bytes.len() as u64)
+    }
+    // Whether the path was there before it is written, rolling back leaves those
+    fn exists(&self, path: &Path) -> bool;
+    // Rolls a written path back, directories only if empty
+    fn remove(&mut self, path: &Path) -> error::Result<()>;
+    // Called after generation and post-hooks, e.g. to upload
+    fn finish(&mut self) -> error::Result<()>;
+}
+
+// The local filesystem
+#[derive(Debug, Default)]
+pub struct LocalOutput;
+
+impl OutputAdapter for LocalOutput {
+    fn prepare(&mut self, out_dir: &Path) -> error::Result<()> {
+        error::check_writable(out_dir)
+    }
+
+    fn create_dir(&mut self, path: &Path) -> error::Result<()> {
+        fs::create_dir_all(path).map_err(Error::fs("create directory", path))
+    }
+
+    fn create_link(&mut self, path: &Path, target: &Path) -> error::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Error::fs("create directory", parent))?;
+        }
+
+        file_mode::create_link(path, target).map_err(Error::fs("create link", path))
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &[u8], executable: bool) -> error::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Error::fs("create directory", parent))?;
+        }
+
+        fs::write(path, contents).map_err(Error::fs("write", path))?;
+        match executable {
+            true => file_mode::set_executable(path).map_err(Error::fs("set the mode of", path)),
+            false => Ok(()),
+        }
+    }
+
+    fn write_stream(
+        &mut self,
+        path: &Path,
+        contents: &mut dyn Read,
+        executable: bool,
+    ) -> error::Result<u64> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Error::fs("create directory", parent))?;
+        }
+
+        let written = fs::File::create(path)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                let written = io::copy(contents, &mut writer)?;
+                writer.flush()?;
+                Ok(written)
+            })
+            .map_err(Error::fs("write", path))?;
+        if executable {
+            file_mode::set_executable(path).map_err(Error::fs("set the mode of", path))?;
+        }
+
+        Ok(written)
+    }
+
+    // Directories may have been there before, e.g. the output directory
+    fn exists(&self, path: &Path) -> bool {
+        fs::symlink_metadata(path).is_ok()
+    }
+
+    fn remove(&mut self, path: &Path) -> error::Result<()> {
+        let removed = match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.is_dir() => {
+                fs::read_dir(path).and_then(|mut entries| match entries.next() {
+                    Some(_) => Ok(()),
+                    None => fs::remove_dir(path),
+                })
+            }
+            Ok(_) => fs::remove_file(path),
+            Err(_) => Ok(()),
+        };
+
+        removed.map_err(Error::fs("remove", path))
+    }
+
+    fn finish(&mut self) -> error::Result<()> {
+        Ok(())
+    }
+}
+
+// Keeps directories and files in memory in the order they are written, e.g. to serve them as zip
+#[derive(Debug, Default)]
+pub struct MemoryOutput {
+    entries: Vec<(PathBuf, MemoryEntry)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoryEntry {
+    Dir,
+    File { contents: Vec<u8>, executable: bool },
+    Link(PathBuf),
+}
+
+impl MemoryOutput {
+    pub fn entries(&self) -> &[(PathBuf, MemoryEntry)] {
+        &self.entries
+    }
+}
+
+impl OutputAdapter for MemoryOutput {
+    fn prepare(&mut self, _out_dir: &Path) -> error::Result<()> {
+        Ok(())
+    }
+
+    fn create_dir(&mut self, path: &Path) -> error::Result<()> {
+        self.entries.push((path.to_path_buf(), MemoryEntry::Dir));
+        Ok(())
+    }
+
+    fn create_link(&mut self, path: &Path, target: &Path) -> error::Result<()> {
+        self.entries
+            .push((path.to_path_buf(), MemoryEntry::Link(target.to_path_buf())));
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &[u8], executable: bool) -> error::Result<()> {
+        let file = MemoryEntry::File {
+            contents: contents.to_vec(),
+            executable,
+        };
+        self.entries.push((path.to_path_buf(), file));
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.entries.iter().any(|(other, _)| other == path)
+    }
+
+    fn remove(&mut self, path: &Path) -> error::Result<()> {
+        self.entries.retain(|(other, _)| other != path);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> error::Result<()> {
+        Ok(())
+    }
+}
+
+// Remote output location of --output
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputUrl {
+    Ssh(SshUrl),
+    S3(S3Url),
+}
+
+impl FromStr for OutputUrl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once("://") {
+            Some(("ssh", _)) => Ok(OutputUrl::Ssh(s.parse()?)),
+            Some(("s3", _)) => Ok(OutputUrl::S3(s.parse()?)),
+            _ => Err(format!(
+                "unsupported output '{}', expected ssh://[user@]host[:port]/path or s3://bucket[/prefix]",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputUrl::Ssh(url) => write!(f, "{}:{}", url.host, url.dir),
+            OutputUrl::S3(url) => write!(f, "{}", url.to_uri()),
+        }
+    }
+}
+
+// e.g. ssh://builder@farm-01:2222/srv/projects
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshUrl {
+    // With user, e.g. builder@farm-01
+    pub host: String,
+    pub port: Option<u16>,
+    pub dir: String,
+}
+
+impl FromStr for SshUrl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected ssh://[user@]host[:port]/path, got '{}'", s);
+        let rest = s.strip_prefix("ssh://").ok_or_else(invalid)?;
+        let (authority, dir) = rest.split_at(rest.find('/').ok_or_else(invalid)?);
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse::<u16>().map_err(|_| invalid())?)),
+            None => (authority, None),
+        };
+        // ssh would take a leading - as an option, e.g. -oProxyCommand=...
+        if host.is_empty() || host.starts_with('-') || host.ends_with('@') || dir.len() < 2 {
+            return Err(invalid());
+        }
+
+        Ok(SshUrl {
+            host: String::from(host),
+            port,
+            dir: String::from(dir),
+        })
+    }
+}
+
+// e.g. s3://scaffolds/teams/audio, S3-compatible stores via AWS_ENDPOINT_URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3Url {
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl S3Url {
+    fn to_uri(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.prefix)
+    }
+}
+
+impl FromStr for S3Url {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s
+            .strip_prefix("s3://")
+            .filter(|rest| !rest.starts_with('/') && !rest.is_empty())
+            .ok_or_else(|| format!("expected s3://bucket[/prefix], got '{}'", s))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+
+        Ok(S3Url {
+            bucket: String::from(bucket),
+            prefix: String::from(prefix.trim_end_matches('/')),
+        })
+    }
+}
+
+// Generates into a local staging directory, finish uploads it with tar over ssh or aws s3 cp
+// The staging directory is removed on drop, also if generation fails
+#[derive(Debug)]
+pub struct RemoteOutput {
+    url: OutputUrl,
+    staging_dir: PathBuf,
+}
+
+impl RemoteOutput {
+    pub fn new(url: OutputUrl) -> error::Result<Self> {
+        let staging_dir = create_staging_dir(&env::temp_dir())?;
+
+        Ok(RemoteOutput { url, staging_dir })
+    }
+
+    // Pass as --output-dir, paths below it end up below the remote directory
+    pub fn staging_dir(&self) -> &Path {
+        &self.staging_dir
+    }
+
+    fn upload(&self) -> error::Result<()> {
+        match &self.url {
+            OutputUrl::Ssh(url) => self.upload_ssh(url),
+            OutputUrl::S3(url) => self.upload_s3(url),
+        }
+    }
+
+    fn upload_ssh(&self, url: &SshUrl) -> error::Result<()> {
+        let mut tar = Command::new("tar")
+            .arg("-C")
+            .arg(&self.staging_dir)
+            .args(["-cf", "-", "."])
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(Error::fs("run tar in", &self.staging_dir))?;
+        let archive = tar
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("tar has no output"))?;
+
+        let mut ssh = Command::new("ssh");
+        if let Some(port) = url.port {
+            ssh.args(["-p", &port.to_string()]);
+        }
+        let dir = shell_quote(&url.dir);
+        let status = ssh
+            .arg("--")
+            .arg(&url.host)
+            .arg(format!("mkdir -p {0} && tar -xf - -C {0}", dir))
+            .stdin(archive)
+            .status()
+            .map_err(|err| io::Error::new(err.kind(), format!("cannot run ssh: {}", err)))?;
+        let tar_status = tar.wait()?;
+
+        match (tar_status.success(), status.success()) {
+            (true, true) => Ok(()),
+            (false, _) => Err(Error::from(io::Error::other(format!(
+                "tar failed with {}",
+                tar_status
+            )))),
+            (true, false) => Err(Error::from(io::Error::other(format!(
+                "uploading to {} failed with {}",
+                self.url, status
+            )))),
+        }
+    }
+
+    fn upload_s3(&self, url: &S3Url) -> error::Result<()> {
+        let status = Command::new("aws")
+            .args(["s3", "cp", "--recursive", "--only-show-errors"])
+            .arg(&self.staging_dir)
+            .arg(url.to_uri())
+            .status()
+            .map_err(|err| io::Error::new(err.kind(), format!("cannot run aws: {}", err)))?;
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(Error::from(io::Error::other(format!(
+                "uploading to {} failed with {}",
+                self.url, status
+            )))),
+        }
+    }
+}
+
+impl OutputAdapter for RemoteOutput {
+    fn prepare(&mut self, out_dir: &Path) -> error::Result<()> {
+        LocalOutput.prepare(out_dir)
+    }
+
+    fn create_dir(&mut self, path: &Path) -> error::Result<()> {
+        LocalOutput.create_dir(path)
+    }
+
+    fn create_link(&mut self, path: &Path, target: &Path) -> error::Result<()> {
+        LocalOutput.create_link(path, target)
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &[u8], executable: bool) -> error::Result<()> {
+        LocalOutput.write_file(path, contents, executable)
+    }
+
+    fn write_stream(
+        &mut self,
+        path: &Path,
+        contents: &mut dyn Read,
+        executable: bool,
+    ) -> error::Result<u64> {
+        LocalOutput.write_stream(path, contents, executable)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        LocalOutput.exists(path)
+    }
+
+    fn remove(&mut self, path: &Path) -> error::Result<()> {
+        LocalOutput.remove(path)
+    }
+
+    fn finish(&mut self) -> error::Result<()> {
+        let result = self.upload();
+        let _ = fs::remove_dir_all(&self.staging_dir);
+
+        result
+    }
+}
+
+impl Drop for RemoteOutput {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staging_dir);
+    }
+}
+
+// A new directory only this run can own, e.g. /tmp/cpp-proj-gen-output-1234-5f3a9c01
+// Creating it fails if anything is there already, even a symlink
+fn create_staging_dir(parent: &Path) -> error::Result<PathBuf> {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+    let mut attempt = 0u32;
+    loop {
+        let dir = parent.join(format!(
+            "cpp-proj-gen-output-{}-{:08x}",
+            process::id(),
+            seed.wrapping_add(attempt.wrapping_mul(0x9e37_79b9))
+        ));
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            Err(err) => return Err(Error::fs("create directory", &dir)(err)),
+        }
+    }
+}
+
+// Single quotes for the remote shell, e.g. it's to 'it'\''s'
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_remove() {
+        let dir = env::temp_dir().join("cpp-proj-gen-test-local-remove");
+        let _ = fs::remove_dir_all(&dir);
+        LocalOutput
+            .write_file(&dir.join("a/b.txt"), b"b", false)
+            .unwrap();
+
+        // Not empty yet
+        LocalOutput.remove(&dir.join("a")).unwrap();
+        assert!(dir.join("a").exists());
+        LocalOutput.remove(&dir.join("a/b.txt")).unwrap();
+        LocalOutput.remove(&dir.join("a")).unwrap();
+        let exists = dir.join("a").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!exists);
+    }
+
+    #[test]
+    fn test_local_write_stream() {
+        let dir = env::temp_dir().join("cpp-proj-gen-test-local-write-stream");
+        let _ = fs::remove_dir_all(&dir);
+        let written = LocalOutput
+            .write_stream(&dir.join("a/b.txt"), &mut &b"streamed"[..], false)
+            .unwrap();
+        let contents = fs::read_to_string(dir.join("a/b.txt"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written, 8);
+        assert_eq!(contents.unwrap(), "streamed");
+    }
+
+    #[test]
+    fn test_parse_ssh_url() {
+        assert_eq!(
+            "ssh://builder@farm-01:2222/srv/projects"
+                .parse::<SshUrl>()
+                .unwrap(),
+            SshUrl {
+                host: String::from("builder@farm-01"),
+                port: Some(2222),
+                dir: String::from("/srv/projects"),
+            }
+        );
+        assert_eq!("ssh://farm-01/srv".parse::<SshUrl>().unwrap().port, None);
+        assert!("ssh://farm-01".parse::<SshUrl>().is_err());
+        assert!("ssh://farm-01/".parse::<SshUrl>().is_err());
+        assert!("ssh://farm-01:port/srv".parse::<SshUrl>().is_err());
+        assert!("ssh://-oProxyCommand=sh/srv".parse::<SshUrl>().is_err());
+        assert!("/srv/projects".parse::<SshUrl>().is_err());
+    }
+
+    #[test]
+    fn test_parse_output_url() {
+        assert_eq!(
+            "s3://scaffolds/teams/audio/".parse::<OutputUrl>().unwrap(),
+            OutputUrl::S3(S3Url {
+                bucket: String::from("scaffolds"),
+                prefix: String::from("teams/audio"),
+            })
+        );
+        assert_eq!(
+            "s3://scaffolds".parse::<OutputUrl>().unwrap().to_string(),
+            "s3://scaffolds/"
+        );
+        assert!(matches!(
+            "ssh://farm-01/srv".parse::<OutputUrl>(),
+            Ok(OutputUrl::Ssh(_))
+        ));
+        assert!("s3:///prefix".parse::<OutputUrl>().is_err());
+        assert!("ftp://host/path".parse::<OutputUrl>().is_err());
+    }
+
+    #[test]
+    fn test_staging_dir() {
+        let parent = env::temp_dir().join("cpp-proj-gen-test-staging-dir");
+        let _ = fs::remove_dir_all(&parent);
+        fs::create_dir_all(&parent).unwrap();
+
+        let first = create_staging_dir(&parent).unwrap();
+        let second = create_staging_dir(&parent).unwrap();
+        assert_ne!(first, second);
+        assert!(first.is_dir() && second.is_dir());
+        fs::remove_dir_all(&parent).unwrap();
+
+        let output = RemoteOutput::new("s3://scaffolds".parse().unwrap()).unwrap();
+        let staging_dir = output.staging_dir().to_path_buf();
+        assert!(staging_dir.is_dir());
+        drop(output);
+        assert!(!staging_dir.exists());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/srv/my dir"), "'/srv/my dir'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
@@ -0,0 +1,662 @@
+/*
+For Homebrew formulae /sa https://docs.brew.sh/Formula-Cookbook
+For PKGBUILD /sa https://wiki.archlinux.org/title/PKGBUILD
+For debian/ /sa https://www.debian.org/doc/manuals/maint-guide/dreq.en.html
+For spec files /sa https://rpm-software-management.github.io/rpm/manual/spec.html
+*/
+
+use crate::build_options;
+use crate::cpp_proj_gen::{self, CppProjGen, Kind, Opt};
+use crate::dependency;
+use std::{io, path::PathBuf, str::FromStr};
+
+const APP_INSTALL_CMAKE: &str = include_str!("../res/packaging/app_install.snippet.in");
+const HOMEBREW_FORMULA: &str = include_str!("../res/packaging/homebrew.rb.in");
+const PKGBUILD: &str = include_str!("../res/packaging/PKGBUILD.in");
+const PKGBUILD_CHECK: &str = include_str!("../res/packaging/PKGBUILD.check.in");
+const DEBIAN_CONTROL: &str = include_str!("../res/packaging/debian/control.in");
+const DEBIAN_RULES: &str = include_str!("../res/packaging/debian/rules.in");
+const DEBIAN_RULES_CONFIGURE: &str = include_str!("../res/packaging/debian/rules.configure.in");
+const DEBIAN_CHANGELOG: &str = include_str!("../res/packaging/debian/changelog.in");
+const DEBIAN_COPYRIGHT: &str = include_str!("../res/packaging/debian/copyright.in");
+const DEBIAN_SOURCE_FORMAT: &str = include_str!("../res/packaging/debian/source_format.in");
+const RPM_SPEC: &str = include_str!("../res/packaging/rpm.spec.in");
+
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// Distribution package recipes, separate from CPack
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Packaging {
+    Homebrew,
+    Aur,
+    Debian,
+    Rpm,
+}
+
+impl Packaging {
+    pub const VARIANTS: &'static [&'static str] = &["homebrew", "aur", "debian", "rpm"];
+}
+
+impl FromStr for Packaging {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "homebrew" => Ok(Packaging::Homebrew),
+            "aur" => Ok(Packaging::Aur),
+            "debian" => Ok(Packaging::Debian),
+            "rpm" => Ok(Packaging::Rpm),
+            _ => Err(format!(
+                "unknown packaging '{}', expected one of: {}",
+                s,
+                Packaging::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Package names of the libraries of the generated code, keyed by the name of their spec
+struct SystemPackage {
+    name: &'static str,
+    homebrew: &'static str,
+    aur: &'static str,
+    debian: &'static str,
+    rpm: &'static str,
+}
+
+const SYSTEM_PACKAGES: &[SystemPackage] = &[
+    SystemPackage {
+        name: "spdlog",
+        homebrew: "spdlog",
+        aur: "spdlog",
+        debian: "libspdlog-dev",
+        rpm: "spdlog-devel",
+    },
+    SystemPackage {
+        name: "glog",
+        homebrew: "glog",
+        aur: "google-glog",
+        debian: "libgoogle-glog-dev",
+        rpm: "glog-devel",
+    },
+    SystemPackage {
+        name: "tomlplusplus",
+        homebrew: "tomlplusplus",
+        aur: "tomlplusplus",
+        debian: "libtomlplusplus-dev",
+        rpm: "tomlplusplus-devel",
+    },
+    SystemPackage {
+        name: "yaml-cpp",
+        homebrew: "yaml-cpp",
+        aur: "yaml-cpp",
+        debian: "libyaml-cpp-dev",
+        rpm: "yaml-cpp-devel",
+    },
+    SystemPackage {
+        name: "nlohmann_json",
+        homebrew: "nlohmann-json",
+        aur: "nlohmann-json",
+        debian: "nlohmann-json3-dev",
+        rpm: "json-devel",
+    },
+    SystemPackage {
+        name: "onetbb",
+        homebrew: "tbb",
+        aur: "onetbb",
+        debian: "libtbb-dev",
+        rpm: "tbb-devel",
+    },
+    SystemPackage {
+        name: "taskflow",
+        homebrew: "taskflow",
+        aur: "taskflow",
+        debian: "libtaskflow-cpp-dev",
+        rpm: "taskflow-devel",
+    },
+    SystemPackage {
+        name: "opencv",
+        homebrew: "opencv",
+        aur: "opencv",
+        debian: "libopencv-dev",
+        rpm: "opencv-devel",
+    },
+];
+
+fn build_system_packages(opt: &Opt) -> Vec<&'static SystemPackage> {
+    dependency::build_feature_dependencies(opt)
+        .iter()
+        .filter_map(|feature| {
+            let name = feature.spec.split('/').next().unwrap();
+            SYSTEM_PACKAGES.iter().find(|package| package.name == name)
+        })
+        .collect()
+}
+
+// Packages install the project, the minimal template has no install rules for libraries
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    if !opt.packaging.is_empty()
+        && opt.kind != Kind::Executable
+        && !opt.builtin_template.has_install_rules()
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "--packaging needs install rules, builtin template {} has none",
+                opt.builtin_template.name()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+// -D<PREFIX>_BUILD_<PART>=OFF of the parts a package leaves out
+fn build_disabled_parts(opt: &Opt, keep_tests: bool) -> Vec<String> {
+    build_options::build_toggles(opt)
+        .iter()
+        .filter(|(suffix, _)| !(keep_tests && *suffix == "TESTS"))
+        .map(|(suffix, _)| format!("-D@CMAKE_VAR_PREFIX@_BUILD_{}=OFF", suffix))
+        .collect()
+}
+
+// Formula class of the formula name, e.g. MyTarget of my-target
+fn build_homebrew_class(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn render_homebrew(opt: &Opt) -> String {
+    let metadata = match &opt.license {
+        Some(license) => format!("  license \"{}\"\n", license),
+        None => String::new(),
+    };
+    let depends: String = build_system_packages(opt)
+        .iter()
+        .map(|package| format!("  depends_on \"{}\"\n", package.homebrew))
+        .collect();
+    let cmake_args: String = build_disabled_parts(opt, false)
+        .iter()
+        .map(|arg| format!("\"{}\", ", arg))
+        .collect();
+    let test = match opt.kind.app_target() {
+        Some(app_target) => format!("system bin/\"{}\"", app_target),
+        None => String::from(
+            "assert_predicate lib/\"cmake/@CMAKE_PROJECT_NAME@/@CMAKE_PROJECT_NAME@-config.cmake\", :exist?",
+        ),
+    };
+
+    HOMEBREW_FORMULA
+        .replace("@HOMEBREW_CLASS@", &build_homebrew_class(&opt.target_name))
+        .replace("@HOMEBREW_METADATA@", &metadata)
+        .replace("@HOMEBREW_DEPENDS@", &depends)
+        .replace("@HOMEBREW_CMAKE_ARGS@", &cmake_args)
+        .replace("@HOMEBREW_TEST@", &test)
+}
+
+// Arch builds run the tests in check()
+fn render_pkgbuild(opt: &Opt) -> String {
+    let depends: Vec<String> = build_system_packages(opt)
+        .iter()
+        .map(|package| format!("'{}'", package.aur))
+        .collect();
+    let cmake_args: String = build_disabled_parts(opt, true)
+        .iter()
+        .map(|arg| format!(" \\\n    {}", arg))
+        .collect();
+    let check = match opt.test_framework {
+        Some(_) => PKGBUILD_CHECK,
+        None => "",
+    };
+
+    PKGBUILD
+        .replace("@AUR_DEPENDS@", &depends.join(" "))
+        .replace("@AUR_CMAKE_ARGS@", &cmake_args)
+        .replace("@AUR_CHECK@", check)
+}
+
+// The epoch was a Thursday
+fn build_weekday(timestamp: i64) -> &'static str {
+    WEEKDAYS[timestamp.div_euclid(86400).rem_euclid(7) as usize]
+}
+
+// RFC 2822 date of debian/changelog
+fn build_debian_date(timestamp: i64) -> String {
+    let (year, month, day) = cpp_proj_gen::civil_from_timestamp(timestamp);
+    let seconds = timestamp.rem_euclid(86400);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} +0000",
+        build_weekday(timestamp),
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// debian/ at the top level, where dpkg-buildpackage looks for it
+fn render_debian(opt: &Opt) -> Vec<(PathBuf, String)> {
+    let system_packages = build_system_packages(opt);
+    let (package, section) = match opt.kind.app_target() {
+        Some(_) => (opt.target_name.clone(), "utils"),
+        None if opt.target_name.starts_with("lib") => {
+            (format!("{}-dev", opt.target_name), "libdevel")
+        }
+        None => (format!("lib{}-dev", opt.target_name), "libdevel"),
+    };
+    let architecture = match opt.kind {
+        Kind::HeaderOnly => "all",
+        _ => "any",
+    };
+    let build_depends: String = system_packages
+        .iter()
+        .map(|package| format!(", {}", package.debian))
+        .collect();
+    // Consumers of a library need the headers of its dependencies too
+    let mut depends = Vec::new();
+    if opt.kind != Kind::HeaderOnly {
+        depends.push("${shlibs:Depends}");
+    }
+    if opt.kind.app_target().is_none() {
+        depends.extend(system_packages.iter().map(|package| package.debian));
+    }
+    let depends: String = depends
+        .iter()
+        .map(|depends| format!(", {}", depends))
+        .collect();
+    // Debian builds have no network for FetchContent of the test framework
+    let cmake_args = build_disabled_parts(opt, false);
+    let configure = match cmake_args.is_empty() {
+        true => String::new(),
+        false => DEBIAN_RULES_CONFIGURE
+            .trim_end()
+            .replace("@DEBIAN_CMAKE_ARGS@", &format!(" {}", cmake_args.join(" "))),
+    };
+    let timestamp = cpp_proj_gen::timestamp_or_now(opt.timestamp);
+    let (year, _, _) = cpp_proj_gen::civil_from_timestamp(timestamp);
+
+    let control = DEBIAN_CONTROL
+        .replace("@DEBIAN_SECTION@", section)
+        .replace("@DEBIAN_BUILD_DEPENDS@", &build_depends)
+        .replace("@DEBIAN_PACKAGE@", &package)
+        .replace("@DEBIAN_ARCHITECTURE@", architecture)
+        .replace("@DEBIAN_DEPENDS@", &depends);
+
+    vec![
+        (PathBuf::from("debian/control"), control),
+        (
+            PathBuf::from("debian/rules"),
+            DEBIAN_RULES.replace("@DEBIAN_CONFIGURE@", &configure),
+        ),
+        (
+            PathBuf::from("debian/changelog"),
+            DEBIAN_CHANGELOG.replace("@DEBIAN_DATE@", &build_debian_date(timestamp)),
+        ),
+        (
+            PathBuf::from("debian/copyright"),
+            DEBIAN_COPYRIGHT.replace("@DEBIAN_YEAR@", &year.to_string()),
+        ),
+        (
+            PathBuf::from("debian/source/format"),
+            String::from(DEBIAN_SOURCE_FORMAT),
+        ),
+    ]
+}
+
+// Maintainer: and changelog trailers need both, e.g. Jane Doe <jane@example.com>
+fn build_maintainer(opt: &Opt) -> String {
+    let author = opt.author.as_deref().unwrap_or("Unknown");
+    if author.contains('<') {
+        return String::from(author);
+    }
+
+    format!(
+        "{} <{}>",
+        author,
+        opt.author_email.as_deref().unwrap_or("unknown@example.com")
+    )
+}
+
+// %changelog date, e.g. Thu Feb 29 2024
+fn build_rpm_date(timestamp: i64) -> String {
+    let (year, month, day) = cpp_proj_gen::civil_from_timestamp(timestamp);
+
+    format!(
+        "{} {} {:02} {}",
+        build_weekday(timestamp),
+        MONTHS[month as usize - 1],
+        day,
+        year
+    )
+}
+
+// Installed files of the library and application targets
+fn build_rpm_files(opt: &Opt) -> Vec<String> {
+    let mut files = Vec::new();
+    if let Some(app_target) = opt.kind.app_target() {
+        files.push(format!("%{{_bindir}}/{}", app_target));
+    }
+    if opt.kind != Kind::Executable {
+        files.push(String::from("%{_includedir}/@INCLUDE_DOMAIN_DIR@/"));
+        files.push(String::from("%{_libdir}/cmake/@CMAKE_PROJECT_NAME@/"));
+    }
+    if !matches!(opt.kind, Kind::Executable | Kind::HeaderOnly) {
+        files.push(String::from("%{_libdir}/lib@CMAKE_TARGET_NAME@.*"));
+    }
+
+    files
+}
+
+// Fedora builds have no network for FetchContent of the test framework either
+fn render_rpm_spec(opt: &Opt) -> String {
+    let system_packages = build_system_packages(opt);
+    let mut requires: Vec<String> = system_packages
+        .iter()
+        .map(|package| format!("BuildRequires:  {}\n", package.rpm))
+        .collect();
+    // Consumers of a library need the headers of its dependencies too
+    if opt.kind.app_target().is_none() {
+        requires.extend(
+            system_packages
+                .iter()
+                .map(|package| format!("Requires:       {}\n", package.rpm)),
+        );
+    }
+    let cmake_args: String = build_disabled_parts(opt, false)
+        .iter()
+        .map(|arg| format!(" {}", arg))
+        .collect();
+
+    RPM_SPEC
+        .replace("@RPM_BUILD_REQUIRES@", &requires.concat())
+        .replace("@RPM_CMAKE_ARGS@", &cmake_args)
+        .replace("@RPM_FILES@", &build_rpm_files(opt).join("\n"))
+        .replace(
+            "@RPM_DATE@",
+            &build_rpm_date(cpp_proj_gen::timestamp_or_now(opt.timestamp)),
+        )
+}
+
+// Adds packaging/<backend>/ recipes building and installing the project with CMake
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if gen.opt().packaging.is_empty() {
+        return gen;
+    }
+
+    let opt = gen.opt();
+    let description = match opt.kind.app_target() {
+        Some(_) => "@CMAKE_PROJECT_NAME@ application",
+        None => "@CMAKE_PROJECT_NAME@ library",
+    };
+    let homepage = opt
+        .homepage
+        .clone()
+        .unwrap_or_else(|| String::from("https://example.com/@CMAKE_TARGET_NAME@"));
+    let maintainer = build_maintainer(opt);
+    let license = opt
+        .license
+        .clone()
+        .unwrap_or_else(|| String::from("unknown"));
+    let recipes: Vec<(PathBuf, String)> = opt
+        .packaging
+        .iter()
+        .flat_map(|packaging| match packaging {
+            Packaging::Homebrew => vec![(
+                PathBuf::from("packaging/homebrew/@CMAKE_TARGET_NAME@.rb"),
+                render_homebrew(opt),
+            )],
+            Packaging::Aur => vec![(
+                PathBuf::from("packaging/aur/PKGBUILD"),
+                render_pkgbuild(opt),
+            )],
+            Packaging::Debian => render_debian(opt),
+            Packaging::Rpm => vec![(
+                PathBuf::from("packaging/rpm/@CMAKE_TARGET_NAME@.spec"),
+                render_rpm_spec(opt),
+            )],
+        })
+        .collect();
+    let app_install = opt
+        .kind
+        .app_target()
+        .map(|app_target| APP_INSTALL_CMAKE.replace("@PACKAGING_APP_TARGET@", app_target));
+
+    // Var values are not replaced themselves
+    let description = gen.render(description);
+    let homepage = gen.render(&homepage);
+
+    let gen = gen
+        .add_cmake_var("@PACKAGING_DESCRIPTION@", &description)
+        .add_cmake_var("@PACKAGING_HOMEPAGE@", &homepage)
+        .add_cmake_var("@PACKAGING_MAINTAINER@", &maintainer)
+        .add_cmake_var("@PACKAGING_LICENSE@", &license);
+    // Applications are not installed otherwise
+    let gen = match app_install {
+        Some(app_install) => gen.add_cmake_snippet(String::from(app_install.trim_end())),
+        None => gen,
+    };
+
+    recipes
+        .into_iter()
+        .fold(gen, |gen, (path, contents)| gen.add_file(path, contents))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::AppConfig;
+    use crate::builtin_template::BuiltinTemplate;
+    use crate::concurrency::Concurrency;
+    use crate::logging::Logging;
+    use crate::test_framework::TestFramework;
+    use crate::test_util::contents;
+
+    fn create_test_opt() -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            packaging: vec![Packaging::Homebrew, Packaging::Aur],
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("aur".parse::<Packaging>(), Ok(Packaging::Aur));
+        assert!("msi".parse::<Packaging>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&create_test_opt()).is_ok());
+        assert!(validate(&Opt {
+            builtin_template: BuiltinTemplate::Minimal,
+            ..create_test_opt()
+        })
+        .is_err());
+        assert!(validate(&Opt {
+            kind: Kind::Executable,
+            builtin_template: BuiltinTemplate::Minimal,
+            ..create_test_opt()
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn test_build_homebrew_class() {
+        assert_eq!(build_homebrew_class("my-target"), "MyTarget");
+        assert_eq!(build_homebrew_class("lib_foo2"), "LibFoo2");
+    }
+
+    #[test]
+    fn test_system_packages_cover_feature_dependencies() {
+        let opt = Opt {
+            kind: Kind::Executable,
+            logging: Some(Logging::Glog),
+            app_config: Some(AppConfig::Json),
+            ..create_test_opt()
+        };
+        let packages: Vec<&str> = build_system_packages(&opt)
+            .iter()
+            .map(|package| package.aur)
+            .collect();
+        assert_eq!(packages, vec!["google-glog", "nlohmann-json"]);
+    }
+
+    #[test]
+    fn test_build_debian_date() {
+        assert_eq!(build_debian_date(0), "Thu, 01 Jan 1970 00:00:00 +0000");
+        assert_eq!(
+            build_debian_date(1709251199),
+            "Thu, 29 Feb 2024 23:59:59 +0000"
+        );
+    }
+
+    #[test]
+    fn test_build_maintainer() {
+        let opt = Opt {
+            author: Some(String::from("Jane Doe")),
+            author_email: Some(String::from("jane@example.com")),
+            ..create_test_opt()
+        };
+        assert_eq!(build_maintainer(&opt), "Jane Doe <jane@example.com>");
+        assert_eq!(
+            build_maintainer(&Opt {
+                author_email: None,
+                ..opt
+            }),
+            "Jane Doe <unknown@example.com>"
+        );
+        assert_eq!(
+            build_maintainer(&create_test_opt()),
+            "Unknown <unknown@example.com>"
+        );
+    }
+
+    #[test]
+    fn test_build_rpm_date() {
+        assert_eq!(build_rpm_date(1709251199), "Thu Feb 29 2024");
+    }
+
+    #[test]
+    fn test_render_rpm_spec() {
+        let spec = render_rpm_spec(&Opt {
+            kind: Kind::Shared,
+            concurrency: Some(Concurrency::Taskflow),
+            test_framework: Some(TestFramework::GTest),
+            ..create_test_opt()
+        });
+        assert!(spec.contains(
+            "BuildRequires:  ninja-build\nBuildRequires:  taskflow-devel\nRequires:       taskflow-devel\n\n"
+        ));
+        assert!(spec.contains("%cmake -G Ninja -D@CMAKE_VAR_PREFIX@_BUILD_TESTS=OFF\n"));
+        assert!(spec.contains("%files\n%{_includedir}/@INCLUDE_DOMAIN_DIR@/\n"));
+        assert!(spec.contains("%{_libdir}/lib@CMAKE_TARGET_NAME@.*\n"));
+
+        let spec = render_rpm_spec(&Opt {
+            kind: Kind::Executable,
+            ..create_test_opt()
+        });
+        assert!(spec.contains("BuildRequires:  ninja-build\n\n%description"));
+        assert!(spec.contains("%files\n%{_bindir}/@CMAKE_TARGET_NAME@\n\n"));
+    }
+
+    #[test]
+    fn test_render_debian() {
+        let opt = Opt {
+            kind: Kind::Static,
+            concurrency: Some(Concurrency::Tbb),
+            examples: true,
+            ..create_test_opt()
+        };
+        let files = render_debian(&opt);
+        let control = &files[0].1;
+        assert!(control.contains("ninja-build, libtbb-dev\n"));
+        assert!(control.contains("Package: libtgtnm-dev\n"));
+        assert!(control.contains("Depends: ${misc:Depends}, ${shlibs:Depends}, libtbb-dev\n"));
+        assert_eq!(
+            files[1].1,
+            "#!/usr/bin/make -f\n\n%:\n\tdh $@ --buildsystem=cmake+ninja\n\n\
+             override_dh_auto_configure:\n\tdh_auto_configure -- -D@CMAKE_VAR_PREFIX@_BUILD_EXAMPLES=OFF\n"
+        );
+
+        let files = render_debian(&Opt {
+            kind: Kind::Executable,
+            ..create_test_opt()
+        });
+        assert!(files[0].1.contains(
+            "Package: tgtnm\nArchitecture: any\nDepends: ${misc:Depends}, ${shlibs:Depends}\n"
+        ));
+        assert!(files[1].1.ends_with("cmake+ninja\n"));
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = CppProjGen::new(Opt {
+            license: Some(String::from("MIT")),
+            homepage: Some(String::from("https://github.com/me/tgtnm")),
+            test_framework: Some(TestFramework::Catch2),
+            ..create_test_opt()
+        })
+        .plan()
+        .unwrap();
+
+        let formula = contents(&plan, "packaging/homebrew/tgtnm.rb").unwrap();
+        assert!(formula.contains("class Tgtnm < Formula\n  desc \"tgtnm library\"\n"));
+        assert!(formula.contains("  url \"https://github.com/me/tgtnm/archive/v0.1.0.tar.gz\"\n"));
+        assert!(formula.contains("  license \"MIT\"\n"));
+        assert!(formula.contains("\"-DTGTNM_BUILD_TESTS=OFF\", *std_cmake_args"));
+        assert!(formula.contains("lib/\"cmake/tgtnm/tgtnm-config.cmake\""));
+
+        let pkgbuild = contents(&plan, "packaging/aur/PKGBUILD").unwrap();
+        assert!(pkgbuild.contains("license=('MIT')\n"));
+        assert!(!pkgbuild.contains("BUILD_TESTS"));
+        assert!(pkgbuild.contains("check() {\n  cd build && ctest"));
+
+        let plan = CppProjGen::new(Opt {
+            author: Some(String::from("Jane Doe")),
+            author_email: Some(String::from("jane@example.com")),
+            timestamp: Some(1709251199),
+            packaging: vec![Packaging::Debian, Packaging::Rpm],
+            ..create_test_opt()
+        })
+        .plan()
+        .unwrap();
+        let control = contents(&plan, "debian/control").unwrap();
+        assert!(control.contains("Maintainer: Jane Doe <jane@example.com>\n"));
+        let changelog = contents(&plan, "debian/changelog").unwrap();
+        assert!(changelog
+            .ends_with(" -- Jane Doe <jane@example.com>  Thu, 29 Feb 2024 23:59:59 +0000\n"));
+        let spec = contents(&plan, "packaging/rpm/tgtnm.spec").unwrap();
+        assert!(spec.contains("* Thu Feb 29 2024 Jane Doe <jane@example.com> - 0.1.0-1\n"));
+
+        let plan = CppProjGen::new(Opt {
+            kind: Kind::Executable,
+            packaging: vec![Packaging::Homebrew],
+            ..create_test_opt()
+        })
+        .plan()
+        .unwrap();
+        let formula = contents(&plan, "packaging/homebrew/tgtnm.rb").unwrap();
+        assert!(formula.contains("  homepage \"https://example.com/tgtnm\"\n"));
+        assert!(formula.contains("system bin/\"tgtnm\""));
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("install(TARGETS tgtnm RUNTIME DESTINATION ${CMAKE_INSTALL_BINDIR})"));
+        assert!(contents(&plan, "packaging/aur/PKGBUILD").is_none());
+    }
+}
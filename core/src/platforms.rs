@@ -0,0 +1,211 @@
+/*
+For VERSIONINFO /sa https://learn.microsoft.com/en-us/windows/win32/menurc/versioninfo-resource
+For MACOSX_BUNDLE_INFO_PLIST /sa https://cmake.org/cmake/help/latest/prop_tgt/MACOSX_BUNDLE_INFO_PLIST.html
+*/
+
+use crate::cpp_proj_gen::CppProjGen;
+use crate::presets;
+use crate::scripts::ScriptFlavor;
+use std::{path::PathBuf, str::FromStr};
+
+const APP_RC: &str = include_str!("../res/platforms/app.rc.in");
+const INFO_PLIST: &str = include_str!("../res/platforms/Info.plist.in");
+const PLATFORMS_CMAKE: &str = include_str!("../res/platforms/CMakeLists.snippet.in");
+
+// Template lines between these markers, e.g. @IF_PLATFORM win,macos@, are kept for the listed platforms only
+const BLOCK_BEGIN: &str = "@IF_PLATFORM ";
+const BLOCK_END: &str = "@ENDIF_PLATFORM@";
+
+// Target platform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Platform {
+    Win,
+    Linux,
+    Macos,
+}
+
+impl Platform {
+    pub const VARIANTS: &'static [&'static str] = &["win", "linux", "macos"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Win => "win",
+            Platform::Linux => "linux",
+            Platform::Macos => "macos",
+        }
+    }
+
+    // Convenience scripts the platform runs without extra tools
+    pub fn script_flavors(self) -> &'static [ScriptFlavor] {
+        match self {
+            Platform::Win => &[ScriptFlavor::PowerShell, ScriptFlavor::Batch],
+            Platform::Linux | Platform::Macos => &[ScriptFlavor::Sh],
+        }
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "win" => Ok(Platform::Win),
+            "linux" => Ok(Platform::Linux),
+            "macos" => Ok(Platform::Macos),
+            _ => Err(format!(
+                "unknown platform '{}', expected one of: {}",
+                s,
+                Platform::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Drops the blocks of platforms not targeted and the marker lines
+pub fn render_blocks(template: &str, platforms: &[Platform]) -> String {
+    if !template.contains(BLOCK_BEGIN) {
+        return String::from(template);
+    }
+
+    let mut filter = BlockFilter::default();
+    template
+        .split_inclusive('\n')
+        .filter(|line| filter.keeps(line, platforms))
+        .collect()
+}
+
+// The same line by line, for templates rendered while they are read
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFilter {
+    keep: bool,
+}
+
+impl Default for BlockFilter {
+    fn default() -> Self {
+        BlockFilter { keep: true }
+    }
+}
+
+impl BlockFilter {
+    pub fn keeps(&mut self, line: &str, platforms: &[Platform]) -> bool {
+        let line = line.trim();
+        if let Some(names) = line
+            .strip_prefix(BLOCK_BEGIN)
+            .and_then(|names| names.strip_suffix('@'))
+        {
+            self.keep = names.split(',').any(|name| {
+                platforms
+                    .iter()
+                    .any(|platform| platform.name() == name.trim())
+            });
+            return false;
+        }
+        if line == BLOCK_END {
+            self.keep = true;
+            return false;
+        }
+
+        self.keep
+    }
+}
+
+// Adds the Windows version resource, the macOS bundle plist and the platform settings of the app
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let platforms = gen.opt().platforms.clone();
+    let app_target = match gen.opt().kind.app_target() {
+        Some(app_target) if !platforms.is_empty() => app_target,
+        _ => return gen,
+    };
+
+    let snippet =
+        render_blocks(PLATFORMS_CMAKE, &platforms).replace("@PLATFORM_APP_TARGET@", app_target);
+    let rc_version = build_rc_version(&gen.opt().project_version);
+    let bundle_identifier = presets::build_bundle_identifier(gen.opt());
+    let gen = gen.add_cmake_snippet(String::from(snippet.trim_end()));
+
+    let gen = match platforms.contains(&Platform::Win) {
+        true => gen.add_cmake_var("@RC_VERSION@", &rc_version).add_file(
+            PathBuf::from("@SOURCE_DIR@/@CMAKE_TARGET_NAME@.rc"),
+            String::from(APP_RC),
+        ),
+        false => gen,
+    };
+    match platforms.contains(&Platform::Macos) {
+        true => gen
+            .add_cmake_var("@BUNDLE_IDENTIFIER@", &bundle_identifier)
+            .add_file(
+                PathBuf::from("cmake/Info.plist.in"),
+                String::from(INFO_PLIST),
+            ),
+        false => gen,
+    }
+}
+
+// e.g. 1.2.3 to 1,2,3,0
+fn build_rc_version(project_version: &str) -> String {
+    let mut parts: Vec<String> = project_version
+        .split('.')
+        .take(4)
+        .map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            match digits.is_empty() {
+                true => String::from("0"),
+                false => digits,
+            }
+        })
+        .collect();
+    parts.resize(4, String::from("0"));
+
+    parts.join(",")
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Kind, Opt};
+    use crate::test_util::contents;
+
+    #[test]
+    fn test_render_blocks() {
+        let template = "a\n@IF_PLATFORM win@\nb\n@ENDIF_PLATFORM@\n  @IF_PLATFORM linux, macos@\nc\n@ENDIF_PLATFORM@\nd\n";
+
+        assert_eq!(render_blocks(template, &[Platform::Win]), "a\nb\nd\n");
+        assert_eq!(render_blocks(template, &[Platform::Macos]), "a\nc\nd\n");
+        assert_eq!(render_blocks(template, &[]), "a\nd\n");
+        assert_eq!(render_blocks("a\n", &[]), "a\n");
+    }
+
+    #[test]
+    fn test_build_rc_version() {
+        assert_eq!(build_rc_version("1.2.3"), "1,2,3,0");
+        assert_eq!(build_rc_version("2.0.0-rc1"), "2,0,0,0");
+        assert_eq!(build_rc_version("1.2.3.4.5"), "1,2,3,4");
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::LibAndApp,
+            platforms: vec![Platform::Win, Platform::Linux],
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .plan()
+            .unwrap();
+
+        let cmake_lists = contents(&plan, "CMakeLists.txt").unwrap();
+        assert!(cmake_lists.contains("target_sources(tgtnm-app PRIVATE source/tgtnm.rc)"));
+        assert!(cmake_lists.contains("INSTALL_RPATH"));
+        assert!(!cmake_lists.contains("MACOSX_BUNDLE"));
+        assert!(!cmake_lists.contains("@IF_PLATFORM"));
+        assert!(contents(&plan, "source/tgtnm.rc")
+            .unwrap()
+            .contains("FILEVERSION 0,1,0,0"));
+        assert!(contents(&plan, "cmake/Info.plist.in").is_none());
+    }
+}
@@ -0,0 +1,1031 @@
+/*
+For FRAMEWORK /sa https://cmake.org/cmake/help/latest/prop_tgt/FRAMEWORK.html
+For Android CMake /sa https://developer.android.com/ndk/guides/cmake
+For ROS2 ament_cmake /sa https://docs.ros.org/en/rolling/How-To-Guides/Ament-CMake-Documentation.html
+For protobuf_generate /sa https://github.com/protocolbuffers/protobuf/blob/main/docs/cmake_protobuf_generate.md
+For CLI11 /sa https://github.com/CLIUtils/CLI11
+For SDL2 with CMake /sa https://wiki.libsdl.org/SDL2/README/cmake
+For Crow /sa https://crowcpp.org/master/getting_started/setup/
+For FindOpenMP /sa https://cmake.org/cmake/help/latest/module/FindOpenMP.html
+For FindMPI /sa https://cmake.org/cmake/help/latest/module/FindMPI.html
+For SYCL 2020 /sa https://registry.khronos.org/SYCL/specs/sycl-2020/html/sycl-2020.html
+For AdaptiveCpp with CMake /sa https://github.com/AdaptiveCpp/AdaptiveCpp/blob/develop/doc/using-hipsycl.md
+For OpenCV with CMake /sa https://docs.opencv.org/4.x/db/df5/tutorial_linux_gcc_cmake.html
+For the PPM format /sa https://netpbm.sourceforge.net/doc/ppm.html
+For JUCE with CMake /sa https://github.com/juce-framework/JUCE/blob/master/docs/CMake%20API.md
+For JNI name mangling /sa https://docs.oracle.com/javase/8/docs/technotes/guides/jni/spec/design.html#resolving_native_method_names
+*/
+
+use crate::compile_commands;
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::defaults::Defaults;
+use crate::dependency::FeatureDependency;
+use crate::error::invalid_input;
+use crate::language::{self, Language};
+use crate::test_framework::TestFramework;
+use std::{io, path::PathBuf, str::FromStr};
+
+const APPLE_FRAMEWORK_CMAKE: &str =
+    include_str!("../res/presets/apple-framework/CMakeLists.snippet.in");
+const APPLE_FRAMEWORK_INFO_PLIST: &str =
+    include_str!("../res/presets/apple-framework/Info.plist.in");
+const APPLE_FRAMEWORK_MM: &str = include_str!("../res/presets/apple-framework/apple.mm.in");
+const ANDROID_CMAKE: &str = include_str!("../res/presets/android/CMakeLists.snippet.in");
+const ANDROID_JNI: &str = include_str!("../res/presets/android/jni.cpp.in");
+const ANDROID_BUILD_GRADLE: &str = include_str!("../res/presets/android/build.gradle.in");
+const ANDROID_NATIVE_LIB: &str = include_str!("../res/presets/android/NativeLib.java.in");
+const ANDROID_MANIFEST: &str = include_str!("../res/presets/android/AndroidManifest.xml.in");
+const ROS2_CMLT: &str = include_str!("../res/presets/ros2/CMakeLists.txt.in");
+const ROS2_PACKAGE_XML: &str = include_str!("../res/presets/ros2/package.xml.in");
+const ROS2_NODE: &str = include_str!("../res/presets/ros2/node.cpp.in");
+const ROS2_LAUNCH: &str = include_str!("../res/presets/ros2/launch.py.in");
+const GRPC_CMAKE: &str = include_str!("../res/presets/grpc-service/CMakeLists.snippet.in");
+const GRPC_PROTO: &str = include_str!("../res/presets/grpc-service/service.proto.in");
+const GRPC_SERVER: &str = include_str!("../res/presets/grpc-service/server.cpp.in");
+const GRPC_SERVER_GRACEFUL: &str =
+    include_str!("../res/presets/grpc-service/server.graceful.cpp.in");
+const GRPC_CLIENT: &str = include_str!("../res/presets/grpc-service/client.cpp.in");
+const GAME_SDL_CMAKE: &str = include_str!("../res/presets/game-sdl/CMakeLists.snippet.in");
+const GAME_SDL_MAIN: &str = include_str!("../res/presets/game-sdl/main.cpp.in");
+const GAME_SDL_ASSETS_README: &str = include_str!("../res/presets/game-sdl/assets.README.md.in");
+const HTTP_SERVICE_CMAKE: &str = include_str!("../res/presets/http-service/CMakeLists.snippet.in");
+const HTTP_SERVICE_MAIN: &str = include_str!("../res/presets/http-service/main.cpp.in");
+const HTTP_SERVICE_MAIN_GRACEFUL: &str =
+    include_str!("../res/presets/http-service/main.graceful.cpp.in");
+const HTTP_SERVICE_ROUTES_H: &str = include_str!("../res/presets/http-service/routes.h.in");
+const HTTP_SERVICE_ROUTES_CPP: &str = include_str!("../res/presets/http-service/routes.cpp.in");
+const HTTP_SERVICE_TEST: &str = include_str!("../res/presets/http-service/test_routes.cpp.in");
+const HTTP_SERVICE_DOCKERFILE: &str = include_str!("../res/presets/http-service/Dockerfile.in");
+const HTTP_SERVICE_DOCKERIGNORE: &str = include_str!("../res/presets/http-service/dockerignore.in");
+const HPC_CMAKE: &str = include_str!("../res/presets/hpc/CMakeLists.snippet.in");
+const HPC_MAIN: &str = include_str!("../res/presets/hpc/main.cpp.in");
+const HPC_KERNEL_H: &str = include_str!("../res/presets/hpc/kernel.h.in");
+const HPC_KERNEL_CPP: &str = include_str!("../res/presets/hpc/kernel.cpp.in");
+const SYCL_CMAKE: &str = include_str!("../res/presets/sycl/CMakeLists.snippet.in");
+const SYCL_CMAKE_PRESETS: &str = include_str!("../res/presets/sycl/CMakePresets.json.in");
+const SYCL_MAIN: &str = include_str!("../res/presets/sycl/main.cpp.in");
+const SYCL_DEVICE_H: &str = include_str!("../res/presets/sycl/device.h.in");
+const SYCL_DEVICE_CPP: &str = include_str!("../res/presets/sycl/device.cpp.in");
+const SYCL_VECTOR_ADD_H: &str = include_str!("../res/presets/sycl/vector_add.h.in");
+const SYCL_VECTOR_ADD_CPP: &str = include_str!("../res/presets/sycl/vector_add.cpp.in");
+const OPENCV_CMAKE: &str = include_str!("../res/presets/opencv/CMakeLists.snippet.in");
+const OPENCV_MAIN: &str = include_str!("../res/presets/opencv/main.cpp.in");
+const AUDIO_PLUGIN_CMLT: &str = include_str!("../res/presets/audio-plugin/CMakeLists.txt.in");
+const AUDIO_PLUGIN_PROCESSOR_H: &str =
+    include_str!("../res/presets/audio-plugin/plugin_processor.h.in");
+const AUDIO_PLUGIN_PROCESSOR_CPP: &str =
+    include_str!("../res/presets/audio-plugin/plugin_processor.cpp.in");
+const AUDIO_PLUGIN_EDITOR_H: &str = include_str!("../res/presets/audio-plugin/plugin_editor.h.in");
+const AUDIO_PLUGIN_EDITOR_CPP: &str =
+    include_str!("../res/presets/audio-plugin/plugin_editor.cpp.in");
+const SHUTDOWN_H: &str = include_str!("../res/presets/shutdown.h.in");
+const CLI_APP_CMAKE: &str = include_str!("../res/presets/cli-app/CMakeLists.snippet.in");
+const CLI_APP_TEST_CMAKE: &str = include_str!("../res/presets/cli-app/test.snippet.in");
+const CLI_APP_VERSION_H: &str = include_str!("../res/presets/cli-app/version.h.in");
+const CLI_APP_OPTIONS_H: &str = include_str!("../res/presets/cli-app/options.h.in");
+const CLI_APP_OPTIONS_CPP: &str = include_str!("../res/presets/cli-app/options.cpp.in");
+const CLI_APP_MAIN: &str = include_str!("../res/presets/cli-app/main.cpp.in");
+const CLI_APP_TEST_GTEST: &str = include_str!("../res/presets/cli-app/test_options.gtest.cpp.in");
+const CLI_APP_TEST_CATCH2: &str = include_str!("../res/presets/cli-app/test_options.catch2.cpp.in");
+const CLI_APP_TEST_DOCTEST: &str =
+    include_str!("../res/presets/cli-app/test_options.doctest.cpp.in");
+
+// Preset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preset {
+    AppleFramework,
+    Android,
+    Ros2,
+    GrpcService,
+    CliApp,
+    GameSdl,
+    HttpService,
+    Hpc,
+    Sycl,
+    Opencv,
+    AudioPlugin,
+}
+
+impl Preset {
+    pub const VARIANTS: &'static [&'static str] = &[
+        "apple-framework",
+        "android",
+        "ros2",
+        "grpc-service",
+        "cli-app",
+        "game-sdl",
+        "http-service",
+        "hpc",
+        "sycl",
+        "opencv",
+        "audio-plugin",
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::AppleFramework => "apple-framework",
+            Preset::Android => "android",
+            Preset::Ros2 => "ros2",
+            Preset::GrpcService => "grpc-service",
+            Preset::CliApp => "cli-app",
+            Preset::GameSdl => "game-sdl",
+            Preset::HttpService => "http-service",
+            Preset::Hpc => "hpc",
+            Preset::Sycl => "sycl",
+            Preset::Opencv => "opencv",
+            Preset::AudioPlugin => "audio-plugin",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Preset::AppleFramework => "macOS/iOS framework bundle with Info.plist and code signing",
+            Preset::Android => "JNI library for the Android NDK, optionally with a Gradle module",
+            Preset::Ros2 => "ROS 2 node with package.xml, ament_cmake and a launch file",
+            Preset::GrpcService => "gRPC server and client with protobuf code generation",
+            Preset::CliApp => "Command line application with CLI11 and a version header",
+            Preset::GameSdl => "SDL2 game with a main loop and an assets folder",
+            Preset::HttpService => "HTTP service with Crow, a health check and a Dockerfile",
+            Preset::Hpc => "OpenMP and MPI application with a parallel kernel and an mpirun test",
+            Preset::Sycl => "SYCL application for icpx or AdaptiveCpp with a sample kernel",
+            Preset::Opencv => "OpenCV application loading, processing and showing a sample image",
+            Preset::AudioPlugin => {
+                "JUCE audio plugin (VST3, AU, standalone) with processor and editor"
+            }
+        }
+    }
+
+    fn kind(self) -> Kind {
+        match self {
+            Preset::AppleFramework | Preset::Android => Kind::Shared,
+            Preset::AudioPlugin => Kind::Static,
+            Preset::Ros2
+            | Preset::GrpcService
+            | Preset::CliApp
+            | Preset::GameSdl
+            | Preset::HttpService
+            | Preset::Hpc
+            | Preset::Sycl
+            | Preset::Opencv => Kind::Executable,
+        }
+    }
+
+    // Kinds the preset's CMake setup works with
+    // Long running servers
+    fn is_service(self) -> bool {
+        matches!(self, Preset::GrpcService | Preset::HttpService)
+    }
+
+    fn supports_kind(self, kind: Kind) -> bool {
+        match self {
+            Preset::AppleFramework => matches!(kind, Kind::Static | Kind::Shared),
+            // System.loadLibrary() needs a shared library
+            Preset::Android => kind == Kind::Shared,
+            // A node
+            Preset::Ros2 => kind == Kind::Executable,
+            // The server, the client is an extra executable
+            Preset::GrpcService => kind == Kind::Executable,
+            Preset::CliApp => kind == Kind::Executable,
+            Preset::GameSdl => kind == Kind::Executable,
+            Preset::HttpService => kind == Kind::Executable,
+            // Launched by mpirun
+            Preset::Hpc => kind == Kind::Executable,
+            Preset::Sycl => kind == Kind::Executable,
+            Preset::Opencv => kind == Kind::Executable,
+            // juce_add_plugin builds the shared code as a static library wrapped per format
+            Preset::AudioPlugin => kind == Kind::Static,
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "apple-framework" => Ok(Preset::AppleFramework),
+            "android" => Ok(Preset::Android),
+            "ros2" => Ok(Preset::Ros2),
+            "grpc-service" => Ok(Preset::GrpcService),
+            "cli-app" => Ok(Preset::CliApp),
+            "game-sdl" => Ok(Preset::GameSdl),
+            "http-service" => Ok(Preset::HttpService),
+            "hpc" => Ok(Preset::Hpc),
+            "sycl" => Ok(Preset::Sycl),
+            "opencv" => Ok(Preset::Opencv),
+            "audio-plugin" => Ok(Preset::AudioPlugin),
+            _ => Err(format!(
+                "unknown preset '{}', expected one of: {}",
+                s,
+                Preset::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Presets rank above config files and environment variables
+pub fn defaults(preset: Option<Preset>) -> Defaults {
+    Defaults {
+        kind: preset.map(Preset::kind),
+        // Option parsing comes with unit tests
+        test_framework: match preset {
+            Some(Preset::CliApp) => Some(TestFramework::Catch2),
+            _ => None,
+        },
+        ..Defaults::default()
+    }
+}
+
+// Package linked to the application, see dependency::apply
+pub fn dependency(opt: &Opt) -> Option<FeatureDependency> {
+    match opt.preset? {
+        // The oldest 4.x on ConanCenter, find_package accepts newer 4.x ones
+        Preset::Opencv => Some(FeatureDependency {
+            spec: "opencv/4.5.5",
+            vcpkg_port: None,
+            cmake_package: Some("OpenCV"),
+            nix_package: None,
+            conda_package: Some("libopencv"),
+            link_target: "opencv_core opencv_imgproc opencv_imgcodecs opencv_highgui",
+            consumer: "@CMAKE_TARGET_NAME@",
+        }),
+        _ => None,
+    }
+}
+
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    if opt.android_gradle && opt.preset != Some(Preset::Android) {
+        return Err(invalid_input(String::from(
+            "--android-gradle needs --preset android",
+        )));
+    }
+
+    if opt.graceful_shutdown && !opt.preset.is_some_and(Preset::is_service) {
+        return Err(invalid_input(String::from(
+            "--graceful-shutdown needs --preset grpc-service or http-service",
+        )));
+    }
+
+    if (opt.plugin_manufacturer_code.is_some() || opt.plugin_code.is_some())
+        && opt.preset != Some(Preset::AudioPlugin)
+    {
+        return Err(invalid_input(String::from(
+            "--plugin-manufacturer-code and --plugin-code need --preset audio-plugin",
+        )));
+    }
+
+    // AU needs an upper-case letter in both codes
+    if let Some(code) = &opt.plugin_manufacturer_code {
+        validate_plugin_code("--plugin-manufacturer-code", code, false)?;
+    }
+    if let Some(code) = &opt.plugin_code {
+        validate_plugin_code("--plugin-code", code, true)?;
+    }
+
+    let preset = match opt.preset {
+        Some(preset) => preset,
+        None => return Ok(()),
+    };
+
+    if !preset.supports_kind(opt.kind) {
+        return Err(invalid_input(format!(
+            "preset {} does not support kind {}",
+            preset.name(),
+            opt.kind.name()
+        )));
+    }
+
+    if opt.language == Language::C {
+        return Err(invalid_input(format!(
+            "preset {} needs C++, use --language cpp or cpp-and-c",
+            preset.name()
+        )));
+    }
+
+    // The SYCL compilers come with their own CMake presets
+    if preset == Preset::Sycl && opt.compiler.is_some() {
+        return Err(invalid_input(String::from(
+            "preset sycl picks icpx or AdaptiveCpp via its CMake presets, drop --compiler",
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_plugin_code(option: &str, code: &str, exactly_one_upper_case: bool) -> io::Result<()> {
+    let upper_case_count = code.chars().filter(char::is_ascii_uppercase).count();
+    let (upper_case_ok, upper_case_rule) = match exactly_one_upper_case {
+        true => (upper_case_count == 1, "exactly one"),
+        false => (upper_case_count >= 1, "at least one"),
+    };
+
+    match code.len() == 4 && code.chars().all(|c| c.is_ascii_alphanumeric()) && upper_case_ok {
+        true => Ok(()),
+        false => Err(invalid_input(format!(
+            "{} '{}' must be 4 letters or digits with {} upper-case letter",
+            option, code, upper_case_rule
+        ))),
+    }
+}
+
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let gen = match gen.opt().graceful_shutdown {
+        true => gen.add_file(
+            PathBuf::from("@SOURCE_DIR@/shutdown.@HEADER_FILE_EXT@"),
+            String::from(SHUTDOWN_H),
+        ),
+        false => gen,
+    };
+
+    match gen.opt().preset {
+        Some(Preset::AppleFramework) => apply_apple_framework(gen),
+        Some(Preset::Android) => apply_android(gen),
+        Some(Preset::Ros2) => apply_ros2(gen),
+        Some(Preset::GrpcService) => apply_grpc_service(gen),
+        Some(Preset::CliApp) => apply_cli_app(gen),
+        Some(Preset::GameSdl) => apply_game_sdl(gen),
+        Some(Preset::HttpService) => apply_http_service(gen),
+        Some(Preset::Hpc) => apply_hpc(gen),
+        Some(Preset::Sycl) => apply_sycl(gen),
+        Some(Preset::Opencv) => apply_opencv(gen),
+        Some(Preset::AudioPlugin) => apply_audio_plugin(gen),
+        None => gen,
+    }
+}
+
+// e.g. com.my-company.my-target
+pub fn build_bundle_identifier(opt: &Opt) -> String {
+    format!(
+        "com.{}.{}",
+        opt.name_space.as_ref().unwrap_or(&opt.target_name),
+        opt.target_name
+    )
+}
+
+fn apply_apple_framework(gen: CppProjGen) -> CppProjGen {
+    let bundle_identifier = build_bundle_identifier(gen.opt());
+
+    gen.add_cmake_var("@BUNDLE_IDENTIFIER@", &bundle_identifier)
+        .add_file(
+            PathBuf::from("resources/Info.plist.in"),
+            String::from(APPLE_FRAMEWORK_INFO_PLIST),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/@CMAKE_TARGET_NAME@_apple.mm"),
+            String::from(APPLE_FRAMEWORK_MM),
+        )
+        .add_cmake_snippet(String::from(APPLE_FRAMEWORK_CMAKE))
+}
+
+fn apply_android(gen: CppProjGen) -> CppProjGen {
+    // e.g. com.mycompany.mytarget, underscores would need escaping in JNI names
+    let package_names: Vec<String> = std::iter::once(String::from("com"))
+        .chain(
+            language::build_identifiers(gen.opt())
+                .iter()
+                .map(|identifier| identifier.replace('_', "").to_lowercase()),
+        )
+        .collect();
+    let java_package = package_names.join(".");
+    let jni_prefix = format!("Java_{}_NativeLib", package_names.join("_"));
+    let with_gradle = gen.opt().android_gradle;
+
+    let gen = gen
+        .add_cmake_var("@JAVA_PACKAGE@", &java_package)
+        .add_cmake_var("@JAVA_PACKAGE_DIR@", &package_names.join("/"))
+        .add_cmake_var("@JNI_PREFIX@", &jni_prefix)
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/@CMAKE_TARGET_NAME@_jni.@SOURCE_FILE_EXT@"),
+            String::from(ANDROID_JNI),
+        )
+        .add_cmake_snippet(String::from(ANDROID_CMAKE));
+
+    if !with_gradle {
+        return gen;
+    }
+
+    gen.add_file(
+        PathBuf::from("android/build.gradle"),
+        String::from(ANDROID_BUILD_GRADLE),
+    )
+    .add_file(
+        PathBuf::from("android/src/main/AndroidManifest.xml"),
+        String::from(ANDROID_MANIFEST),
+    )
+    .add_file(
+        PathBuf::from("android/src/main/java/@JAVA_PACKAGE_DIR@/NativeLib.java"),
+        String::from(ANDROID_NATIVE_LIB),
+    )
+}
+
+fn apply_ros2(gen: CppProjGen) -> CppProjGen {
+    // Package names are lower case with underscores, e.g. my_company_my_target
+    let package_name = language::build_identifiers(gen.opt())
+        .join("_")
+        .to_lowercase();
+    let maintainer = gen
+        .opt()
+        .author
+        .clone()
+        .unwrap_or_else(|| String::from("TODO"));
+    let license = gen
+        .opt()
+        .license
+        .clone()
+        .unwrap_or_else(|| String::from("TODO"));
+
+    gen.add_cmake_var("@ROS_PACKAGE_NAME@", &package_name)
+        .add_cmake_var("@ROS_MAINTAINER@", &maintainer)
+        .add_cmake_var("@ROS_LICENSE@", &license)
+        .set_cmake_lists_template(String::from(ROS2_CMLT))
+        .add_file(PathBuf::from("package.xml"), String::from(ROS2_PACKAGE_XML))
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+            String::from(ROS2_NODE),
+        )
+        .add_file(
+            PathBuf::from("launch/@ROS_PACKAGE_NAME@.launch.py"),
+            String::from(ROS2_LAUNCH),
+        )
+}
+
+// gRPC and protobuf are found via find_package(CONFIG), e.g. from vcpkg, conan or the system
+fn apply_grpc_service(gen: CppProjGen) -> CppProjGen {
+    // Versioned API package, e.g. my_company.my_target.v1
+    let identifiers = language::build_identifiers(gen.opt());
+    let proto_package = format!("{}.v1", identifiers.join("."));
+    let proto_cpp_namespace = format!("{}::v1", identifiers.join("::"));
+    let server = match gen.opt().graceful_shutdown {
+        true => GRPC_SERVER_GRACEFUL,
+        false => GRPC_SERVER,
+    };
+
+    gen.add_cmake_var("@PROTO_PACKAGE@", &proto_package)
+        .add_cmake_var("@PROTO_CPP_NAMESPACE@", &proto_cpp_namespace)
+        .add_file(
+            PathBuf::from("proto/@C_PREFIX@.proto"),
+            String::from(GRPC_PROTO),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+            String::from(server),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/client.@SOURCE_FILE_EXT@"),
+            String::from(GRPC_CLIENT),
+        )
+        .add_cmake_snippet(String::from(GRPC_CMAKE))
+}
+
+// CLI11 is fetched via FetchContent, the version header is configured from project()
+fn apply_cli_app(gen: CppProjGen) -> CppProjGen {
+    let test_options = match gen.opt().test_framework {
+        Some(TestFramework::GTest) => Some(CLI_APP_TEST_GTEST),
+        Some(TestFramework::Catch2) => Some(CLI_APP_TEST_CATCH2),
+        Some(TestFramework::Doctest) => Some(CLI_APP_TEST_DOCTEST),
+        _ => None,
+    };
+
+    let gen = gen
+        .add_file(
+            PathBuf::from("cmake/version.h.in"),
+            String::from(CLI_APP_VERSION_H),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/options.@HEADER_FILE_EXT@"),
+            String::from(CLI_APP_OPTIONS_H),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/options.@SOURCE_FILE_EXT@"),
+            String::from(CLI_APP_OPTIONS_CPP),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+            String::from(CLI_APP_MAIN),
+        )
+        .add_cmake_snippet(String::from(CLI_APP_CMAKE));
+
+    match test_options {
+        Some(test_options) => gen
+            .add_file(
+                PathBuf::from("@TEST_DIR@/@TEST_FILE_PREFIX@options@TEST_FILE_SUFFIX@"),
+                String::from(test_options),
+            )
+            .add_cmake_snippet_to(
+                PathBuf::from("@TEST_DIR@/CMakeLists.txt"),
+                String::from(CLI_APP_TEST_CMAKE),
+            ),
+        None => gen,
+    }
+}
+
+// SDL2 comes from find_package() if installed, otherwise via FetchContent
+fn apply_game_sdl(gen: CppProjGen) -> CppProjGen {
+    gen.add_toplevel_dir(PathBuf::from("assets"))
+        .add_file(
+            PathBuf::from("assets/README.md"),
+            String::from(GAME_SDL_ASSETS_README),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+            String::from(GAME_SDL_MAIN),
+        )
+        .add_cmake_snippet(String::from(GAME_SDL_CMAKE))
+}
+
+// Crow is found via find_package(CONFIG), the Dockerfile builds it from source
+fn apply_http_service(gen: CppProjGen) -> CppProjGen {
+    let main = match gen.opt().graceful_shutdown {
+        true => HTTP_SERVICE_MAIN_GRACEFUL,
+        false => HTTP_SERVICE_MAIN,
+    };
+
+    gen.add_file(
+        PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+        String::from(main),
+    )
+    .add_file(
+        PathBuf::from("@SOURCE_DIR@/routes.@HEADER_FILE_EXT@"),
+        String::from(HTTP_SERVICE_ROUTES_H),
+    )
+    .add_file(
+        PathBuf::from("@SOURCE_DIR@/routes.@SOURCE_FILE_EXT@"),
+        String::from(HTTP_SERVICE_ROUTES_CPP),
+    )
+    .add_file(
+        PathBuf::from("@TEST_DIR@/integration/@TEST_FILE_PREFIX@routes@TEST_FILE_SUFFIX@"),
+        String::from(HTTP_SERVICE_TEST),
+    )
+    .add_file(
+        PathBuf::from("Dockerfile"),
+        String::from(HTTP_SERVICE_DOCKERFILE),
+    )
+    .add_file(
+        PathBuf::from(".dockerignore"),
+        String::from(HTTP_SERVICE_DOCKERIGNORE),
+    )
+    .add_cmake_snippet(String::from(HTTP_SERVICE_CMAKE))
+}
+
+// OpenMP and MPI come with the compiler and e.g. Open MPI or MPICH installed on the system
+fn apply_hpc(gen: CppProjGen) -> CppProjGen {
+    gen.add_file(
+        PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+        String::from(HPC_MAIN),
+    )
+    .add_file(
+        PathBuf::from("@SOURCE_DIR@/kernel.@HEADER_FILE_EXT@"),
+        String::from(HPC_KERNEL_H),
+    )
+    .add_file(
+        PathBuf::from("@SOURCE_DIR@/kernel.@SOURCE_FILE_EXT@"),
+        String::from(HPC_KERNEL_CPP),
+    )
+    .add_cmake_snippet(String::from(HPC_CMAKE))
+}
+
+// The compiler is picked by the icpx-* and adaptivecpp-* CMake presets
+fn apply_sycl(gen: CppProjGen) -> CppProjGen {
+    let presets =
+        compile_commands::render_presets(SYCL_CMAKE_PRESETS, gen.opt().no_compile_commands);
+
+    gen.add_file(PathBuf::from("CMakePresets.json"), presets)
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+            String::from(SYCL_MAIN),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/device.@HEADER_FILE_EXT@"),
+            String::from(SYCL_DEVICE_H),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/device.@SOURCE_FILE_EXT@"),
+            String::from(SYCL_DEVICE_CPP),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/vector_add.@HEADER_FILE_EXT@"),
+            String::from(SYCL_VECTOR_ADD_H),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/vector_add.@SOURCE_FILE_EXT@"),
+            String::from(SYCL_VECTOR_ADD_CPP),
+        )
+        .add_cmake_snippet(String::from(SYCL_CMAKE))
+}
+
+// OpenCV comes from the --package-manager or the system, see dependency()
+fn apply_opencv(gen: CppProjGen) -> CppProjGen {
+    gen.add_toplevel_dir(PathBuf::from("assets"))
+        .add_file(
+            PathBuf::from("assets/sample.ppm"),
+            build_sample_image(32, 24),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/main.@SOURCE_FILE_EXT@"),
+            String::from(OPENCV_MAIN),
+        )
+        .add_cmake_snippet(String::from(OPENCV_CMAKE))
+}
+
+// Plain PPM, text and readable by imread, a gradient with a white square in the middle
+fn build_sample_image(width: usize, height: usize) -> String {
+    let rows: Vec<String> = (0..height)
+        .map(|y| {
+            let pixels: Vec<String> = (0..width)
+                .map(|x| {
+                    let inside = (width / 4..width * 3 / 4).contains(&x)
+                        && (height / 4..height * 3 / 4).contains(&y);
+                    match inside {
+                        true => String::from("255 255 255"),
+                        false => format!("{} {} 64", x * 255 / width, y * 255 / height),
+                    }
+                })
+                .collect();
+            pixels.join(" ")
+        })
+        .collect();
+
+    format!("P3\n{} {}\n255\n{}\n", width, height, rows.join("\n"))
+}
+
+// JUCE is fetched via FetchContent, the plugin target replaces the library of the kind
+fn apply_audio_plugin(gen: CppProjGen) -> CppProjGen {
+    let company_name = gen
+        .opt()
+        .name_space
+        .clone()
+        .unwrap_or_else(|| gen.opt().target_name.clone());
+    let manufacturer_code = gen
+        .opt()
+        .plugin_manufacturer_code
+        .clone()
+        .unwrap_or_else(|| build_plugin_code(&company_name));
+    let plugin_code = gen
+        .opt()
+        .plugin_code
+        .clone()
+        .unwrap_or_else(|| build_plugin_code(&gen.opt().target_name));
+
+    gen.add_cmake_var("@PLUGIN_COMPANY_NAME@", &company_name)
+        .add_cmake_var("@PLUGIN_MANUFACTURER_CODE@", &manufacturer_code)
+        .add_cmake_var("@PLUGIN_CODE@", &plugin_code)
+        .set_cmake_lists_template(String::from(AUDIO_PLUGIN_CMLT))
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/plugin_processor.@HEADER_FILE_EXT@"),
+            String::from(AUDIO_PLUGIN_PROCESSOR_H),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/plugin_processor.@SOURCE_FILE_EXT@"),
+            String::from(AUDIO_PLUGIN_PROCESSOR_CPP),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/plugin_editor.@HEADER_FILE_EXT@"),
+            String::from(AUDIO_PLUGIN_EDITOR_H),
+        )
+        .add_file(
+            PathBuf::from("@SOURCE_DIR@/plugin_editor.@SOURCE_FILE_EXT@"),
+            String::from(AUDIO_PLUGIN_EDITOR_CPP),
+        )
+}
+
+// e.g. Myta of my-target, an upper-case letter followed by lower-case ones as GarageBand wants
+fn build_plugin_code(name: &str) -> String {
+    let letters: Vec<char> = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .chain(std::iter::repeat('x'))
+        .take(4)
+        .collect();
+
+    letters
+        .iter()
+        .enumerate()
+        .map(|(index, letter)| match index {
+            0 if letter.is_ascii_digit() => 'P',
+            0 => letter.to_ascii_uppercase(),
+            _ => letter.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Compiler;
+    use crate::cpp_proj_gen::PlanEntry;
+    use crate::test_util::contents;
+    use std::path::Path;
+
+    fn create_test_gen(preset: Preset) -> CppProjGen {
+        create_test_gen_with(Opt {
+            preset: Some(preset),
+            kind: preset.kind(),
+            ..Opt::default()
+        })
+    }
+
+    fn create_test_gen_with(opt: Opt) -> CppProjGen {
+        let opt = Opt {
+            name_space: Some(String::from("nmspc")),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..opt
+        };
+
+        CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .add_test_dir(PathBuf::from("test"))
+    }
+
+    #[test]
+    fn test_from_str() {
+        for variant in Preset::VARIANTS {
+            assert_eq!(variant.parse::<Preset>().unwrap().name(), *variant);
+        }
+        assert!("qt-app".parse::<Preset>().is_err());
+        assert!(Preset::VARIANTS.contains(&Preset::GameSdl.name()));
+        assert!(Preset::VARIANTS.contains(&Preset::HttpService.name()));
+    }
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            preset: Some(Preset::AppleFramework),
+            kind: Kind::Executable,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+
+        let opt = Opt {
+            preset: Some(Preset::AppleFramework),
+            kind: Kind::Shared,
+            language: Language::C,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+    }
+
+    #[test]
+    fn test_apple_framework() {
+        let paths = create_test_gen(Preset::AppleFramework).build_paths();
+
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/resources/Info.plist.in"
+        )));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/source/my-target_apple.mm"
+        )));
+    }
+
+    #[test]
+    fn test_android() {
+        let gen = create_test_gen_with(Opt {
+            preset: Some(Preset::Android),
+            kind: Kind::Shared,
+            android_gradle: true,
+            ..Opt::default()
+        });
+        let paths = gen.build_paths();
+
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/source/my-target_jni.cpp"
+        )));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/android/src/main/java/com/nmspc/mytarget/NativeLib.java"
+        )));
+    }
+
+    #[test]
+    fn test_grpc_service() {
+        let paths = create_test_gen(Preset::GrpcService).build_paths();
+
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/proto/nmspc_my_target.proto"
+        )));
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/source/client.cpp")));
+    }
+
+    #[test]
+    fn test_ros2() {
+        let paths = create_test_gen(Preset::Ros2).build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/package.xml")));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/launch/nmspc_my_target.launch.py"
+        )));
+        // The node replaces the generic main
+        assert_eq!(
+            paths
+                .iter()
+                .filter(|path| path.ends_with("source/main.cpp"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_cli_app() {
+        let gen = create_test_gen_with(Opt {
+            preset: Some(Preset::CliApp),
+            kind: Kind::Executable,
+            test_framework: Some(TestFramework::Catch2),
+            ..Opt::default()
+        });
+        let paths = gen.build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/cmake/version.h.in")));
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/source/options.cpp")));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/test/test_options.cpp"
+        )));
+        assert_eq!(
+            defaults(Some(Preset::CliApp)).test_framework,
+            Some(TestFramework::Catch2)
+        );
+    }
+
+    #[test]
+    fn test_game_sdl() {
+        let paths = create_test_gen(Preset::GameSdl).build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/assets")));
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/assets/README.md")));
+    }
+
+    #[test]
+    fn test_http_service() {
+        let paths = create_test_gen(Preset::HttpService).build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/Dockerfile")));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/test/integration/test_routes.cpp"
+        )));
+    }
+
+    #[test]
+    fn test_hpc() {
+        let plan = create_test_gen(Preset::Hpc).plan().unwrap();
+
+        assert!(contents(&plan, "source/kernel.cpp")
+            .unwrap()
+            .contains("#pragma omp parallel for reduction(+ : sum)"));
+        assert!(contents(&plan, "source/main.cpp")
+            .unwrap()
+            .contains("MPI_Init(&argc, &argv);"));
+
+        let cmake_lists = contents(&plan, "CMakeLists.txt").unwrap();
+        assert!(cmake_lists.contains("        OpenMP::OpenMP_CXX\n        MPI::MPI_CXX\n"));
+        assert!(cmake_lists.contains("add_test(NAME my-target-mpirun"));
+    }
+
+    #[test]
+    fn test_sycl() {
+        let opt = Opt {
+            preset: Some(Preset::Sycl),
+            kind: Kind::Executable,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+        assert!(validate(&Opt {
+            compiler: Some(Compiler::Clang),
+            ..opt.clone()
+        })
+        .is_err());
+
+        let plan = create_test_gen_with(opt).plan().unwrap();
+
+        let presets: serde_json::Value =
+            serde_json::from_str(&contents(&plan, "CMakePresets.json").unwrap()).unwrap();
+        assert_eq!(
+            presets["configurePresets"][0]["cacheVariables"]["CMAKE_CXX_COMPILER"],
+            "icpx"
+        );
+        assert_eq!(
+            presets["configurePresets"][2]["cacheVariables"]["NMSPC_MY_TARGET_SYCL_IMPLEMENTATION"],
+            "AdaptiveCpp"
+        );
+        assert!(contents(&plan, "source/device.cpp")
+            .unwrap()
+            .contains("sycl::device{sycl::gpu_selector_v}"));
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("target_link_options(my-target PRIVATE -fsycl)"));
+    }
+
+    #[test]
+    fn test_opencv() {
+        let paths = create_test_gen(Preset::Opencv).build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/assets/sample.ppm")));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/cmake/dependencies.cmake"
+        )));
+
+        let opt = Opt {
+            preset: Some(Preset::Opencv),
+            ..Opt::default()
+        };
+        assert_eq!(dependency(&opt).unwrap().spec, "opencv/4.5.5");
+        assert!(dependency(&Opt::default()).is_none());
+    }
+
+    #[test]
+    fn test_build_sample_image() {
+        let image = build_sample_image(4, 2);
+
+        assert_eq!(
+            image,
+            "P3\n4 2\n255\n0 0 64 255 255 255 255 255 255 191 0 64\n0 127 64 63 127 64 127 127 64 191 127 64\n"
+        );
+    }
+
+    #[test]
+    fn test_audio_plugin() {
+        let opt = Opt {
+            preset: Some(Preset::AudioPlugin),
+            kind: Kind::Static,
+            plugin_code: Some(String::from("Gain")),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+        for code in ["GAIN", "gain", "Gai", "Ga-n"] {
+            assert!(validate(&Opt {
+                plugin_code: Some(String::from(code)),
+                ..opt.clone()
+            })
+            .is_err());
+        }
+        assert!(validate(&Opt {
+            plugin_manufacturer_code: Some(String::from("ACME")),
+            ..opt.clone()
+        })
+        .is_ok());
+        assert!(validate(&Opt {
+            preset: None,
+            ..opt.clone()
+        })
+        .is_err());
+
+        let plan = create_test_gen_with(opt).plan().unwrap();
+        let cmake_lists = plan
+            .iter()
+            .find_map(|entry| match entry {
+                PlanEntry::File { path, contents, .. }
+                    if path == Path::new("test_out_dir/my-target/CMakeLists.txt") =>
+                {
+                    Some(contents.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(cmake_lists.contains("    COMPANY_NAME \"nmspc\"\n"));
+        assert!(cmake_lists.contains("    PLUGIN_MANUFACTURER_CODE Nmsp\n    PLUGIN_CODE Gain\n"));
+        assert!(cmake_lists.contains("    source/plugin_editor.cpp\n"));
+        assert!(plan
+            .iter()
+            .any(|entry| entry.path()
+                == Path::new("test_out_dir/my-target/source/plugin_processor.h")));
+    }
+
+    #[test]
+    fn test_build_plugin_code() {
+        assert_eq!(build_plugin_code("my-target"), "Myta");
+        assert_eq!(build_plugin_code("fx"), "Fxxx");
+        assert_eq!(build_plugin_code("3d-reverb"), "Pdre");
+    }
+
+    #[test]
+    fn test_graceful_shutdown() {
+        let opt = Opt {
+            graceful_shutdown: true,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+
+        let opt = Opt {
+            preset: Some(Preset::HttpService),
+            kind: Kind::Executable,
+            ..opt
+        };
+        assert!(validate(&opt).is_ok());
+
+        let plan = create_test_gen_with(opt).plan().unwrap();
+        let main = plan
+            .iter()
+            .find_map(|entry| match entry {
+                PlanEntry::File { path, contents, .. }
+                    if path == Path::new("test_out_dir/my-target/source/main.cpp") =>
+                {
+                    Some(contents.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(main.contains("#include \"shutdown.h\""));
+        assert!(main.contains("    app.signal_clear();\n"));
+        assert!(plan
+            .iter()
+            .any(|entry| entry.path() == Path::new("test_out_dir/my-target/source/shutdown.h")));
+    }
+}
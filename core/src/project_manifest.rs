@@ -0,0 +1,183 @@
+/*
+For toml /sa https://docs.rs/toml/0.8/toml/
+*/
+
+use crate::cpp_proj_gen::{Kind, Opt};
+use crate::error::{self, Error};
+use crate::language::Language;
+use crate::naming::TestPattern;
+use crate::source_listing::SourceListing;
+use crate::test_framework::TestFramework;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// Relative to the project directory
+pub const MANIFEST_PATH: &str = ".cpp-proj-gen/manifest.toml";
+
+// Written into every generated project, commands on existing projects read it
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProjectManifest {
+    pub generator_version: String,
+    pub target_name: String,
+    pub name_space: Option<String>,
+    pub kind: String,
+    pub language: String,
+    // SPDX identifier and author of the file headers
+    pub license: Option<String>,
+    pub author: Option<String>,
+    pub test_framework: Option<String>,
+    // Missing in manifests of older versions, explicit then
+    pub source_listing: Option<String>,
+    // Set by add-dependency, e.g. vcpkg
+    pub package_manager: Option<String>,
+    // Naming policy, missing in manifests of older versions, .h, .cpp and test_* then
+    pub header_ext: Option<String>,
+    pub source_ext: Option<String>,
+    pub test_pattern: Option<String>,
+    // Set by adopt if the project's directories differ, include, source and test otherwise
+    pub include_dir: Option<String>,
+    pub source_dir: Option<String>,
+    pub test_dir: Option<String>,
+    // Generated files relative to the project directory, with / separators
+    pub files: Vec<String>,
+}
+
+impl ProjectManifest {
+    pub fn new(opt: &Opt, files: Vec<String>) -> Self {
+        ProjectManifest {
+            generator_version: String::from(env!("CARGO_PKG_VERSION")),
+            target_name: opt.target_name.clone(),
+            name_space: opt.name_space.clone(),
+            kind: String::from(opt.kind.name()),
+            language: String::from(opt.language.name()),
+            license: opt.license.clone(),
+            author: opt.author.clone(),
+            test_framework: opt
+                .test_framework
+                .map(|framework| String::from(framework.name())),
+            source_listing: Some(String::from(opt.source_listing.name())),
+            package_manager: opt
+                .package_manager
+                .map(|package_manager| String::from(package_manager.name())),
+            header_ext: Some(opt.header_ext.clone()),
+            source_ext: Some(opt.source_ext.clone()),
+            test_pattern: Some(opt.test_pattern.to_string()),
+            include_dir: None,
+            source_dir: None,
+            test_dir: None,
+            files,
+        }
+    }
+
+    pub fn load(project_dir: &Path) -> error::Result<Self> {
+        let path = project_dir.join(MANIFEST_PATH);
+        let contents = fs::read_to_string(&path).map_err(Error::fs("read", &path))?;
+
+        toml::from_str(&contents).map_err(|err| {
+            Error::from(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), err.message()),
+            ))
+        })
+    }
+
+    pub fn store(&self, project_dir: &Path) -> error::Result<()> {
+        let path = project_dir.join(MANIFEST_PATH);
+
+        fs::write(&path, self.to_toml()?).map_err(Error::fs("write", &path))
+    }
+
+    pub fn to_toml(&self) -> io::Result<String> {
+        toml::to_string(self).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn include_dir(&self) -> &str {
+        self.include_dir.as_deref().unwrap_or("include")
+    }
+
+    pub fn test_dir(&self) -> &str {
+        self.test_dir.as_deref().unwrap_or("test")
+    }
+
+    // Options the project was generated with, as far as recorded
+    pub fn to_opt(&self) -> io::Result<Opt> {
+        let invalid_data = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+        let test_framework = match &self.test_framework {
+            Some(framework) => Some(framework.parse::<TestFramework>().map_err(invalid_data)?),
+            None => None,
+        };
+        let source_listing = match &self.source_listing {
+            Some(listing) => listing.parse::<SourceListing>().map_err(invalid_data)?,
+            None => SourceListing::Explicit,
+        };
+        let default_opt = Opt::default();
+        let test_pattern = match &self.test_pattern {
+            Some(pattern) => pattern.parse::<TestPattern>().map_err(invalid_data)?,
+            None => default_opt.test_pattern.clone(),
+        };
+
+        Ok(Opt {
+            target_name: self.target_name.clone(),
+            name_space: self.name_space.clone(),
+            kind: self.kind.parse::<Kind>().map_err(invalid_data)?,
+            language: self.language.parse::<Language>().map_err(invalid_data)?,
+            license: self.license.clone(),
+            author: self.author.clone(),
+            test_framework,
+            source_listing,
+            header_ext: self.header_ext.clone().unwrap_or(default_opt.header_ext),
+            source_ext: self.source_ext.clone().unwrap_or(default_opt.source_ext),
+            test_pattern,
+            ..Opt::default()
+        })
+    }
+}
+
+// e.g. include/nmspc/tgtnm/tgtnm.h on every platform
+pub fn to_manifest_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+pub fn from_manifest_path(path: &str) -> PathBuf {
+    path.split('/').collect()
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let opt = Opt {
+            name_space: Some(String::from("nmspc")),
+            target_name: String::from("tgtnm"),
+            kind: Kind::Shared,
+            test_framework: Some(TestFramework::Catch2),
+            ..Opt::default()
+        };
+        let manifest = ProjectManifest::new(&opt, vec![String::from("CMakeLists.txt")]);
+        let parsed: ProjectManifest = toml::from_str(&manifest.to_toml().unwrap()).unwrap();
+
+        assert_eq!(parsed, manifest);
+
+        let restored = parsed.to_opt().unwrap();
+        assert_eq!(restored.kind, Kind::Shared);
+        assert_eq!(restored.test_framework, Some(TestFramework::Catch2));
+    }
+
+    #[test]
+    fn test_manifest_path() {
+        let path = Path::new("include").join("nmspc").join("tgtnm.h");
+
+        assert_eq!(to_manifest_path(&path), "include/nmspc/tgtnm.h");
+        assert_eq!(from_manifest_path("include/nmspc/tgtnm.h"), path);
+    }
+}
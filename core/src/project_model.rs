@@ -0,0 +1,233 @@
+/*
+For CMake targets /sa https://cmake.org/cmake/help/latest/manual/cmake-buildsystem.7.html#binary-targets
+*/
+
+use crate::cpp_proj_gen::{self, Kind, Opt, CMLT_FILE_NAME};
+use crate::dependency::{self, FeatureDependency};
+use crate::template_vars::VarValue;
+use crate::warning::Warning;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+pub type PathBufVec = Vec<PathBuf>;
+// Ordered so that replacing vars and listing files is reproducible
+pub type CmakeVarsMap = BTreeMap<String, VarValue>;
+// Directory vars like @TEST_DIR@ as paths, which need not be UTF-8
+pub type PathVarsMap = BTreeMap<String, PathBuf>;
+// Relative file path and template
+pub type TemplateFileVec = Vec<(PathBuf, String)>;
+// Relative CMake file path and snippet
+pub type CmakeSnippetVec = Vec<(PathBuf, String)>;
+// Relative file path and contents copied as they are
+pub type BinaryFileVec = Vec<(PathBuf, Vec<u8>)>;
+// Relative file path, path of the large template and whether it is binary
+pub type StreamedFileVec = Vec<(PathBuf, PathBuf, bool)>;
+// Relative link path and its target, relative to the link's directory
+pub type LinkVec = Vec<(PathBuf, PathBuf)>;
+// Relative file path and whether it gets the executable bit
+pub type ExecutablesMap = BTreeMap<PathBuf, bool>;
+// Vars set from outside, e.g. @VENDOR@ by -D VENDOR=acme
+pub type DefinedVarsSet = BTreeSet<String>;
+
+// CMake target of the project, e.g. tgtnm and tgtnm-app of lib-and-app
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub name: String,
+    // Executable for the app of lib-and-app
+    pub kind: Kind,
+}
+
+// What a project consists of, paths relative to the project directory and unrendered
+// Built from the options by CppProjGen::new() and its features, rendered and written by CppProjGen
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectModel {
+    pub targets: Vec<Target>,
+    pub directories: PathBufVec,
+    pub cmake_lists_file: PathBuf,
+    // Replaces the built-in template of the kind
+    pub cmake_lists_template: Option<String>,
+    pub vars: CmakeVarsMap,
+    pub path_vars: PathVarsMap,
+    pub defined_vars: DefinedVarsSet,
+    pub cmake_snippets: CmakeSnippetVec,
+    pub files: TemplateFileVec,
+    pub binary_files: BinaryFileVec,
+    pub streamed_files: StreamedFileVec,
+    pub links: LinkVec,
+    pub executables: ExecutablesMap,
+    // Packages the generated code uses, e.g. spdlog of --logging spdlog
+    pub dependencies: Vec<FeatureDependency>,
+    pub warnings: Vec<Warning>,
+}
+
+impl ProjectModel {
+    // Targets, vars and dependencies of the options, no directories or files yet
+    pub fn new(opt: &Opt) -> Self {
+        let library_kind = match opt.kind {
+            Kind::LibAndApp => Kind::Static,
+            kind => kind,
+        };
+        let mut targets = vec![Target {
+            name: opt.target_name.clone(),
+            kind: library_kind,
+        }];
+        if opt.kind == Kind::LibAndApp {
+            targets.push(Target {
+                name: format!("{}-app", opt.target_name),
+                kind: Kind::Executable,
+            });
+        }
+
+        ProjectModel {
+            targets,
+            directories: Vec::new(),
+            cmake_lists_file: PathBuf::from(CMLT_FILE_NAME),
+            cmake_lists_template: None,
+            vars: cpp_proj_gen::build_cmake_vars(opt),
+            path_vars: PathVarsMap::new(),
+            defined_vars: DefinedVarsSet::new(),
+            cmake_snippets: Vec::new(),
+            files: Vec::new(),
+            binary_files: Vec::new(),
+            streamed_files: Vec::new(),
+            links: Vec::new(),
+            executables: ExecutablesMap::new(),
+            dependencies: dependency::build_feature_dependencies(opt),
+            warnings: Vec::new(),
+        }
+    }
+
+    // Adding a path twice replaces the former template in its place
+    pub fn add_file(&mut self, path: PathBuf, template: String) {
+        self.binary_files.retain(|(other, _)| *other != path);
+        self.streamed_files.retain(|(other, _, _)| *other != path);
+        self.links.retain(|(other, _)| *other != path);
+        match self.files.iter_mut().find(|(other, _)| *other == path) {
+            Some(file) => file.1 = template,
+            None => self.files.push((path, template)),
+        }
+    }
+
+    pub fn add_binary_file(&mut self, path: PathBuf, contents: Vec<u8>) {
+        self.files.retain(|(other, _)| *other != path);
+        self.streamed_files.retain(|(other, _, _)| *other != path);
+        self.links.retain(|(other, _)| *other != path);
+        match self
+            .binary_files
+            .iter_mut()
+            .find(|(other, _)| *other == path)
+        {
+            Some(file) => file.1 = contents,
+            None => self.binary_files.push((path, contents)),
+        }
+    }
+
+    pub fn add_streamed_file(&mut self, path: PathBuf, source: PathBuf, binary: bool) {
+        self.files.retain(|(other, _)| *other != path);
+        self.binary_files.retain(|(other, _)| *other != path);
+        self.links.retain(|(other, _)| *other != path);
+        match self
+            .streamed_files
+            .iter_mut()
+            .find(|(other, _, _)| *other == path)
+        {
+            Some(file) => *file = (path, source, binary),
+            None => self.streamed_files.push((path, source, binary)),
+        }
+    }
+
+    pub fn add_link(&mut self, path: PathBuf, target: PathBuf) {
+        self.files.retain(|(other, _)| *other != path);
+        self.binary_files.retain(|(other, _)| *other != path);
+        self.streamed_files.retain(|(other, _, _)| *other != path);
+        match self.links.iter_mut().find(|(other, _)| *other == path) {
+            Some(link) => link.1 = target,
+            None => self.links.push((path, target)),
+        }
+    }
+
+    // Text, binary and streamed files and links as added, before rendering their paths
+    pub fn file_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files
+            .iter()
+            .map(|(path, _)| path)
+            .chain(self.binary_files.iter().map(|(path, _)| path))
+            .chain(self.streamed_files.iter().map(|(path, _, _)| path))
+            .chain(self.links.iter().map(|(path, _)| path))
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{CppProjGen, PlanEntry};
+
+    #[test]
+    fn test_targets() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            kind: Kind::LibAndApp,
+            ..Opt::default()
+        };
+
+        assert_eq!(
+            ProjectModel::new(&opt).targets,
+            vec![
+                Target {
+                    name: String::from("tgtnm"),
+                    kind: Kind::Static,
+                },
+                Target {
+                    name: String::from("tgtnm-app"),
+                    kind: Kind::Executable,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_file_replaces() {
+        let mut model = ProjectModel::new(&Opt::default());
+        model.add_file(PathBuf::from("a.txt"), String::from("a"));
+        model.add_link(PathBuf::from("b"), PathBuf::from("a.txt"));
+        model.add_binary_file(PathBuf::from("a.txt"), vec![0]);
+
+        assert!(model.files.is_empty());
+        assert_eq!(model.binary_files, vec![(PathBuf::from("a.txt"), vec![0])]);
+        assert_eq!(
+            model.file_paths().collect::<Vec<_>>(),
+            vec![&PathBuf::from("a.txt"), &PathBuf::from("b")]
+        );
+    }
+
+    // Without the features of the options, only what is in the model
+    #[test]
+    fn test_plan_from_model() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("out")),
+            ..Opt::default()
+        };
+        let mut model = ProjectModel::new(&opt);
+        model.cmake_lists_template = Some(String::from("project(@CMAKE_TARGET_NAME@)"));
+        model.directories.push(PathBuf::from("docs"));
+        model.add_file(
+            PathBuf::from("docs/@CMAKE_TARGET_NAME@.md"),
+            String::from("# @CMAKE_TARGET_NAME@"),
+        );
+
+        let plan = CppProjGen::from_model(opt, model).plan().unwrap();
+        let file = |path: &str, contents: &str| PlanEntry::File {
+            path: PathBuf::from(path),
+            contents: String::from(contents),
+            executable: false,
+        };
+
+        assert!(plan.contains(&file("out/tgtnm/CMakeLists.txt", "project(tgtnm)\n")));
+        assert!(plan.contains(&file("out/tgtnm/docs/tgtnm.md", "# tgtnm\n")));
+        assert!(plan.contains(&PlanEntry::Dir(PathBuf::from("out/tgtnm/docs"))));
+    }
+}
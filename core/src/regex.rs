@@ -0,0 +1,362 @@
+/*
+For regular expression syntax /sa https://en.wikipedia.org/wiki/Regular_expression#POSIX_extended
+For backtracking matchers /sa https://swtch.com/~rsc/regexp/regexp1.html
+*/
+
+// Small backtracking regular expressions, enough for validating template variables
+// Supports literals, ., [a-z] and [^...] classes, \d \w \s and their negations, ( | ),
+// the quantifiers * + ? {n} {n,} {n,m} and the anchors ^ $
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regex {
+    alternatives: Alternatives,
+}
+
+type Alternatives = Vec<Sequence>;
+type Sequence = Vec<Repeat>;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Repeat {
+    node: Node,
+    min: usize,
+    max: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Group(Alternatives),
+    Start,
+    End,
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+        };
+        let alternatives = parser.parse_alternatives()?;
+
+        match parser.peek() {
+            None => Ok(Regex { alternatives }),
+            Some(_) => Err(format!("unmatched ')' in pattern '{}'", pattern)),
+        }
+    }
+
+    // The whole text has to match, as when validating a value
+    pub fn is_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+
+        match_alternatives(&self.alternatives, &chars, 0, &|pos| pos == chars.len())
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn parse_alternatives(&mut self) -> Result<Alternatives, String> {
+        let mut alternatives = vec![self.parse_sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.parse_sequence()?);
+        }
+
+        Ok(alternatives)
+    }
+
+    fn parse_sequence(&mut self) -> Result<Sequence, String> {
+        let mut sequence = Vec::new();
+
+        while let Some(c) = self.peek() {
+            let node = match c {
+                '|' | ')' => break,
+                '*' | '+' | '?' | '{' => {
+                    return Err(format!("nothing to repeat before '{}'", c));
+                }
+                _ => self.parse_node()?,
+            };
+            let (min, max) = self.parse_quantifier()?;
+            sequence.push(Repeat { node, min, max });
+        }
+
+        Ok(sequence)
+    }
+
+    fn parse_node(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some('(') => {
+                // Groups don't capture, so (?:...) is the same
+                if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                }
+                let alternatives = self.parse_alternatives()?;
+                match self.next() {
+                    Some(')') => Ok(Node::Group(alternatives)),
+                    _ => Err(String::from("missing ')'")),
+                }
+            }
+            Some('[') => self.parse_class(),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self.parse_escape(),
+            Some(c) => Ok(Node::Char(c)),
+            None => Err(String::from("unexpected end of pattern")),
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<Node, String> {
+        let c = self
+            .next()
+            .ok_or_else(|| String::from("pattern ends with '\\'"))?;
+
+        Ok(match (escape_ranges(c), c) {
+            (Some(ranges), _) => Node::Class {
+                ranges,
+                negated: c.is_ascii_uppercase(),
+            },
+            (None, 'n') => Node::Char('\n'),
+            (None, 't') => Node::Char('\t'),
+            (None, 'r') => Node::Char('\r'),
+            (None, c) => Node::Char(c),
+        })
+    }
+
+    // e.g. [a-z_], [^0-9] or []x], a '-' first or last is literal
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = match self.next() {
+                Some(']') if !first => break,
+                Some('\\') => {
+                    let c = self.next().ok_or_else(|| String::from("missing ']'"))?;
+                    match escape_ranges(c) {
+                        Some(_) if c.is_ascii_uppercase() => {
+                            return Err(format!("'\\{}' is not supported inside [...]", c));
+                        }
+                        Some(escaped) => {
+                            ranges.extend(escaped);
+                            first = false;
+                            continue;
+                        }
+                        None => c,
+                    }
+                }
+                Some(c) => c,
+                None => return Err(String::from("missing ']'")),
+            };
+            first = false;
+
+            let is_range = self.peek() == Some('-')
+                && !matches!(self.chars.get(self.pos + 1), Some(']') | None);
+            if !is_range {
+                ranges.push((c, c));
+                continue;
+            }
+
+            self.pos += 1;
+            let end = match self.next() {
+                Some('\\') => self.next().ok_or_else(|| String::from("missing ']'"))?,
+                Some(end) => end,
+                None => return Err(String::from("missing ']'")),
+            };
+            if end < c {
+                return Err(format!("invalid range '{}-{}'", c, end));
+            }
+            ranges.push((c, end));
+        }
+
+        Ok(Node::Class { ranges, negated })
+    }
+
+    // Defaults to exactly once
+    fn parse_quantifier(&mut self) -> Result<(usize, usize), String> {
+        let quantifier = match self.peek() {
+            Some('*') => (0, usize::MAX),
+            Some('+') => (1, usize::MAX),
+            Some('?') => (0, 1),
+            Some('{') => return self.parse_counts(),
+            _ => return Ok((1, 1)),
+        };
+        self.pos += 1;
+
+        Ok(quantifier)
+    }
+
+    // {n}, {n,} or {n,m}
+    fn parse_counts(&mut self) -> Result<(usize, usize), String> {
+        let start = self.pos;
+        let end = self.chars[start..]
+            .iter()
+            .position(|c| *c == '}')
+            .map(|offset| start + offset)
+            .ok_or_else(|| String::from("missing '}'"))?;
+        let counts: String = self.chars[start + 1..end].iter().collect();
+        self.pos = end + 1;
+
+        let parse = |count: &str| {
+            count
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid repetition '{{{}}}'", counts))
+        };
+        let (min, max) = match counts.split_once(',') {
+            Some((min, "")) => (parse(min)?, usize::MAX),
+            Some((min, max)) => (parse(min)?, parse(max)?),
+            None => (parse(&counts)?, parse(&counts)?),
+        };
+
+        match min <= max {
+            true => Ok((min, max)),
+            false => Err(format!("invalid repetition '{{{}}}'", counts)),
+        }
+    }
+}
+
+// \d, \w, \s and the negated \D, \W, \S
+fn escape_ranges(c: char) -> Option<Vec<(char, char)>> {
+    match c.to_ascii_lowercase() {
+        'd' => Some(vec![('0', '9')]),
+        'w' => Some(vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')]),
+        's' => Some(vec![
+            (' ', ' '),
+            ('\t', '\t'),
+            ('\n', '\n'),
+            ('\r', '\r'),
+            ('\u{b}', '\u{c}'),
+        ]),
+        _ => None,
+    }
+}
+
+// Each step hands the end position of its match to the continuation of the rest
+fn match_alternatives(
+    alternatives: &[Sequence],
+    text: &[char],
+    pos: usize,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    alternatives
+        .iter()
+        .any(|sequence| match_sequence(sequence, text, pos, rest))
+}
+
+fn match_sequence(
+    sequence: &[Repeat],
+    text: &[char],
+    pos: usize,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    match sequence.split_first() {
+        Some((repeat, tail)) => match_repeat(repeat, 0, text, pos, &|end| {
+            match_sequence(tail, text, end, rest)
+        }),
+        None => rest(pos),
+    }
+}
+
+// Greedy, empty matches only count up to the minimum so loops like (a*)* end
+fn match_repeat(
+    repeat: &Repeat,
+    count: usize,
+    text: &[char],
+    pos: usize,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    if count < repeat.max
+        && match_node(&repeat.node, text, pos, &|end| {
+            (end != pos || count < repeat.min) && match_repeat(repeat, count + 1, text, end, rest)
+        })
+    {
+        return true;
+    }
+
+    count >= repeat.min && rest(pos)
+}
+
+fn match_node(node: &Node, text: &[char], pos: usize, rest: &dyn Fn(usize) -> bool) -> bool {
+    match node {
+        Node::Char(c) => text.get(pos) == Some(c) && rest(pos + 1),
+        Node::Any => pos < text.len() && rest(pos + 1),
+        Node::Class { ranges, negated } => match text.get(pos) {
+            Some(c) => {
+                let in_class = ranges
+                    .iter()
+                    .any(|(first, last)| (first..=last).contains(&c));
+                in_class != *negated && rest(pos + 1)
+            }
+            None => false,
+        },
+        Node::Group(alternatives) => match_alternatives(alternatives, text, pos, rest),
+        Node::Start => pos == 0 && rest(pos),
+        Node::End => pos == text.len() && rest(pos),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn test_is_match() {
+        assert!(is_match("abc", "abc"));
+        assert!(!is_match("abc", "abcd"));
+        assert!(is_match("^[A-Z][a-z0-9_]*$", "Vendor_1"));
+        assert!(!is_match("[A-Z][a-z0-9_]*", "vendor"));
+        assert!(is_match("a.c", "a-c"));
+        assert!(is_match("(foo|bar)+", "foobarfoo"));
+        assert!(!is_match("(foo|bar)+", ""));
+        assert!(is_match("(?:ab)?c", "c"));
+        assert!(is_match("\\d{1,3}(\\.\\d{1,3}){3}", "192.168.0.1"));
+        assert!(!is_match("\\d{1,3}(\\.\\d{1,3}){3}", "192.168.0"));
+        assert!(is_match("[^ ]+", "no-spaces"));
+        assert!(!is_match("[^ ]+", "a space"));
+        assert!(is_match("[-a-c]*", "-abc-"));
+        assert!(is_match("\\w+@\\w+\\.com", "me@example.com"));
+        assert!(is_match("\\S\\s\\D", "a b"));
+        assert!(is_match("(a*)*b", "aaab"));
+        assert!(!is_match("(a*)*b", "aaaa"));
+        assert!(is_match("x{2,}", "xxxx"));
+        assert!(!is_match("x{2,}", "x"));
+        assert!(is_match("[\\d.]+", "1.2.3"));
+        assert!(is_match("a\\tb", "a\tb"));
+    }
+
+    #[test]
+    fn test_new() {
+        for pattern in &[
+            "(ab", "ab)", "*a", "[a-", "a{2,1}", "a{x}", "[z-a]", "a\\", "[\\D]",
+        ] {
+            assert!(Regex::new(pattern).is_err(), "{}", pattern);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+/*
+For the project manifest /sa project_manifest.rs
+*/
+
+use crate::collisions;
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::error::{self, Error};
+use crate::project_manifest::{self, ProjectManifest};
+use std::{fs, path::Path};
+
+// Vars whose values derive from the target name or the namespace
+const NAME_VARS: &[&str] = &[
+    "@CMAKE_TARGET_NAME@",
+    "@CMAKE_PROJECT_NAME@",
+    "@INCLUDE_DOMAIN_DIR@",
+    "@CPP_NAMESPACE@",
+    "@CPP_NAMESPACE_BEGIN@",
+    "@CPP_NAMESPACE_END@",
+    "@C_PREFIX@",
+    "@CMAKE_VAR_PREFIX@",
+    "@INCLUDE_GUARD@",
+    "@CMAKE_EXPORT_NAMESPACE@",
+    "@CMAKE_ALIAS_NAME@",
+];
+
+// Renames the target and optionally the namespace in all files of the project manifest,
+// returns the diff of all files instead with dry_run
+pub fn rename_project(
+    project_dir: &Path,
+    target_name: &str,
+    name_space: Option<&str>,
+    dry_run: bool,
+) -> error::Result<String> {
+    let mut manifest = ProjectManifest::load(project_dir)?;
+    let old_opt = manifest.to_opt()?;
+    let new_opt = Opt {
+        target_name: String::from(target_name),
+        name_space: name_space.map(String::from).or(old_opt.name_space.clone()),
+        ..old_opt.clone()
+    };
+    collisions::validate(&new_opt, &[])?;
+
+    let replacements = build_replacements(&old_opt, &new_opt);
+    let mut files = Vec::new();
+    let mut diff = String::new();
+
+    for file in &manifest.files {
+        let old_path = project_dir.join(project_manifest::from_manifest_path(file));
+        let new_file = replace_names(file, &replacements);
+        let new_path = project_dir.join(project_manifest::from_manifest_path(&new_file));
+
+        // Files the user deleted since generation are skipped
+        let contents = match fs::read_to_string(&old_path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+        let new_contents = replace_names(&contents, &replacements);
+
+        if dry_run {
+            diff.push_str(&build_diff(file, &new_file, &contents, &new_contents));
+        } else {
+            if let Some(parent) = new_path.parent() {
+                fs::create_dir_all(parent).map_err(Error::fs("create directory", parent))?;
+            }
+            fs::write(&new_path, new_contents).map_err(Error::fs("write", &new_path))?;
+            if new_path != old_path {
+                fs::remove_file(&old_path).map_err(Error::fs("remove", &old_path))?;
+                remove_empty_parents(project_dir, &old_path);
+            }
+        }
+
+        files.push(new_file);
+    }
+
+    if dry_run {
+        return Ok(diff);
+    }
+
+    manifest.target_name = new_opt.target_name;
+    manifest.name_space = new_opt.name_space;
+    manifest.files = files;
+    manifest.store(project_dir)?;
+
+    Ok(diff)
+}
+
+// Old and new values of the name vars, longest first so that e.g. the project name wins over the target name
+fn build_replacements(old_opt: &Opt, new_opt: &Opt) -> Vec<(String, String)> {
+    let old_gen = CppProjGen::new(old_opt.clone());
+    let new_gen = CppProjGen::new(new_opt.clone());
+
+    let mut replacements: Vec<(String, String)> = NAME_VARS
+        .iter()
+        .filter_map(|var| Some((old_gen.cmake_var(var)?, new_gen.cmake_var(var)?)))
+        .filter(|(old, new)| !old.is_empty() && old != new)
+        .map(|(old, new)| (String::from(old), String::from(new)))
+        .collect();
+    replacements.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+    replacements.dedup_by(|(a, _), (b, _)| a == b);
+
+    replacements
+}
+
+// One pass, replaced text is not looked at again, names must not be part of longer words
+fn replace_names(text: &str, replacements: &[(String, String)]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let previous = result.chars().last();
+        let replacement = replacements.iter().find(|(old, _)| {
+            rest.starts_with(old.as_str())
+                && !previous.is_some_and(|c| c.is_ascii_alphanumeric())
+                && !rest[old.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphanumeric())
+        });
+
+        match replacement {
+            Some((old, new)) => {
+                result.push_str(new);
+                rest = &rest[old.len()..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    result
+}
+
+// Line by line, renaming does not add or remove lines
+fn build_diff(old_file: &str, new_file: &str, contents: &str, new_contents: &str) -> String {
+    if old_file == new_file && contents == new_contents {
+        return String::new();
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", old_file, new_file);
+    for (number, (old, new)) in contents.lines().zip(new_contents.lines()).enumerate() {
+        if old != new {
+            diff.push_str(&format!("@@ {} @@\n-{}\n+{}\n", number + 1, old, new));
+        }
+    }
+
+    diff
+}
+
+// Stops at the first non-empty directory or the project directory
+fn remove_empty_parents(project_dir: &Path, path: &Path) {
+    for dir in path.ancestors().skip(1) {
+        if dir == project_dir || fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_opt(name_space: Option<&str>, target_name: &str) -> Opt {
+        Opt {
+            name_space: name_space.map(String::from),
+            target_name: String::from(target_name),
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_replace_names() {
+        let replacements = build_replacements(
+            &create_test_opt(Some("nmspc"), "tgtnm"),
+            &create_test_opt(Some("other"), "widget"),
+        );
+
+        assert_eq!(
+            replace_names("include/nmspc/tgtnm/tgtnm.h", &replacements),
+            "include/other/widget/widget.h"
+        );
+        assert_eq!(
+            replace_names("project(nmspc-tgtnm VERSION 1.0)", &replacements),
+            "project(other-widget VERSION 1.0)"
+        );
+        assert_eq!(
+            replace_names("#ifndef NMSPC_TGTNM_TGTNM_H", &replacements),
+            "#ifndef OTHER_WIDGET_WIDGET_H"
+        );
+        assert_eq!(
+            replace_names("tgtnmx and nmspc::tgtnm", &replacements),
+            "tgtnmx and other::widget"
+        );
+    }
+
+    #[test]
+    fn test_replace_names_once() {
+        let replacements = build_replacements(
+            &create_test_opt(None, "app"),
+            &create_test_opt(None, "my-app"),
+        );
+
+        assert_eq!(
+            replace_names("add_executable(app-app)", &replacements),
+            "add_executable(my-app-my-app)"
+        );
+    }
+
+    #[test]
+    fn test_build_diff() {
+        assert_eq!(build_diff("a", "a", "x\ny", "x\ny"), "");
+        assert_eq!(
+            build_diff("a", "b", "x\ny", "x\nz"),
+            "--- a/a\n+++ b/b\n@@ 2 @@\n-y\n+z\n"
+        );
+    }
+}
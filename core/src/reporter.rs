@@ -0,0 +1,140 @@
+/*
+For JSON Lines /sa https://jsonlines.org/
+*/
+
+use serde::{Serialize, Serializer};
+use std::path::{Path, PathBuf};
+
+// What happens during generation, steps count written paths from 1 to total
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum GenEvent {
+    Planned {
+        directories: usize,
+        files: usize,
+    },
+    DirCreated {
+        step: usize,
+        total: usize,
+        #[serde(serialize_with = "serialize_lossy")]
+        path: PathBuf,
+    },
+    FileRendered {
+        #[serde(serialize_with = "serialize_lossy")]
+        path: PathBuf,
+    },
+    FileWritten {
+        step: usize,
+        total: usize,
+        #[serde(serialize_with = "serialize_lossy")]
+        path: PathBuf,
+        bytes: u64,
+    },
+    LinkCreated {
+        step: usize,
+        total: usize,
+        #[serde(serialize_with = "serialize_lossy")]
+        path: PathBuf,
+        #[serde(serialize_with = "serialize_lossy")]
+        target: PathBuf,
+    },
+    // e.g. a template pack file whose condition does not hold
+    Skipped {
+        #[serde(serialize_with = "serialize_lossy")]
+        path: PathBuf,
+        reason: String,
+    },
+    HookStarted {
+        command: String,
+    },
+    HookFinished {
+        command: String,
+        success: bool,
+    },
+    Warning {
+        message: String,
+    },
+    // Another run holds the lock of the output root, see --wait
+    LockWaiting {
+        #[serde(serialize_with = "serialize_lossy")]
+        path: PathBuf,
+        holder: String,
+    },
+    // A remote output is uploaded after the post-hooks
+    UploadStarted {
+        url: String,
+    },
+}
+
+impl GenEvent {
+    // Step, total and path of written paths
+    pub fn progress(&self) -> Option<(usize, usize, &Path)> {
+        match self {
+            GenEvent::DirCreated { step, total, path }
+            | GenEvent::FileWritten {
+                step, total, path, ..
+            }
+            | GenEvent::LinkCreated {
+                step, total, path, ..
+            } => Some((*step, *total, path)),
+            _ => None,
+        }
+    }
+}
+
+// Receives the events of CppProjGen::gen, template pack files and hooks
+pub trait Reporter {
+    fn event(&mut self, event: &GenEvent);
+}
+
+// Collects the events, e.g. for services answering with them afterwards
+impl Reporter for Vec<GenEvent> {
+    fn event(&mut self, event: &GenEvent) {
+        self.push(event.clone());
+    }
+}
+
+// Paths need not be UTF-8, JSON strings must
+fn serialize_lossy<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let event = GenEvent::FileWritten {
+            step: 2,
+            total: 8,
+            path: PathBuf::from("tgtnm/CMakeLists.txt"),
+            bytes: 42,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"file-written","step":2,"total":8,"path":"tgtnm/CMakeLists.txt","bytes":42}"#
+        );
+        assert_eq!(
+            event.progress(),
+            Some((2, 8, Path::new("tgtnm/CMakeLists.txt")))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_serialize_non_utf8_path() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let event = GenEvent::FileRendered {
+            path: PathBuf::from(OsStr::from_bytes(b"t\xe9st")),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            "{\"event\":\"file-rendered\",\"path\":\"t\u{fffd}st\"}"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+/*
+For cmake -E copy_directory /sa https://cmake.org/cmake/help/latest/manual/cmake.1.html#run-a-command-line-tool
+For add_custom_command(TARGET) /sa https://cmake.org/cmake/help/latest/command/add_custom_command.html#build-events
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::project_manifest;
+use std::io;
+
+const RESOURCES_CMAKE: &str = include_str!("../res/resources/CMakeLists.snippet.in");
+const RESOURCES_README: &str = include_str!("../res/resources/README.md.in");
+
+// Only executables have a directory to copy the resources next to
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    match (&opt.resources, opt.kind.app_target()) {
+        (Some(_), None) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "--resources needs an executable, kind {} has none",
+                opt.kind.name()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Adds the resource directory with a README and the copy and install rules
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (dir, app_target) = match (&gen.opt().resources, gen.opt().kind.app_target()) {
+        (Some(dir), Some(app_target)) => (dir.clone(), app_target),
+        _ => return gen,
+    };
+    // CMake paths use / on every platform
+    let cmake_dir = project_manifest::to_manifest_path(&dir);
+
+    gen.add_cmake_var("@RESOURCE_DIR@", &cmake_dir)
+        .add_toplevel_dir(dir.clone())
+        .add_file(
+            dir.join("README.md"),
+            RESOURCES_README.replace("@RESOURCE_APP_TARGET@", app_target),
+        )
+        .add_cmake_snippet(RESOURCES_CMAKE.replace("@RESOURCE_APP_TARGET@", app_target))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::{Kind, PlanEntry};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            kind: Kind::Static,
+            resources: Some(PathBuf::from("assets")),
+            ..Opt::default()
+        };
+
+        assert!(validate(&opt).is_err());
+        assert!(validate(&Opt {
+            kind: Kind::LibAndApp,
+            ..opt
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            kind: Kind::LibAndApp,
+            resources: Some(PathBuf::from("data/assets")),
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+        let cmake_lists = plan
+            .iter()
+            .find_map(|entry| match entry {
+                PlanEntry::File { path, contents, .. }
+                    if path == Path::new("test_out_dir/tgtnm/CMakeLists.txt") =>
+                {
+                    Some(contents.clone())
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(cmake_lists.contains("$<TARGET_FILE_DIR:tgtnm-app>/data/assets"));
+        assert!(cmake_lists.contains("TGTNM_RESOURCE_DIR=\"data/assets\""));
+        assert!(plan
+            .iter()
+            .any(|entry| entry.path() == Path::new("test_out_dir/tgtnm/data/assets/README.md")));
+    }
+}
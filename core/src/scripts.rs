@@ -0,0 +1,176 @@
+/*
+For cmake --preset /sa https://cmake.org/cmake/help/latest/manual/cmake.1.html#cmdoption-cmake-preset
+For ctest /sa https://cmake.org/cmake/help/latest/manual/ctest.1.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::platforms::Platform;
+use std::path::PathBuf;
+
+const BUILD_SH: &str = include_str!("../res/scripts/build.sh.in");
+const BUILD_PS1: &str = include_str!("../res/scripts/build.ps1.in");
+const BUILD_BAT: &str = include_str!("../res/scripts/build.bat.in");
+const TEST_SH: &str = include_str!("../res/scripts/test.sh.in");
+const TEST_PS1: &str = include_str!("../res/scripts/test.ps1.in");
+const TEST_BAT: &str = include_str!("../res/scripts/test.bat.in");
+
+// Kind of convenience script, chosen by the targeted platforms and not by the host
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+pub enum ScriptFlavor {
+    Sh,
+    PowerShell,
+    // cmd wrapper of the PowerShell script, e.g. to double-click
+    Batch,
+}
+
+impl ScriptFlavor {
+    // The build and test script templates
+    fn templates(self) -> (&'static str, &'static str) {
+        match self {
+            ScriptFlavor::Sh => (BUILD_SH, TEST_SH),
+            ScriptFlavor::PowerShell => (BUILD_PS1, TEST_PS1),
+            ScriptFlavor::Batch => (BUILD_BAT, TEST_BAT),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ScriptFlavor::Sh => "sh",
+            ScriptFlavor::PowerShell => "ps1",
+            ScriptFlavor::Batch => "bat",
+        }
+    }
+}
+
+// The flavors of the --platforms, sh and PowerShell without any
+pub fn script_flavors(platforms: &[Platform]) -> Vec<ScriptFlavor> {
+    if platforms.is_empty() {
+        return vec![ScriptFlavor::Sh, ScriptFlavor::PowerShell];
+    }
+
+    let mut flavors: Vec<ScriptFlavor> = platforms
+        .iter()
+        .flat_map(|platform| platform.script_flavors().iter().copied())
+        .collect();
+    flavors.sort();
+    flavors.dedup();
+
+    flavors
+}
+
+// Build directory and configure arguments for a reference to the configuration, e.g. $config
+pub fn build_configure(opt: &Opt, config: &str) -> (String, String) {
+    match opt.compiler {
+        // The CMake presets of --compiler, e.g. gcc-debug
+        Some(compiler) => (
+            format!("build/{}-{}", compiler.name(), config),
+            format!("--preset \"{}-{}\"", compiler.name(), config),
+        ),
+        None => (
+            format!("build/{}", config),
+            format!("-DCMAKE_BUILD_TYPE=\"{}\"", config),
+        ),
+    }
+}
+
+// Adds e.g. scripts/build.sh and build.ps1, plus test.sh and test.ps1 with tests
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if !gen.opt().scripts {
+        return gen;
+    }
+
+    // sh and PowerShell both expand $config
+    let (build_dir, configure_args) = build_configure(gen.opt(), "$config");
+    let render = |template: &str| {
+        template
+            .replace("@SCRIPTS_BUILD_DIR@", &build_dir)
+            .replace("@SCRIPTS_CONFIGURE_ARGS@", &configure_args)
+    };
+    let mut scripts = Vec::new();
+    for flavor in script_flavors(&gen.opt().platforms) {
+        let (build, test) = flavor.templates();
+        scripts.push((format!("build.{}", flavor.extension()), render(build)));
+        if gen.opt().test_framework.is_some() {
+            scripts.push((format!("test.{}", flavor.extension()), render(test)));
+        }
+    }
+
+    scripts.into_iter().fold(gen, |gen, (name, contents)| {
+        gen.add_file(PathBuf::from("scripts").join(name), contents)
+    })
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Compiler;
+    use crate::test_framework::TestFramework;
+    use crate::test_util::contents;
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            scripts: true,
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt.clone()).plan().unwrap();
+
+        assert!(contents(&plan, "scripts/build.sh")
+            .unwrap()
+            .contains("cmake -S . -B \"build/$config\" -G Ninja -DCMAKE_BUILD_TYPE=\"$config\"\n"));
+        assert!(contents(&plan, "scripts/test.sh").is_none());
+
+        let plan = CppProjGen::new(Opt {
+            compiler: Some(Compiler::Clang),
+            test_framework: Some(TestFramework::Doctest),
+            ..opt
+        })
+        .plan()
+        .unwrap();
+
+        assert!(contents(&plan, "scripts/build.ps1").unwrap().contains(
+            "cmake -S . -B \"build/clang-$config\" -G Ninja --preset \"clang-$config\"\n"
+        ));
+        assert!(contents(&plan, "scripts/test.ps1")
+            .unwrap()
+            .contains("Set-Location \"build/clang-$config\""));
+        assert!(contents(&plan, "scripts/build.bat").is_none());
+    }
+
+    #[test]
+    fn test_script_flavors() {
+        assert_eq!(
+            script_flavors(&[]),
+            vec![ScriptFlavor::Sh, ScriptFlavor::PowerShell]
+        );
+        assert_eq!(script_flavors(&[Platform::Linux]), vec![ScriptFlavor::Sh]);
+        assert_eq!(
+            script_flavors(&[Platform::Macos, Platform::Win, Platform::Linux]),
+            vec![
+                ScriptFlavor::Sh,
+                ScriptFlavor::PowerShell,
+                ScriptFlavor::Batch
+            ]
+        );
+
+        let plan = CppProjGen::new(Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            scripts: true,
+            test_framework: Some(TestFramework::Doctest),
+            platforms: vec![Platform::Win],
+            ..Opt::default()
+        })
+        .plan()
+        .unwrap();
+
+        assert!(contents(&plan, "scripts/build.sh").is_none());
+        assert!(contents(&plan, "scripts/test.bat")
+            .unwrap()
+            .contains("-File \"%~dp0test.ps1\" %*\r\n"));
+        assert!(contents(&plan, "scripts/build.ps1").is_some());
+    }
+}
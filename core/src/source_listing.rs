@@ -0,0 +1,243 @@
+/*
+For file(GLOB) /sa https://cmake.org/cmake/help/latest/command/file.html#glob
+For CONFIGURE_DEPENDS /sa https://cmake.org/cmake/help/latest/command/file.html#glob-recurse
+*/
+
+use crate::cpp_proj_gen::{Kind, Opt};
+use crate::language::{self, Language};
+use crate::naming;
+use std::str::FromStr;
+
+// How targets list their sources
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceListing {
+    // Every file listed, add-test appends to the lists
+    Explicit,
+    // file(GLOB_RECURSE), re-run CMake after adding files
+    Glob,
+    // file(GLOB_RECURSE ... CONFIGURE_DEPENDS), checked on every build
+    GlobConfigureDepends,
+}
+
+impl SourceListing {
+    pub const VARIANTS: &'static [&'static str] = &["explicit", "glob", "glob-configure-depends"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SourceListing::Explicit => "explicit",
+            SourceListing::Glob => "glob",
+            SourceListing::GlobConfigureDepends => "glob-configure-depends",
+        }
+    }
+}
+
+impl FromStr for SourceListing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "explicit" => Ok(SourceListing::Explicit),
+            "glob" => Ok(SourceListing::Glob),
+            "glob-configure-depends" => Ok(SourceListing::GlobConfigureDepends),
+            _ => Err(format!(
+                "unknown source listing '{}', expected one of: {}",
+                s,
+                SourceListing::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// Replaces @SOURCE_GLOB@, placed before the target, and @TARGET_SOURCES@, its source list
+pub fn render_target_sources(template: &str, opt: &Opt) -> String {
+    let (glob, sources) = build_target_sources(opt);
+
+    template
+        .replace("@SOURCE_GLOB@", &glob)
+        .replace("@TARGET_SOURCES@", &sources)
+}
+
+// Same for @TEST_SOURCE_GLOB@ and @TEST_SOURCES@ of the test executable
+pub fn render_test_sources(template: &str, opt: &Opt) -> String {
+    let (glob, sources) = build_test_sources(opt);
+
+    template
+        .replace("@TEST_SOURCE_GLOB@", &glob)
+        .replace("@TEST_SOURCES@", &sources)
+}
+
+fn build_target_sources(opt: &Opt) -> (String, String) {
+    let header = "@INCLUDE_DIR@/@INCLUDE_DOMAIN_DIR@/@CMAKE_TARGET_NAME@.@HEADER_FILE_EXT@";
+    let source = "@SOURCE_DIR@/@CMAKE_TARGET_NAME@.@SOURCE_FILE_EXT@";
+    let main = "@SOURCE_DIR@/main.@SOURCE_FILE_EXT@";
+
+    if opt.source_listing == SourceListing::Explicit {
+        let files: &[&str] = match opt.kind {
+            Kind::HeaderOnly => &[header],
+            Kind::Static | Kind::Shared | Kind::LibAndApp => &[header, source],
+            Kind::Executable => &[header, source, main],
+        };
+        // Commented out until there are sources
+        let prefix = if language::has_samples(opt) { "" } else { "# " };
+        let files: Vec<String> = files
+            .iter()
+            .map(|file| format!("{}{}", prefix, file))
+            .collect();
+        return (String::new(), files.join("\n    "));
+    }
+
+    let mut patterns = build_patterns("@INCLUDE_DIR@", &header_exts(opt));
+    if opt.kind != Kind::HeaderOnly {
+        patterns.extend(build_patterns("@SOURCE_DIR@", &source_exts(opt)));
+    }
+
+    let var = "@CMAKE_VAR_PREFIX@_SOURCES";
+    let mut glob = build_glob(opt.source_listing, var, &patterns);
+    // The application target owns main
+    if opt.kind == Kind::LibAndApp {
+        glob.push_str(&format!(
+            "list(FILTER {} EXCLUDE REGEX \"/main\\\\.(c|{})$\")\n\n",
+            var, opt.source_ext
+        ));
+    }
+
+    (glob, format!("${{{}}}", var))
+}
+
+fn build_test_sources(opt: &Opt) -> (String, String) {
+    if opt.source_listing == SourceListing::Explicit {
+        return (
+            String::new(),
+            String::from("@TEST_FILE_PREFIX@@CMAKE_TARGET_NAME@@TEST_FILE_SUFFIX@"),
+        );
+    }
+
+    let var = "@CMAKE_VAR_PREFIX@_TEST_SOURCES";
+    let patterns = build_patterns(".", &test_exts(opt));
+
+    (
+        build_glob(opt.source_listing, var, &patterns),
+        format!("${{{}}}", var),
+    )
+}
+
+// e.g. file(GLOB_RECURSE MY_TARGET_SOURCES CONFIGURE_DEPENDS include/*.h source/*.cpp)
+fn build_glob(listing: SourceListing, var: &str, patterns: &[String]) -> String {
+    let configure_depends = match listing {
+        SourceListing::GlobConfigureDepends => " CONFIGURE_DEPENDS",
+        _ => "",
+    };
+
+    format!(
+        "file(GLOB_RECURSE {}{}\n    {}\n)\n\n",
+        var,
+        configure_depends,
+        patterns.join("\n    ")
+    )
+}
+
+fn build_patterns(dir: &str, exts: &[&str]) -> Vec<String> {
+    exts.iter()
+        .map(|ext| match dir {
+            "." => format!("*.{}", ext),
+            _ => format!("{}/*.{}", dir, ext),
+        })
+        .collect()
+}
+
+fn header_exts(opt: &Opt) -> Vec<&str> {
+    let mut exts = match opt.language {
+        Language::C => vec!["h"],
+        _ => vec!["h", "hpp"],
+    };
+    if !exts.contains(&opt.header_ext.as_str()) {
+        exts.push(&opt.header_ext);
+    }
+
+    exts
+}
+
+fn source_exts(opt: &Opt) -> Vec<&str> {
+    match opt.language {
+        Language::Cpp => vec![&opt.source_ext],
+        Language::C => vec!["c"],
+        Language::CppAndC => vec!["c", &opt.source_ext],
+    }
+}
+
+// Test files may have their own extension, e.g. with --test-pattern "*_test.cc"
+fn test_exts(opt: &Opt) -> Vec<&str> {
+    let mut exts = source_exts(opt);
+    let test_ext = opt.test_pattern.ext(naming::source_ext(opt));
+    if !exts.contains(&test_ext) {
+        exts.push(test_ext);
+    }
+
+    exts
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_opt(kind: Kind, source_listing: SourceListing) -> Opt {
+        Opt {
+            kind,
+            source_listing,
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_build_target_sources_explicit() {
+        let (glob, sources) =
+            build_target_sources(&create_test_opt(Kind::Executable, SourceListing::Explicit));
+
+        assert!(glob.is_empty());
+        assert_eq!(sources.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_build_target_sources_without_samples() {
+        let (_, sources) =
+            build_target_sources(&create_test_opt(Kind::Static, SourceListing::Explicit));
+        assert_eq!(
+            sources,
+            "# @INCLUDE_DIR@/@INCLUDE_DOMAIN_DIR@/@CMAKE_TARGET_NAME@.@HEADER_FILE_EXT@\n    \
+             # @SOURCE_DIR@/@CMAKE_TARGET_NAME@.@SOURCE_FILE_EXT@"
+        );
+    }
+
+    #[test]
+    fn test_build_target_sources_glob() {
+        let (glob, sources) = build_target_sources(&create_test_opt(
+            Kind::LibAndApp,
+            SourceListing::GlobConfigureDepends,
+        ));
+
+        assert!(
+            glob.starts_with("file(GLOB_RECURSE @CMAKE_VAR_PREFIX@_SOURCES CONFIGURE_DEPENDS\n")
+        );
+        assert!(glob.contains("    @SOURCE_DIR@/*.cpp\n"));
+        assert!(glob.contains("list(FILTER @CMAKE_VAR_PREFIX@_SOURCES EXCLUDE"));
+        assert_eq!(sources, "${@CMAKE_VAR_PREFIX@_SOURCES}");
+
+        let (glob, _) =
+            build_target_sources(&create_test_opt(Kind::HeaderOnly, SourceListing::Glob));
+        assert!(!glob.contains("@SOURCE_DIR@"));
+        assert!(!glob.contains("CONFIGURE_DEPENDS"));
+    }
+
+    #[test]
+    fn test_build_test_sources() {
+        let (glob, sources) =
+            build_test_sources(&create_test_opt(Kind::Static, SourceListing::Glob));
+
+        assert_eq!(
+            glob,
+            "file(GLOB_RECURSE @CMAKE_VAR_PREFIX@_TEST_SOURCES\n    *.cpp\n)\n\n"
+        );
+        assert_eq!(sources, "${@CMAKE_VAR_PREFIX@_TEST_SOURCES}");
+    }
+}
@@ -0,0 +1,364 @@
+/*
+For io::Read /sa https://doc.rust-lang.org/std/io/trait.Read.html
+For io::copy /sa https://doc.rust-lang.org/std/io/fn.copy.html
+*/
+
+use crate::encoding;
+use crate::error::Error;
+use crate::normalize::{LineNormalizer, TrailingNewline};
+use crate::platforms::{BlockFilter, Platform};
+use crate::template_vars::VarReplacer;
+use std::{
+    io::{self, Read},
+    path::Path,
+    str,
+};
+
+// Read from the template per step
+const CHUNK_SIZE: usize = 64 * 1024;
+// Longest line kept whole, longer ones are split and a var used across the split stays as it is
+const MAX_LINE: usize = 1024 * 1024;
+
+// How a streamed template is rendered, like the text files kept in memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamText {
+    pub replacer: VarReplacer,
+    // Of the @IF_PLATFORM blocks
+    pub platforms: Vec<Platform>,
+    // License banner of sources
+    pub banner: Option<String>,
+    pub normalizer: LineNormalizer,
+}
+
+// Template rendered while it is read, whole lines at a time so var uses are never split
+// Memory stays at about a chunk and a line, whatever the size of the template
+pub struct VarsReader<R> {
+    inner: R,
+    text: StreamText,
+    keep_bom: bool,
+    chunk_size: usize,
+    // Read but not rendered yet, up to the next newline
+    pending: Vec<u8>,
+    rendered: Vec<u8>,
+    // Position in rendered
+    position: usize,
+    // Bytes of the template rendered so far, for encoding errors
+    offset: usize,
+    at_start: bool,
+    at_end: bool,
+    finished: bool,
+    invalid: Option<String>,
+    filter: BlockFilter,
+    // Whether the line continued by the next template bytes is kept, a long line is split
+    keep_line: bool,
+    template_mid_line: bool,
+    // Of the rendered file, set by its start
+    separator: Option<&'static str>,
+    rendered_mid_line: bool,
+    // Held back until a line follows, blank lines at the end are dropped for a single newline
+    blank_lines: usize,
+}
+
+impl<R: Read> VarsReader<R> {
+    pub fn new(inner: R, text: StreamText, keep_bom: bool) -> Self {
+        VarsReader {
+            inner,
+            text,
+            keep_bom,
+            chunk_size: CHUNK_SIZE,
+            pending: Vec::new(),
+            rendered: Vec::new(),
+            position: 0,
+            offset: 0,
+            at_start: true,
+            at_end: false,
+            finished: false,
+            invalid: None,
+            filter: BlockFilter::default(),
+            keep_line: true,
+            template_mid_line: false,
+            separator: None,
+            rendered_mid_line: false,
+            blank_lines: 0,
+        }
+    }
+
+    // Why reading failed if the template is no UTF-8, the writer only sees an io::Error
+    pub fn encoding_error(&self, path: &Path) -> Option<Error> {
+        self.invalid.as_ref().map(|reason| Error::Encoding {
+            path: path.to_path_buf(),
+            reason: reason.clone(),
+        })
+    }
+
+    // Reads a chunk and renders the complete lines of what is pending
+    fn fill(&mut self) -> io::Result<()> {
+        let len = self.pending.len();
+        self.pending.resize(len + self.chunk_size, 0);
+        let read = self.inner.read(&mut self.pending[len..])?;
+        self.pending.truncate(len + read);
+        self.at_end = read == 0;
+
+        if self.at_start {
+            if self.pending.len() < encoding::UTF8_BOM.len() && !self.at_end {
+                return Ok(());
+            }
+            self.at_start = false;
+            if encoding::is_utf16(&self.pending) {
+                return Err(self.invalid(String::from("it is UTF-16")));
+            }
+            if !self.keep_bom && self.pending.starts_with(encoding::UTF8_BOM.as_bytes()) {
+                self.pending.drain(..encoding::UTF8_BOM.len());
+                self.offset += encoding::UTF8_BOM.len();
+            }
+        }
+
+        let end = match self.pending.iter().rposition(|byte| *byte == b'\n') {
+            _ if self.at_end => self.pending.len(),
+            Some(newline) => newline + 1,
+            None if self.pending.len() > MAX_LINE => self.pending.len(),
+            None => return Ok(()),
+        };
+        let end = match str::from_utf8(&self.pending[..end]) {
+            Ok(_) => end,
+            // A character split by the chunk, completed by the next one
+            Err(err) if err.error_len().is_none() && !self.at_end => err.valid_up_to(),
+            Err(err) => {
+                let reason = format!("invalid byte at offset {}", self.offset + err.valid_up_to());
+                return Err(self.invalid(reason));
+            }
+        };
+
+        let text = String::from(str::from_utf8(&self.pending[..end]).expect("checked above"));
+        self.rendered = self.render(&text).into_bytes();
+        self.position = 0;
+        self.offset += end;
+        self.pending.drain(..end);
+
+        Ok(())
+    }
+
+    // Like CppProjGen::render, file_header::add_banner and normalize::apply of a whole file
+    fn render(&mut self, text: &str) -> String {
+        let mut kept = String::new();
+        for line in text.split_inclusive('\n') {
+            if !self.template_mid_line {
+                self.keep_line = self.filter.keeps(line, &self.text.platforms);
+            }
+            self.template_mid_line = !line.ends_with('\n');
+            if self.keep_line {
+                kept.push_str(line);
+            }
+        }
+
+        let mut rendered = self.text.replacer.replace(&kept);
+        if let Some(banner) = self.text.banner.take() {
+            rendered = format!("{}\n{}", banner, rendered);
+        }
+
+        self.normalize(&rendered)
+    }
+
+    fn normalize(&mut self, rendered: &str) -> String {
+        let normalizer = &self.text.normalizer;
+        let separator = *self
+            .separator
+            .get_or_insert_with(|| normalizer.separator(rendered));
+        let single = normalizer.trailing_newline == TrailingNewline::Single;
+
+        let mut normalized = String::new();
+        for segment in rendered.split_inclusive('\n') {
+            let (line, newline) = match segment.strip_suffix('\n') {
+                Some(line) => (line, true),
+                None => (segment, false),
+            };
+            let line = match self.rendered_mid_line {
+                false => normalizer.line(line),
+                true if newline => String::from(line.strip_suffix('\r').unwrap_or(line)),
+                true => String::from(line),
+            };
+
+            if line.is_empty() && !self.rendered_mid_line && (single || !newline) {
+                self.blank_lines += usize::from(newline);
+                continue;
+            }
+            for _ in 0..self.blank_lines {
+                normalized.push_str(separator);
+            }
+            self.blank_lines = 0;
+            normalized.push_str(&line);
+            if newline {
+                normalized.push_str(separator);
+            }
+            self.rendered_mid_line = !newline;
+        }
+
+        normalized
+    }
+
+    // The newline a single trailing newline adds
+    fn finish(&mut self) -> String {
+        self.finished = true;
+        let single = self.text.normalizer.trailing_newline == TrailingNewline::Single;
+
+        match (single && self.rendered_mid_line, self.separator) {
+            (true, Some(separator)) => String::from(separator),
+            _ => String::new(),
+        }
+    }
+
+    fn invalid(&mut self, reason: String) -> io::Error {
+        let err = io::Error::new(io::ErrorKind::InvalidData, reason.clone());
+        self.invalid = Some(reason);
+
+        err
+    }
+}
+
+impl<R: Read> Read for VarsReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.rendered.len() {
+            if self.at_end && self.pending.is_empty() {
+                if self.finished {
+                    return Ok(0);
+                }
+                self.rendered = self.finish().into_bytes();
+                self.position = 0;
+                continue;
+            }
+            self.fill()?;
+        }
+
+        let len = buf.len().min(self.rendered.len() - self.position);
+        buf[..len].copy_from_slice(&self.rendered[self.position..self.position + len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::Opt;
+    use crate::normalize::{self, Indent, LineEndings};
+    use crate::template_vars::VarValue;
+    use std::collections::BTreeMap;
+
+    fn stream_text(path: &Path, opt: &Opt) -> StreamText {
+        let vars: BTreeMap<String, VarValue> = vec![
+            (String::from("@NAME@"), VarValue::from("tgtnm")),
+            (String::from("@SHARED@"), VarValue::Bool(true)),
+        ]
+        .into_iter()
+        .collect();
+
+        StreamText {
+            replacer: VarReplacer::new(&vars),
+            platforms: opt.platforms.clone(),
+            banner: None,
+            normalizer: LineNormalizer::new(path, opt),
+        }
+    }
+
+    fn render_text(template: &[u8], chunk_size: usize, text: StreamText) -> io::Result<String> {
+        let mut reader = VarsReader::new(template, text, false);
+        reader.chunk_size = chunk_size;
+
+        let mut rendered = String::new();
+        reader.read_to_string(&mut rendered).map(|_| rendered)
+    }
+
+    fn render(template: &[u8], chunk_size: usize, keep_bom: bool) -> io::Result<String> {
+        let opt = Opt {
+            trailing_newline: TrailingNewline::Keep,
+            ..Opt::default()
+        };
+        let mut reader =
+            VarsReader::new(template, stream_text(Path::new("big.txt"), &opt), keep_bom);
+        reader.chunk_size = chunk_size;
+
+        let mut rendered = String::new();
+        reader.read_to_string(&mut rendered).map(|_| rendered)
+    }
+
+    #[test]
+    fn test_vars_reader() {
+        let template = "project(@NAME@)\noption(SHARED @SHARED|cmake@)\n// ä @NAME|upper@";
+        let expected = "project(tgtnm)\noption(SHARED ON)\n// ä TGTNM";
+
+        for chunk_size in &[1, 2, 5, 64, CHUNK_SIZE] {
+            let rendered = render(template.as_bytes(), *chunk_size, false).unwrap();
+            assert_eq!(rendered, expected, "chunk size {}", chunk_size);
+        }
+        assert_eq!(render(b"", 1, false).unwrap(), "");
+    }
+
+    #[test]
+    fn test_vars_reader_text() {
+        let template = "#pragma once\r\n@IF_PLATFORM win@\n\t// @NAME@ on win\n@ENDIF_PLATFORM@\n@IF_PLATFORM linux@\n\t// @NAME@ on linux\n@ENDIF_PLATFORM@\n\n\n";
+        let path = Path::new("include/big.h");
+        let opt = Opt {
+            platforms: vec![Platform::Linux],
+            line_endings: LineEndings::Crlf,
+            indent: Indent::Spaces,
+            license: Some(String::from("MIT")),
+            ..Opt::default()
+        };
+        // As if kept in memory
+        let expected = normalize::apply(
+            path,
+            &format!(
+                "// SPDX-License-Identifier: MIT\n\n{}",
+                "#pragma once\r\n\t// tgtnm on linux\n\n\n"
+            ),
+            &opt,
+        );
+        assert_eq!(
+            expected,
+            "// SPDX-License-Identifier: MIT\r\n\r\n#pragma once\r\n    // tgtnm on linux\r\n"
+        );
+
+        for chunk_size in &[1, 2, 5, 64, CHUNK_SIZE] {
+            let text = StreamText {
+                banner: Some(String::from("// SPDX-License-Identifier: MIT\n")),
+                ..stream_text(path, &opt)
+            };
+            let rendered = render_text(template.as_bytes(), *chunk_size, text).unwrap();
+            assert_eq!(rendered, expected, "chunk size {}", chunk_size);
+        }
+
+        let text = stream_text(Path::new("run.sh"), &opt);
+        assert_eq!(
+            render_text(b"#!/bin/sh\r\n@NAME@", 3, text).unwrap(),
+            "#!/bin/sh\ntgtnm\n"
+        );
+        let text = stream_text(path, &opt);
+        assert_eq!(render_text(b"\n\n", 1, text).unwrap(), "");
+    }
+
+    #[test]
+    fn test_vars_reader_bom() {
+        assert_eq!(render(b"\xef\xbb\xbf@NAME@", 1, false).unwrap(), "tgtnm");
+        assert_eq!(
+            render(b"\xef\xbb\xbf@NAME@", 2, true).unwrap(),
+            "\u{feff}tgtnm"
+        );
+    }
+
+    #[test]
+    fn test_vars_reader_encoding_error() {
+        let text = stream_text(Path::new("big.txt"), &Opt::default());
+        let mut reader = VarsReader::new(&b"ok\n\xff"[..], text, false);
+        reader.chunk_size = 2;
+        let mut rendered = Vec::new();
+
+        assert!(reader.read_to_end(&mut rendered).is_err());
+        assert!(matches!(
+            reader.encoding_error(Path::new("big.txt")),
+            Some(Error::Encoding { reason, .. }) if reason == "invalid byte at offset 3"
+        ));
+        assert!(render(b"\xff\xfeh\0", 64, false).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+/*
+For .gitmodules /sa https://git-scm.com/docs/gitmodules
+For git submodule add /sa https://git-scm.com/docs/git-submodule#Documentation/git-submodule.txt-add
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::error::{self, Error};
+use crate::reporter::{GenEvent, Reporter};
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const GITMODULES_FILE: &str = ".gitmodules";
+const EXTERN_DIR: &str = "extern";
+
+// e.g. --submodule fmt=https://github.com/fmtlib/fmt.git
+pub fn parse_submodule(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        // A plain directory name, neither '.', '..' nor nested
+        Some((name, url))
+            if !url.is_empty() && Path::new(name).file_name() == Some(OsStr::new(name)) =>
+        {
+            Ok((String::from(name), String::from(url)))
+        }
+        _ => Err(format!("expected NAME=GIT-URL, got '{}'", text)),
+    }
+}
+
+// The extern/<name> directories are left to 'git submodule add', which refuses existing ones
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    if gen.opt().submodules.is_empty() {
+        return gen;
+    }
+
+    let gitmodules = build_gitmodules(&gen.opt().submodules);
+    let snippet = build_cmake_snippet(&gen.opt().submodules);
+
+    gen.add_file(PathBuf::from(GITMODULES_FILE), gitmodules)
+        .add_cmake_snippet(snippet)
+}
+
+fn submodule_path(name: &str) -> String {
+    format!("{}/{}", EXTERN_DIR, name)
+}
+
+fn build_gitmodules(submodules: &[(String, String)]) -> String {
+    submodules
+        .iter()
+        .map(|(name, url)| {
+            let path = submodule_path(name);
+            format!(
+                "[submodule \"{}\"]\n\tpath = {}\n\turl = {}\n",
+                path, path, url
+            )
+        })
+        .collect()
+}
+
+// Guarded, so configuring works before the submodules are checked out
+fn build_cmake_snippet(submodules: &[(String, String)]) -> String {
+    let calls: Vec<String> = submodules
+        .iter()
+        .map(|(name, _)| {
+            let path = submodule_path(name);
+            format!(
+                "if(EXISTS ${{CMAKE_CURRENT_LIST_DIR}}/{}/CMakeLists.txt)\n    add_subdirectory({})\nendif()",
+                path, path
+            )
+        })
+        .collect();
+
+    calls.join("\n")
+}
+
+// With --git-init, initializes the repository and adds the submodules
+pub fn run(project_dir: &Path, opt: &Opt, reporter: &mut dyn Reporter) -> error::Result<()> {
+    if !opt.git_init {
+        return Ok(());
+    }
+
+    run_git(project_dir, &[String::from("init")], reporter)?;
+    for (name, url) in &opt.submodules {
+        let args = [
+            String::from("submodule"),
+            String::from("add"),
+            url.clone(),
+            submodule_path(name),
+        ];
+        run_git(project_dir, &args, reporter)?;
+    }
+
+    Ok(())
+}
+
+fn run_git(project_dir: &Path, args: &[String], reporter: &mut dyn Reporter) -> error::Result<()> {
+    let command = format!("git {}", args.join(" "));
+    reporter.event(&GenEvent::HookStarted {
+        command: command.clone(),
+    });
+
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(project_dir)
+        .status()
+        .map_err(Error::fs("run git in", project_dir))?;
+    reporter.event(&GenEvent::HookFinished {
+        command: command.clone(),
+        success: status.success(),
+    });
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(Error::from(io::Error::other(format!(
+            "'{}' failed with {}",
+            command, status
+        )))),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::contents;
+
+    #[test]
+    fn test_parse_submodule() {
+        assert_eq!(
+            parse_submodule("fmt=https://github.com/fmtlib/fmt.git").unwrap(),
+            (
+                String::from("fmt"),
+                String::from("https://github.com/fmtlib/fmt.git")
+            )
+        );
+        assert!(parse_submodule("fmt").is_err());
+        assert!(parse_submodule("fmt=").is_err());
+        assert!(parse_submodule("../fmt=https://example.com/fmt.git").is_err());
+        assert!(parse_submodule("a/b=https://example.com/b.git").is_err());
+        assert!(parse_submodule(".=https://example.com/b.git").is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            submodules: vec![(
+                String::from("fmt"),
+                String::from("https://github.com/fmtlib/fmt.git"),
+            )],
+            ..Opt::default()
+        };
+        let plan = CppProjGen::new(opt).plan().unwrap();
+
+        assert_eq!(
+            contents(&plan, ".gitmodules").unwrap(),
+            "[submodule \"extern/fmt\"]\n\tpath = extern/fmt\n\turl = https://github.com/fmtlib/fmt.git\n"
+        );
+        assert!(contents(&plan, "CMakeLists.txt")
+            .unwrap()
+            .contains("    add_subdirectory(extern/fmt)\n"));
+        assert!(!plan.iter().any(|entry| entry
+            .path()
+            .starts_with("test_out_dir/my-target/extern/fmt")));
+    }
+}
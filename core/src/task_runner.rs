@@ -0,0 +1,161 @@
+/*
+For just /sa https://just.systems/man/en/
+For Task /sa https://taskfile.dev/usage/
+For run-clang-tidy /sa https://clang.llvm.org/extra/clang-tidy/#using-clang-tidy
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::language::Language;
+use crate::scripts;
+use std::{path::PathBuf, str::FromStr};
+
+const JUSTFILE: &str = include_str!("../res/task_runner/justfile.in");
+const JUSTFILE_TEST: &str = include_str!("../res/task_runner/justfile.test.in");
+const TASKFILE: &str = include_str!("../res/task_runner/Taskfile.yml.in");
+const TASKFILE_TEST: &str = include_str!("../res/task_runner/Taskfile.test.yml.in");
+const MAKEFILE: &str = include_str!("../res/task_runner/Makefile.in");
+const MAKEFILE_TEST: &str = include_str!("../res/task_runner/Makefile.test.in");
+
+// Tool running the build, test, format, lint and clean recipes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskRunner {
+    Just,
+    Task,
+    Make,
+}
+
+impl TaskRunner {
+    pub const VARIANTS: &'static [&'static str] = &["just", "task", "make"];
+
+    // File name, template, test recipe template and reference to the configuration variable
+    fn templates(self) -> (&'static str, &'static str, &'static str, &'static str) {
+        match self {
+            TaskRunner::Just => ("justfile", JUSTFILE, JUSTFILE_TEST, "{{config}}"),
+            TaskRunner::Task => ("Taskfile.yml", TASKFILE, TASKFILE_TEST, "{{.CONFIG}}"),
+            TaskRunner::Make => ("Makefile", MAKEFILE, MAKEFILE_TEST, "$(CONFIG)"),
+        }
+    }
+}
+
+impl FromStr for TaskRunner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "just" => Ok(TaskRunner::Just),
+            "task" => Ok(TaskRunner::Task),
+            "make" => Ok(TaskRunner::Make),
+            _ => Err(format!(
+                "unknown task runner '{}', expected one of: {}",
+                s,
+                TaskRunner::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// clang-format over the headers and sources of the include, source and test dirs
+fn build_format_command(opt: &Opt) -> String {
+    let mut names = vec!["-name '*.@HEADER_FILE_EXT@'", "-name '*.@SOURCE_FILE_EXT@'"];
+    if opt.language == Language::CppAndC {
+        names.push("-name '*.c'");
+    }
+
+    format!(
+        "find @INCLUDE_DIR@ @SOURCE_DIR@ @TEST_DIR@ {} | xargs clang-format -i",
+        names.join(" -o ")
+    )
+}
+
+// Adds the justfile, Taskfile.yml or Makefile of --task-runner
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let task_runner = match gen.opt().task_runner {
+        Some(task_runner) => task_runner,
+        None => return gen,
+    };
+
+    let (name, template, test_template, config) = task_runner.templates();
+    let (build_dir, configure_args) = scripts::build_configure(gen.opt(), config);
+    let has_tests = gen.opt().test_framework.is_some();
+    let test = if has_tests { test_template } else { "" };
+    let contents = template
+        .replace("@TASKS_TEST@", test)
+        .replace("@TASKS_PHONY_TEST@", if has_tests { "test " } else { "" })
+        .replace("@TASKS_FORMAT@", &build_format_command(gen.opt()))
+        .replace("@TASKS_BUILD_DIR@", &build_dir)
+        .replace("@TASKS_CONFIGURE_ARGS@", &configure_args);
+
+    gen.add_file(PathBuf::from(name), contents)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Compiler;
+    use crate::cpp_proj_gen::PlanEntry;
+    use crate::test_framework::TestFramework;
+    use crate::test_util::contents;
+
+    fn create_test_plan(opt: Opt) -> Vec<PlanEntry> {
+        CppProjGen::new(opt)
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .add_test_dir(PathBuf::from("test"))
+            .plan()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("task".parse::<TaskRunner>(), Ok(TaskRunner::Task));
+        assert_eq!(
+            "ninja".parse::<TaskRunner>(),
+            Err(String::from(
+                "unknown task runner 'ninja', expected one of: just, task, make"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            task_runner: Some(TaskRunner::Just),
+            ..Opt::default()
+        };
+        let plan = create_test_plan(opt.clone());
+
+        let justfile = contents(&plan, "justfile").unwrap();
+        assert!(justfile.contains(
+            "    cmake -S . -B \"build/{{config}}\" -G Ninja -DCMAKE_BUILD_TYPE=\"{{config}}\"\n"
+        ));
+        assert!(justfile.contains(
+            "    find include source test -name '*.h' -o -name '*.cpp' | xargs clang-format -i\n"
+        ));
+        assert!(!justfile.contains("ctest"));
+        assert!(contents(&plan, "Makefile").is_none());
+
+        let plan = create_test_plan(Opt {
+            task_runner: Some(TaskRunner::Make),
+            compiler: Some(Compiler::Gcc),
+            test_framework: Some(TestFramework::Catch2),
+            ..opt.clone()
+        });
+
+        let makefile = contents(&plan, "Makefile").unwrap();
+        assert!(makefile.contains(".PHONY: build test format lint clean\n"));
+        assert!(makefile.contains("\tcmake --build \"build/gcc-$(CONFIG)\"\n"));
+        assert!(makefile.contains("test: build\n\tcd \"build/gcc-$(CONFIG)\" && ctest"));
+
+        let plan = create_test_plan(Opt {
+            task_runner: Some(TaskRunner::Task),
+            ..opt
+        });
+
+        assert!(contents(&plan, "Taskfile.yml")
+            .unwrap()
+            .contains("      - run-clang-tidy -p \"build/{{.CONFIG}}\" -quiet\n"));
+    }
+}
@@ -0,0 +1,615 @@
+/*
+For git clone /sa https://git-scm.com/docs/git-clone
+For the XDG cache directory /sa https://specifications.freedesktop.org/basedir-spec/latest/
+For git verify-tag /sa https://git-scm.com/docs/git-verify-tag
+For sha2 /sa https://docs.rs/sha2/0.10/sha2/
+*/
+
+use crate::cpp_proj_gen::Opt;
+use crate::error::{self, Error};
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+// Git config key of the clone remembering the requested ref
+const REF_CONFIG_KEY: &str = "cpp-proj-gen.ref";
+
+// Remote template pack, e.g. https://github.com/org/pack.git#v1.2.0, optionally pinned like lib#v1.2.0@sha256:<checksum>
+#[derive(Debug, Clone, PartialEq)]
+struct Remote {
+    url: String,
+    reference: Option<String>,
+    sha256: Option<String>,
+}
+
+impl Remote {
+    fn parse(text: &str) -> Option<Self> {
+        let is_remote = ["https://", "http://", "ssh://", "git://", "file://", "git@"]
+            .iter()
+            .any(|scheme| text.starts_with(scheme));
+        if !is_remote {
+            return None;
+        }
+
+        let (text, sha256) = match text.rsplit_once("@sha256:") {
+            Some((text, sha256)) => (text, Some(sha256)),
+            None => (text, None),
+        };
+        let (url, reference) = match text.rsplit_once('#') {
+            Some((url, reference)) if !reference.is_empty() => (url, Some(reference)),
+            _ => (text, None),
+        };
+
+        Some(Remote {
+            url: String::from(url),
+            reference: reference.map(String::from),
+            sha256: sha256.map(String::from),
+        })
+    }
+
+    // Readable URL and ref plus a hash of both, e.g. https+github.com-org-pack.git@v1.2.0-1a2b3c4d5e6f7a8b
+    fn cache_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.url.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.reference.as_deref().unwrap_or_default().as_bytes());
+        let hash: String = hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        format!("{}-{}", self.display_key(), hash)
+    }
+
+    // Only for display, different URLs may map to the same name, e.g. org-x/pack and org/x-pack
+    fn display_key(&self) -> String {
+        // The scheme stays apart, the same path over http and https are different packs
+        let (scheme, url) = match self.url.split_once("://") {
+            Some((scheme, rest)) => (Some(scheme), rest),
+            None => (None, self.url.as_str()),
+        };
+        let key: String = url
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+                _ => '-',
+            })
+            .collect();
+        let mut key = String::from(key.trim_matches('-'));
+        if let Some(scheme) = scheme {
+            key = format!("{}+{}", scheme.to_ascii_lowercase(), key);
+        }
+
+        if let Some(reference) = &self.reference {
+            key.push('@');
+            key.push_str(&reference.replace(['/', '\\'], "-"));
+        }
+
+        key
+    }
+}
+
+// e.g. ~/.cache/cpp-proj-gen/templates
+pub fn cache_dir() -> Option<PathBuf> {
+    let cache_dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+
+    Some(cache_dir.join("cpp-proj-gen").join("templates"))
+}
+
+// Replaces remote template packs by their verified cached clones, cloning the missing ones unless offline
+pub fn resolve(opt: &Opt) -> error::Result<Vec<PathBuf>> {
+    opt.template_dirs
+        .iter()
+        .map(|template_dir| {
+            let remote = match template_dir.to_str().and_then(|text| remote(opt, text)) {
+                Some(remote) => remote,
+                None => return Ok(template_dir.clone()),
+            };
+
+            let cache_dir = cache_dir().ok_or_else(no_cache_dir)?;
+            let clone_dir = cache_dir.join(remote.cache_key());
+            if clone_dir.is_dir() {
+                verify(opt, &remote, &clone_dir)?;
+                return Ok(clone_dir);
+            }
+
+            if opt.offline {
+                return Err(Error::from(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "template pack {} is not cached, run once without --offline",
+                        remote.url
+                    ),
+                )));
+            }
+
+            fs::create_dir_all(&cache_dir).map_err(Error::fs("create directory", &cache_dir))?;
+            clone_into_cache(&remote, &clone_dir)?;
+            verify(opt, &remote, &clone_dir)?;
+
+            Ok(clone_dir)
+        })
+        .collect()
+}
+
+// A git URL, or a bare name like lib#v1 under the registry unless it is a local directory
+fn remote(opt: &Opt, text: &str) -> Option<Remote> {
+    if let Some(remote) = Remote::parse(text) {
+        return Some(remote);
+    }
+
+    let registry = opt.template_registry.as_ref()?;
+    let is_bare_name = !text.is_empty() && !text.contains(['/', '\\']) && !text.starts_with('.');
+    if !is_bare_name || Path::new(text).is_dir() {
+        return None;
+    }
+
+    Remote::parse(&format!("{}/{}", registry.trim_end_matches('/'), text))
+}
+
+// Verified by a pinned checksum or a valid signature, the cache is checked on every use
+fn verify(opt: &Opt, remote: &Remote, clone_dir: &Path) -> error::Result<()> {
+    let checksum = checksum(clone_dir)?;
+
+    // A pin of the entry itself holds even with --allow-unverified or a signature
+    if let Some(pinned) = &remote.sha256 {
+        if pinned.eq_ignore_ascii_case(&checksum) {
+            return Ok(());
+        }
+
+        return Err(Error::from(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "template pack {} has checksum {}, its entry pins {}",
+                remote.url, checksum, pinned
+            ),
+        )));
+    }
+
+    if opt
+        .template_sha256
+        .iter()
+        .any(|pinned| pinned.eq_ignore_ascii_case(&checksum))
+    {
+        return Ok(());
+    }
+
+    if opt.verify_template_signature {
+        return verify_signature(remote, clone_dir);
+    }
+
+    if opt.allow_unverified {
+        return Ok(());
+    }
+
+    Err(Error::from(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!(
+            "template pack {} is not verified, pin it with --template-sha256 {}, \
+             pass --verify-template-signature or --allow-unverified",
+            remote.url, checksum
+        ),
+    )))
+}
+
+// sha256 over the relative paths and contents of all files but .git, sorted by path
+fn checksum(pack_dir: &Path) -> error::Result<String> {
+    let mut files = Vec::new();
+    collect_files(pack_dir, pack_dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for relative in files {
+        let path = pack_dir.join(&relative);
+        let contents = fs::read(&path).map_err(Error::fs("read", &path))?;
+
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+// Relative paths with / separators, independent of the platform
+fn collect_files(pack_dir: &Path, dir: &Path, files: &mut Vec<String>) -> error::Result<()> {
+    for entry in fs::read_dir(dir).map_err(Error::fs("read", dir))? {
+        let path = entry.map_err(Error::fs("read", dir))?.path();
+        if path.file_name() == Some(".git".as_ref()) {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_files(pack_dir, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(pack_dir) {
+            let components: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push(components.join("/"));
+        }
+    }
+
+    Ok(())
+}
+
+// A signed tag if a ref was requested, the signed commit otherwise, either must be the checkout
+fn verify_signature(remote: &Remote, clone_dir: &Path) -> error::Result<()> {
+    let object = signed_object(remote, clone_dir);
+    let verb = match remote.reference {
+        Some(_) => "verify-tag",
+        None => "verify-commit",
+    };
+
+    let signed = run_git(
+        Command::new("git")
+            .current_dir(clone_dir)
+            .args([verb, &object]),
+    )
+    .is_ok();
+    let commit = rev_parse(clone_dir, &format!("{}^{{commit}}", object))?;
+    if signed && !commit.is_empty() && commit == rev_parse(clone_dir, "HEAD")? {
+        return Ok(());
+    }
+
+    Err(Error::from(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!(
+            "template pack {} has no valid signature, check the trusted keys of git",
+            remote.url
+        ),
+    )))
+}
+
+// An update checks out FETCH_HEAD, the local tag may still name the old object then
+fn signed_object(remote: &Remote, clone_dir: &Path) -> String {
+    match &remote.reference {
+        Some(_) if clone_dir.join(".git").join("FETCH_HEAD").is_file() => {
+            String::from("FETCH_HEAD")
+        }
+        Some(reference) => reference.clone(),
+        None => String::from("HEAD"),
+    }
+}
+
+fn rev_parse(clone_dir: &Path, object: &str) -> error::Result<String> {
+    let output = Command::new("git")
+        .current_dir(clone_dir)
+        .args(["rev-parse", "--verify", "--quiet", object])
+        .output()
+        .map_err(Error::fs("run git in", clone_dir))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Clone directories of the cached packs, sorted
+pub fn cached_packs(cache_dir: &Path) -> error::Result<Vec<PathBuf>> {
+    if !cache_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut clone_dirs = Vec::new();
+    for entry in fs::read_dir(cache_dir).map_err(Error::fs("read", cache_dir))? {
+        let path = entry.map_err(Error::fs("read", cache_dir))?.path();
+        let is_temp = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if !is_temp && path.join(".git").is_dir() {
+            clone_dirs.push(path);
+        }
+    }
+    clone_dirs.sort();
+
+    Ok(clone_dirs)
+}
+
+// Clones next to the cache entry and renames it, an interrupted clone never looks cached
+fn clone_into_cache(remote: &Remote, clone_dir: &Path) -> error::Result<()> {
+    let file_name = clone_dir.file_name().unwrap_or_default().to_string_lossy();
+    let temp_dir = clone_dir.with_file_name(format!(".{}.tmp-{}", file_name, process::id()));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).map_err(Error::fs("remove", &temp_dir))?;
+    }
+
+    let result = clone(remote, &temp_dir).and_then(|()| {
+        fs::rename(&temp_dir, clone_dir).or_else(|err| match clone_dir.is_dir() {
+            // Cloned by another run meanwhile
+            true => Ok(()),
+            false => Err(Error::fs("rename", &temp_dir)(err)),
+        })
+    });
+
+    if temp_dir.exists() {
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    result
+}
+
+fn clone(remote: &Remote, clone_dir: &Path) -> error::Result<()> {
+    let mut args = vec!["clone", "--depth", "1"];
+    if let Some(reference) = &remote.reference {
+        args.extend(["--branch", reference.as_str()]);
+    }
+    args.push(remote.url.as_str());
+
+    run_git(Command::new("git").args(&args).arg(clone_dir))?;
+
+    if let Some(reference) = &remote.reference {
+        run_git(Command::new("git").current_dir(clone_dir).args([
+            "config",
+            REF_CONFIG_KEY,
+            reference,
+        ]))?;
+    }
+
+    Ok(())
+}
+
+// Fetches the remembered ref, or the default branch, and checks it out
+pub fn update(clone_dir: &Path) -> error::Result<()> {
+    let reference = Command::new("git")
+        .current_dir(clone_dir)
+        .args(["config", "--get", REF_CONFIG_KEY])
+        .output()
+        .map_err(Error::fs("run git in", clone_dir))?;
+    let reference = match reference.status.success() {
+        true => String::from_utf8_lossy(&reference.stdout)
+            .trim()
+            .to_string(),
+        false => String::from("HEAD"),
+    };
+
+    run_git(
+        Command::new("git")
+            .current_dir(clone_dir)
+            .args(["fetch", "--depth", "1", "origin", &reference]),
+    )?;
+    run_git(
+        Command::new("git")
+            .current_dir(clone_dir)
+            .args(["checkout", "--force", "FETCH_HEAD"]),
+    )
+}
+
+fn run_git(command: &mut Command) -> error::Result<()> {
+    let status = command.status().map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("cannot run git, which remote template packs need: {}", err),
+        )
+    })?;
+
+    if !status.success() {
+        return Err(Error::from(io::Error::other(format!(
+            "git failed with {}",
+            status
+        ))));
+    }
+
+    Ok(())
+}
+
+pub fn no_cache_dir() -> Error {
+    Error::from(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no user cache directory for remote template packs",
+    ))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote() {
+        assert_eq!(Remote::parse("templates/company"), None);
+        assert_eq!(
+            Remote::parse("https://github.com/org/pack.git#v1.2.0"),
+            Some(Remote {
+                url: String::from("https://github.com/org/pack.git"),
+                reference: Some(String::from("v1.2.0")),
+                sha256: None,
+            })
+        );
+        assert_eq!(
+            Remote::parse("git@github.com:org/pack.git")
+                .unwrap()
+                .reference,
+            None
+        );
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let remote = Remote::parse("https://github.com/org/pack.git#feature/x").unwrap();
+        assert_eq!(
+            remote.display_key(),
+            "https+github.com-org-pack.git@feature-x"
+        );
+        assert!(remote
+            .cache_key()
+            .starts_with("https+github.com-org-pack.git@feature-x-"));
+        assert_eq!(remote.cache_key().len(), remote.display_key().len() + 17);
+
+        let remote = Remote::parse("http://github.com/org/pack.git#feature/x").unwrap();
+        assert_eq!(
+            remote.display_key(),
+            "http+github.com-org-pack.git@feature-x"
+        );
+
+        let remote = Remote::parse("git@github.com:org/pack.git").unwrap();
+        assert_eq!(remote.display_key(), "git-github.com-org-pack.git");
+
+        let remote = Remote::parse("file:///srv/pack").unwrap();
+        assert_eq!(remote.display_key(), "file+srv-pack");
+    }
+
+    #[test]
+    fn test_cache_key_collision() {
+        let keys: Vec<String> = [
+            "https://github.com/org-x/pack",
+            "https://github.com/org/x-pack",
+            "https://github.com/org/x-pack#main",
+            "http://github.com/org/x-pack",
+        ]
+        .iter()
+        .map(|url| Remote::parse(url).unwrap().cache_key())
+        .collect();
+
+        let same_display = |url: &str| Remote::parse(url).unwrap().display_key();
+        assert_eq!(
+            same_display("https://github.com/org-x/pack"),
+            same_display("https://github.com/org/x-pack")
+        );
+        for (index, key) in keys.iter().enumerate() {
+            assert!(keys[index + 1..].iter().all(|other| other != key));
+        }
+    }
+
+    #[test]
+    fn test_registry_remote() {
+        let opt = Opt {
+            template_registry: Some(String::from("https://github.com/my-company/")),
+            ..Opt::default()
+        };
+
+        assert_eq!(
+            remote(&opt, "lib#v1"),
+            Some(Remote {
+                url: String::from("https://github.com/my-company/lib"),
+                reference: Some(String::from("v1")),
+                sha256: None,
+            })
+        );
+        assert_eq!(remote(&opt, "templates/company"), None);
+        assert_eq!(remote(&Opt::default(), "lib#v1"), None);
+    }
+
+    #[test]
+    fn test_registry_pin() {
+        let opt = Opt {
+            template_registry: Some(String::from("https://github.com/my-company")),
+            allow_unverified: true,
+            ..Opt::default()
+        };
+        let remote = remote(&opt, "lib#v1@sha256:00ff").unwrap();
+        assert_eq!(remote.url, "https://github.com/my-company/lib");
+        assert_eq!(remote.reference.as_deref(), Some("v1"));
+        assert_eq!(remote.sha256.as_deref(), Some("00ff"));
+        assert_eq!(
+            remote.cache_key(),
+            Remote::parse("https://github.com/my-company/lib#v1")
+                .unwrap()
+                .cache_key()
+        );
+
+        let pack_dir = std::env::temp_dir().join("cpp-proj-gen-registry-pin");
+        fs::create_dir_all(&pack_dir).unwrap();
+        fs::write(pack_dir.join("a.txt"), "a").unwrap();
+        assert!(verify(&opt, &remote, &pack_dir).is_err());
+
+        let pinned = Remote {
+            sha256: Some(checksum(&pack_dir).unwrap().to_uppercase()),
+            ..remote
+        };
+        assert!(verify(&Opt::default(), &pinned, &pack_dir).is_ok());
+
+        fs::remove_dir_all(&pack_dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_verifies_fetched_tag() {
+        let dir = std::env::temp_dir().join("cpp-proj-gen-fetched-tag");
+        let _ = fs::remove_dir_all(&dir);
+        let origin = dir.join("origin");
+        fs::create_dir_all(&origin).unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            run_git(
+                Command::new("git")
+                    .current_dir(dir)
+                    .args(["-c", "user.name=a", "-c", "user.email=a@b"])
+                    .args(args),
+            )
+            .unwrap()
+        };
+        git(&origin, &["init", "-q"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "one"]);
+        git(&origin, &["tag", "-a", "v1", "-m", "v1"]);
+
+        let url = format!("file://{}#v1", origin.display());
+        let remote = Remote::parse(&url).unwrap();
+        let clone_dir = dir.join("clone");
+        clone(&remote, &clone_dir).unwrap();
+        assert_eq!(signed_object(&remote, &clone_dir), "v1");
+        let old_head = rev_parse(&clone_dir, "HEAD").unwrap();
+
+        // The tag moves, the local one keeps naming the old object
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "two"]);
+        git(&origin, &["tag", "-f", "-a", "v1", "-m", "v1"]);
+        update(&clone_dir).unwrap();
+        let head = rev_parse(&clone_dir, "HEAD").unwrap();
+        git(&clone_dir, &["tag", "-f", "v1", &old_head]);
+
+        assert_eq!(signed_object(&remote, &clone_dir), "FETCH_HEAD");
+        assert_eq!(rev_parse(&clone_dir, "FETCH_HEAD^{commit}").unwrap(), head);
+        assert_ne!(rev_parse(&clone_dir, "v1^{commit}").unwrap(), head);
+        assert!(verify_signature(&remote, &clone_dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_clone_leaves_no_cache_entry() {
+        let cache_dir = std::env::temp_dir().join("cpp-proj-gen-failed-clone");
+        let _ = fs::remove_dir_all(&cache_dir);
+        fs::create_dir_all(&cache_dir).unwrap();
+        let remote = Remote::parse("file:///cpp-proj-gen-missing-pack").unwrap();
+        let clone_dir = cache_dir.join(remote.cache_key());
+
+        assert!(clone_into_cache(&remote, &clone_dir).is_err());
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_local() {
+        let opt = Opt {
+            template_dirs: vec![PathBuf::from("templates/company")],
+            offline: true,
+            ..Opt::default()
+        };
+
+        assert_eq!(resolve(&opt).unwrap(), opt.template_dirs);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let pack_dir = std::env::temp_dir().join("cpp-proj-gen-checksum");
+        fs::create_dir_all(pack_dir.join("docs")).unwrap();
+        fs::create_dir_all(pack_dir.join(".git")).unwrap();
+        fs::write(pack_dir.join("docs/a.md"), "a").unwrap();
+        fs::write(pack_dir.join(".git/HEAD"), "ignored").unwrap();
+        let before = checksum(&pack_dir).unwrap();
+
+        fs::write(pack_dir.join(".git/HEAD"), "changed").unwrap();
+        assert_eq!(checksum(&pack_dir).unwrap(), before);
+        assert_eq!(before.len(), 64);
+
+        fs::write(pack_dir.join("docs/a.md"), "b").unwrap();
+        assert_ne!(checksum(&pack_dir).unwrap(), before);
+    }
+}
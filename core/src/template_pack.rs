@@ -0,0 +1,1117 @@
+/*
+For toml /sa https://docs.rs/toml/0.8/toml/
+For Command /sa https://doc.rust-lang.org/std/process/struct.Command.html
+*/
+
+use crate::cancel::CancelToken;
+use crate::cmake_version::Version;
+use crate::cpp_proj_gen::{self, CppProjGen, Kind, Opt};
+use crate::encoding::{self, TemplateContents};
+use crate::error::{self, invalid_input, Error};
+use crate::file_mode;
+use crate::platforms::Platform;
+use crate::regex::Regex;
+use crate::reporter::{GenEvent, Reporter};
+use crate::template_vars::VarValue;
+use crate::tools::Tool;
+use crate::warning::Warning;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const MANIFEST_FILE_NAME: &str = "template.toml";
+// Invalid answers in --interactive mode are asked again this often
+const PROMPT_ATTEMPTS: usize = 3;
+
+// Template pack manifest, e.g.
+//
+// name = "company"
+// kinds = ["static", "shared"]
+// post-hooks = ["git init"]
+//
+// [variables.VENDOR]
+// description = "Vendor name"
+// required = true
+// pattern = "[A-Za-z][A-Za-z0-9_]*"
+//
+// [variables.LEVEL]
+// type = "enum"
+// values = ["low", "high"]
+// default = "low"
+//
+// [tools]
+// cmake = "3.20.0"
+//
+// [optional-tools]
+// doxygen = "1.9.0"
+//
+// [[files]]
+// path = "docs/shared-library.md"
+// condition = "kind == shared"
+//
+// [[files]]
+// path = "res/app.icns"
+// binary = true
+//
+// [[files]]
+// path = "tools/release"
+// executable = true
+//
+// [[files]]
+// path = "include/latest"
+// link = "v2"
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Manifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    // Supported project kinds, all if empty
+    pub kinds: Vec<String>,
+    pub variables: BTreeMap<String, Variable>,
+    // Program and minimum version
+    pub tools: BTreeMap<String, String>,
+    // Like tools, missing ones are warnings
+    pub optional_tools: BTreeMap<String, String>,
+    // Shell commands run in the generated project
+    pub post_hooks: Vec<String>,
+    // Extra files of the pack
+    pub files: Vec<TemplateFile>,
+    // Extensions of files copied as they are, besides e.g. png and ico
+    pub binary_extensions: Vec<String>,
+}
+
+// Template variable, rendered as @NAME@
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Variable {
+    pub description: Option<String>,
+    pub default: Option<String>,
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub variable_type: VariableType,
+    // Choices of an enum
+    pub values: Vec<String>,
+    // Regular expression the whole value has to match
+    pub pattern: Option<String>,
+    // Asked in --interactive mode, defaults to the description
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VariableType {
+    #[default]
+    String,
+    // true or false, also accepting on/off, yes/no and 1/0
+    Bool,
+    // One of values
+    Enum,
+    // e.g. 1.2.3 or 1.0.0-rc.1+build.5
+    Semver,
+    // Comma-separated, e.g. a.cpp,b.cpp
+    List,
+}
+
+impl Variable {
+    // The value as rendered, e.g. true of a bool given as ON, or why it is invalid
+    pub fn check(&self, value: &str) -> Result<String, String> {
+        let value = match self.variable_type {
+            VariableType::String => String::from(value),
+            VariableType::Bool => match value.to_ascii_lowercase().as_str() {
+                "true" | "on" | "yes" | "1" => String::from("true"),
+                "false" | "off" | "no" | "0" => String::from("false"),
+                _ => return Err(String::from("expected a boolean, e.g. true or false")),
+            },
+            VariableType::Enum if self.values.iter().any(|choice| choice == value) => {
+                String::from(value)
+            }
+            VariableType::Enum => {
+                return Err(format!("expected one of: {}", self.values.join(", ")));
+            }
+            VariableType::Semver if is_semver(value) => String::from(value),
+            VariableType::Semver => {
+                return Err(String::from(
+                    "expected a semantic version, e.g. 1.2.3 or 1.0.0-rc.1",
+                ));
+            }
+            VariableType::List => split_list(value).join(","),
+        };
+
+        // Per item of a list
+        match &self.pattern {
+            Some(pattern) => {
+                let regex = Regex::new(pattern)?;
+                let items = match self.variable_type {
+                    VariableType::List => split_list(&value),
+                    _ => vec![value.as_str()],
+                };
+                match items.iter().all(|item| regex.is_match(item)) {
+                    true => Ok(value),
+                    false => Err(format!("expected a match of the pattern '{}'", pattern)),
+                }
+            }
+            None => Ok(value),
+        }
+    }
+
+    // Checked value as rendered, e.g. a bool as ON or OFF by @NAME|cmake@
+    pub fn value(&self, checked: &str) -> VarValue {
+        match self.variable_type {
+            VariableType::Bool => VarValue::Bool(checked == "true"),
+            VariableType::List => {
+                VarValue::List(split_list(checked).into_iter().map(String::from).collect())
+            }
+            VariableType::String | VariableType::Enum | VariableType::Semver => {
+                VarValue::from(checked)
+            }
+        }
+    }
+
+    // Declarations the values can never satisfy
+    fn validate(&self) -> Result<(), String> {
+        if self.variable_type == VariableType::Enum && self.values.is_empty() {
+            return Err(String::from("an enum needs values"));
+        }
+        if self.variable_type != VariableType::Enum && !self.values.is_empty() {
+            return Err(String::from("values need type = \"enum\""));
+        }
+        if let Some(pattern) = &self.pattern {
+            Regex::new(pattern)?;
+        }
+        if let Some(default) = &self.default {
+            self.check(default)
+                .map_err(|reason| format!("default '{}': {}", default, reason))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Asks for the value of a missing required variable, again with the reason the last answer was invalid
+pub type Prompt<'a> = &'a mut dyn FnMut(&str, &Variable, Option<&str>) -> io::Result<String>;
+
+// File relative to the pack, written to the same path in the project
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TemplateFile {
+    pub path: PathBuf,
+    // Path of the file in its pack, set on load
+    #[serde(skip)]
+    pub source: PathBuf,
+    // Canonical directory of its pack, set on load
+    #[serde(skip)]
+    pub pack_dir: PathBuf,
+    // e.g. "tests == true && kind != header-only", always written if unset
+    pub condition: Option<String>,
+    // Copied as it is if true, rendered if false, by extension and contents if unset
+    pub binary: Option<bool>,
+    // Sets or clears the executable bit on Unix, as in the pack or by shebang if unset
+    pub executable: Option<bool>,
+    // Symlink target relative to the file's directory, the pack needs no file then
+    pub link: Option<PathBuf>,
+}
+
+impl Manifest {
+    // Stacked packs, later manifests overlay earlier ones
+    pub fn load(template_dirs: &[PathBuf]) -> error::Result<Option<Self>> {
+        let mut result: Option<Self> = None;
+
+        for template_dir in template_dirs {
+            if let Some(manifest) = Self::load_one(template_dir)? {
+                result = Some(match result {
+                    Some(base) => base.overlay(manifest),
+                    None => manifest,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    // The manifest is optional, packs without one accept everything
+    fn load_one(template_dir: &Path) -> error::Result<Option<Self>> {
+        let path = template_dir.join(MANIFEST_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path).map_err(Error::fs("read", &path))?;
+        let mut manifest = parse_manifest(&contents, &path)?;
+        let pack_dir = template_dir
+            .canonicalize()
+            .map_err(Error::fs("read", template_dir))?;
+        for file in &mut manifest.files {
+            file.source = template_dir.join(&file.path);
+            file.pack_dir = pack_dir.clone();
+        }
+
+        Ok(Some(manifest))
+    }
+
+    // Overlay values win, lists of hooks are appended, files with the same path replaced
+    fn overlay(mut self, overlay: Manifest) -> Self {
+        self.name = overlay.name.or(self.name);
+        self.description = overlay.description.or(self.description);
+        if !overlay.kinds.is_empty() {
+            self.kinds = overlay.kinds;
+        }
+        self.variables.extend(overlay.variables);
+        self.tools.extend(overlay.tools);
+        self.optional_tools.extend(overlay.optional_tools);
+        self.post_hooks.extend(overlay.post_hooks);
+        self.binary_extensions.extend(overlay.binary_extensions);
+        for file in overlay.files {
+            self.files.retain(|other| other.path != file.path);
+            self.files.push(file);
+        }
+
+        self
+    }
+
+    pub fn validate(&self, opt: &Opt) -> io::Result<()> {
+        for kind in &self.kinds {
+            kind.parse::<Kind>().map_err(invalid_data)?;
+        }
+
+        for (name, variable) in &self.variables {
+            variable.validate().map_err(|reason| {
+                invalid_data(format!(
+                    "template pack {}, variable {}: {}",
+                    self.display_name(),
+                    name,
+                    reason
+                ))
+            })?;
+        }
+
+        if !self.kinds.is_empty() && !self.kinds.iter().any(|kind| kind == opt.kind.name()) {
+            return Err(invalid_input(format!(
+                "template pack {} does not support kind {}, expected one of: {}",
+                self.display_name(),
+                opt.kind.name(),
+                self.kinds.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Defines win over defaults, missing required variables are prompted for or fail
+    // Values are checked against the declarations, e.g. bools become true or false
+    pub fn resolve_variables(
+        &self,
+        defines: &[(String, String)],
+        mut prompt: Option<Prompt>,
+    ) -> io::Result<BTreeMap<String, String>> {
+        let mut values: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in defines {
+            let value = match self.variables.get(name) {
+                Some(variable) => variable.check(value).map_err(|reason| {
+                    invalid_input(format!("-D {}={}: {}", name, value, reason))
+                })?,
+                None => value.clone(),
+            };
+            values.insert(name.clone(), value);
+        }
+        let mut missing = Vec::new();
+
+        for (name, variable) in &self.variables {
+            if values.contains_key(name) {
+                continue;
+            }
+
+            match (&variable.default, prompt.as_mut()) {
+                (Some(default), _) => {
+                    values.insert(name.clone(), variable.check(default).map_err(invalid_data)?);
+                }
+                (None, Some(prompt)) if variable.required => {
+                    values.insert(name.clone(), ask(prompt, name, variable)?);
+                }
+                (None, None) if variable.required => missing.push(name.as_str()),
+                (None, _) => {}
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(invalid_input(format!(
+                "template pack {} needs {}, pass -D NAME=VALUE or --interactive",
+                self.display_name(),
+                missing.join(", ")
+            )));
+        }
+
+        Ok(values)
+    }
+
+    // Typed var of a resolved value, text if undeclared
+    pub fn var_value(&self, name: &str, value: &str) -> VarValue {
+        match self.variables.get(name) {
+            Some(variable) => variable.value(value),
+            None => VarValue::from(value),
+        }
+    }
+
+    // Declared or a key of a file condition, unlike vars only set with -D
+    pub fn uses_variable(&self, name: &str) -> bool {
+        let in_condition = |condition: &String| {
+            condition.split("&&").any(|term| {
+                term.split(['=', '!'])
+                    .next()
+                    .is_some_and(|key| key.trim() == name)
+            })
+        };
+
+        self.variables.contains_key(name)
+            || self
+                .files
+                .iter()
+                .filter_map(|file| file.condition.as_ref())
+                .any(in_condition)
+    }
+
+    pub fn tools(&self) -> io::Result<Vec<Tool>> {
+        let required = self.tools.iter().map(|tool| (tool, false));
+        let optional = self.optional_tools.iter().map(|tool| (tool, true));
+
+        required
+            .chain(optional)
+            .map(|((program, min_version), optional)| {
+                Ok(Tool {
+                    name: program.clone(),
+                    programs: vec![program.clone()],
+                    min_version: Some(min_version.parse::<Version>().map_err(invalid_data)?),
+                    hint: match optional {
+                        true => "used by the template pack if found",
+                        false => "required by the template pack",
+                    },
+                    optional,
+                })
+            })
+            .collect()
+    }
+
+    // Runs every hook through the shell, stops at the first failing one or once cancelled
+    // The CLI built with the async feature awaits run_post_hooks_async() instead
+    pub fn run_post_hooks(
+        &self,
+        project_dir: &Path,
+        reporter: &mut dyn Reporter,
+        cancel: &CancelToken,
+    ) -> error::Result<()> {
+        for hook in &self.post_hooks {
+            cancel.check()?;
+            reporter.event(&GenEvent::HookStarted {
+                command: hook.clone(),
+            });
+
+            let status = shell_command(hook)
+                .current_dir(project_dir)
+                .status()
+                .map_err(Error::fs("run post-hook in", project_dir))?;
+            reporter.event(&GenEvent::HookFinished {
+                command: hook.clone(),
+                success: status.success(),
+            });
+
+            if !status.success() {
+                return Err(Error::HookFailed {
+                    command: hook.clone(),
+                    status: status.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Adds the files whose conditions hold, their paths and contents get the vars replaced
+    pub fn add_files(
+        &self,
+        gen: CppProjGen,
+        variables: &BTreeMap<String, String>,
+        reporter: &mut dyn Reporter,
+    ) -> error::Result<CppProjGen> {
+        let mut gen = gen;
+
+        for file in &self.files {
+            gen.cancel_token().check()?;
+            if let Some(condition) = &file.condition {
+                if !evaluate_condition(condition, gen.opt(), variables)? {
+                    reporter.event(&GenEvent::Skipped {
+                        path: file.path.clone(),
+                        reason: format!("condition '{}' does not hold", condition),
+                    });
+                    continue;
+                }
+            }
+
+            if gen.has_file(&file.path) {
+                gen = gen.add_warning(Warning::OverwrittenFile {
+                    path: file.path.clone(),
+                });
+            }
+
+            // Declared in the manifest or a symlink in the pack
+            let link = match &file.link {
+                Some(target) => Some(target.clone()),
+                None => match fs::symlink_metadata(&file.source) {
+                    Ok(metadata) if metadata.is_symlink() => {
+                        Some(fs::read_link(&file.source).map_err(Error::fs("read", &file.source))?)
+                    }
+                    _ => None,
+                },
+            };
+            if let Some(target) = link {
+                gen = gen.add_link(file.path.clone(), target);
+                continue;
+            }
+            check_in_pack(file)?;
+
+            let binary = file.binary.or_else(|| {
+                encoding::has_binary_extension(&file.path, &self.binary_extensions).then_some(true)
+            });
+            let executable = file.executable.or_else(|| {
+                fs::metadata(&file.source)
+                    .is_ok_and(|metadata| file_mode::is_executable(&metadata))
+                    .then_some(true)
+            });
+            if let Some(executable) = executable {
+                gen = gen.set_executable(file.path.clone(), executable);
+            }
+            gen = match encoding::read_template(&file.source, binary, gen.opt().keep_bom)? {
+                TemplateContents::Text(template) => gen.add_file(file.path.clone(), template),
+                TemplateContents::Binary(contents) => {
+                    gen.add_binary_file(file.path.clone(), contents)
+                }
+                TemplateContents::Large { binary } => {
+                    gen.add_streamed_file(file.path.clone(), file.source.clone(), binary)
+                }
+            };
+        }
+
+        Ok(gen)
+    }
+
+    fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("in --template")
+    }
+}
+
+// Asks again after invalid answers, naming the reason
+fn ask(prompt: &mut Prompt, name: &str, variable: &Variable) -> io::Result<String> {
+    let mut reason: Option<String> = None;
+
+    for _ in 0..PROMPT_ATTEMPTS {
+        match variable.check(&prompt(name, variable, reason.as_deref())?) {
+            Ok(value) => return Ok(value),
+            Err(message) => reason = Some(message),
+        }
+    }
+
+    Err(invalid_input(format!(
+        "no valid value for {} after {} attempts, {}",
+        name,
+        PROMPT_ATTEMPTS,
+        reason.unwrap_or_default()
+    )))
+}
+
+// MAJOR.MINOR.PATCH without leading zeros, optionally with -PRERELEASE and +BUILD identifiers
+fn is_semver(text: &str) -> bool {
+    let (version, build) = match text.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (text, None),
+    };
+    let (core, pre_release) = match version.split_once('-') {
+        Some((core, pre_release)) => (core, Some(pre_release)),
+        None => (version, None),
+    };
+    let is_number = |part: &str| {
+        !part.is_empty()
+            && part.chars().all(|c| c.is_ascii_digit())
+            && (part == "0" || !part.starts_with('0'))
+    };
+    let are_identifiers = |identifiers: &str| {
+        identifiers.split('.').all(|identifier| {
+            !identifier.is_empty()
+                && identifier
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    };
+
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| is_number(part))
+        && pre_release.is_none_or(are_identifiers)
+        && build.is_none_or(are_identifiers)
+}
+
+// Trimmed items, empty ones dropped
+fn split_list(text: &str) -> Vec<&str> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+// e.g. -D VENDOR=acme
+pub fn parse_define(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((String::from(name), String::from(value))),
+        _ => Err(format!("expected NAME=VALUE, got '{}'", text)),
+    }
+}
+
+// Terms joined by &&, e.g. "kind == shared && VENDOR != acme"
+fn evaluate_condition(
+    condition: &str,
+    opt: &Opt,
+    variables: &BTreeMap<String, String>,
+) -> io::Result<bool> {
+    condition.split("&&").try_fold(true, |holds, term| {
+        Ok(holds && evaluate_term(term.trim(), opt, variables)?)
+    })
+}
+
+fn evaluate_term(term: &str, opt: &Opt, variables: &BTreeMap<String, String>) -> io::Result<bool> {
+    let (key, expected, equal) = match (term.split_once("!="), term.split_once("==")) {
+        (Some((key, expected)), _) => (key.trim(), expected.trim(), false),
+        (None, Some((key, expected))) => (key.trim(), expected.trim(), true),
+        (None, None) => {
+            return Err(invalid_data(format!(
+                "condition '{}' is not of the form KEY == VALUE or KEY != VALUE",
+                term
+            )))
+        }
+    };
+
+    // Holds if the platform is one of the targeted ones
+    if key == "platform" {
+        let platform = expected
+            .trim_matches('"')
+            .parse::<Platform>()
+            .map_err(invalid_data)?;
+        return Ok(opt.platforms.contains(&platform) == equal);
+    }
+
+    let actual = condition_value(key, opt, variables).ok_or_else(|| {
+        invalid_data(format!(
+            "unknown key '{}' in condition, expected an option or template variable",
+            key
+        ))
+    })?;
+
+    Ok((actual == expected.trim_matches('"')) == equal)
+}
+
+// Options by their command line names, template variables by their names
+fn condition_value(key: &str, opt: &Opt, variables: &BTreeMap<String, String>) -> Option<String> {
+    let value = match key {
+        "kind" => opt.kind.name(),
+        "language" => opt.language.name(),
+        "tests" => match opt.test_framework {
+            Some(_) => "true",
+            None => "false",
+        },
+        "test-framework" => opt
+            .test_framework
+            .map_or("none", |framework| framework.name()),
+        "preset" => opt.preset.map_or("none", |preset| preset.name()),
+        _ => return variables.get(key).cloned(),
+    };
+
+    Some(String::from(value))
+}
+
+pub fn shell_command(hook: &str) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(hook);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(hook);
+        command
+    }
+}
+
+// File paths with '..' or absolute ones would read and write outside the pack and project
+fn parse_manifest(contents: &str, path: &Path) -> error::Result<Manifest> {
+    let manifest: Manifest = toml::from_str(contents)
+        .map_err(|err| invalid_data(format!("{}: {}", path.display(), err.message())))?;
+
+    match manifest
+        .files
+        .iter()
+        .find(|file| !cpp_proj_gen::is_below_root(&file.path))
+    {
+        Some(file) => Err(Error::PathEscape {
+            path: file.path.clone(),
+        }),
+        None => Ok(manifest),
+    }
+}
+
+// A symlinked directory of the pack could lead the file outside of it
+fn check_in_pack(file: &TemplateFile) -> error::Result<()> {
+    let source = file
+        .source
+        .canonicalize()
+        .map_err(Error::fs("read", &file.source))?;
+
+    match source.starts_with(&file.pack_dir) {
+        true => Ok(()),
+        false => Err(Error::PathEscape {
+            path: file.path.clone(),
+        }),
+    }
+}
+
+fn invalid_data<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpp_proj_gen::PlanEntry;
+    use crate::test_util::entry;
+
+    const MANIFEST: &str = r#"
+name = "company"
+kinds = ["static", "shared"]
+post-hooks = ["git init"]
+
+[variables.VENDOR]
+description = "Vendor name"
+required = true
+
+[variables.SUPPORT_URL]
+default = "https://example.com"
+pattern = "https?://.+"
+
+[variables.LEVEL]
+type = "enum"
+values = ["low", "high"]
+default = "low"
+
+[variables.SHARED]
+type = "bool"
+default = "ON"
+
+[variables.API_VERSION]
+type = "semver"
+prompt = "Version of the API"
+
+[tools]
+cmake = "3.20.0"
+
+[optional-tools]
+doxygen = "1.9.0"
+"#;
+
+    fn create_test_manifest() -> Manifest {
+        parse_manifest(MANIFEST, Path::new("template.toml")).unwrap()
+    }
+
+    #[test]
+    fn test_uses_variable() {
+        let contents = "[[files]]\npath = \"a.md\"\ncondition = \"kind == shared && ABI != none\"";
+        let manifest = parse_manifest(contents, Path::new("template.toml")).unwrap();
+        assert!(manifest.uses_variable("ABI"));
+        assert!(!manifest.uses_variable("VENDOR"));
+
+        assert!(create_test_manifest().uses_variable("VENDOR"));
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = create_test_manifest();
+
+        assert_eq!(manifest.name.as_deref(), Some("company"));
+        assert!(manifest.variables["VENDOR"].required);
+        let tools = manifest.tools().unwrap();
+        assert_eq!(tools[0].min_version, Some(Version::new(3, 20, 0)));
+        assert!(!tools[0].optional);
+        assert_eq!(tools[1].name, "doxygen");
+        assert!(tools[1].optional);
+        assert!(parse_manifest("unknown = 1", Path::new("template.toml")).is_err());
+        for path in &["../escape.txt", "docs/../../escape.txt", "/etc/escape.txt"] {
+            let contents = format!("[[files]]\npath = \"{}\"", path);
+            assert!(matches!(
+                parse_manifest(&contents, Path::new("template.toml")),
+                Err(Error::PathEscape { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_overlay() {
+        let overlay = parse_manifest(
+            r#"
+kinds = ["shared"]
+post-hooks = ["git add ."]
+
+[variables.VENDOR]
+default = "acme"
+"#,
+            Path::new("template.toml"),
+        )
+        .unwrap();
+        let manifest = create_test_manifest().overlay(overlay);
+
+        assert_eq!(manifest.name.as_deref(), Some("company"));
+        assert_eq!(manifest.kinds, vec![String::from("shared")]);
+        assert_eq!(manifest.post_hooks.len(), 2);
+        assert_eq!(
+            manifest.variables["VENDOR"].default.as_deref(),
+            Some("acme")
+        );
+        assert!(manifest.variables.contains_key("SUPPORT_URL"));
+    }
+
+    #[test]
+    fn test_add_binary_files() {
+        let template_dir = std::env::temp_dir().join("cpp-proj-gen-test-binary-files");
+        let _ = fs::remove_dir_all(&template_dir);
+        fs::create_dir_all(template_dir.join("res")).unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE_NAME),
+            "binary-extensions = [\"dat\"]\n\
+             [[files]]\npath = \"res/logo.png\"\n\
+             [[files]]\npath = \"res/blob.dat\"\n\
+             [[files]]\npath = \"res/@CMAKE_TARGET_NAME@.txt\"\n\
+             [[files]]\npath = \"res/raw.txt\"\nbinary = true\nexecutable = true\n",
+        )
+        .unwrap();
+        for name in &["logo.png", "blob.dat", "@CMAKE_TARGET_NAME@.txt", "raw.txt"] {
+            fs::write(template_dir.join("res").join(name), "@CMAKE_TARGET_NAME@").unwrap();
+        }
+
+        let manifest = Manifest::load(std::slice::from_ref(&template_dir))
+            .unwrap()
+            .unwrap();
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+        let plan = manifest
+            .add_files(
+                CppProjGen::new(opt),
+                &BTreeMap::new(),
+                &mut Vec::<GenEvent>::new(),
+            )
+            .unwrap()
+            .plan()
+            .unwrap();
+        fs::remove_dir_all(&template_dir).unwrap();
+
+        let contents = |name: &str| entry(&plan, &format!("res/{}", name)).cloned();
+        for name in &["logo.png", "blob.dat", "raw.txt"] {
+            assert!(
+                matches!(contents(name), Some(PlanEntry::Binary { contents, executable, .. }) if contents == b"@CMAKE_TARGET_NAME@" && executable == (*name == "raw.txt")),
+                "{}",
+                name
+            );
+        }
+        assert!(matches!(
+            contents("tgtnm.txt"),
+            Some(PlanEntry::File { contents, .. }) if contents == "tgtnm\n"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_add_files_warnings() {
+        let template_dir = std::env::temp_dir().join("cpp-proj-gen-test-files-warnings");
+        let _ = fs::remove_dir_all(&template_dir);
+        fs::create_dir_all(template_dir.join("source")).unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE_NAME),
+            "[[files]]\npath = \"source/tgtnm.cpp\"\n[[files]]\npath = \"source/extra.cpp\"\n",
+        )
+        .unwrap();
+        for name in &["tgtnm.cpp", "extra.cpp"] {
+            fs::write(template_dir.join("source").join(name), "").unwrap();
+        }
+
+        let manifest = Manifest::load(std::slice::from_ref(&template_dir))
+            .unwrap()
+            .unwrap();
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            sample_sources: true,
+            ..Opt::default()
+        };
+        let warnings = manifest
+            .add_files(
+                CppProjGen::new(opt).add_source_dir(PathBuf::from("source")),
+                &BTreeMap::new(),
+                &mut Vec::<GenEvent>::new(),
+            )
+            .unwrap()
+            .warnings()
+            .unwrap();
+        fs::remove_dir_all(&template_dir).unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![Warning::OverwrittenFile {
+                path: PathBuf::from("source/tgtnm.cpp")
+            }]
+        );
+    }
+
+    #[test]
+    fn test_add_links() {
+        let template_dir = std::env::temp_dir().join("cpp-proj-gen-test-links");
+        let _ = fs::remove_dir_all(&template_dir);
+        fs::create_dir_all(template_dir.join("include/v2")).unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE_NAME),
+            "[[files]]\npath = \"include/latest\"\n\
+             [[files]]\npath = \"include/current\"\nlink = \"v2\"\n",
+        )
+        .unwrap();
+        std::os::unix::fs::symlink("v2", template_dir.join("include/latest")).unwrap();
+
+        let manifest = Manifest::load(std::slice::from_ref(&template_dir))
+            .unwrap()
+            .unwrap();
+        let opt = Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+        let plan = manifest
+            .add_files(
+                CppProjGen::new(opt),
+                &BTreeMap::new(),
+                &mut Vec::<GenEvent>::new(),
+            )
+            .unwrap()
+            .plan()
+            .unwrap();
+        fs::remove_dir_all(&template_dir).unwrap();
+
+        for name in &["current", "latest"] {
+            assert!(plan.contains(&PlanEntry::Link {
+                path: Path::new("test_out_dir/tgtnm/include").join(name),
+                target: PathBuf::from("v2"),
+            }));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_add_files_symlinked_dir() {
+        let test_dir = std::env::temp_dir().join("cpp-proj-gen-test-symlinked-dir");
+        let _ = fs::remove_dir_all(&test_dir);
+        let template_dir = test_dir.join("pack");
+        fs::create_dir_all(template_dir.join("include/v2")).unwrap();
+        fs::create_dir_all(test_dir.join("secrets")).unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE_NAME),
+            "[[files]]\npath = \"include/latest/api.h\"\n\
+             [[files]]\npath = \"res/key.pem\"\n",
+        )
+        .unwrap();
+        fs::write(template_dir.join("include/v2/api.h"), "").unwrap();
+        fs::write(test_dir.join("secrets/key.pem"), "").unwrap();
+        std::os::unix::fs::symlink("v2", template_dir.join("include/latest")).unwrap();
+        std::os::unix::fs::symlink("../secrets", template_dir.join("res")).unwrap();
+
+        let manifest = Manifest::load(std::slice::from_ref(&template_dir))
+            .unwrap()
+            .unwrap();
+        let result = manifest.add_files(
+            CppProjGen::new(Opt::default()),
+            &BTreeMap::new(),
+            &mut Vec::<GenEvent>::new(),
+        );
+        let inside = check_in_pack(&manifest.files[0]);
+        fs::remove_dir_all(&test_dir).unwrap();
+
+        assert!(inside.is_ok());
+        assert!(matches!(
+            result,
+            Err(Error::PathEscape { path }) if path == Path::new("res/key.pem")
+        ));
+    }
+
+    #[test]
+    fn test_validate() {
+        let manifest = create_test_manifest();
+
+        assert!(manifest.validate(&Opt::default()).is_ok());
+
+        let opt = Opt {
+            kind: Kind::Executable,
+            ..Opt::default()
+        };
+        assert!(manifest.validate(&opt).is_err());
+    }
+
+    #[test]
+    fn test_resolve_variables() {
+        let manifest = create_test_manifest();
+
+        assert!(manifest.resolve_variables(&[], None).is_err());
+
+        let defines = vec![(String::from("VENDOR"), String::from("acme"))];
+        let values = manifest.resolve_variables(&defines, None).unwrap();
+        assert_eq!(values["VENDOR"], "acme");
+        assert_eq!(values["SUPPORT_URL"], "https://example.com");
+
+        let mut prompt = |_: &str, _: &Variable, _: Option<&str>| Ok(String::from("prompted"));
+        let values = manifest.resolve_variables(&[], Some(&mut prompt)).unwrap();
+        assert_eq!(values["VENDOR"], "prompted");
+    }
+
+    #[test]
+    fn test_check_variables() {
+        let manifest = create_test_manifest();
+
+        let values = manifest
+            .resolve_variables(&[(String::from("VENDOR"), String::from("acme"))], None)
+            .unwrap();
+        assert_eq!(values["LEVEL"], "low");
+        assert_eq!(values["SHARED"], "true");
+        assert!(!values.contains_key("API_VERSION"));
+
+        for (name, value) in &[
+            ("LEVEL", "medium"),
+            ("SHARED", "maybe"),
+            ("API_VERSION", "1.2"),
+            ("SUPPORT_URL", "ftp://example.com"),
+        ] {
+            let defines = vec![
+                (String::from("VENDOR"), String::from("acme")),
+                (String::from(*name), String::from(*value)),
+            ];
+            let err = manifest.resolve_variables(&defines, None).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with(&format!("-D {}={}: expected", name, value)));
+        }
+
+        let variable = &manifest.variables["API_VERSION"];
+        assert_eq!(
+            variable.check("1.0.0-rc.1+build.5"),
+            Ok(String::from("1.0.0-rc.1+build.5"))
+        );
+        for version in &["01.2.3", "1.2.3-", "1.2.3+a..b", "v1.2.3"] {
+            assert!(variable.check(version).is_err());
+        }
+    }
+
+    #[test]
+    fn test_var_value() {
+        let manifest = create_test_manifest();
+        assert_eq!(manifest.var_value("SHARED", "true"), VarValue::Bool(true));
+        assert_eq!(manifest.var_value("LEVEL", "low"), VarValue::from("low"));
+        assert_eq!(manifest.var_value("UNDECLARED", "x"), VarValue::from("x"));
+
+        let variable = Variable {
+            variable_type: VariableType::List,
+            pattern: Some(String::from("[a-z]+\\.cpp")),
+            ..Variable::default()
+        };
+        let checked = variable.check(" a.cpp, b.cpp,").unwrap();
+        assert_eq!(checked, "a.cpp,b.cpp");
+        assert_eq!(
+            variable.value(&checked),
+            VarValue::List(vec![String::from("a.cpp"), String::from("b.cpp")])
+        );
+        assert!(variable.check("a.cpp,B.h").is_err());
+    }
+
+    #[test]
+    fn test_validate_variables() {
+        for declaration in &[
+            "type = \"enum\"",
+            "values = [\"a\"]",
+            "pattern = \"(a\"",
+            "type = \"bool\"\ndefault = \"maybe\"",
+            "pattern = \"[a-z]+\"\ndefault = \"ABC\"",
+        ] {
+            let contents = format!("[variables.X]\n{}", declaration);
+            let manifest = parse_manifest(&contents, Path::new("template.toml")).unwrap();
+            assert!(
+                manifest.validate(&Opt::default()).is_err(),
+                "{}",
+                declaration
+            );
+        }
+        assert!(parse_manifest(
+            "[variables.X]\ntype = \"number\"",
+            Path::new("template.toml")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_ask() {
+        let variable = Variable {
+            variable_type: VariableType::Enum,
+            values: vec![String::from("low"), String::from("high")],
+            ..Variable::default()
+        };
+
+        let mut answers = vec!["high", "medium"];
+        let mut reasons = Vec::new();
+        let mut answer = |_: &str, _: &Variable, reason: Option<&str>| {
+            reasons.push(reason.map(String::from));
+            Ok(String::from(answers.pop().unwrap()))
+        };
+        let mut prompt: Prompt = &mut answer;
+        assert_eq!(ask(&mut prompt, "LEVEL", &variable).unwrap(), "high");
+        assert_eq!(reasons.len(), 2);
+        assert_eq!(reasons[0], None);
+        assert!(reasons[1].is_some());
+
+        let mut answer = |_: &str, _: &Variable, _: Option<&str>| Ok(String::from("medium"));
+        let mut prompt: Prompt = &mut answer;
+        assert!(ask(&mut prompt, "LEVEL", &variable).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_condition() {
+        let opt = Opt {
+            kind: Kind::Shared,
+            platforms: vec![Platform::Win],
+            ..Opt::default()
+        };
+        let variables = [(String::from("VENDOR"), String::from("acme"))]
+            .iter()
+            .cloned()
+            .collect();
+
+        assert!(evaluate_condition("kind == shared", &opt, &variables).unwrap());
+        assert!(!evaluate_condition("kind == shared && tests == true", &opt, &variables).unwrap());
+        assert!(evaluate_condition("VENDOR != \"other\"", &opt, &variables).unwrap());
+        assert!(evaluate_condition("unknown == 1", &opt, &variables).is_err());
+        assert!(evaluate_condition("kind", &opt, &variables).is_err());
+        assert!(
+            evaluate_condition("platform == win && platform != macos", &opt, &variables).unwrap()
+        );
+        assert!(evaluate_condition("platform == amiga", &opt, &variables).is_err());
+    }
+
+    #[test]
+    fn test_parse_define() {
+        assert_eq!(
+            parse_define("A=b=c").unwrap(),
+            (String::from("A"), String::from("b=c"))
+        );
+        assert!(parse_define("=b").is_err());
+        assert!(parse_define("A").is_err());
+    }
+}
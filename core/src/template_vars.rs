@@ -0,0 +1,255 @@
+/*
+For CMake booleans /sa https://cmake.org/cmake/help/latest/command/if.html#basic-expressions
+For CMake lists /sa https://cmake.org/cmake/help/latest/manual/cmake-language.7.html#lists
+For Aho-Corasick /sa https://docs.rs/aho-corasick/1/aho_corasick/struct.AhoCorasick.html
+*/
+
+use aho_corasick::{AhoCorasick, Input, MatchKind};
+use std::collections::BTreeMap;
+
+// Value of a template var, rendered as @NAME@ or through filters as @NAME|cmake@
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarValue {
+    Text(String),
+    // true or false, ON or OFF through |cmake
+    Bool(bool),
+    // ;-separated like a CMake list, newline-joined through |lines
+    List(Vec<String>),
+}
+
+impl VarValue {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            VarValue::Text(text) => Some(text),
+            VarValue::Bool(_) | VarValue::List(_) => None,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        match self {
+            VarValue::Text(text) => text.clone(),
+            VarValue::Bool(value) => value.to_string(),
+            VarValue::List(items) => items.join(";"),
+        }
+    }
+
+    // cmake, lines, upper, lower or quote, None for an unknown filter
+    pub fn filter(&self, filter: &str) -> Option<VarValue> {
+        let text = |text: String| Some(VarValue::Text(text));
+
+        match (filter, self) {
+            ("cmake", VarValue::Bool(true)) => text(String::from("ON")),
+            ("cmake", VarValue::Bool(false)) => text(String::from("OFF")),
+            ("cmake", VarValue::List(items)) => text(items.join(";")),
+            ("lines", VarValue::List(items)) => text(items.join("\n")),
+            ("cmake", _) | ("lines", _) => Some(self.clone()),
+            ("upper", _) => Some(self.map_items(|item| item.to_uppercase())),
+            ("lower", _) => Some(self.map_items(|item| item.to_lowercase())),
+            ("quote", _) => Some(self.map_items(quote)),
+            _ => None,
+        }
+    }
+
+    // Lists per item, bools as their text
+    fn map_items(&self, f: impl Fn(&str) -> String) -> VarValue {
+        match self {
+            VarValue::List(items) => VarValue::List(items.iter().map(|item| f(item)).collect()),
+            _ => VarValue::Text(f(&self.render())),
+        }
+    }
+}
+
+impl From<&str> for VarValue {
+    fn from(text: &str) -> Self {
+        VarValue::Text(String::from(text))
+    }
+}
+
+// e.g. "say \"hi\"" as a C++ or CMake string literal
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Whether contents use the var keyed as @NAME@, filtered or not
+pub fn uses_var(contents: &str, var: &str) -> bool {
+    contents.contains(var)
+        || var
+            .strip_suffix('@')
+            .is_some_and(|name| contents.contains(&format!("{}|", name)))
+}
+
+// What a pattern of the replacer stands for
+#[derive(Debug, Clone, PartialEq)]
+enum Replacement {
+    // @NAME@, rendered once
+    Plain(String),
+    // @NAME| of a filtered use
+    Filtered(VarValue),
+}
+
+// Vars keyed as @NAME@ compiled into one automaton, replaced in a single pass over the contents
+// Values are not scanned again, so a value containing @OTHER@ stays as it is
+// Filtered uses with unknown filters are left as they are
+#[derive(Debug, Clone)]
+pub struct VarReplacer {
+    searcher: Option<AhoCorasick>,
+    patterns: Vec<String>,
+    replacements: Vec<Replacement>,
+}
+
+// Same vars, the automaton follows from them
+impl PartialEq for VarReplacer {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns == other.patterns && self.replacements == other.replacements
+    }
+}
+
+impl VarReplacer {
+    pub fn new(vars: &BTreeMap<String, VarValue>) -> Self {
+        let mut patterns = Vec::new();
+        let mut replacements = Vec::new();
+
+        for (var, value) in vars {
+            patterns.push(var.clone());
+            replacements.push(Replacement::Plain(value.render()));
+
+            if let Some(name) = var.strip_suffix('@') {
+                patterns.push(format!("{}|", name));
+                replacements.push(Replacement::Filtered(value.clone()));
+            }
+        }
+
+        // Leftmost-longest, so @NAME@ never shadows @NAME_SUFFIX@
+        let searcher = match patterns.is_empty() {
+            true => None,
+            false => Some(
+                AhoCorasick::builder()
+                    .match_kind(MatchKind::LeftmostLongest)
+                    .build(&patterns)
+                    .expect("var patterns are small"),
+            ),
+        };
+
+        VarReplacer {
+            searcher,
+            patterns,
+            replacements,
+        }
+    }
+
+    pub fn replace(&self, contents: &str) -> String {
+        let searcher = match &self.searcher {
+            Some(searcher) => searcher,
+            None => return String::from(contents),
+        };
+        let mut result = String::with_capacity(contents.len());
+        // Copied up to, searched from
+        let mut copied = 0;
+        let mut from = 0;
+
+        while let Some(found) = searcher.find(Input::new(contents).range(from..)) {
+            match &self.replacements[found.pattern().as_usize()] {
+                Replacement::Plain(rendered) => {
+                    result.push_str(&contents[copied..found.start()]);
+                    result.push_str(rendered);
+                    copied = found.end();
+                    from = found.end();
+                }
+                Replacement::Filtered(value) => match filter(&contents[found.end()..], value) {
+                    Some((rendered, len)) => {
+                        result.push_str(&contents[copied..found.start()]);
+                        result.push_str(&rendered);
+                        copied = found.end() + len;
+                        from = copied;
+                    }
+                    // Left as it is, the search goes on after @NAME|
+                    None => from = found.end(),
+                },
+            }
+        }
+        result.push_str(&contents[copied..]);
+
+        result
+    }
+}
+
+// Filters up to the closing @, e.g. "cmake|upper@ rest" -> rendered value and length of "cmake|upper@"
+fn filter(after: &str, value: &VarValue) -> Option<(String, usize)> {
+    let end = after.find('@')?;
+    let filtered = after[..end]
+        .split('|')
+        .try_fold(value.clone(), |value, filter| value.filter(filter.trim()))?;
+
+    Some((filtered.render(), end + 1))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_vars() -> BTreeMap<String, VarValue> {
+        [
+            ("@NAME@", VarValue::from("My Target")),
+            ("@SHARED@", VarValue::Bool(true)),
+            ("@TESTS@", VarValue::Bool(false)),
+            (
+                "@SOURCES@",
+                VarValue::List(vec![String::from("a.cpp"), String::from("b.cpp")]),
+            ),
+        ]
+        .iter()
+        .map(|(var, value)| (String::from(*var), value.clone()))
+        .collect()
+    }
+
+    #[test]
+    fn test_replace() {
+        let replacer = VarReplacer::new(&create_test_vars());
+
+        for (template, expected) in &[
+            ("@NAME@", "My Target"),
+            ("@NAME|upper@ @NAME|lower@", "MY TARGET my target"),
+            ("@NAME|quote@", "\"My Target\""),
+            ("@SHARED@ @SHARED|cmake@ @TESTS|cmake@", "true ON OFF"),
+            ("@SOURCES@", "a.cpp;b.cpp"),
+            ("@SOURCES|cmake@", "a.cpp;b.cpp"),
+            ("@SOURCES|lines@", "a.cpp\nb.cpp"),
+            ("@SOURCES|quote|lines@", "\"a.cpp\"\n\"b.cpp\""),
+            ("@SOURCES|upper|cmake@", "A.CPP;B.CPP"),
+            ("@NAME|unknown@ @NAME@", "@NAME|unknown@ My Target"),
+            ("@UNSET|cmake@", "@UNSET|cmake@"),
+            ("user@NAME|cmake", "user@NAME|cmake"),
+            ("@@NAME@@", "@My Target@"),
+            ("@NAME|unknown|@NAME@", "@NAME|unknown|My Target"),
+        ] {
+            assert_eq!(replacer.replace(template), *expected, "{}", template);
+        }
+    }
+
+    #[test]
+    fn test_replace_without_vars() {
+        let replacer = VarReplacer::new(&BTreeMap::new());
+
+        assert_eq!(replacer.replace("@NAME@"), "@NAME@");
+    }
+
+    #[test]
+    fn test_uses_var() {
+        assert!(uses_var("name: @NAME@", "@NAME@"));
+        assert!(uses_var("@NAME|upper@", "@NAME@"));
+        assert!(!uses_var("@NAMES@ @OTHER_NAME@", "@NAME@"));
+    }
+
+    #[test]
+    fn test_filter() {
+        for filter in &["cmake", "lines", "upper", "lower", "quote"] {
+            assert!(VarValue::Bool(true).filter(filter).is_some(), "{}", filter);
+        }
+        assert_eq!(VarValue::from("x").filter("onoff"), None);
+        assert_eq!(
+            VarValue::from("C:\\\"x\"").filter("quote"),
+            Some(VarValue::from("\"C:\\\\\\\"x\\\"\""))
+        );
+    }
+}
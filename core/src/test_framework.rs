@@ -0,0 +1,406 @@
+/*
+For FetchContent /sa https://cmake.org/cmake/help/latest/module/FetchContent.html
+For GoogleTest /sa https://cmake.org/cmake/help/latest/module/GoogleTest.html
+For test properties /sa https://cmake.org/cmake/help/latest/manual/cmake-properties.7.html#properties-on-tests
+For CDash submission /sa https://cmake.org/cmake/help/latest/module/CTest.html
+*/
+
+use crate::cpp_proj_gen::{CppProjGen, Kind, Opt};
+use crate::error::invalid_input;
+use crate::language::Language;
+use crate::source_listing;
+use std::{io, path::PathBuf, str::FromStr};
+
+const GTEST_CMLT: &str = include_str!("../res/tests/gtest/CMakeLists.txt.in");
+const GTEST_TEST: &str = include_str!("../res/tests/gtest/test.cpp.in");
+const GTEST_ADD_TEST: &str = include_str!("../res/tests/gtest/add-test.cpp.in");
+const CATCH2_CMLT: &str = include_str!("../res/tests/catch2/CMakeLists.txt.in");
+const CATCH2_TEST: &str = include_str!("../res/tests/catch2/test.cpp.in");
+const CATCH2_ADD_TEST: &str = include_str!("../res/tests/catch2/add-test.cpp.in");
+const DOCTEST_CMLT: &str = include_str!("../res/tests/doctest/CMakeLists.txt.in");
+const DOCTEST_TEST: &str = include_str!("../res/tests/doctest/test.cpp.in");
+const DOCTEST_ADD_TEST: &str = include_str!("../res/tests/doctest/add-test.cpp.in");
+const UNITY_CMLT: &str = include_str!("../res/tests/unity/CMakeLists.txt.in");
+const UNITY_TEST: &str = include_str!("../res/tests/unity/test.c.in");
+const CMOCKA_CMLT: &str = include_str!("../res/tests/cmocka/CMakeLists.txt.in");
+const CMOCKA_TEST: &str = include_str!("../res/tests/cmocka/test.c.in");
+const CTEST_CONFIG: &str = include_str!("../res/tests/CTestConfig.cmake.in");
+
+// Test framework
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestFramework {
+    GTest,
+    Catch2,
+    Doctest,
+    Unity,
+    CMocka,
+}
+
+impl TestFramework {
+    pub const VARIANTS: &'static [&'static str] =
+        &["gtest", "catch2", "doctest", "unity", "cmocka"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TestFramework::GTest => "gtest",
+            TestFramework::Catch2 => "catch2",
+            TestFramework::Doctest => "doctest",
+            TestFramework::Unity => "unity",
+            TestFramework::CMocka => "cmocka",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            TestFramework::GTest => "GoogleTest for C++",
+            TestFramework::Catch2 => "Catch2 v3 for C++",
+            TestFramework::Doctest => "doctest for C++",
+            TestFramework::Unity => "Unity for C",
+            TestFramework::CMocka => "CMocka for C, found via find_package()",
+        }
+    }
+
+    pub fn is_c(self) -> bool {
+        matches!(self, TestFramework::Unity | TestFramework::CMocka)
+    }
+
+    // Include and function for registering each test case with CTest
+    fn discovery(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            TestFramework::GTest => Some(("include(GoogleTest)", "gtest_discover_tests")),
+            TestFramework::Catch2 => Some((
+                "list(APPEND CMAKE_MODULE_PATH ${catch2_SOURCE_DIR}/extras)\ninclude(Catch)",
+                "catch_discover_tests",
+            )),
+            TestFramework::Doctest => Some((
+                "include(${doctest_SOURCE_DIR}/scripts/cmake/doctest.cmake)",
+                "doctest_discover_tests",
+            )),
+            TestFramework::Unity | TestFramework::CMocka => None,
+        }
+    }
+
+    fn default_discovery(self) -> TestDiscovery {
+        match self {
+            TestFramework::GTest => TestDiscovery::Discover,
+            _ => TestDiscovery::AddTest,
+        }
+    }
+
+    // Test file joining the existing test executable, C frameworks bring their own main per file
+    pub fn add_test_template(self) -> Option<&'static str> {
+        match self {
+            TestFramework::GTest => Some(GTEST_ADD_TEST),
+            TestFramework::Catch2 => Some(CATCH2_ADD_TEST),
+            TestFramework::Doctest => Some(DOCTEST_ADD_TEST),
+            TestFramework::Unity | TestFramework::CMocka => None,
+        }
+    }
+
+    fn templates(self) -> (&'static str, &'static str) {
+        match self {
+            TestFramework::GTest => (GTEST_CMLT, GTEST_TEST),
+            TestFramework::Catch2 => (CATCH2_CMLT, CATCH2_TEST),
+            TestFramework::Doctest => (DOCTEST_CMLT, DOCTEST_TEST),
+            TestFramework::Unity => (UNITY_CMLT, UNITY_TEST),
+            TestFramework::CMocka => (CMOCKA_CMLT, CMOCKA_TEST),
+        }
+    }
+}
+
+impl FromStr for TestFramework {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gtest" => Ok(TestFramework::GTest),
+            "catch2" => Ok(TestFramework::Catch2),
+            "doctest" => Ok(TestFramework::Doctest),
+            "unity" => Ok(TestFramework::Unity),
+            "cmocka" => Ok(TestFramework::CMocka),
+            _ => Err(format!(
+                "unknown test framework '{}', expected one of: {}",
+                s,
+                TestFramework::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// How tests are registered with CTest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestDiscovery {
+    // One CTest test per test executable
+    AddTest,
+    // One CTest test per test case, e.g. via gtest_discover_tests()
+    Discover,
+}
+
+impl TestDiscovery {
+    pub const VARIANTS: &'static [&'static str] = &["add-test", "discover"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TestDiscovery::AddTest => "add-test",
+            TestDiscovery::Discover => "discover",
+        }
+    }
+}
+
+impl FromStr for TestDiscovery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add-test" => Ok(TestDiscovery::AddTest),
+            "discover" => Ok(TestDiscovery::Discover),
+            _ => Err(format!(
+                "unknown test discovery '{}', expected one of: {}",
+                s,
+                TestDiscovery::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+// C frameworks test C samples, C++ frameworks C++ samples
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let framework = match opt.test_framework {
+        Some(framework) => framework,
+        None if has_ctest_options(opt) => {
+            return Err(invalid_input(String::from(
+                "CTest options need --test-framework",
+            )))
+        }
+        None => return Ok(()),
+    };
+
+    if opt.test_discovery == Some(TestDiscovery::Discover) && framework.discovery().is_none() {
+        return Err(invalid_input(format!(
+            "test framework {} does not support --test-discovery {}",
+            framework.name(),
+            TestDiscovery::Discover.name()
+        )));
+    }
+
+    let c_samples = opt.language == Language::C;
+    if framework.is_c() != c_samples {
+        return Err(invalid_input(format!(
+            "test framework {} does not fit the {} samples, use {}",
+            framework.name(),
+            opt.language.source_file_ext(),
+            if c_samples {
+                "unity or cmocka"
+            } else {
+                "gtest, catch2 or doctest"
+            }
+        )));
+    }
+
+    Ok(())
+}
+
+// Adds test/CMakeLists.txt and a sample test
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (framework, kind) = match gen.opt().test_framework {
+        Some(framework) => (framework, gen.opt().kind),
+        None => return gen,
+    };
+
+    let (cmake_lists, test) = framework.templates();
+    let cmake_lists = source_listing::render_test_sources(cmake_lists, gen.opt())
+        .replace("@TEST_UNDER_TEST@", build_under_test(kind))
+        .replace(
+            "@TEST_REGISTRATION@",
+            &build_test_registration(framework, gen.opt()),
+        );
+    let cdash_drop_site = gen.opt().cdash_drop_site.clone();
+
+    let gen = gen
+        .add_file(PathBuf::from("@TEST_DIR@/CMakeLists.txt"), cmake_lists)
+        .add_file(
+            PathBuf::from("@TEST_DIR@/@TEST_FILE_PREFIX@@CMAKE_TARGET_NAME@@TEST_FILE_SUFFIX@"),
+            String::from(test),
+        );
+
+    // include(CTest) enables testing and adds the dashboard targets
+    match cdash_drop_site {
+        Some(drop_site) => gen
+            .add_cmake_var("@CDASH_DROP_SITE@", &drop_site)
+            .add_file(
+                PathBuf::from("CTestConfig.cmake"),
+                String::from(CTEST_CONFIG),
+            )
+            .add_cmake_snippet(String::from(
+                "if(@CMAKE_VAR_PREFIX@_BUILD_TESTS)\n    include(CTest)\n    add_subdirectory(@TEST_DIR@)\nendif()",
+            )),
+        None => gen.add_cmake_snippet(String::from(
+            "if(@CMAKE_VAR_PREFIX@_BUILD_TESTS)\n    enable_testing()\n    add_subdirectory(@TEST_DIR@)\nendif()",
+        )),
+    }
+}
+
+fn has_ctest_options(opt: &Opt) -> bool {
+    opt.ctest_timeout.is_some()
+        || !opt.test_labels.is_empty()
+        || opt.test_discovery.is_some()
+        || opt.cdash_drop_site.is_some()
+}
+
+fn build_test_properties(opt: &Opt) -> Vec<String> {
+    let mut properties = Vec::new();
+
+    if let Some(timeout) = opt.ctest_timeout {
+        properties.push(format!("TIMEOUT {}", timeout));
+    }
+
+    if !opt.test_labels.is_empty() {
+        properties.push(format!("LABELS \"{}\"", opt.test_labels.join(";")));
+    }
+
+    properties
+}
+
+fn build_test_registration(framework: TestFramework, opt: &Opt) -> String {
+    let target = "@CMAKE_TARGET_NAME@-test";
+    let properties = build_test_properties(opt);
+    let discovery = opt
+        .test_discovery
+        .unwrap_or_else(|| framework.default_discovery());
+
+    match (discovery, framework.discovery()) {
+        (TestDiscovery::Discover, Some((include, function))) if properties.is_empty() => {
+            format!("{}\n{}({})", include, function, target)
+        }
+        (TestDiscovery::Discover, Some((include, function))) => format!(
+            "{}\n{}({}\n    PROPERTIES {}\n)",
+            include,
+            function,
+            target,
+            properties.join(" ")
+        ),
+        _ if properties.is_empty() => format!("add_test(NAME {} COMMAND {})", target, target),
+        _ => format!(
+            "add_test(NAME {} COMMAND {})\n\nset_tests_properties({}\n    PROPERTIES {}\n)",
+            target,
+            target,
+            target,
+            properties.join(" ")
+        ),
+    }
+}
+
+// Executables cannot be linked, their sources are compiled into the test instead
+fn build_under_test(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Executable => {
+            "target_sources(@CMAKE_TARGET_NAME@-test
+    PRIVATE
+        ${PROJECT_SOURCE_DIR}/@SOURCE_DIR@/@CMAKE_TARGET_NAME@.@SOURCE_FILE_EXT@
+)
+
+target_include_directories(@CMAKE_TARGET_NAME@-test
+    PRIVATE
+        ${PROJECT_SOURCE_DIR}/@INCLUDE_DIR@
+)"
+        }
+        _ => {
+            "target_link_libraries(@CMAKE_TARGET_NAME@-test
+    PRIVATE
+        @CMAKE_ALIAS_NAME@
+)"
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let opt = Opt {
+            test_framework: Some(TestFramework::Unity),
+            language: Language::C,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_ok());
+
+        let opt = Opt {
+            test_framework: Some(TestFramework::GTest),
+            language: Language::C,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+
+        let opt = Opt {
+            test_framework: Some(TestFramework::CMocka),
+            language: Language::CppAndC,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+    }
+
+    #[test]
+    fn test_test_files() {
+        let opt = Opt {
+            test_framework: Some(TestFramework::Catch2),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            ..Opt::default()
+        };
+
+        let paths = CppProjGen::new(opt)
+            .add_test_dir(PathBuf::from("test"))
+            .build_paths();
+
+        assert!(paths.contains(&PathBuf::from("test_out_dir/my-target/test/CMakeLists.txt")));
+        assert!(paths.contains(&PathBuf::from(
+            "test_out_dir/my-target/test/test_my-target.cpp"
+        )));
+    }
+
+    #[test]
+    fn test_build_under_test() {
+        assert!(build_under_test(Kind::Executable).starts_with("target_sources"));
+        // Tests run against the library, not the application
+        assert!(build_under_test(Kind::LibAndApp).starts_with("target_link_libraries"));
+    }
+
+    #[test]
+    fn test_build_test_registration() {
+        let opt = Opt::default();
+        assert_eq!(
+            build_test_registration(TestFramework::Catch2, &opt),
+            "add_test(NAME @CMAKE_TARGET_NAME@-test COMMAND @CMAKE_TARGET_NAME@-test)"
+        );
+        assert!(build_test_registration(TestFramework::GTest, &opt)
+            .ends_with("gtest_discover_tests(@CMAKE_TARGET_NAME@-test)"));
+
+        let opt = Opt {
+            ctest_timeout: Some(30),
+            test_labels: vec![String::from("unit"), String::from("fast")],
+            test_discovery: Some(TestDiscovery::Discover),
+            ..Opt::default()
+        };
+        assert!(build_test_registration(TestFramework::Doctest, &opt)
+            .contains("PROPERTIES TIMEOUT 30 LABELS \"unit;fast\""));
+    }
+
+    #[test]
+    fn test_validate_ctest_options() {
+        let opt = Opt {
+            ctest_timeout: Some(30),
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+
+        let opt = Opt {
+            test_framework: Some(TestFramework::Unity),
+            test_discovery: Some(TestDiscovery::Discover),
+            language: Language::C,
+            ..Opt::default()
+        };
+        assert!(validate(&opt).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+// Helpers shared by the unit tests of the features
+
+use crate::cpp_proj_gen::PlanEntry;
+use std::path::Path;
+
+// Rendered file of a plan for a test project in test_out_dir, e.g. "scripts/build.sh"
+pub fn contents(plan: &[PlanEntry], name: &str) -> Option<String> {
+    match entry(plan, name) {
+        Some(PlanEntry::File { contents, .. }) => Some(contents.clone()),
+        _ => None,
+    }
+}
+
+// Any entry of the plan, e.g. the binary "res/logo.png"
+pub fn entry<'a>(plan: &'a [PlanEntry], name: &str) -> Option<&'a PlanEntry> {
+    plan.iter()
+        .find(|entry| project_path(entry.path()) == Some(Path::new(name)))
+}
+
+// Path below the project directory, e.g. include/tgtnm.h of test_out_dir/tgtnm/include/tgtnm.h
+fn project_path(path: &Path) -> Option<&Path> {
+    let mut components = path.strip_prefix("test_out_dir").ok()?.components();
+    components.next()?;
+
+    Some(components.as_path())
+}
@@ -0,0 +1,194 @@
+/*
+For toolchain files /sa https://cmake.org/cmake/help/latest/manual/cmake-toolchains.7.html
+For toolchainFile presets /sa https://cmake.org/cmake/help/latest/manual/cmake-presets.7.html#configure-preset
+*/
+
+use crate::compiler::Compiler;
+use crate::cpp_proj_gen::{CppProjGen, Opt};
+use crate::error::invalid_input;
+use crate::language::Language;
+use std::{io, path::PathBuf};
+
+const TOOLCHAIN_CMAKE: &str = include_str!("../res/toolchain/Toolchain.cmake.in");
+const CONFIGURE_PRESET: &str = include_str!("../res/toolchain/preset.json.in");
+const BUILD_PRESET: &str = include_str!("../res/toolchain/build_preset.json.in");
+
+// Ends of the preset lists of CMakePresets.json.in
+const CONFIGURE_PRESETS_END: &str = "        }\n    ],\n    \"buildPresets\"";
+const BUILD_PRESETS_END: &str = "        }\n    ]\n}";
+
+// The version is read with -dumpversion, so MSVC cannot be pinned
+pub fn validate(opt: &Opt) -> io::Result<()> {
+    let version = match &opt.toolchain_version {
+        Some(version) => version,
+        None => return Ok(()),
+    };
+
+    match opt.compiler {
+        Some(Compiler::Msvc) | None => {
+            return Err(invalid_input(String::from(
+                "--toolchain-version needs --compiler gcc, clang or apple-clang",
+            )))
+        }
+        _ => (),
+    }
+    let is_version = version
+        .split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if !is_version {
+        return Err(invalid_input(format!(
+            "expected a toolchain version like 13 or 17.0, got '{}'",
+            version
+        )));
+    }
+
+    Ok(())
+}
+
+// One pin_compiler call per project language, e.g. g++-13 before g++
+fn build_pins(opt: &Opt, compiler: Compiler, version: &str) -> String {
+    let major = version.split('.').next().unwrap();
+    let version_flag = match compiler {
+        // -dumpversion of GCC prints only the major version
+        Compiler::Gcc => "-dumpfullversion",
+        _ => "-dumpversion",
+    };
+    let languages: &[(&str, &str)] = match opt.language {
+        Language::Cpp => &[("CXX", compiler.cxx_compiler())],
+        Language::C => &[("C", compiler.c_compiler())],
+        Language::CppAndC => &[
+            ("C", compiler.c_compiler()),
+            ("CXX", compiler.cxx_compiler()),
+        ],
+    };
+
+    languages
+        .iter()
+        .map(|(lang, name)| {
+            let names = match compiler {
+                // Xcode has no versioned names
+                Compiler::AppleClang => String::from(*name),
+                _ => format!("{}-{};{}", name, major, name),
+            };
+            format!("pin_compiler({} \"{}\" {})", lang, names, version_flag)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Adds the require-toolchain presets to the presets of --compiler
+pub fn render_presets(presets: String, opt: &Opt) -> String {
+    if opt.toolchain_version.is_none() {
+        return presets;
+    }
+
+    presets
+        .replace(
+            CONFIGURE_PRESETS_END,
+            &format!(
+                "        }},\n{}    ],\n    \"buildPresets\"",
+                CONFIGURE_PRESET
+            ),
+        )
+        .replace(
+            BUILD_PRESETS_END,
+            &format!("        }},\n{}    ]\n}}", BUILD_PRESET),
+        )
+}
+
+// Adds cmake/Toolchain.cmake pinning the compiler version
+pub fn apply(gen: CppProjGen) -> CppProjGen {
+    let (compiler, version) = match (gen.opt().compiler, &gen.opt().toolchain_version) {
+        (Some(compiler), Some(version)) => (compiler, version.clone()),
+        _ => return gen,
+    };
+    let toolchain = TOOLCHAIN_CMAKE.replace(
+        "@TOOLCHAIN_PINS@",
+        &build_pins(gen.opt(), compiler, &version),
+    );
+
+    gen.add_cmake_var("@TOOLCHAIN_VERSION@", &version)
+        .add_file(PathBuf::from("cmake/Toolchain.cmake"), toolchain)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::contents;
+
+    fn create_test_opt() -> Opt {
+        Opt {
+            target_name: String::from("tgtnm"),
+            output_dir: Some(PathBuf::from("test_out_dir")),
+            compiler: Some(Compiler::Gcc),
+            toolchain_version: Some(String::from("13")),
+            ..Opt::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&create_test_opt()).is_ok());
+        for (compiler, version) in [
+            (None, "13"),
+            (Some(Compiler::Msvc), "19"),
+            (Some(Compiler::Clang), "17."),
+            (Some(Compiler::Clang), "latest"),
+        ] {
+            assert!(validate(&Opt {
+                compiler,
+                toolchain_version: Some(String::from(version)),
+                ..create_test_opt()
+            })
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_build_pins() {
+        assert_eq!(
+            build_pins(&create_test_opt(), Compiler::Gcc, "13"),
+            "pin_compiler(CXX \"g++-13;g++\" -dumpfullversion)"
+        );
+        assert_eq!(
+            build_pins(
+                &Opt {
+                    language: Language::CppAndC,
+                    ..create_test_opt()
+                },
+                Compiler::Clang,
+                "17.0"
+            ),
+            "pin_compiler(C \"clang-17;clang\" -dumpversion)\n\
+             pin_compiler(CXX \"clang++-17;clang++\" -dumpversion)"
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let plan = CppProjGen::new(create_test_opt()).plan().unwrap();
+
+        let presets: serde_json::Value =
+            serde_json::from_str(&contents(&plan, "CMakePresets.json").unwrap()).unwrap();
+        assert_eq!(
+            presets["configurePresets"][2]["toolchainFile"],
+            "${sourceDir}/cmake/Toolchain.cmake"
+        );
+        assert_eq!(
+            presets["buildPresets"][2]["configurePreset"],
+            "require-toolchain"
+        );
+        assert!(contents(&plan, "cmake/Toolchain.cmake")
+            .unwrap()
+            .contains("expected gcc 13\")"));
+
+        let plan = CppProjGen::new(Opt {
+            toolchain_version: None,
+            ..create_test_opt()
+        })
+        .plan()
+        .unwrap();
+        assert!(contents(&plan, "cmake/Toolchain.cmake").is_none());
+    }
+}
@@ -0,0 +1,252 @@
+/*
+For Command /sa https://doc.rust-lang.org/std/process/struct.Command.html
+*/
+
+use crate::cmake_version::Version;
+use crate::cpp_proj_gen::Opt;
+use crate::dependency::PackageManager;
+use crate::warning::Warning;
+use std::{env, io, process::Command};
+
+// A tool needed to build the generated project
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tool {
+    pub name: String,
+    // Candidates are tried in order, the first one found is used
+    pub programs: Vec<String>,
+    pub min_version: Option<Version>,
+    pub hint: &'static str,
+    // Missing optional tools are warnings, e.g. doxygen for docs
+    pub optional: bool,
+}
+
+// Found tools, e.g. "cmake 3.28.1", and the missing optional ones
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ToolReport {
+    pub found: Vec<String>,
+    pub warnings: Vec<Warning>,
+}
+
+pub fn required_tools(opt: &Opt) -> Vec<Tool> {
+    let cmake_version = opt
+        .cmake_version
+        .as_ref()
+        .and_then(|version| version.parse::<Version>().ok());
+
+    let mut tools = vec![Tool {
+        name: String::from("cmake"),
+        programs: vec![String::from("cmake")],
+        min_version: cmake_version,
+        hint: "install CMake from https://cmake.org/download/",
+        optional: false,
+    }];
+
+    if opt.language.has_cpp() {
+        tools.push(Tool {
+            name: String::from("C++ compiler"),
+            programs: compiler_candidates(opt, "CXX"),
+            min_version: None,
+            hint: "install a C++ compiler or point the CXX environment variable to one",
+            optional: false,
+        });
+    }
+
+    if opt.language.has_c() {
+        tools.push(Tool {
+            name: String::from("C compiler"),
+            programs: compiler_candidates(opt, "CC"),
+            min_version: None,
+            hint: "install a C compiler or point the CC environment variable to one",
+            optional: false,
+        });
+    }
+
+    // The scripts and task runner recipes configure with -G Ninja
+    if opt.scripts || opt.task_runner.is_some() {
+        tools.push(Tool {
+            name: String::from("ninja"),
+            programs: vec![String::from("ninja")],
+            min_version: None,
+            hint: "install Ninja from https://ninja-build.org/",
+            optional: false,
+        });
+    }
+
+    if opt.package_manager == Some(PackageManager::Conan) {
+        tools.push(Tool {
+            name: String::from("conan"),
+            programs: vec![String::from("conan")],
+            min_version: None,
+            hint: "install Conan from https://docs.conan.io/2/installation.html",
+            optional: false,
+        });
+    }
+
+    tools
+}
+
+// Runs '<program> --version' for every tool, fails with all problems of required ones found
+pub fn check_tools(opt: &Opt, extra_tools: Vec<Tool>) -> io::Result<ToolReport> {
+    let mut problems = Vec::new();
+    let mut report = ToolReport::default();
+
+    for tool in required_tools(opt).into_iter().chain(extra_tools) {
+        match check_tool(&tool) {
+            Ok(found) => report.found.push(found),
+            Err(problem) if tool.optional => report.warnings.push(Warning::MissingTool { problem }),
+            Err(problem) => problems.push(problem),
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("tool check failed:\n  {}", problems.join("\n  ")),
+        ));
+    }
+
+    Ok(report)
+}
+
+fn check_tool(tool: &Tool) -> Result<String, String> {
+    for program in &tool.programs {
+        let output = match Command::new(program).args(version_args(program)).output() {
+            Ok(output) if output.status.success() => output,
+            // Not installed or not working, e.g. cl outside a developer prompt
+            _ => continue,
+        };
+
+        // cl prints its banner to stderr
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        let version = parse_version_output(&text);
+
+        return match (tool.min_version, version) {
+            (Some(min_version), Some(version)) if version < min_version => Err(format!(
+                "{} {} is too old, {} is required: {}",
+                program, version, min_version, tool.hint
+            )),
+            (_, Some(version)) => Ok(format!("{} {}", program, version)),
+            (_, None) => Ok(String::from(program)),
+        };
+    }
+
+    Err(format!("{} not found: {}", tool.name, tool.hint))
+}
+
+// cl has no --version, without arguments it prints its banner and usage
+fn version_args(program: &str) -> &'static [&'static str] {
+    match program {
+        "cl" | "cl.exe" => &[],
+        _ => &["--version"],
+    }
+}
+
+// env_var is either CC or CXX
+fn compiler_candidates(opt: &Opt, env_var: &str) -> Vec<String> {
+    let is_cxx = env_var == "CXX";
+
+    if let Some(compiler) = opt.compiler {
+        let program = if is_cxx {
+            compiler.cxx_compiler()
+        } else {
+            compiler.c_compiler()
+        };
+        return vec![String::from(program)];
+    }
+
+    if let Ok(program) = env::var(env_var) {
+        return vec![program];
+    }
+
+    let programs: &[&str] = if is_cxx {
+        &["c++", "g++", "clang++", "cl"]
+    } else {
+        &["cc", "gcc", "clang", "cl"]
+    };
+
+    programs.iter().map(|program| program.to_string()).collect()
+}
+
+// First version looking word, e.g. 'cmake version 3.28.1' -> 3.28.1
+fn parse_version_output(text: &str) -> Option<Version> {
+    text.split_whitespace().find_map(|word| {
+        let digits: String = word
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+
+        digits.trim_end_matches('.').parse::<Version>().ok()
+    })
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_output() {
+        let cmake = "cmake version 3.28.1\n\nCMake suite maintained and supported by Kitware.";
+        assert_eq!(parse_version_output(cmake), Some(Version::new(3, 28, 1)));
+
+        let gcc = "g++ (Ubuntu 11.4.0-1ubuntu1~22.04) 11.4.0";
+        assert_eq!(parse_version_output(gcc), Some(Version::new(11, 4, 0)));
+
+        assert_eq!(parse_version_output("no version here"), None);
+    }
+
+    #[test]
+    fn test_required_tools() {
+        let names = |opt: &Opt| -> Vec<String> {
+            required_tools(opt)
+                .into_iter()
+                .map(|tool| tool.name)
+                .collect()
+        };
+
+        let opt = Opt::default();
+        assert!(!names(&opt).contains(&String::from("ninja")));
+        assert!(!names(&opt).contains(&String::from("conan")));
+
+        let opt = Opt {
+            scripts: true,
+            package_manager: Some(PackageManager::Conan),
+            ..Opt::default()
+        };
+        assert!(names(&opt).contains(&String::from("ninja")));
+        assert!(names(&opt).contains(&String::from("conan")));
+    }
+
+    #[test]
+    fn test_missing_tool() {
+        let tool = Tool {
+            name: String::from("missing"),
+            programs: vec![String::from("cpp-proj-gen-missing-tool")],
+            min_version: None,
+            hint: "install it",
+            optional: false,
+        };
+
+        assert!(check_tool(&tool).is_err());
+    }
+
+    #[test]
+    fn test_failing_tool() {
+        let tool = Tool {
+            name: String::from("failing"),
+            programs: vec![String::from("false")],
+            min_version: None,
+            hint: "install it",
+            optional: false,
+        };
+
+        assert!(check_tool(&tool).is_err());
+    }
+
+    #[test]
+    fn test_version_args() {
+        assert!(version_args("cl").is_empty());
+        assert_eq!(version_args("ninja"), &["--version"]);
+    }
+}
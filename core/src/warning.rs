@@ -0,0 +1,37 @@
+/*
+For warnings versus errors /sa https://doc.rust-lang.org/rustc/lints/levels.html
+*/
+
+use std::{fmt, path::PathBuf};
+
+// Problem that does not stop the generation unless --deny-warnings is passed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    // Set with -D but used by no template, e.g. a typo of a pack variable
+    UnusedVariable { name: String },
+    // An optional tool of the template pack is missing or too old
+    MissingTool { problem: String },
+    // A template pack file replaces a generated one, e.g. source/main.cpp
+    OverwrittenFile { path: PathBuf },
+    // e.g. a CMake version too low for a feature
+    Other(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::UnusedVariable { name } => {
+                write!(f, "variable {} is set but used by no template", name)
+            }
+            Warning::MissingTool { problem } => write!(f, "optional tool {}", problem),
+            Warning::OverwrittenFile { path } => {
+                write!(
+                    f,
+                    "template pack file {} replaces a generated one",
+                    path.display()
+                )
+            }
+            Warning::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
@@ -0,0 +1,977 @@
+/*
+For structopt /sa https://docs.rs/structopt/0.3.23/structopt/
+For ArgMatches /sa https://docs.rs/clap/2.33.3/clap/struct.ArgMatches.html#method.occurrences_of
+*/
+
+use cpp_proj_gen::abi_check::AbiCheck;
+use cpp_proj_gen::app_config::AppConfig;
+use cpp_proj_gen::builtin_template::BuiltinTemplate;
+use cpp_proj_gen::codeowners::CodeOwner;
+use cpp_proj_gen::community_files::Forge;
+use cpp_proj_gen::compiler::Compiler;
+use cpp_proj_gen::concurrency::Concurrency;
+use cpp_proj_gen::cpp_proj_gen::{Kind, Opt};
+use cpp_proj_gen::defaults::Defaults;
+use cpp_proj_gen::dependency::PackageManager;
+use cpp_proj_gen::dependency_updates::DependencyUpdates;
+use cpp_proj_gen::docs::DocsBackend;
+use cpp_proj_gen::extra_languages::ExtraLanguage;
+use cpp_proj_gen::fuzzing::FuzzEngine;
+use cpp_proj_gen::i18n::I18n;
+use cpp_proj_gen::language::{Language, CXX_STANDARDS, C_STANDARDS};
+use cpp_proj_gen::logging::Logging;
+use cpp_proj_gen::naming::{TestPattern, HEADER_EXTS, SOURCE_EXTS};
+use cpp_proj_gen::normalize::{Indent, LineEndings, TrailingNewline};
+use cpp_proj_gen::output::OutputUrl;
+use cpp_proj_gen::packaging::Packaging;
+use cpp_proj_gen::platforms::Platform;
+use cpp_proj_gen::presets::Preset;
+use cpp_proj_gen::source_listing::SourceListing;
+use cpp_proj_gen::submodules::parse_submodule;
+use cpp_proj_gen::task_runner::TaskRunner;
+use cpp_proj_gen::template_pack::parse_define;
+use cpp_proj_gen::test_framework::{TestDiscovery, TestFramework};
+use std::path::PathBuf;
+use structopt::{clap::ArgMatches, StructOpt};
+
+// Command line options, the library gets them as Opt
+#[derive(Debug, StructOpt)]
+#[structopt(name = "cpp-proj-gen", about = "C++ project generator.")]
+pub struct Cli {
+    // Project name
+    #[structopt(short, long, help = "e.g. company name")]
+    pub name_space: Option<String>,
+
+    // Target name
+    #[structopt(short, long, default_value = "my-target")]
+    pub target_name: String,
+
+    // CMake version
+    #[structopt(
+        short,
+        long,
+        help = "Minimum CMake version, inferred from the selected features if not set"
+    )]
+    pub cmake_version: Option<String>,
+
+    // Output directory
+    #[structopt(short, long, parse(from_os_str))]
+    pub output_dir: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        conflicts_with = "output-dir",
+        help = "Remote output, ssh://user@host/path via tar and ssh or s3://bucket/prefix via aws"
+    )]
+    pub output: Option<OutputUrl>,
+
+    // Project kind
+    #[structopt(short, long, default_value = "static", possible_values = Kind::VARIANTS)]
+    pub kind: Kind,
+
+    // Template directories, later ones overlay earlier ones
+    #[structopt(
+        name = "template",
+        long = "template",
+        alias = "template-dir",
+        number_of_values = 1,
+        parse(from_os_str),
+        help = "Template pack directory or git URL[#ref] overriding the built-in templates, repeat to stack overlays"
+    )]
+    pub template_dirs: Vec<PathBuf>,
+
+    // Template registry
+    #[structopt(
+        long,
+        help = "Base URL of bare --template names, e.g. https://github.com/my-company makes --template lib#v1 https://github.com/my-company/lib#v1"
+    )]
+    pub template_registry: Option<String>,
+
+    // Offline mode
+    #[structopt(
+        long,
+        help = "Uses only cached remote template packs, without network access"
+    )]
+    pub offline: bool,
+
+    // Remote template pack verification
+    #[structopt(
+        long,
+        number_of_values = 1,
+        help = "Checksum a remote template pack must match, can be repeated"
+    )]
+    pub template_sha256: Vec<String>,
+
+    #[structopt(
+        long,
+        help = "Accepts remote template packs with a tag or commit signature trusted by git"
+    )]
+    pub verify_template_signature: bool,
+
+    #[structopt(long, help = "Renders remote template packs without verification")]
+    pub allow_unverified: bool,
+
+    // Built-in template pack
+    #[structopt(
+        long,
+        default_value = "standard",
+        possible_values = BuiltinTemplate::VARIANTS,
+        help = "Built-in templates the --template packs overlay"
+    )]
+    pub builtin_template: BuiltinTemplate,
+
+    // Source listing
+    #[structopt(
+        long,
+        default_value = "explicit",
+        possible_values = SourceListing::VARIANTS,
+        help = "Lists sources explicitly or collects them with file(GLOB_RECURSE)"
+    )]
+    pub source_listing: SourceListing,
+
+    // Author
+    #[structopt(long)]
+    pub author: Option<String>,
+
+    #[structopt(
+        long,
+        help = "E-mail address of the author, e.g. for the maintainer of --packaging"
+    )]
+    pub author_email: Option<String>,
+
+    // License
+    #[structopt(long, help = "SPDX license identifier, e.g. MIT")]
+    pub license: Option<String>,
+
+    // Homepage
+    #[structopt(
+        long,
+        help = "Project URL, e.g. https://github.com/me/my-target, used by --packaging"
+    )]
+    pub homepage: Option<String>,
+
+    // Project version
+    #[structopt(
+        long,
+        default_value = "0.1.0",
+        help = "Version passed to CMake's project()"
+    )]
+    pub project_version: String,
+
+    // Community files
+    #[structopt(
+        long,
+        help = "Adds CHANGELOG.md, CONTRIBUTING.md and CODE_OF_CONDUCT.md"
+    )]
+    pub community_files: bool,
+
+    // Forge
+    #[structopt(
+        long,
+        possible_values = Forge::VARIANTS,
+        help = "Adds issue and pull request templates of the forge"
+    )]
+    pub forge: Option<Forge>,
+
+    // Code owners
+    #[structopt(
+        long,
+        number_of_values = 1,
+        help = "Owner and paths for CODEOWNERS, e.g. core@my-org:/@SOURCE_DIR@/,*.cmake, repeatable"
+    )]
+    pub codeowners: Vec<CodeOwner>,
+
+    #[structopt(
+        long,
+        possible_values = Forge::VARIANTS,
+        help = "Writes CODEOWNERS into .github/ or .gitlab/, defaults to --forge or else github"
+    )]
+    pub codeowners_flavor: Option<Forge>,
+
+    // Package manager
+    #[structopt(
+        long,
+        possible_values = PackageManager::VARIANTS,
+        help = "Package manager of the project, recorded for add-dependency"
+    )]
+    pub package_manager: Option<PackageManager>,
+
+    // Dependency update bots
+    #[structopt(
+        long,
+        possible_values = DependencyUpdates::VARIANTS,
+        help = "Adds a Dependabot or Renovate config for submodules and the package manager"
+    )]
+    pub dependency_updates: Option<DependencyUpdates>,
+
+    // Documentation
+    #[structopt(
+        long,
+        possible_values = DocsBackend::VARIANTS,
+        help = "Adds a docs target built with Doxygen, or an mkdocs site with Read the Docs config"
+    )]
+    pub docs: Option<DocsBackend>,
+
+    #[structopt(long, help = "Uses the Material theme for --docs mkdocs")]
+    pub docs_material: bool,
+
+    // Timestamp
+    #[structopt(
+        long,
+        env = "SOURCE_DATE_EPOCH",
+        help = "Unix time for @GENERATION_DATE@ and @GENERATION_YEAR@, which stay empty otherwise"
+    )]
+    pub timestamp: Option<i64>,
+
+    // Language
+    #[structopt(long, default_value = "cpp", possible_values = Language::VARIANTS)]
+    pub language: Language,
+
+    // C++ standard
+    #[structopt(long, default_value = "17", possible_values = CXX_STANDARDS)]
+    pub cxx_standard: String,
+
+    // C standard
+    #[structopt(long, default_value = "11", possible_values = C_STANDARDS)]
+    pub c_standard: String,
+
+    // Extra languages
+    #[structopt(
+        long = "extra-language",
+        use_delimiter = true,
+        possible_values = ExtraLanguage::VARIANTS,
+        help = "Compiles samples of more languages into the target, e.g. fortran,asm,cuda"
+    )]
+    pub extra_languages: Vec<ExtraLanguage>,
+
+    // Sample sources
+    #[structopt(
+        long,
+        help = "Adds a sample library and program, implied by C projects and the features using them"
+    )]
+    pub sample_sources: bool,
+
+    // Naming policy
+    #[structopt(long, default_value = "h", possible_values = HEADER_EXTS)]
+    pub header_ext: String,
+
+    #[structopt(
+        long,
+        default_value = "cpp",
+        possible_values = SOURCE_EXTS,
+        help = "Extension of C++ sources, C sources keep .c"
+    )]
+    pub source_ext: String,
+
+    #[structopt(
+        long,
+        default_value = "test_*",
+        help = "Test file names, * is the tested name, e.g. *_test or *_test.cc"
+    )]
+    pub test_pattern: TestPattern,
+
+    // Resources
+    #[structopt(
+        long,
+        value_name = "dir",
+        parse(from_os_str),
+        help = "Creates an assets directory copied next to the executable after each build"
+    )]
+    pub resources: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help = "Compiles the files of resources/ into the target, read via resources::find"
+    )]
+    pub embed_resources: bool,
+
+    // Internationalization
+    #[structopt(
+        long,
+        possible_values = I18n::VARIANTS,
+        help = "Adds translation catalogs, the CMake rules to extract and compile them and a localized main"
+    )]
+    pub i18n: Option<I18n>,
+
+    // Logging
+    #[structopt(
+        long,
+        possible_values = Logging::VARIANTS,
+        help = "Adds a logging setup header called from main, spdlog and glog via the package manager"
+    )]
+    pub logging: Option<Logging>,
+
+    // Application config
+    #[structopt(
+        long,
+        possible_values = AppConfig::VARIANTS,
+        help = "Adds a sample config file and an AppConfig class reading it with toml++, yaml-cpp or nlohmann-json"
+    )]
+    pub app_config: Option<AppConfig>,
+
+    // Concurrency
+    #[structopt(
+        long,
+        possible_values = Concurrency::VARIANTS,
+        help = "Adds a parallel_sum example with a thread pool, oneTBB or a Taskflow task graph"
+    )]
+    pub concurrency: Option<Concurrency>,
+
+    // Compilation database
+    #[structopt(
+        long,
+        help = "Neither exports compile_commands.json nor links it into the source directory for clangd"
+    )]
+    pub no_compile_commands: bool,
+
+    // Convenience scripts
+    #[structopt(
+        long,
+        help = "Adds scripts/build.sh, build.ps1, test.sh and test.ps1 configuring with Ninja"
+    )]
+    pub scripts: bool,
+
+    // Task runner
+    #[structopt(
+        long,
+        possible_values = TaskRunner::VARIANTS,
+        help = "Adds a justfile, Taskfile.yml or Makefile with build, test, format, lint and clean recipes"
+    )]
+    pub task_runner: Option<TaskRunner>,
+
+    // Nix development shell
+    #[structopt(
+        long,
+        help = "Adds flake.nix and shell.nix with the compiler, CMake, Ninja and package manager for nix develop"
+    )]
+    pub nix: bool,
+
+    // conda environment
+    #[structopt(
+        long,
+        help = "Adds environment.yml with the compilers, CMake, Ninja and libraries from conda-forge"
+    )]
+    pub conda_env: bool,
+
+    // Distribution packages
+    #[structopt(
+        long,
+        use_delimiter = true,
+        possible_values = Packaging::VARIANTS,
+        help = "Adds package recipes, e.g. homebrew,aur,debian, building and installing with CMake"
+    )]
+    pub packaging: Vec<Packaging>,
+
+    // Source archive
+    #[structopt(
+        long,
+        help = "Adds a dist target packing the sources without build artifacts with CPack"
+    )]
+    pub dist: bool,
+
+    // Single header
+    #[structopt(
+        long,
+        help = "Adds a <target>-amalgamate target inlining the headers into one, for header-only kinds"
+    )]
+    pub amalgamate: bool,
+
+    // ABI compatibility
+    #[structopt(
+        long,
+        possible_values = AbiCheck::VARIANTS,
+        help = "Adds an abi-check target comparing the shared library with a baseline in abi/"
+    )]
+    pub abi_check: Option<AbiCheck>,
+
+    // Platforms
+    #[structopt(
+        long,
+        use_delimiter = true,
+        possible_values = Platform::VARIANTS,
+        help = "Target platforms, e.g. win,linux,macos, selecting the @IF_PLATFORM blocks of templates"
+    )]
+    pub platforms: Vec<Platform>,
+
+    // Preset
+    #[structopt(
+        long,
+        possible_values = Preset::VARIANTS,
+        help = "Scaffolds a project for a specific platform or use case"
+    )]
+    pub preset: Option<Preset>,
+
+    // Android Gradle module
+    #[structopt(long, help = "Adds a minimal Gradle module to the android preset")]
+    pub android_gradle: bool,
+
+    #[structopt(
+        long,
+        help = "Service presets stop on SIGINT/SIGTERM via a run loop and clean up at exit"
+    )]
+    pub graceful_shutdown: bool,
+
+    // Audio plugin codes
+    #[structopt(
+        long,
+        help = "Four characters with an upper-case letter identifying the audio-plugin manufacturer, e.g. Mnfc"
+    )]
+    pub plugin_manufacturer_code: Option<String>,
+
+    #[structopt(
+        long,
+        help = "Four characters with exactly one upper-case letter identifying the audio-plugin, e.g. Plgn"
+    )]
+    pub plugin_code: Option<String>,
+
+    // Test framework
+    #[structopt(long, possible_values = TestFramework::VARIANTS)]
+    pub test_framework: Option<TestFramework>,
+
+    // CTest timeout
+    #[structopt(long, help = "Timeout in seconds for each test")]
+    pub ctest_timeout: Option<u32>,
+
+    // CTest labels
+    #[structopt(
+        long = "test-label",
+        number_of_values = 1,
+        help = "Label attached to the tests, can be repeated"
+    )]
+    pub test_labels: Vec<String>,
+
+    // CTest registration
+    #[structopt(
+        long,
+        possible_values = TestDiscovery::VARIANTS,
+        help = "Registers one test per executable or per test case, defaults per framework"
+    )]
+    pub test_discovery: Option<TestDiscovery>,
+
+    // CDash
+    #[structopt(
+        long,
+        help = "Generates CTestConfig.cmake for submitting to this CDash host"
+    )]
+    pub cdash_drop_site: Option<String>,
+
+    // Fuzzing
+    #[structopt(
+        long,
+        possible_values = FuzzEngine::VARIANTS,
+        help = "Adds a fuzz/ directory with a harness for this engine"
+    )]
+    pub fuzzing: Option<FuzzEngine>,
+
+    // Static analysis
+    #[structopt(long, help = "Wires clang-tidy and cppcheck into the build")]
+    pub static_analysis: bool,
+
+    // include-what-you-use
+    #[structopt(long, help = "Wires include-what-you-use into the build")]
+    pub iwyu: bool,
+
+    // Compiler
+    #[structopt(
+        long,
+        possible_values = Compiler::VARIANTS,
+        help = "Tunes warning flags and generates CMake presets"
+    )]
+    pub compiler: Option<Compiler>,
+
+    // Toolchain pinning
+    #[structopt(
+        long,
+        help = "Pins the version of --compiler, e.g. 13, in cmake/Toolchain.cmake with a require-toolchain preset"
+    )]
+    pub toolchain_version: Option<String>,
+
+    // IDE folders
+    #[structopt(
+        long,
+        help = "Groups targets and sources into folders in Visual Studio and Xcode"
+    )]
+    pub ide_folders: bool,
+
+    // Placeholders in empty directories
+    #[structopt(
+        long,
+        value_name = "file-name",
+        help = "Drops a placeholder file, .gitkeep if no name is given, into empty directories"
+    )]
+    pub empty_dir_keep: Option<Option<String>>,
+
+    // Normalization of the rendered text files
+    #[structopt(
+        long,
+        default_value = "lf",
+        possible_values = LineEndings::VARIANTS,
+        help = "Line endings of the generated text files, scripts stay lf and batch files crlf"
+    )]
+    pub line_endings: LineEndings,
+
+    #[structopt(
+        long,
+        default_value = "single",
+        possible_values = TrailingNewline::VARIANTS,
+        help = "Ends every generated text file with exactly one newline, or keeps them as rendered"
+    )]
+    pub trailing_newline: TrailingNewline,
+
+    #[structopt(
+        long,
+        default_value = "keep",
+        possible_values = Indent::VARIANTS,
+        help = "Converts leading tabs to spaces or spaces to tabs, Makefiles and YAML are kept"
+    )]
+    pub indent: Indent,
+
+    #[structopt(
+        long,
+        default_value = "4",
+        help = "Columns of a tab when converting the indentation"
+    )]
+    pub tab_width: usize,
+
+    #[structopt(
+        long,
+        help = "Keeps UTF-8 byte order marks of template files, they are stripped by default"
+    )]
+    pub keep_bom: bool,
+
+    #[structopt(
+        long,
+        help = "Fails before writing anything if there are warnings, e.g. unused variables"
+    )]
+    pub deny_warnings: bool,
+
+    // Git submodules
+    #[structopt(
+        long = "submodule",
+        value_name = "name=git-url",
+        number_of_values = 1,
+        parse(try_from_str = parse_submodule),
+        help = "Adds the git submodule extern/<name> to .gitmodules and the build, can be repeated"
+    )]
+    pub submodules: Vec<(String, String)>,
+
+    // Git repository
+    #[structopt(
+        long,
+        help = "Runs 'git init' and 'git submodule add' in the generated project"
+    )]
+    pub git_init: bool,
+
+    // Examples
+    #[structopt(
+        long,
+        help = "Adds an examples/ directory with a program using the library"
+    )]
+    pub examples: bool,
+
+    // Template variables
+    #[structopt(
+        short = "D",
+        long = "define",
+        number_of_values = 1,
+        parse(try_from_str = parse_define),
+        help = "Sets the template variable @NAME@, e.g. -D VENDOR=acme"
+    )]
+    pub defines: Vec<(String, String)>,
+
+    // Interactive mode
+    #[structopt(
+        long,
+        help = "Prompts for required template variables not given via -D"
+    )]
+    pub interactive: bool,
+
+    // Tool check
+    #[structopt(
+        long,
+        help = "Checks the tools needed to build the project before generating"
+    )]
+    pub check_tools: bool,
+
+    // Lock of the output directory
+    #[structopt(
+        long,
+        help = "Waits for other runs generating into the same output directory"
+    )]
+    pub wait: bool,
+
+    #[structopt(
+        long,
+        conflicts_with = "wait",
+        help = "Generates without taking the lock of the output directory"
+    )]
+    pub no_lock: bool,
+
+    #[structopt(
+        long,
+        help = "Cancels the generation after this many seconds, removing what it created"
+    )]
+    pub timeout: Option<u64>,
+
+    // Statistics
+    #[structopt(
+        long,
+        help = "Prints timings, counts and bytes written after generating"
+    )]
+    pub stats: bool,
+
+    // Discovery
+    #[structopt(long, help = "Lists the available presets")]
+    pub list_presets: bool,
+
+    #[structopt(long, help = "Lists the available build systems")]
+    pub list_build_systems: bool,
+
+    #[structopt(long, help = "Lists the available test frameworks")]
+    pub list_test_frameworks: bool,
+
+    #[structopt(long, help = "Lists common SPDX license identifiers")]
+    pub list_licenses: bool,
+
+    #[structopt(long, help = "Lists the built-in template packs")]
+    pub list_builtin_templates: bool,
+
+    #[structopt(long, help = "Prints listings and generation events as JSON")]
+    pub json: bool,
+
+    // Subcommand
+    #[structopt(subcommand)]
+    pub cmd: Option<Command>,
+}
+
+impl Cli {
+    // Options of the library and the subcommand
+    pub fn into_parts(self) -> (Opt, Option<Command>) {
+        let opt = Opt {
+            name_space: self.name_space,
+            target_name: self.target_name,
+            cmake_version: self.cmake_version,
+            output_dir: self.output_dir,
+            output: self.output,
+            kind: self.kind,
+            template_dirs: self.template_dirs,
+            template_registry: self.template_registry,
+            offline: self.offline,
+            template_sha256: self.template_sha256,
+            verify_template_signature: self.verify_template_signature,
+            allow_unverified: self.allow_unverified,
+            builtin_template: self.builtin_template,
+            source_listing: self.source_listing,
+            author: self.author,
+            author_email: self.author_email,
+            license: self.license,
+            homepage: self.homepage,
+            project_version: self.project_version,
+            community_files: self.community_files,
+            forge: self.forge,
+            codeowners: self.codeowners,
+            codeowners_flavor: self.codeowners_flavor,
+            package_manager: self.package_manager,
+            dependency_updates: self.dependency_updates,
+            docs: self.docs,
+            docs_material: self.docs_material,
+            timestamp: self.timestamp,
+            language: self.language,
+            cxx_standard: self.cxx_standard,
+            c_standard: self.c_standard,
+            extra_languages: self.extra_languages,
+            sample_sources: self.sample_sources,
+            header_ext: self.header_ext,
+            source_ext: self.source_ext,
+            test_pattern: self.test_pattern,
+            resources: self.resources,
+            embed_resources: self.embed_resources,
+            i18n: self.i18n,
+            logging: self.logging,
+            app_config: self.app_config,
+            concurrency: self.concurrency,
+            no_compile_commands: self.no_compile_commands,
+            scripts: self.scripts,
+            task_runner: self.task_runner,
+            nix: self.nix,
+            conda_env: self.conda_env,
+            packaging: self.packaging,
+            dist: self.dist,
+            amalgamate: self.amalgamate,
+            abi_check: self.abi_check,
+            platforms: self.platforms,
+            preset: self.preset,
+            android_gradle: self.android_gradle,
+            graceful_shutdown: self.graceful_shutdown,
+            plugin_manufacturer_code: self.plugin_manufacturer_code,
+            plugin_code: self.plugin_code,
+            test_framework: self.test_framework,
+            ctest_timeout: self.ctest_timeout,
+            test_labels: self.test_labels,
+            test_discovery: self.test_discovery,
+            cdash_drop_site: self.cdash_drop_site,
+            fuzzing: self.fuzzing,
+            static_analysis: self.static_analysis,
+            iwyu: self.iwyu,
+            compiler: self.compiler,
+            toolchain_version: self.toolchain_version,
+            ide_folders: self.ide_folders,
+            empty_dir_keep: self.empty_dir_keep,
+            line_endings: self.line_endings,
+            trailing_newline: self.trailing_newline,
+            indent: self.indent,
+            tab_width: self.tab_width,
+            keep_bom: self.keep_bom,
+            deny_warnings: self.deny_warnings,
+            submodules: self.submodules,
+            git_init: self.git_init,
+            examples: self.examples,
+            defines: self.defines,
+            interactive: self.interactive,
+            check_tools: self.check_tools,
+            wait: self.wait,
+            no_lock: self.no_lock,
+            timeout: self.timeout,
+            stats: self.stats,
+            list_presets: self.list_presets,
+            list_build_systems: self.list_build_systems,
+            list_test_frameworks: self.list_test_frameworks,
+            list_licenses: self.list_licenses,
+            list_builtin_templates: self.list_builtin_templates,
+            json: self.json,
+        };
+
+        (opt, self.cmd)
+    }
+}
+
+// Subcommands
+#[derive(Debug, Clone, StructOpt)]
+pub enum Command {
+    /// Generates all projects listed in a YAML spec file
+    Batch {
+        #[structopt(parse(from_os_str))]
+        spec_file: PathBuf,
+    },
+    /// Gets or sets values of the user configuration
+    Config(ConfigCommand),
+    /// Manages the cache of remote template packs
+    Template(TemplateCommand),
+    /// Renames the target and namespace of a generated project
+    Rename {
+        #[structopt(long = "target", help = "New target name")]
+        target_name: String,
+        #[structopt(long, help = "New namespace, kept if not given")]
+        name_space: Option<String>,
+        #[structopt(long, help = "Prints the changes as diff without writing them")]
+        dry_run: bool,
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        project_dir: PathBuf,
+    },
+    /// Writes a project manifest for an existing CMake project
+    Adopt {
+        #[structopt(default_value = ".", parse(from_os_str))]
+        project_dir: PathBuf,
+        #[structopt(
+            long = "target",
+            help = "Target name, inferred from CMakeLists.txt if not given"
+        )]
+        target_name: Option<String>,
+        #[structopt(
+            long,
+            help = "Namespace, inferred from the include directory if not given"
+        )]
+        name_space: Option<String>,
+        #[structopt(long, help = "Prints the manifest without writing it")]
+        dry_run: bool,
+    },
+    /// Checks that tracked sources start with the SPDX license header
+    CheckHeaders {
+        #[structopt(long, help = "Adds or replaces missing and outdated headers")]
+        fix: bool,
+        #[structopt(long, help = "SPDX license identifier, the recorded one if not given")]
+        license: Option<String>,
+        #[structopt(long, help = "Copyright holder, the recorded one if not given")]
+        author: Option<String>,
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        project_dir: PathBuf,
+    },
+    /// Adds a dependency like fmt/10.2.1 to a generated project
+    AddDependency {
+        dependency: String,
+        #[structopt(
+            long,
+            possible_values = PackageManager::VARIANTS,
+            help = "Package manager to add it to, recorded for later calls"
+        )]
+        package_manager: Option<PackageManager>,
+        #[structopt(long, help = "Imported target to link, e.g. fmt::fmt-header-only")]
+        link_target: Option<String>,
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        project_dir: PathBuf,
+    },
+    /// Adds a test file for a class like Widget to a generated project
+    AddTest {
+        class_name: String,
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        project_dir: PathBuf,
+    },
+    /// Writes a reproducible source tarball of a generated project
+    Dist {
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        project_dir: PathBuf,
+        #[structopt(
+            long,
+            parse(from_os_str),
+            help = "Archive path, <project>-<version>.tar if not given"
+        )]
+        output: Option<PathBuf>,
+        #[structopt(
+            long,
+            env = "SOURCE_DATE_EPOCH",
+            default_value = "0",
+            help = "Unix time of all archive entries"
+        )]
+        mtime: u64,
+    },
+    /// Serves POST /generate, answering with the rendered project as zip
+    Serve {
+        #[structopt(long, default_value = "127.0.0.1:8080", help = "Address to listen on")]
+        address: String,
+        #[structopt(long, default_value = "1000", help = "Maximum files per request")]
+        max_files: usize,
+        #[structopt(
+            long,
+            default_value = "16777216",
+            help = "Maximum rendered bytes per request"
+        )]
+        max_bytes: u64,
+        #[structopt(
+            long,
+            default_value = "10",
+            help = "Maximum render time in seconds per request"
+        )]
+        render_timeout: u64,
+    },
+}
+
+// Config subcommands
+#[derive(Debug, Clone, StructOpt)]
+pub enum ConfigCommand {
+    /// Prints a value of the user configuration
+    Get { key: String },
+    /// Sets a value of the user configuration
+    Set { key: String, value: String },
+    /// Removes a value from the user configuration
+    Unset { key: String },
+    /// Prints the user configuration
+    List,
+    /// Prints the path of the user configuration file
+    Path,
+}
+
+// Template subcommands
+#[derive(Debug, Clone, StructOpt)]
+pub enum TemplateCommand {
+    /// Fetches the latest state of all cached remote template packs
+    Update,
+    /// Prints the cached remote template packs
+    List,
+    /// Prints the path of the template cache
+    Path,
+}
+
+// Applies the defaults to all options the user did not pass explicitly
+pub fn apply_defaults(opt: &mut Opt, matches: &ArgMatches, defaults: Defaults) {
+    let is_explicit = |name: &str| matches.occurrences_of(name) > 0;
+
+    if !is_explicit("name-space") {
+        opt.name_space = defaults.name_space.or_else(|| opt.name_space.take());
+    }
+    if !is_explicit("cmake-version") {
+        opt.cmake_version = defaults.cmake_version.or_else(|| opt.cmake_version.take());
+    }
+    if !is_explicit("output-dir") {
+        opt.output_dir = defaults.output_dir.or_else(|| opt.output_dir.take());
+    }
+    if !is_explicit("kind") {
+        if let Some(kind) = defaults.kind {
+            opt.kind = kind;
+        }
+    }
+    // The default template directory is the only layer then
+    if !is_explicit("template") {
+        if let Some(template_dir) = defaults.template_dir {
+            opt.template_dirs = vec![template_dir];
+        }
+    }
+    if !is_explicit("template-registry") {
+        opt.template_registry = defaults
+            .template_registry
+            .or_else(|| opt.template_registry.take());
+    }
+    if !is_explicit("author") {
+        opt.author = defaults.author.or_else(|| opt.author.take());
+    }
+    if !is_explicit("author-email") {
+        opt.author_email = defaults.author_email.or_else(|| opt.author_email.take());
+    }
+    if !is_explicit("license") {
+        opt.license = defaults.license.or_else(|| opt.license.take());
+    }
+    if !is_explicit("test-framework") {
+        opt.test_framework = defaults.test_framework.or(opt.test_framework);
+    }
+    if !is_explicit("header-ext") {
+        if let Some(header_ext) = defaults.header_ext {
+            opt.header_ext = header_ext;
+        }
+    }
+    if !is_explicit("source-ext") {
+        if let Some(source_ext) = defaults.source_ext {
+            opt.source_ext = source_ext;
+        }
+    }
+    if !is_explicit("test-pattern") {
+        if let Some(test_pattern) = defaults.test_pattern {
+            opt.test_pattern = test_pattern;
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_command_line() {
+        let (parsed, cmd) = Cli::from_iter(&["cpp-proj-gen"]).into_parts();
+        let expected = Opt {
+            timestamp: None,
+            ..parsed
+        };
+
+        assert!(cmd.is_none());
+        assert_eq!(format!("{:?}", Opt::default()), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn test_command_line_wins() {
+        let defaults = Defaults {
+            name_space: Some(String::from("env_nmspc")),
+            cmake_version: Some(String::from("3.99.0")),
+            kind: Some(Kind::Shared),
+            ..Defaults::default()
+        };
+        let args = ["cpp-proj-gen", "--cmake-version", "3.20.0"];
+        let matches = Cli::clap().get_matches_from(args);
+        let (mut opt, _) = Cli::from_clap(&matches).into_parts();
+
+        apply_defaults(&mut opt, &matches, defaults);
+
+        assert_eq!(opt.cmake_version.as_deref(), Some("3.20.0"));
+        assert_eq!(opt.name_space.as_deref(), Some("env_nmspc"));
+        assert_eq!(opt.kind, Kind::Shared);
+    }
+}
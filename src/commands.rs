@@ -0,0 +1,204 @@
+/*
+For print! and stdout /sa https://doc.rust-lang.org/std/macro.print.html
+*/
+
+use crate::cli::{ConfigCommand, TemplateCommand};
+use cpp_proj_gen::batch::{self, BatchResult};
+use cpp_proj_gen::config;
+use cpp_proj_gen::cpp_proj_gen::Opt;
+use cpp_proj_gen::dependency::{self, PackageManager};
+use cpp_proj_gen::error;
+use cpp_proj_gen::template_cache;
+use cpp_proj_gen::template_pack::{Variable, VariableType};
+use cpp_proj_gen::{add_test, adopt, discovery, dist, file_header, project_manifest, rename};
+use std::{
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+pub fn discovery(opt: &Opt) -> io::Result<()> {
+    print!("{}", discovery::render_listings(opt)?);
+
+    Ok(())
+}
+
+pub fn config(cmd: &ConfigCommand) -> io::Result<()> {
+    let path = config::user_config_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no user config directory"))?;
+
+    match cmd {
+        ConfigCommand::Get { key } => {
+            if let Some(value) = config::get(&path, key)? {
+                println!("{}", value);
+            }
+        }
+        ConfigCommand::Set { key, value } => config::set(&path, key, value)?,
+        ConfigCommand::Unset { key } => config::unset(&path, key)?,
+        ConfigCommand::List => {
+            for (key, value) in config::list(&path)? {
+                println!("{} = {}", key, value);
+            }
+        }
+        ConfigCommand::Path => println!("{}", path.display()),
+    }
+
+    Ok(())
+}
+
+pub fn template(cmd: &TemplateCommand) -> error::Result<()> {
+    let cache_dir = template_cache::cache_dir().ok_or_else(template_cache::no_cache_dir)?;
+
+    match cmd {
+        TemplateCommand::Update => {
+            for clone_dir in template_cache::cached_packs(&cache_dir)? {
+                println!("Updating: {}", clone_dir.display());
+                template_cache::update(&clone_dir)?;
+            }
+        }
+        TemplateCommand::List => {
+            for clone_dir in template_cache::cached_packs(&cache_dir)? {
+                println!("{}", clone_dir.display());
+            }
+        }
+        TemplateCommand::Path => println!("{}", cache_dir.display()),
+    }
+
+    Ok(())
+}
+
+// A dry run prints the diff
+pub fn rename(
+    project_dir: &Path,
+    target_name: &str,
+    name_space: Option<&str>,
+    dry_run: bool,
+) -> error::Result<()> {
+    let diff = rename::rename_project(project_dir, target_name, name_space, dry_run)?;
+    if dry_run {
+        print!("{}", diff);
+    }
+
+    Ok(())
+}
+
+// A dry run prints the manifest
+pub fn adopt(
+    project_dir: &Path,
+    target_name: Option<&str>,
+    name_space: Option<&str>,
+    dry_run: bool,
+) -> error::Result<()> {
+    let manifest = adopt::adopt_project(project_dir, target_name, name_space, dry_run)?;
+    match dry_run {
+        true => print!("{}", manifest),
+        false => println!(
+            "Created: {}",
+            project_dir.join(project_manifest::MANIFEST_PATH).display()
+        ),
+    }
+
+    Ok(())
+}
+
+pub fn add_test(project_dir: &Path, class_name: &str) -> error::Result<()> {
+    let added = add_test::add_test(project_dir, class_name)?;
+
+    println!("Created: {}", added.test_path.display());
+    match added.registered {
+        true => println!("Updated: {}", added.cmake_lists.display()),
+        false => println!(
+            "Not registered, {} globs its sources",
+            added.cmake_lists.display()
+        ),
+    }
+
+    Ok(())
+}
+
+pub fn dist(project_dir: &Path, output: Option<&Path>, mtime: u64) -> error::Result<()> {
+    let output = dist::write_archive(project_dir, output, mtime)?;
+    println!("Created: {}", output.display());
+
+    Ok(())
+}
+
+pub fn check_headers(
+    project_dir: &Path,
+    license: Option<&str>,
+    author: Option<&str>,
+    fix: bool,
+) -> error::Result<()> {
+    let missing = file_header::check_headers(project_dir, license, author, fix)?;
+    for file in &missing {
+        match fix {
+            true => println!("Fixed: {}", file),
+            false => println!("Missing header: {}", file),
+        }
+    }
+
+    file_header::check_missing(&missing, fix)
+}
+
+pub fn add_dependency(
+    project_dir: &Path,
+    spec: &str,
+    package_manager: Option<PackageManager>,
+    link_target: Option<&str>,
+) -> error::Result<()> {
+    let report = dependency::add_dependency(project_dir, spec, package_manager, link_target)?;
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    for path in &report.updated {
+        println!("Updated: {}", path.display());
+    }
+
+    Ok(())
+}
+
+// Reports per project, fails if any project failed
+pub fn batch<F>(spec_file: &Path, base: &Opt, gen: F) -> error::Result<()>
+where
+    F: Fn(Opt) -> error::Result<()>,
+{
+    let results = batch::gen_all(spec_file, base, gen)?;
+    for BatchResult { name, result } in &results {
+        match result {
+            Ok(()) => println!("[ok] {}", name),
+            Err(err) => eprintln!("[failed] {}: {}", name, err),
+        }
+    }
+
+    let generated = results
+        .iter()
+        .filter(|result| result.result.is_ok())
+        .count();
+    println!("{} of {} projects generated", generated, results.len());
+
+    batch::check_results(&results)
+}
+
+// Asks on stdin, e.g. "VENDOR (Vendor name): " or "LEVEL (Level) [low/high]: "
+pub fn prompt_stdin(name: &str, variable: &Variable, reason: Option<&str>) -> io::Result<String> {
+    if let Some(reason) = reason {
+        eprintln!("{}: {}", name, reason);
+    }
+
+    match variable.prompt.as_ref().or(variable.description.as_ref()) {
+        Some(text) => print!("{} ({})", name, text),
+        None => print!("{}", name),
+    }
+    match variable.variable_type {
+        VariableType::Bool => print!(" [true/false]"),
+        VariableType::Enum => print!(" [{}]", variable.values.join("/")),
+        VariableType::List => print!(" [comma-separated]"),
+        VariableType::String | VariableType::Semver => {}
+    }
+    print!(": ");
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+
+    Ok(String::from(line.trim()))
+}
@@ -0,0 +1,6 @@
+/*
+For re-exports /sa https://doc.rust-lang.org/reference/items/use-declarations.html#use-visibility
+*/
+
+// The library of cpp-proj-gen-core, for tools that depended on this crate before the split
+pub use cpp_proj_gen_core::*;
@@ -1,17 +1,324 @@
-mod cpp_proj_gen;
-use std::path::PathBuf;
-use structopt::StructOpt;
+mod cli;
+mod commands;
+mod reporters;
+mod serve;
 
-fn main() -> std::io::Result<()> {
-    let opt = cpp_proj_gen::Opt::from_args();
+use cancel::CancelToken;
+use cli::{Cli, Command};
+use cpp_proj_gen::cpp_proj_gen::{CppProjGen, Opt};
+#[cfg(feature = "async")]
+use cpp_proj_gen::gen_async;
+use cpp_proj_gen::{
+    builtin_template, cancel, cmake_version, config, defaults, discovery, error, limits, lock,
+    output, presets, reporter, submodules, template_cache, template_pack, template_vars, tools,
+    warning,
+};
+use defaults::Defaults;
+use limits::Limits;
+use lock::GenLock;
+use output::{LocalOutput, OutputAdapter, RemoteOutput};
+use reporter::{GenEvent, Reporter};
+use reporters::{JsonReporter, ProgressBarReporter};
+use std::{collections::BTreeSet, path::PathBuf, process, time::Duration};
+use structopt::{
+    clap::{self, ErrorKind},
+    StructOpt,
+};
+use template_pack::{Manifest, Prompt};
+use template_vars::VarValue;
+use warning::Warning;
 
-    let progress = |text: String| println!("Created: {}", text);
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {}", err);
+        process::exit(err.exit_code());
+    }
+}
+
+// Usage errors are invalid options, help and version exit successfully
+fn exit_usage(err: clap::Error) -> ! {
+    match err.kind {
+        ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => err.exit(),
+        _ => {
+            eprintln!("{}", err.message);
+            process::exit(error::EXIT_VALIDATION);
+        }
+    }
+}
+
+fn run() -> error::Result<()> {
+    let matches = match Cli::clap().get_matches_safe() {
+        Ok(matches) => matches,
+        Err(err) => exit_usage(err),
+    };
+    let (mut opt, cmd) = Cli::from_clap(&matches).into_parts();
+
+    if discovery::is_requested(&opt) {
+        return Ok(commands::discovery(&opt)?);
+    }
+
+    if let Some(Command::Config(cmd)) = &cmd {
+        return Ok(commands::config(cmd)?);
+    }
+
+    if let Some(Command::Template(cmd)) = &cmd {
+        return commands::template(cmd);
+    }
+
+    if let Some(Command::Rename {
+        target_name,
+        name_space,
+        dry_run,
+        project_dir,
+    }) = &cmd
+    {
+        return commands::rename(project_dir, target_name, name_space.as_deref(), *dry_run);
+    }
+
+    if let Some(Command::Adopt {
+        project_dir,
+        target_name,
+        name_space,
+        dry_run,
+    }) = &cmd
+    {
+        return commands::adopt(
+            project_dir,
+            target_name.as_deref(),
+            name_space.as_deref(),
+            *dry_run,
+        );
+    }
+
+    if let Some(Command::AddTest {
+        class_name,
+        project_dir,
+    }) = &cmd
+    {
+        return commands::add_test(project_dir, class_name);
+    }
+
+    if let Some(Command::Dist {
+        project_dir,
+        output,
+        mtime,
+    }) = &cmd
+    {
+        return commands::dist(project_dir, output.as_deref(), *mtime);
+    }
+
+    if let Some(Command::CheckHeaders {
+        fix,
+        license,
+        author,
+        project_dir,
+    }) = &cmd
+    {
+        return commands::check_headers(project_dir, license.as_deref(), author.as_deref(), *fix);
+    }
+
+    if let Some(Command::Serve {
+        address,
+        max_files,
+        max_bytes,
+        render_timeout,
+    }) = &cmd
+    {
+        let limits = Limits {
+            max_files: Some(*max_files),
+            max_bytes: Some(*max_bytes),
+            render_timeout: Some(Duration::from_secs(*render_timeout)),
+        };
+        return serve::run(address, limits);
+    }
+
+    if let Some(Command::AddDependency {
+        dependency,
+        package_manager,
+        link_target,
+        project_dir,
+    }) = &cmd
+    {
+        return commands::add_dependency(
+            project_dir,
+            dependency,
+            *package_manager,
+            link_target.as_deref(),
+        );
+    }
+
+    let defaults = presets::defaults(opt.preset)
+        .or(config::load_defaults()?)
+        .or(Defaults::from_env()?);
+    cli::apply_defaults(&mut opt, &matches, defaults);
+
+    match cmd {
+        Some(Command::Batch { spec_file }) => commands::batch(&spec_file, &opt, gen_project),
+        _ => gen_project(opt),
+    }
+}
+
+fn gen_project(mut opt: Opt) -> error::Result<()> {
+    #[cfg(feature = "async")]
+    check_async_output(&opt)?;
+
+    let mut reporter: Box<dyn Reporter + Send> = match opt.json {
+        true => Box::new(JsonReporter),
+        false => Box::new(ProgressBarReporter::new()),
+    };
+
+    builtin_template::resolve(&mut opt);
+    let mut warnings: Vec<Warning> = cmake_version::resolve(&mut opt)?
+        .into_iter()
+        .map(Warning::Other)
+        .collect();
+
+    opt.template_dirs = template_cache::resolve(&opt)?;
+    let manifest = Manifest::load(&opt.template_dirs)?;
+    let variables = match &manifest {
+        Some(manifest) => {
+            manifest.validate(&opt)?;
+            let mut prompt = commands::prompt_stdin;
+            let prompt: Option<Prompt> = match opt.interactive {
+                true => Some(&mut prompt),
+                false => None,
+            };
+            manifest.resolve_variables(&opt.defines, prompt)?
+        }
+        None => opt.defines.iter().cloned().collect(),
+    };
+
+    if opt.check_tools {
+        let extra_tools = match &manifest {
+            Some(manifest) => manifest.tools()?,
+            None => Vec::new(),
+        };
+        let report = tools::check_tools(&opt, extra_tools)?;
+        for found in &report.found {
+            println!("Found: {}", found);
+        }
+        warnings.extend(report.warnings);
+    }
+
+    let stats = opt.stats;
+    let upload_url = opt.output.as_ref().map(ToString::to_string);
+    // Remote outputs are staged locally, the lock only guards local output directories
+    let (mut output, no_lock): (Box<dyn OutputAdapter>, bool) = match opt.output.take() {
+        Some(url) => {
+            let output = RemoteOutput::new(url)?;
+            opt.output_dir = Some(output.staging_dir().to_path_buf());
+            (Box::new(output), true)
+        }
+        None => (Box::new(LocalOutput), opt.no_lock),
+    };
+    let wait = opt.wait;
+    let cancel = CancelToken::default();
+    if let Some(timeout) = opt.timeout {
+        cancel.cancel_after(Duration::from_secs(timeout));
+    }
+    // Only vars set with -D that the pack does not know of may be typos
+    let defined_vars: BTreeSet<String> = opt
+        .defines
+        .iter()
+        .map(|(name, _)| name.clone())
+        .filter(|name| {
+            manifest
+                .as_ref()
+                .is_none_or(|manifest| !manifest.uses_variable(name))
+        })
+        .collect();
+
+    let gen = variables.iter().fold(
+        CppProjGen::new(opt)
+            .set_cancel_token(cancel.clone())
+            .add_include_dir(PathBuf::from("include"))
+            .add_source_dir(PathBuf::from("source"))
+            .add_test_dir(PathBuf::from("test")),
+        |gen, (name, value)| {
+            let value = match &manifest {
+                Some(manifest) => manifest.var_value(name, value),
+                None => VarValue::from(value.as_str()),
+            };
+            let var = format!("@{}@", name);
+            match defined_vars.contains(name) {
+                true => gen.add_defined_var(&var, value),
+                false => gen.add_cmake_value(&var, value),
+            }
+        },
+    );
+    let gen = warnings.into_iter().fold(gen, CppProjGen::add_warning);
+    let gen = match &manifest {
+        Some(manifest) => manifest.add_files(gen, &variables, reporter.as_mut())?,
+        None => gen,
+    };
+    // Held until the post-hooks are done
+    let _lock = match (no_lock, gen.out_dir().parent()) {
+        (false, Some(output_root)) => Some(GenLock::acquire(output_root, wait, reporter.as_mut())?),
+        _ => None,
+    };
+    #[cfg(not(feature = "async"))]
+    let report = gen.gen(output.as_mut(), Some(reporter.as_mut()))?; // or 'None' for no reporter!
+    #[cfg(feature = "async")]
+    let report = gen_async::block_on(gen.gen_async(Some(reporter.as_mut())))??;
 
-    cpp_proj_gen::CppProjGen::new(opt)
-        .add_include_dir(PathBuf::from("include"))
-        .add_source_dir(PathBuf::from("source"))
-        .add_toplevel_dir(PathBuf::from("test"))
-        .gen(Some(progress))?; // or 'None' for no callback!
+    submodules::run(gen.out_dir(), gen.opt(), reporter.as_mut())?;
+
+    if let Some(manifest) = &manifest {
+        #[cfg(not(feature = "async"))]
+        manifest.run_post_hooks(gen.out_dir(), reporter.as_mut(), &cancel)?;
+        #[cfg(feature = "async")]
+        gen_async::block_on(manifest.run_post_hooks_async(
+            gen.out_dir(),
+            reporter.as_mut(),
+            &cancel,
+        ))??;
+    }
+    if let Some(url) = upload_url {
+        reporter.event(&GenEvent::UploadStarted { url });
+    }
+    output.finish()?;
+
+    for warning in &report.warnings {
+        reporter.event(&GenEvent::Warning {
+            message: warning.to_string(),
+        });
+    }
+    if stats {
+        println!("{}", report);
+    }
 
     Ok(())
 }
+
+// gen_async() writes only into the local filesystem, a remote output would never be uploaded
+#[cfg(feature = "async")]
+fn check_async_output(opt: &Opt) -> error::Result<()> {
+    match &opt.output {
+        Some(url) => Err(error::Error::from(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "--output {} needs a build without the async feature, use --output-dir",
+                url
+            ),
+        ))),
+        None => Ok(()),
+    }
+}
+
+// Unit tests
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_async_output() {
+        let opt = Opt {
+            output: Some("s3://scaffolds".parse().unwrap()),
+            ..Opt::default()
+        };
+        let err = check_async_output(&opt).unwrap_err();
+
+        assert_eq!(err.exit_code(), error::EXIT_VALIDATION);
+        assert!(check_async_output(&Opt::default()).is_ok());
+    }
+}
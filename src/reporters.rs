@@ -0,0 +1,88 @@
+/*
+For indicatif /sa https://docs.rs/indicatif/0.17/indicatif/
+For JSON Lines /sa https://jsonlines.org/
+*/
+
+use cpp_proj_gen::reporter::{GenEvent, Reporter};
+use indicatif::{ProgressBar, ProgressStyle};
+
+const PROGRESS_TEMPLATE: &str = "[{elapsed_precise}] {wide_bar} {pos}/{len} (ETA {eta}) {msg}";
+
+// Prints one line per written path, hook and warning, for logs and terminals without a progress bar
+#[derive(Debug, Default)]
+pub struct PrintReporter;
+
+impl Reporter for PrintReporter {
+    fn event(&mut self, event: &GenEvent) {
+        match event {
+            GenEvent::DirCreated { path, .. } | GenEvent::FileWritten { path, .. } => {
+                println!("Created: {}", path.display())
+            }
+            GenEvent::LinkCreated { path, target, .. } => {
+                println!("Created: {} -> {}", path.display(), target.display())
+            }
+            GenEvent::Skipped { path, reason } => {
+                println!("Skipped: {} ({})", path.display(), reason)
+            }
+            GenEvent::HookStarted { command } => println!("Running: {}", command),
+            GenEvent::Warning { message } => eprintln!("Warning: {}", message),
+            GenEvent::LockWaiting { path, holder } => {
+                eprintln!("Waiting for {} held by {}", path.display(), holder)
+            }
+            GenEvent::UploadStarted { url } => println!("Uploading to {}", url),
+            _ => {}
+        }
+    }
+}
+
+// Progress bar with ETA on stderr, prints lines like PrintReporter if stderr is no terminal
+pub struct ProgressBarReporter {
+    bar: ProgressBar,
+}
+
+impl ProgressBarReporter {
+    pub fn new() -> Self {
+        let bar = ProgressBar::new(0);
+        if let Ok(style) = ProgressStyle::with_template(PROGRESS_TEMPLATE) {
+            bar.set_style(style);
+        }
+
+        ProgressBarReporter { bar }
+    }
+}
+
+impl Default for ProgressBarReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporter for ProgressBarReporter {
+    fn event(&mut self, event: &GenEvent) {
+        let (step, total, path) = match event.progress() {
+            Some(progress) if !self.bar.is_hidden() => progress,
+            _ => return PrintReporter.event(event),
+        };
+
+        self.bar.set_length(total as u64);
+        self.bar.set_position(step as u64);
+        self.bar.set_message(path.to_string_lossy().into_owned());
+
+        if step == total {
+            self.bar.finish_and_clear();
+            println!("Created: {} paths", total);
+        }
+    }
+}
+
+// One JSON object per line on stdout, for GUI wrappers streaming the progress
+#[derive(Debug, Default)]
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn event(&mut self, event: &GenEvent) {
+        if let Ok(json) = serde_json::to_string(event) {
+            println!("{}", json);
+        }
+    }
+}
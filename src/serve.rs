@@ -0,0 +1,600 @@
+/*
+For HTTP/1.1 messages /sa https://www.rfc-editor.org/rfc/rfc9112
+For TcpListener /sa https://doc.rust-lang.org/std/net/struct.TcpListener.html
+For Content-Disposition filename* /sa https://www.rfc-editor.org/rfc/rfc6266#section-4.3
+*/
+
+use crate::cli::{self, Cli};
+use cpp_proj_gen::archive::ZipWriter;
+use cpp_proj_gen::cpp_proj_gen::{CppProjGen, Opt};
+use cpp_proj_gen::error::{self, invalid_input, Error};
+use cpp_proj_gen::limits::Limits;
+use cpp_proj_gen::output::{MemoryEntry, MemoryOutput, OutputAdapter};
+use cpp_proj_gen::warning::Warning;
+use cpp_proj_gen::{builtin_template, cmake_version, collisions, presets};
+use serde_json::{Map, Value};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+use structopt::StructOpt;
+
+const MAX_BODY_BYTES: usize = 64 * 1024;
+// Request line and headers together, like the 8 KiB of common proxies
+const MAX_HEADER_BYTES: u64 = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+const MAX_CONNECTIONS: usize = 16;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Options a request may set, everything touching the server's filesystem or network is left out
+const REQUEST_OPTIONS: &[&str] = &[
+    "target-name",
+    "name-space",
+    "kind",
+    "cmake-version",
+    "builtin-template",
+    "source-listing",
+    "author",
+    "author-email",
+    "license",
+    "homepage",
+    "project-version",
+    "community-files",
+    "forge",
+    "codeowners",
+    "codeowners-flavor",
+    "package-manager",
+    "dependency-updates",
+    "docs",
+    "docs-material",
+    "timestamp",
+    "language",
+    "cxx-standard",
+    "c-standard",
+    "extra-language",
+    "sample-sources",
+    "header-ext",
+    "source-ext",
+    "test-pattern",
+    "resources",
+    "embed-resources",
+    "i18n",
+    "logging",
+    "app-config",
+    "concurrency",
+    "no-compile-commands",
+    "scripts",
+    "task-runner",
+    "nix",
+    "conda-env",
+    "packaging",
+    "dist",
+    "amalgamate",
+    "abi-check",
+    "line-endings",
+    "trailing-newline",
+    "indent",
+    "tab-width",
+    "keep-bom",
+    "deny-warnings",
+    "platforms",
+    "preset",
+    "android-gradle",
+    "graceful-shutdown",
+    "plugin-manufacturer-code",
+    "plugin-code",
+    "test-framework",
+    "ctest-timeout",
+    "test-label",
+    "test-discovery",
+    "cdash-drop-site",
+    "fuzzing",
+    "static-analysis",
+    "iwyu",
+    "compiler",
+    "toolchain-version",
+    "ide-folders",
+    "empty-dir-keep",
+    "examples",
+    "submodule",
+    "define",
+];
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn text(status: &'static str, message: &str) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type", String::from("text/plain; charset=utf-8"))],
+            body: format!("{}\n", message).into_bytes(),
+        }
+    }
+
+    fn zip(file_name: &str, body: Vec<u8>) -> Self {
+        Response {
+            status: "200 OK",
+            headers: vec![
+                ("Content-Type", String::from("application/zip")),
+                ("Content-Disposition", content_disposition(file_name)),
+            ],
+            body,
+        }
+    }
+
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(stream, "HTTP/1.1 {}\r\n", self.status)?;
+        for (name, value) in &self.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        // Web frontends call it from their own origin
+        write!(stream, "Access-Control-Allow-Origin: *\r\n")?;
+        write!(stream, "Content-Length: {}\r\n", self.body.len())?;
+        write!(stream, "Connection: close\r\n\r\n")?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+// Serves POST /generate until killed, each connection on its own thread within the limits
+pub fn run(address: &str, limits: Limits) -> error::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    println!("Serving on http://{}/generate", listener.local_addr()?);
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Warning: cannot accept connection: {}", err);
+                continue;
+            }
+        };
+        // Slow clients only hold their own thread, up to MAX_CONNECTIONS of them
+        if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            let busy = Response::text("503 Service Unavailable", "too many connections");
+            if let Err(err) = busy.write_to(&mut stream) {
+                eprintln!("Warning: cannot answer request: {}", err);
+            }
+            continue;
+        }
+        let connections = Arc::clone(&connections);
+        thread::spawn(move || {
+            if let Err(err) = handle(&mut stream, &limits) {
+                eprintln!("Warning: cannot answer request: {}", err);
+            }
+            connections.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+
+    Ok(())
+}
+
+fn handle(stream: &mut TcpStream, limits: &Limits) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let response = match read_request(&mut BufReader::new(&*stream)) {
+        Ok(request) => respond(&request, limits),
+        Err(response) => response,
+    };
+
+    response.write_to(stream)
+}
+
+fn respond(request: &Request, limits: &Limits) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/generate") => match generate(&request.body, limits) {
+            Ok(response) => response,
+            Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                Response::text("400 Bad Request", &err.to_string())
+            }
+            Err(err) => Response::text("500 Internal Server Error", &err.to_string()),
+        },
+        // CORS preflight of browsers posting JSON
+        ("OPTIONS", "/generate") => Response {
+            status: "204 No Content",
+            headers: vec![
+                ("Access-Control-Allow-Methods", String::from("POST")),
+                ("Access-Control-Allow-Headers", String::from("Content-Type")),
+            ],
+            body: Vec::new(),
+        },
+        (_, "/generate") => Response::text("405 Method Not Allowed", "use POST"),
+        _ => Response::text("404 Not Found", "not found, use POST /generate"),
+    }
+}
+
+// Fails with the response to send instead, e.g. 431 for headers over MAX_HEADER_BYTES
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let mut header_bytes = 0;
+    let line = read_header_line(reader, &mut header_bytes)?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (String::from(method), String::from(path)),
+        _ => {
+            return Err(bad_request(format!(
+                "invalid request line '{}'",
+                line.trim()
+            )))
+        }
+    };
+
+    let mut content_length = 0;
+    for header_count in 0.. {
+        let line = read_header_line(reader, &mut header_bytes)?;
+        if line.trim().is_empty() {
+            break;
+        }
+        if header_count == MAX_HEADERS {
+            return Err(headers_too_large(format!(
+                "more than {} header fields",
+                MAX_HEADERS
+            )));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().map_err(|_| {
+                    bad_request(format!("invalid Content-Length '{}'", value.trim()))
+                })?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(bad_request(format!(
+            "the body exceeds {} bytes",
+            MAX_BODY_BYTES
+        )));
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|err| bad_request(err.to_string()))?;
+
+    Ok(Request { method, path, body })
+}
+
+// Reads at most what is left of MAX_HEADER_BYTES, an empty line at the end of the stream
+fn read_header_line(reader: &mut impl BufRead, header_bytes: &mut u64) -> Result<String, Response> {
+    let mut line = String::new();
+    let left = MAX_HEADER_BYTES - *header_bytes;
+    let read = reader
+        .by_ref()
+        .take(left)
+        .read_line(&mut line)
+        .map_err(|err| bad_request(err.to_string()))?;
+    *header_bytes += read as u64;
+
+    if read as u64 == left && !line.ends_with('\n') {
+        return Err(headers_too_large(format!(
+            "the request line and headers exceed {} bytes",
+            MAX_HEADER_BYTES
+        )));
+    }
+
+    Ok(line)
+}
+
+// Renders the project of a JSON body like {"target-name": "my-lib", "kind": "shared"} into a zip
+fn generate(body: &[u8], limits: &Limits) -> io::Result<Response> {
+    let options: Map<String, Value> = match body.is_empty() {
+        true => Map::new(),
+        false => serde_json::from_slice(body)
+            .map_err(|err| invalid_input(format!("expected a JSON object: {}", err)))?,
+    };
+    let mut opt = parse_options(&options)?;
+    // The target name becomes a header value and CMake code
+    collisions::validate(&opt, &[])?;
+    // Paths below the output directory are relative to the archive root
+    opt.output_dir = Some(PathBuf::new());
+
+    builtin_template::resolve(&mut opt);
+    let warnings = cmake_version::resolve(&mut opt)?;
+    let file_name = opt.target_name.clone();
+    let gen = CppProjGen::new(opt)
+        .add_include_dir(PathBuf::from("include"))
+        .add_source_dir(PathBuf::from("source"))
+        .add_test_dir(PathBuf::from("test"))
+        .set_limits(*limits);
+    // Only fail the request with --deny-warnings, a zip has no place for them otherwise
+    let gen = warnings
+        .into_iter()
+        .map(Warning::Other)
+        .fold(gen, CppProjGen::add_warning);
+
+    let mut output = MemoryOutput::default();
+    gen.gen(&mut output, None).map_err(to_io)?;
+    output.finish().map_err(to_io)?;
+
+    Ok(Response::zip(&file_name, build_zip(&output)?))
+}
+
+// Maps the JSON object to command line arguments, so requests are validated like the CLI
+fn parse_options(options: &Map<String, Value>) -> io::Result<Opt> {
+    let mut args = vec![String::from("cpp-proj-gen")];
+
+    for (name, value) in options {
+        if !REQUEST_OPTIONS.contains(&name.as_str()) {
+            return Err(invalid_input(format!(
+                "unknown option '{}', expected one of: {}",
+                name,
+                REQUEST_OPTIONS.join(", ")
+            )));
+        }
+
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                Value::Bool(true) => args.push(format!("--{}", name)),
+                Value::Bool(false) | Value::Null => {}
+                Value::String(value) => args.extend([format!("--{}", name), value.clone()]),
+                Value::Number(value) => args.extend([format!("--{}", name), value.to_string()]),
+                _ => {
+                    return Err(invalid_input(format!(
+                        "option '{}' must be a string, number, boolean or array of those",
+                        name
+                    )))
+                }
+            }
+        }
+    }
+
+    let matches = Cli::clap()
+        .get_matches_from_safe(args)
+        .map_err(|err| invalid_input(err.message))?;
+    let (mut opt, _) = Cli::from_clap(&matches).into_parts();
+    // Like the CLI, without the server's own config file and environment
+    let defaults = presets::defaults(opt.preset);
+    cli::apply_defaults(&mut opt, &matches, defaults);
+
+    Ok(opt)
+}
+
+fn build_zip(output: &MemoryOutput) -> io::Result<Vec<u8>> {
+    let mut zip = ZipWriter::default();
+
+    for (path, entry) in output.entries() {
+        let name = archive_name(path)?;
+        match entry {
+            MemoryEntry::Dir => zip.add_dir(&name)?,
+            MemoryEntry::File {
+                contents,
+                executable,
+            } => zip.add_file(&name, contents, *executable)?,
+            MemoryEntry::Link(target) => zip.add_link(&name, &archive_target(target))?,
+        }
+    }
+
+    zip.finish()
+}
+
+// e.g. my-target/include/my-target.h, with forward slashes on every platform
+fn archive_name(path: &Path) -> io::Result<String> {
+    let mut parts = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            _ => {
+                return Err(io::Error::other(format!(
+                    "cannot archive '{}', it is not below the project directory",
+                    path.display()
+                )))
+            }
+        }
+    }
+
+    Ok(parts.join("/"))
+}
+
+// e.g. ../docs, validated by gen() to stay in the project
+fn archive_target(target: &Path) -> String {
+    target
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Invalid requests, e.g. a path escape or warnings with --deny-warnings, become invalid input for the status code
+fn to_io(err: Error) -> io::Error {
+    match (err.exit_code(), err) {
+        (error::EXIT_VALIDATION | error::EXIT_DENIED_WARNINGS, err) => {
+            invalid_input(err.to_string())
+        }
+        (_, Error::Io(err)) => err,
+        (_, err) => io::Error::other(err.to_string()),
+    }
+}
+
+// ASCII filename for old clients, the UTF-8 one percent-encoded as filename*
+fn content_disposition(file_name: &str) -> String {
+    let ascii: String = file_name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '+' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    let encoded: String = format!("{}.zip", file_name)
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b'_' | b'-' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}.zip\"; filename*=UTF-8''{}",
+        ascii, encoded
+    )
+}
+
+fn bad_request(message: String) -> Response {
+    Response::text("400 Bad Request", &message)
+}
+
+fn headers_too_large(message: String) -> Response {
+    Response::text("431 Request Header Fields Too Large", &message)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpp_proj_gen::cpp_proj_gen::Kind;
+
+    fn post(body: &str) -> Request {
+        Request {
+            method: String::from("POST"),
+            path: String::from("/generate"),
+            body: Vec::from(body),
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /generate HTTP/1.1\r\nHost: localhost\r\ncontent-length: 2\r\n\r\n{}";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/generate");
+        assert_eq!(request.body, b"{}");
+        assert!(read_request(&mut "\r\n".as_bytes()).is_err());
+
+        let long_line = format!(
+            "GET /{} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_HEADER_BYTES as usize)
+        );
+        let err = read_request(&mut long_line.as_bytes()).unwrap_err();
+        assert_eq!(err.status, "431 Request Header Fields Too Large");
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X: y\r\n".repeat(MAX_HEADERS + 1)
+        );
+        let err = read_request(&mut many_headers.as_bytes()).unwrap_err();
+        assert_eq!(err.status, "431 Request Header Fields Too Large");
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = serde_json::from_str(
+            r#"{"target-name": "tgtnm", "kind": "shared", "ide-folders": true, "iwyu": false, "define": ["A=1", "B=2"]}"#,
+        )
+        .unwrap();
+        let opt = parse_options(&options).unwrap();
+
+        assert_eq!(opt.target_name, "tgtnm");
+        assert_eq!(opt.kind, Kind::Shared);
+        assert!(opt.ide_folders);
+        assert!(!opt.iwyu);
+        assert_eq!(opt.defines.len(), 2);
+
+        let options = serde_json::from_str(r#"{"output-dir": "/etc"}"#).unwrap();
+        assert!(parse_options(&options).is_err());
+        let options = serde_json::from_str(r#"{"kind": "huge"}"#).unwrap();
+        assert!(parse_options(&options).is_err());
+    }
+
+    #[test]
+    fn test_respond() {
+        let limits = Limits::default();
+        let response = respond(&post(r#"{"target-name": "tgtnm"}"#), &limits);
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.starts_with(b"PK\x03\x04"));
+        assert!(response
+            .body
+            .windows(b"tgtnm/CMakeLists.txt".len())
+            .any(|window| window == b"tgtnm/CMakeLists.txt"));
+
+        assert_eq!(respond(&post("[]"), &limits).status, "400 Bad Request");
+        assert_eq!(
+            respond(&post(r#"{"target-name": "../tgtnm"}"#), &limits).status,
+            "400 Bad Request"
+        );
+        for preset in [r#"{"preset": "cli-app"}"#, r#"{"preset": "hpc"}"#] {
+            assert_eq!(respond(&post(preset), &limits).status, "200 OK");
+        }
+        assert_eq!(
+            respond(&post(r#"{"preset": "cli-app", "kind": "static"}"#), &limits).status,
+            "400 Bad Request"
+        );
+        let get = Request {
+            method: String::from("GET"),
+            ..post("")
+        };
+        assert_eq!(respond(&get, &limits).status, "405 Method Not Allowed");
+
+        let limits = Limits {
+            max_files: Some(1),
+            ..Limits::default()
+        };
+        assert_eq!(respond(&post("{}"), &limits).status, "400 Bad Request");
+    }
+
+    #[test]
+    fn test_respond_invalid_target_name() {
+        let limits = Limits::default();
+        let response = respond(
+            &post(r#"{"target-name": "x\r\nSet-Cookie: pwned=1\r\nX-A: \""}"#),
+            &limits,
+        );
+        assert_eq!(response.status, "400 Bad Request");
+        assert!(response
+            .headers
+            .iter()
+            .all(|(name, _)| *name != "Set-Cookie"));
+
+        let response = respond(&post(r#"{"target-name": "my lib)"}"#), &limits);
+        assert_eq!(response.status, "400 Bad Request");
+        let response = respond(&post(r#"{"target-name": "test"}"#), &limits);
+        assert_eq!(response.status, "400 Bad Request");
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            content_disposition("my-lib"),
+            "attachment; filename=\"my-lib.zip\"; filename*=UTF-8''my-lib.zip"
+        );
+        assert_eq!(
+            content_disposition("a\r\n\"b"),
+            "attachment; filename=\"a___b.zip\"; filename*=UTF-8''a%0D%0A%22b.zip"
+        );
+    }
+
+    #[test]
+    fn test_archive_name() {
+        assert_eq!(
+            archive_name(Path::new("tgtnm/include/tgtnm.h")).unwrap(),
+            "tgtnm/include/tgtnm.h"
+        );
+        assert!(archive_name(Path::new("/etc/passwd")).is_err());
+        assert!(archive_name(Path::new("../tgtnm")).is_err());
+    }
+}